@@ -1,25 +1,72 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use tracing::debug;
 
-use crate::config::{self, CliOverrides, GerritConfig};
-use crate::gerrit::{Credentials, GerritClient};
+use crate::cache::Cache;
+use crate::config::{self, CliOverrides, GerritConfig, LoadedCredentials};
+use crate::gerrit::{AccountInfo, AuthType, CredentialRefresher, Credentials, GerritClient};
 use crate::git::GitRepo;
+use crate::secrets;
 use crate::subprocess;
 
 /// Indicates where credentials were sourced from.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CredentialSource {
-    /// Loaded from `~/.config/grt/credentials.toml`.
+    /// Loaded from the OS keyring or `~/.config/grt/credentials.toml`.
     File,
     /// Obtained via `git credential fill`.
     GitHelper,
 }
 
+/// Re-asks the git credential helper when Gerrit refuses its credentials
+/// mid-session, e.g. after a token expired and the helper has a newer one.
+#[derive(Debug)]
+struct GitCredentialHelper {
+    url: String,
+    root: PathBuf,
+    auth_type: AuthType,
+}
+
+impl CredentialRefresher for GitCredentialHelper {
+    fn reject(&self, creds: &Credentials) {
+        let _ = subprocess::git_credential_reject(
+            &self.url,
+            &creds.username,
+            &creds.password,
+            &self.root,
+        );
+    }
+
+    fn fill(&self) -> Option<Credentials> {
+        match subprocess::git_credential_fill(&self.url, &self.root) {
+            Ok(Some((username, password))) => Some(Credentials {
+                username,
+                password,
+                auth_type: self.auth_type,
+            }),
+            Ok(None) => None,
+            Err(e) => {
+                debug!("git credential fill failed: {e:#}");
+                None
+            }
+        }
+    }
+
+    fn approve(&self, creds: &Credentials) {
+        let _ = subprocess::git_credential_approve(
+            &self.url,
+            &creds.username,
+            &creds.password,
+            &self.root,
+        );
+    }
+}
+
 /// Application context holding shared resources.
 pub struct App {
     pub config: GerritConfig,
@@ -59,7 +106,8 @@ impl App {
                    1. Run grt from inside a repo that has a .gitreview file\n\
                    2. Set gitreview.host in git config\n\
                    3. Use --server <host> to specify the host directly\n\
-                   4. Mark a server as 'default = true' in {config_path}\n\
+                   4. Add a Gerrit remote (ssh:// or https://) to the repo\n\
+                   5. Mark a server as 'default = true' in {config_path}\n\
                  \n\
                  Example credentials.toml entry:\n\
                  \n\
@@ -81,7 +129,8 @@ impl App {
         }
 
         let base_url = config.gerrit_base_url()?;
-        let gerrit = GerritClient::new(base_url, None, config.ssl_verify)?;
+        let gerrit =
+            GerritClient::with_http_options(base_url, None, config.ssl_verify, &config.http)?;
 
         Ok(Self {
             config,
@@ -99,33 +148,51 @@ impl App {
         })
     }
 
-    /// Acquire credentials: try credentials.toml first, then git credential helper.
+    /// Return the offline change cache under the repository's git directory.
+    pub fn cache(&self) -> Result<Cache> {
+        Ok(Cache::for_git_dir(&self.require_git()?.common_dir()))
+    }
+
+    /// Acquire credentials: try the OS keyring and credentials.toml first,
+    /// then git credential helper.
     ///
     /// When credentials come from the git helper, a successful `authenticate_and_verify`
     /// will call `git credential approve` so the helper can cache them.
     ///
     /// Refuses to send credentials over plain HTTP unless `--insecure` was passed.
     pub fn authenticate(&mut self) -> Result<()> {
-        // The REST API always uses HTTPS unless scheme is explicitly "http".
-        // SSH scheme (the default) maps to HTTPS for API requests, so only
-        // block when scheme is literally "http" without --insecure.
-        if self.config.scheme == "http" && !self.insecure {
-            anyhow::bail!(
-                "refusing to send credentials over plain HTTP (scheme: {}). \
-                 Use --insecure to override, or switch to HTTPS",
-                self.config.scheme,
-            );
-        }
+        self.check_secure_transport()?;
 
-        // Try credentials.toml first
+        // Try the keyring and credentials.toml first; an unusable keyring
+        // (no Secret Service running, locked, ...) is skipped
+        let from_keyring = match secrets::load(&self.config.host) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                debug!("skipping OS keyring: {e:#}");
+                None
+            }
+        };
+        if let Some(loaded) = from_keyring {
+            debug!("credentials loaded from the OS keyring");
+            let auth_type = self.config.auth_type.unwrap_or(loaded.auth_type);
+            self.set_credentials(
+                loaded.username,
+                loaded.password,
+                auth_type,
+                CredentialSource::File,
+            )?;
+            return Ok(());
+        }
         if let Some(config_dir) = dirs::config_dir() {
             match config::load_credentials(&self.config.host, &config_dir) {
                 Ok(Some(loaded)) => {
                     debug!("credentials loaded from credentials.toml");
+                    // The repo's `auth_type` setting wins over the stored one
+                    let auth_type = self.config.auth_type.unwrap_or(loaded.auth_type);
                     self.set_credentials(
                         loaded.username,
                         loaded.password,
-                        loaded.auth_type,
+                        auth_type,
                         CredentialSource::File,
                     )?;
                     return Ok(());
@@ -139,7 +206,7 @@ impl App {
             }
         }
 
-        // Fall back to git credential helper (always Basic auth)
+        // Fall back to git credential helper (Basic unless the repo says otherwise)
         let url = self.config.gerrit_base_url()?.to_string();
         let root = self.require_git()?.root()?;
         let (username, password) = subprocess::git_credential_fill(&url, &root)
@@ -150,12 +217,42 @@ impl App {
         self.set_credentials(
             username,
             password,
-            crate::gerrit::AuthType::Basic,
+            self.config.auth_type.unwrap_or_default(),
             CredentialSource::GitHelper,
         )?;
         Ok(())
     }
 
+    /// Use `creds` instead of looking credentials up, and check them by
+    /// calling `/accounts/self` (for `grt auth login`).
+    pub async fn verify_credentials(&mut self, creds: LoadedCredentials) -> Result<AccountInfo> {
+        self.check_secure_transport()?;
+        self.set_credentials(
+            creds.username,
+            creds.password,
+            creds.auth_type,
+            CredentialSource::File,
+        )?;
+        self.gerrit
+            .get_self_account()
+            .await
+            .context("verifying credentials against Gerrit")
+    }
+
+    /// The REST API always uses HTTPS unless scheme is explicitly "http".
+    /// SSH scheme (the default) maps to HTTPS for API requests, so only
+    /// block when scheme is literally "http" without --insecure.
+    fn check_secure_transport(&self) -> Result<()> {
+        if self.config.scheme == "http" && !self.insecure {
+            anyhow::bail!(
+                "refusing to send credentials over plain HTTP (scheme: {}). \
+                 Use --insecure to override, or switch to HTTPS",
+                self.config.scheme,
+            );
+        }
+        Ok(())
+    }
+
     /// Authenticate and verify credentials by calling `/accounts/self`.
     ///
     /// On success with git-helper-sourced credentials, calls `git credential approve`.
@@ -186,7 +283,7 @@ impl App {
         &mut self,
         username: String,
         password: String,
-        auth_type: crate::gerrit::AuthType,
+        auth_type: AuthType,
         source: CredentialSource,
     ) -> Result<()> {
         self.gerrit.set_credentials(Credentials {
@@ -197,11 +294,24 @@ impl App {
         self.credential_source = Some(source);
         // Re-create client with auth prefix
         let base_url = self.config.gerrit_base_url()?;
-        self.gerrit = GerritClient::new(
-            base_url,
-            self.gerrit.credentials().cloned(),
+        self.gerrit = GerritClient::with_http_options(
+            base_url.clone(),
+            self.gerrit.credentials(),
             self.config.ssl_verify,
+            &self.config.http,
         )?;
+        // Helper credentials can go stale mid-session; ask the helper again
+        // on a 401 rather than failing outright
+        if source == CredentialSource::GitHelper {
+            if let Some(root) = self.git.as_ref().and_then(|g| g.root().ok()) {
+                self.gerrit
+                    .set_credential_refresher(Arc::new(GitCredentialHelper {
+                        url: base_url.to_string(),
+                        root,
+                        auth_type,
+                    }));
+            }
+        }
         Ok(())
     }
 
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! The attention set of a change for `grt attention` and `grt status`.
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::gerrit::ChangeInfo;
+use crate::reviewers::account_label;
+
+/// Reason recorded when `--reason` is not given; Gerrit requires one.
+pub const DEFAULT_REASON: &str = "Updated with grt";
+
+/// One user in the attention set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AttentionEntry {
+    pub account: String,
+    pub reason: Option<String>,
+    pub last_update: Option<String>,
+}
+
+/// The attention set of `change`, sorted by account.
+///
+/// Empty when the server does not report one (Gerrit < 3.3).
+pub fn attention_entries(change: &ChangeInfo) -> Vec<AttentionEntry> {
+    let mut entries: Vec<AttentionEntry> = change
+        .attention_set
+        .iter()
+        .flatten()
+        .map(|(_, info)| AttentionEntry {
+            account: account_label(&info.account),
+            reason: info.reason.clone(),
+            last_update: info.last_update.clone(),
+        })
+        .collect();
+    entries.sort_by(|a, b| a.account.cmp(&b.account));
+    entries
+}
+
+/// One line per user: the account and, when known, why it needs attention.
+pub fn format_entries(entries: &[AttentionEntry]) -> String {
+    let width = entries.iter().map(|e| e.account.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for entry in entries {
+        match entry.reason.as_deref() {
+            Some(reason) => {
+                let _ = writeln!(out, "  {:width$}  {reason}", entry.account);
+            }
+            None => {
+                let _ = writeln!(out, "  {}", entry.account);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change() -> ChangeInfo {
+        serde_json::from_value(serde_json::json!({
+            "_number": 12345,
+            "attention_set": {
+                "1000097": {
+                    "account": {"_account_id": 1000097, "name": "Bob"},
+                    "last_update": "2026-01-02 10:00:00.000000000",
+                    "reason": "Reviewer replied"
+                },
+                "1000096": {
+                    "account": {"_account_id": 1000096, "name": "Alice", "email": "alice@example.com"},
+                    "last_update": "2026-01-01 09:00:00.000000000",
+                    "reason": "Added by Bob using the hovercard menu"
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn entries_sorted_by_account() {
+        let entries = attention_entries(&change());
+        let accounts: Vec<&str> = entries.iter().map(|e| e.account.as_str()).collect();
+        assert_eq!(accounts, ["Alice <alice@example.com>", "Bob"]);
+        assert_eq!(entries[1].reason.as_deref(), Some("Reviewer replied"));
+    }
+
+    #[test]
+    fn entries_empty_without_attention_set() {
+        assert!(attention_entries(&ChangeInfo::default()).is_empty());
+    }
+
+    #[test]
+    fn format_aligns_reasons() {
+        let text = format_entries(&attention_entries(&change()));
+        assert_eq!(
+            text,
+            "  Alice <alice@example.com>  Added by Bob using the hovercard menu\n\
+             \x20 Bob                        Reviewer replied\n"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! `grt backport`: cherry-pick a change onto other branches for review.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::push;
+use crate::subprocess;
+
+/// Local branch a backport to `target` is prepared on: `backport/<change>/<target>`.
+pub fn backport_branch_name(change_number: i64, target: &str) -> String {
+    format!("backport/{change_number}/{target}")
+}
+
+/// Deduplicate `targets`, keeping their order, and reject the change's own branch.
+pub fn backport_targets<'a>(targets: &'a [String], source: Option<&str>) -> Result<Vec<&'a str>> {
+    let mut out: Vec<&str> = Vec::with_capacity(targets.len());
+    for target in targets {
+        if Some(target.as_str()) == source {
+            bail!("change is already on {target}; backport targets must be other branches");
+        }
+        if !out.contains(&target.as_str()) {
+            out.push(target);
+        }
+    }
+    Ok(out)
+}
+
+/// One prepared backport.
+#[derive(Debug, Clone, Serialize)]
+pub struct Backport {
+    pub target: String,
+    pub branch: String,
+    /// The cherry-picked commit.
+    pub commit: String,
+    pub change_id: String,
+}
+
+/// Create `branch` from `remote/target` and cherry-pick `commit` onto it with `-x`.
+///
+/// The Change-Id is kept, so Gerrit links the backport to the original
+/// change, unless `new_change_id` is set: then it is removed and the
+/// commit-msg hook generates a new one. A conflicting cherry-pick is aborted,
+/// leaving `branch` at `remote/target`, so the caller can move on.
+pub fn cherry_pick_onto(
+    remote: &str,
+    target: &str,
+    branch: &str,
+    commit: &str,
+    new_change_id: bool,
+    work_dir: &Path,
+) -> Result<Backport> {
+    subprocess::git_fetch_ref(remote, target, work_dir)
+        .with_context(|| format!("fetching {target} from {remote}"))?;
+    subprocess::git_checkout_or_reset_branch(branch, "FETCH_HEAD", work_dir)?;
+    subprocess::git_set_upstream_tracking(branch, &format!("{remote}/{target}"), work_dir)?;
+    if let Err(e) = subprocess::git_cherry_pick_indicate(commit, work_dir) {
+        // Best effort: the pick may have failed before starting
+        let _ = subprocess::git_cherry_pick_abort(work_dir);
+        return Err(e).with_context(|| {
+            format!(
+                "cherry-pick onto {target} failed; to resolve it by hand, run \
+                 `git checkout {branch} && git cherry-pick -x {commit}`, then `grt push {target}`"
+            )
+        });
+    }
+
+    let before = subprocess::git_output(&["log", "-1", "--format=%B"], work_dir)?;
+    let change_id = if new_change_id {
+        subprocess::git_regenerate_changeid(work_dir)?;
+        let after = subprocess::git_output(&["log", "-1", "--format=%B"], work_dir)?;
+        push::verify_amend_added_change_id(&before, &after)?
+    } else {
+        push::validate_change_id(&before)?
+    };
+    Ok(Backport {
+        target: target.to_string(),
+        branch: branch.to_string(),
+        commit: subprocess::git_rev_parse_head(work_dir)?,
+        change_id,
+    })
+}
+
+/// Summary of the prepared backports: target, local branch, and Change-Id.
+pub fn format_backports(backports: &[Backport]) -> String {
+    let target_width = backports.iter().map(|b| b.target.len()).max().unwrap_or(0);
+    let branch_width = backports.iter().map(|b| b.branch.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for b in backports {
+        let _ = writeln!(
+            out,
+            "  {:target_width$}  {:branch_width$}  {}",
+            b.target, b.branch, b.change_id
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGE_ID: &str = "I0123456789abcdef0123456789abcdef01234567";
+
+    fn git(args: &[&str], dir: &Path) -> String {
+        subprocess::git_output(args, dir).unwrap()
+    }
+
+    #[test]
+    fn branch_name_includes_change_and_target() {
+        assert_eq!(
+            backport_branch_name(12345, "stable/2024.1"),
+            "backport/12345/stable/2024.1"
+        );
+    }
+
+    #[test]
+    fn targets_are_deduplicated_in_order() {
+        let targets = vec![
+            "stable/2024.1".to_string(),
+            "stable/2023.2".to_string(),
+            "stable/2024.1".to_string(),
+        ];
+        assert_eq!(
+            backport_targets(&targets, Some("master")).unwrap(),
+            vec!["stable/2024.1", "stable/2023.2"]
+        );
+    }
+
+    #[test]
+    fn targets_reject_source_branch() {
+        let targets = vec!["master".to_string()];
+        let err = backport_targets(&targets, Some("master")).unwrap_err();
+        assert!(err.to_string().contains("already on master"));
+    }
+
+    #[test]
+    fn cherry_pick_onto_keeps_change_id_and_records_origin() {
+        let upstream = tempfile::tempdir().unwrap();
+        let up = upstream.path();
+        git(&["init", "-q", "-b", "master"], up);
+        git(&["config", "user.email", "test@example.com"], up);
+        git(&["config", "user.name", "Test"], up);
+        std::fs::write(up.join("a"), "base\n").unwrap();
+        git(&["add", "a"], up);
+        git(&["commit", "-q", "-m", "base"], up);
+        git(&["branch", "stable/1"], up);
+        std::fs::write(up.join("b"), "fix\n").unwrap();
+        git(&["add", "b"], up);
+        let message = format!("Fix it\n\nChange-Id: {CHANGE_ID}");
+        git(&["commit", "-q", "-m", &message], up);
+        let fix = git(&["rev-parse", "HEAD"], up);
+
+        let work = tempfile::tempdir().unwrap();
+        let w = work.path();
+        git(&["clone", "-q", up.to_str().unwrap(), "."], w);
+        git(&["config", "user.email", "test@example.com"], w);
+        git(&["config", "user.name", "Test"], w);
+
+        let backport =
+            cherry_pick_onto("origin", "stable/1", "backport/1/stable/1", &fix, false, w).unwrap();
+        assert_eq!(backport.change_id, CHANGE_ID);
+        assert_eq!(git(&["branch", "--show-current"], w), "backport/1/stable/1");
+        let log = git(&["log", "-1", "--format=%B"], w);
+        assert!(
+            log.contains(&format!("(cherry picked from commit {fix})")),
+            "{log}"
+        );
+        assert_eq!(
+            git(&["rev-parse", "--abbrev-ref", "@{upstream}"], w),
+            "origin/stable/1"
+        );
+        let parent = git(&["rev-parse", "HEAD^"], w);
+        assert_eq!(parent, git(&["rev-parse", "origin/stable/1"], w));
+    }
+}
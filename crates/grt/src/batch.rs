@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Branches for `grt push --all-branches`: local branches matching a glob,
+//! each pushed to the branch it tracks.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::git::LOCAL_REMOTE;
+use crate::push;
+use crate::subprocess;
+
+/// `git for-each-ref` format read by [`parse_branches`].
+const BRANCH_FORMAT: &str = "%(refname:short)%09%(upstream:remotename)%09%(upstream:remoteref)";
+
+/// A local branch and the upstream it tracks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalBranch {
+    pub name: String,
+    /// Upstream remote, `.` for a local upstream, `None` when not tracking.
+    pub remote: Option<String>,
+    /// Upstream branch without `refs/heads/`, `None` when not tracking.
+    pub merge: Option<String>,
+}
+
+/// Parse `git for-each-ref` output in [`BRANCH_FORMAT`].
+pub fn parse_branches(output: &str) -> Vec<LocalBranch> {
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next().filter(|n| !n.is_empty())?;
+            let remote = fields.next().and_then(non_empty);
+            let merge = fields
+                .next()
+                .map(|r| r.strip_prefix("refs/heads/").unwrap_or(r))
+                .and_then(non_empty);
+            Some(LocalBranch {
+                name: name.to_string(),
+                remote,
+                merge,
+            })
+        })
+        .collect()
+}
+
+/// Ref pattern for a branch glob: `fix/*` becomes `refs/heads/fix/*`.
+///
+/// Matching is git's: `*` does not cross a `/`.
+pub fn branch_ref_pattern(pattern: &str) -> String {
+    if pattern.starts_with("refs/") {
+        pattern.to_string()
+    } else {
+        format!("refs/heads/{pattern}")
+    }
+}
+
+/// One matching branch and what would be pushed from it.
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    pub branch: String,
+    /// Remote to push to; a local upstream pushes to the configured remote.
+    pub remote: String,
+    /// Target branch, `None` when the branch tracks nothing.
+    pub target: Option<String>,
+    /// Commits on the branch that are not on its upstream.
+    pub commits: usize,
+    /// Of those, the commits without a Change-Id footer.
+    pub missing_change_ids: usize,
+}
+
+impl BatchEntry {
+    /// Why the branch is left alone, `None` when it is pushed.
+    pub fn skip_reason(&self) -> Option<&'static str> {
+        if self.target.is_none() {
+            Some("no upstream branch")
+        } else if self.commits == 0 {
+            Some("nothing to push")
+        } else if self.missing_change_ids > 0 {
+            Some("missing Change-Id")
+        } else {
+            None
+        }
+    }
+
+    fn target_label(&self) -> String {
+        match self.target {
+            Some(ref target) => format!("{}/{target}", self.remote),
+            None => "-".to_string(),
+        }
+    }
+}
+
+/// Local branches matching `pattern`, with their upstream and unpushed commits.
+///
+/// `default_remote` is used for branches whose upstream is another local
+/// branch, matching `--track`.
+pub fn collect_branches(
+    pattern: &str,
+    default_remote: &str,
+    work_dir: &Path,
+) -> Result<Vec<BatchEntry>> {
+    let format = format!("--format={BRANCH_FORMAT}");
+    let output = subprocess::git_output(
+        &["for-each-ref", &format, &branch_ref_pattern(pattern)],
+        work_dir,
+    )?;
+    let mut entries = Vec::new();
+    for branch in parse_branches(&output) {
+        let remote = match branch.remote.as_deref() {
+            Some(remote) if remote != LOCAL_REMOTE => remote.to_string(),
+            _ => default_remote.to_string(),
+        };
+        let (commits, missing_change_ids) = match branch.merge {
+            Some(_) => {
+                let messages = unpushed_messages(&branch.name, work_dir)?;
+                let missing = messages
+                    .iter()
+                    .filter(|m| push::extract_change_id(m).is_none())
+                    .count();
+                (messages.len(), missing)
+            }
+            None => (0, 0),
+        };
+        entries.push(BatchEntry {
+            branch: branch.name,
+            remote,
+            target: branch.merge,
+            commits,
+            missing_change_ids,
+        });
+    }
+    Ok(entries)
+}
+
+/// Messages of the commits on `branch` that are not on its upstream.
+fn unpushed_messages(branch: &str, work_dir: &Path) -> Result<Vec<String>> {
+    let range = format!("{branch}@{{upstream}}..{branch}");
+    let output = subprocess::git_output(&["log", "--format=%B%x00", &range], work_dir)?;
+    Ok(output
+        .split('\0')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// What happened to one branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOutcome {
+    Pushed,
+    Skipped(&'static str),
+    Failed(String),
+}
+
+/// Plan shown before pushing: one line per branch with its target and
+/// commit count, or the reason it is skipped.
+pub fn format_plan(entries: &[BatchEntry]) -> String {
+    let rows: Vec<[String; 3]> = entries
+        .iter()
+        .map(|e| {
+            let status = match e.skip_reason() {
+                Some(reason) => format!("skip: {reason}"),
+                None => format!("{} commit(s)", e.commits),
+            };
+            [e.branch.clone(), e.target_label(), status]
+        })
+        .collect();
+    format_rows(&rows)
+}
+
+/// Summary printed after pushing: one line per branch with its outcome.
+pub fn format_summary(entries: &[BatchEntry], outcomes: &[BatchOutcome], dry_run: bool) -> String {
+    let rows: Vec<[String; 3]> = entries
+        .iter()
+        .zip(outcomes)
+        .map(|(e, outcome)| {
+            let result = match outcome {
+                BatchOutcome::Pushed if dry_run => "dry run".to_string(),
+                BatchOutcome::Pushed => format!("pushed {} commit(s)", e.commits),
+                BatchOutcome::Skipped(reason) => format!("skipped: {reason}"),
+                BatchOutcome::Failed(error) => format!("failed: {error}"),
+            };
+            [e.branch.clone(), e.target_label(), result]
+        })
+        .collect();
+    format_rows(&rows)
+}
+
+fn format_rows(rows: &[[String; 3]]) -> String {
+    let branch_width = rows.iter().map(|r| r[0].len()).max().unwrap_or(0);
+    let target_width = rows.iter().map(|r| r[1].len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for [branch, target, status] in rows {
+        let _ = writeln!(
+            out,
+            "  {branch:branch_width$}  {target:target_width$}  {status}"
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(args: &[&str], dir: &Path) -> String {
+        subprocess::git_output(args, dir).unwrap()
+    }
+
+    fn entry(branch: &str, target: Option<&str>, commits: usize, missing: usize) -> BatchEntry {
+        BatchEntry {
+            branch: branch.to_string(),
+            remote: "gerrit".to_string(),
+            target: target.map(str::to_string),
+            commits,
+            missing_change_ids: missing,
+        }
+    }
+
+    #[test]
+    fn parse_branches_reads_upstreams() {
+        let output = "fix/a\torigin\trefs/heads/stable/1\nfix/b\t.\trefs/heads/main\nfix/c\t\t\n";
+        assert_eq!(
+            parse_branches(output),
+            vec![
+                LocalBranch {
+                    name: "fix/a".to_string(),
+                    remote: Some("origin".to_string()),
+                    merge: Some("stable/1".to_string()),
+                },
+                LocalBranch {
+                    name: "fix/b".to_string(),
+                    remote: Some(".".to_string()),
+                    merge: Some("main".to_string()),
+                },
+                LocalBranch {
+                    name: "fix/c".to_string(),
+                    remote: None,
+                    merge: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn branch_ref_pattern_prefixes_heads() {
+        assert_eq!(branch_ref_pattern("fix/*"), "refs/heads/fix/*");
+        assert_eq!(branch_ref_pattern("refs/heads/x"), "refs/heads/x");
+    }
+
+    #[test]
+    fn skip_reasons() {
+        assert_eq!(
+            entry("a", None, 0, 0).skip_reason(),
+            Some("no upstream branch")
+        );
+        assert_eq!(
+            entry("a", Some("main"), 0, 0).skip_reason(),
+            Some("nothing to push")
+        );
+        assert_eq!(
+            entry("a", Some("main"), 2, 1).skip_reason(),
+            Some("missing Change-Id")
+        );
+        assert_eq!(entry("a", Some("main"), 2, 0).skip_reason(), None);
+    }
+
+    #[test]
+    fn summary_lists_outcomes() {
+        let entries = [
+            entry("fix/one", Some("main"), 1, 0),
+            entry("fix/two", None, 0, 0),
+            entry("fix/three", Some("stable/1"), 2, 0),
+        ];
+        let outcomes = [
+            BatchOutcome::Pushed,
+            BatchOutcome::Skipped("no upstream branch"),
+            BatchOutcome::Failed("rejected".to_string()),
+        ];
+        let summary = format_summary(&entries, &outcomes, false);
+        assert_eq!(
+            summary,
+            "  fix/one    gerrit/main      pushed 1 commit(s)\n\
+             \x20 fix/two    -                skipped: no upstream branch\n\
+             \x20 fix/three  gerrit/stable/1  failed: rejected\n"
+        );
+        assert!(format_summary(&entries[..1], &outcomes[..1], true).contains("dry run"));
+    }
+
+    #[test]
+    fn collect_branches_counts_unpushed_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        git(&["init", "-q", "-b", "main"], d);
+        git(&["config", "user.email", "test@example.com"], d);
+        git(&["config", "user.name", "Test"], d);
+        git(&["commit", "-q", "--allow-empty", "-m", "base"], d);
+        git(&["checkout", "-q", "-b", "fix/one", "--track", "main"], d);
+        git(
+            &[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "one\n\nChange-Id: I0123456789abcdef0123456789abcdef01234567",
+            ],
+            d,
+        );
+        git(&["checkout", "-q", "-b", "fix/two", "--track", "main"], d);
+        git(&["commit", "-q", "--allow-empty", "-m", "no footer"], d);
+        git(&["branch", "fix/untracked", "main"], d);
+        git(&["branch", "other", "main"], d);
+
+        let entries = collect_branches("fix/*", "gerrit", d).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.branch.as_str()).collect();
+        assert_eq!(names, vec!["fix/one", "fix/two", "fix/untracked"]);
+        assert_eq!(entries[0].remote, "gerrit");
+        assert_eq!(entries[0].target.as_deref(), Some("main"));
+        assert_eq!(entries[0].skip_reason(), None);
+        assert_eq!(entries[1].skip_reason(), Some("missing Change-Id"));
+        assert_eq!(entries[2].skip_reason(), Some("no upstream branch"));
+    }
+}
@@ -0,0 +1,301 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! On-disk cache of change metadata and comments for reading offline.
+//!
+//! Entries are JSON files under `<git dir>/grt/cache/`: `comments/` keyed by
+//! the change (and which revisions were fetched), `lists/` keyed by the
+//! `grt review -l` query. Per-host entries such as server capabilities live
+//! in the user's cache directory instead, shared by all repositories.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::capabilities::Capabilities;
+use crate::gerrit::{ChangeInfo, CommentInfo, GerritError};
+
+/// How a command uses the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Fetch from Gerrit and update the cache, falling back to the cached
+    /// copy when Gerrit is unreachable.
+    #[default]
+    Auto,
+    /// Read only the cache (`--cached`).
+    Offline,
+    /// Fetch from Gerrit and never fall back (`--refresh`).
+    Refresh,
+}
+
+impl CacheMode {
+    /// Mode selected by the `--cached` / `--refresh` flags.
+    pub fn from_flags(cached: bool, refresh: bool) -> Self {
+        match (cached, refresh) {
+            (true, _) => CacheMode::Offline,
+            (false, true) => CacheMode::Refresh,
+            (false, false) => CacheMode::Auto,
+        }
+    }
+}
+
+/// Comments for one change as last fetched from Gerrit.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedComments {
+    /// Seconds since the Unix epoch when the entry was fetched.
+    pub fetched_at: u64,
+    pub change: ChangeInfo,
+    pub comments: HashMap<String, Vec<CommentInfo>>,
+    /// Empty when robot comments were excluded from the fetch.
+    #[serde(default)]
+    pub robot_comments: HashMap<String, Vec<CommentInfo>>,
+}
+
+/// Result of a `grt review -l` query as last fetched from Gerrit.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedList {
+    /// Seconds since the Unix epoch when the entry was fetched.
+    pub fetched_at: u64,
+    pub changes: Vec<ChangeInfo>,
+}
+
+/// Cache directory for one repository.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// Cache rooted at `dir` (normally `<git dir>/grt/cache`).
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Cache for the repository whose git directory is `git_dir`.
+    pub fn for_git_dir(git_dir: &Path) -> Self {
+        Self::new(git_dir.join("grt").join("cache"))
+    }
+
+    /// User-wide cache under the platform cache directory, for entries keyed
+    /// by Gerrit host rather than repository.
+    pub fn for_user() -> Option<Self> {
+        dirs::cache_dir().map(|d| Self::new(d.join("grt")))
+    }
+
+    pub fn load_comments(&self, key: &str) -> Result<Option<CachedComments>> {
+        self.load("comments", key)
+    }
+
+    pub fn store_comments(&self, key: &str, entry: &CachedComments) -> Result<()> {
+        self.store("comments", key, entry)
+    }
+
+    pub fn load_list(&self, query: &str) -> Result<Option<CachedList>> {
+        self.load("lists", query)
+    }
+
+    pub fn store_list(&self, query: &str, entry: &CachedList) -> Result<()> {
+        self.store("lists", query, entry)
+    }
+
+    pub fn load_capabilities(&self, host: &str) -> Result<Option<Capabilities>> {
+        self.load("capabilities", host)
+    }
+
+    pub fn store_capabilities(&self, host: &str, entry: &Capabilities) -> Result<()> {
+        self.store("capabilities", host, entry)
+    }
+
+    fn path(&self, kind: &str, key: &str) -> PathBuf {
+        self.dir
+            .join(kind)
+            .join(format!("{}.json", urlencoding::encode(key)))
+    }
+
+    fn load<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Result<Option<T>> {
+        let path = self.path(kind, key);
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        let entry = serde_json::from_str(&data)
+            .with_context(|| format!("parsing cache entry {}", path.display()))?;
+        Ok(Some(entry))
+    }
+
+    /// Write via a temporary file so an interrupted run never leaves half an entry.
+    fn store<T: Serialize>(&self, kind: &str, key: &str, entry: &T) -> Result<()> {
+        let path = self.path(kind, key);
+        let parent = path.parent().expect("cache path has a parent");
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display()))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(entry)?)
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path).with_context(|| format!("writing {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Cache key for the comments of `change`.
+///
+/// `revision` is `None` for all revisions, otherwise the revision asked for
+/// (`"current"` when none was given).
+pub fn comments_key(change: &str, revision: Option<&str>) -> String {
+    format!("{change}@{}", revision.unwrap_or("all"))
+}
+
+/// Current time in seconds since the Unix epoch.
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Human-readable age of an entry fetched at `fetched_at`, e.g. `3 hours ago`.
+pub fn describe_age(fetched_at: u64, now: u64) -> String {
+    let secs = now.saturating_sub(fetched_at);
+    let (count, unit) = match secs {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (secs / 60, "minute"),
+        3_600..=86_399 => (secs / 3_600, "hour"),
+        _ => (secs / 86_400, "day"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{count} {unit}{plural} ago")
+}
+
+/// Whether `err` means Gerrit could not be reached, so a cached copy is the
+/// best we can do.
+pub fn is_offline_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<GerritError>()
+            .is_some_and(GerritError::is_retryable)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(number: i64) -> ChangeInfo {
+        ChangeInfo {
+            number: Some(number),
+            subject: Some("Fix the thing".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn comments_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::for_git_dir(dir.path());
+        let key = comments_key("I0123abcd", Some("current"));
+        assert!(cache.load_comments(&key).unwrap().is_none());
+
+        let entry = CachedComments {
+            fetched_at: 42,
+            change: change(12345),
+            comments: HashMap::new(),
+            robot_comments: HashMap::new(),
+        };
+        cache.store_comments(&key, &entry).unwrap();
+
+        let loaded = cache.load_comments(&key).unwrap().unwrap();
+        assert_eq!(loaded.fetched_at, 42);
+        assert_eq!(loaded.change.number, Some(12345));
+        assert!(dir.path().join("grt/cache/comments").is_dir());
+    }
+
+    #[test]
+    fn list_keys_with_slashes_stay_in_cache_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let query = "status:open project:org/repo branch:stable/1.0";
+        let entry = CachedList {
+            fetched_at: 7,
+            changes: vec![change(1), change(2)],
+        };
+        cache.store_list(query, &entry).unwrap();
+
+        let loaded = cache.load_list(query).unwrap().unwrap();
+        assert_eq!(loaded.changes.len(), 2);
+        assert_eq!(
+            std::fs::read_dir(dir.path().join("lists")).unwrap().count(),
+            1
+        );
+        assert!(cache.load_list("status:open").unwrap().is_none());
+    }
+
+    #[test]
+    fn capabilities_keyed_by_host() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let entry = Capabilities {
+            fetched_at: 9,
+            version: "2.16.28".to_string(),
+            note_db: Some(true),
+        };
+        cache
+            .store_capabilities("review.example.com", &entry)
+            .unwrap();
+
+        let loaded = cache
+            .load_capabilities("review.example.com")
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.version, "2.16.28");
+        assert_eq!(loaded.note_db, Some(true));
+        assert!(cache
+            .load_capabilities("other.example.com")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn comments_key_distinguishes_revisions() {
+        assert_eq!(comments_key("12345", None), "12345@all");
+        assert_eq!(comments_key("12345", Some("current")), "12345@current");
+        assert_ne!(
+            comments_key("12345", Some("2")),
+            comments_key("12345", Some("3"))
+        );
+    }
+
+    #[test]
+    fn cache_mode_from_flags() {
+        assert_eq!(CacheMode::from_flags(false, false), CacheMode::Auto);
+        assert_eq!(CacheMode::from_flags(true, false), CacheMode::Offline);
+        assert_eq!(CacheMode::from_flags(false, true), CacheMode::Refresh);
+    }
+
+    #[test]
+    fn describe_age_units() {
+        assert_eq!(describe_age(100, 130), "just now");
+        assert_eq!(describe_age(0, 60), "1 minute ago");
+        assert_eq!(describe_age(0, 7_200), "2 hours ago");
+        assert_eq!(describe_age(0, 3 * 86_400), "3 days ago");
+        assert_eq!(describe_age(200, 100), "just now");
+    }
+
+    #[test]
+    fn offline_errors_are_network_and_5xx() {
+        let network = anyhow::Error::new(GerritError::Network("refused".into()));
+        assert!(is_offline_error(&network.context("fetching change")));
+        let unavailable = anyhow::Error::new(GerritError::ServerError {
+            status: 503,
+            body: String::new(),
+        });
+        assert!(is_offline_error(&unavailable));
+        assert!(!is_offline_error(&anyhow::Error::new(
+            GerritError::NotFound
+        )));
+        assert!(!is_offline_error(&anyhow::anyhow!("bad input")));
+    }
+}
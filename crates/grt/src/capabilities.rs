@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Gerrit server capabilities: which version-dependent features a server
+//! supports, so grt can refuse up front instead of surfacing an opaque 400.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// How long a probed host's capabilities are trusted before probing again.
+pub const CAPABILITIES_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// `major.minor.patch` of a Gerrit release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ServerVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse a version as reported by `/config/server/version` or
+    /// `gerrit version`, e.g. `3.9.1`, `3.10.0-rc2-12-gabc1234` or
+    /// `gerrit version 2.16.28`. Missing minor or patch numbers count as 0.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let s = s.strip_prefix("gerrit version").unwrap_or(s).trim();
+        let s = s.strip_prefix('v').unwrap_or(s);
+        let mut parts = s.split('.').map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse::<u32>().ok()
+        });
+        let major = parts.next()??;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.patch == 0 {
+            write!(f, "{}.{}", self.major, self.minor)
+        } else {
+            write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        }
+    }
+}
+
+/// A grt feature that older Gerrit releases lack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// Hashtags on changes (NoteDb only before 3.0).
+    Hashtags,
+    /// Work-in-progress changes (`--wip` / `--ready`).
+    WorkInProgress,
+    /// Comments posted by bots through the robot comments API.
+    RobotComments,
+    /// The attention set of users expected to act on a change.
+    AttentionSet,
+}
+
+impl Feature {
+    /// First Gerrit release with the feature.
+    pub fn min_version(self) -> ServerVersion {
+        match self {
+            Feature::Hashtags => ServerVersion::new(2, 13, 0),
+            Feature::RobotComments => ServerVersion::new(2, 14, 0),
+            Feature::WorkInProgress => ServerVersion::new(2, 15, 0),
+            Feature::AttentionSet => ServerVersion::new(3, 3, 0),
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Feature::Hashtags => "hashtags",
+            Feature::WorkInProgress => "work-in-progress changes",
+            Feature::RobotComments => "robot comments",
+            Feature::AttentionSet => "attention sets",
+        }
+    }
+}
+
+/// What a Gerrit host supports, as last probed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// Seconds since the Unix epoch when the host was probed.
+    pub fetched_at: u64,
+    /// Version string as the server reported it.
+    pub version: String,
+    /// `note_db_enabled` from `/config/server/info`; only 2.x servers send
+    /// it, and SSH-only setups cannot fetch it at all.
+    #[serde(default)]
+    pub note_db: Option<bool>,
+}
+
+impl Capabilities {
+    pub fn server_version(&self) -> Option<ServerVersion> {
+        ServerVersion::parse(&self.version)
+    }
+
+    /// Whether the probe is recent enough to trust at `now`.
+    pub fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.fetched_at) < CAPABILITIES_TTL_SECS
+    }
+
+    /// Whether the server supports `feature`.
+    ///
+    /// Unparseable versions (development builds, custom forks) are assumed
+    /// to support everything and left for Gerrit to judge.
+    pub fn supports(&self, feature: Feature) -> bool {
+        let Some(version) = self.server_version() else {
+            return true;
+        };
+        if version < feature.min_version() {
+            return false;
+        }
+        match feature {
+            Feature::Hashtags => version.major >= 3 || self.note_db != Some(false),
+            _ => true,
+        }
+    }
+
+    /// Fail with a clear message when the server lacks `feature`.
+    pub fn require(&self, feature: Feature) -> anyhow::Result<()> {
+        if self.supports(feature) {
+            return Ok(());
+        }
+        let min = feature.min_version();
+        if self.server_version().is_some_and(|v| v >= min) {
+            anyhow::bail!(
+                "{} require NoteDb on Gerrit {} (or Gerrit >= 3.0); this server runs ReviewDb",
+                feature.description(),
+                self.version
+            );
+        }
+        anyhow::bail!(
+            "{} require Gerrit >= {min} (server runs {})",
+            feature.description(),
+            self.version
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(version: &str, note_db: Option<bool>) -> Capabilities {
+        Capabilities {
+            fetched_at: 1_000,
+            version: version.to_string(),
+            note_db,
+        }
+    }
+
+    #[test]
+    fn parse_versions() {
+        assert_eq!(
+            ServerVersion::parse("3.9.1"),
+            Some(ServerVersion::new(3, 9, 1))
+        );
+        assert_eq!(
+            ServerVersion::parse("3.10.0-rc2-12-gabc1234"),
+            Some(ServerVersion::new(3, 10, 0))
+        );
+        assert_eq!(
+            ServerVersion::parse("gerrit version 2.16.28\n"),
+            Some(ServerVersion::new(2, 16, 28))
+        );
+        assert_eq!(
+            ServerVersion::parse("3.11"),
+            Some(ServerVersion::new(3, 11, 0))
+        );
+        assert_eq!(ServerVersion::parse("unknown"), None);
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(ServerVersion::new(2, 9, 0) < ServerVersion::new(2, 15, 0));
+        assert!(ServerVersion::new(3, 0, 0) > ServerVersion::new(2, 16, 28));
+        assert_eq!(ServerVersion::new(2, 15, 0).to_string(), "2.15");
+        assert_eq!(ServerVersion::new(3, 9, 1).to_string(), "3.9.1");
+    }
+
+    #[test]
+    fn old_server_lacks_wip() {
+        let old = caps("2.14.20", None);
+        assert!(!old.supports(Feature::WorkInProgress));
+        assert!(old.supports(Feature::RobotComments));
+        let err = old.require(Feature::WorkInProgress).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "work-in-progress changes require Gerrit >= 2.15 (server runs 2.14.20)"
+        );
+    }
+
+    #[test]
+    fn attention_set_needs_3_3() {
+        assert!(!caps("3.2.14", None).supports(Feature::AttentionSet));
+        assert!(caps("3.3.0", None).supports(Feature::AttentionSet));
+        assert_eq!(
+            caps("3.2.14", None)
+                .require(Feature::AttentionSet)
+                .unwrap_err()
+                .to_string(),
+            "attention sets require Gerrit >= 3.3 (server runs 3.2.14)"
+        );
+    }
+
+    #[test]
+    fn hashtags_need_notedb_on_2x() {
+        assert!(caps("2.16.28", Some(true)).supports(Feature::Hashtags));
+        assert!(caps("2.16.28", None).supports(Feature::Hashtags));
+        let reviewdb = caps("2.16.28", Some(false));
+        assert!(!reviewdb.supports(Feature::Hashtags));
+        assert!(reviewdb
+            .require(Feature::Hashtags)
+            .unwrap_err()
+            .to_string()
+            .contains("NoteDb"));
+        assert!(caps("3.9.1", Some(false)).supports(Feature::Hashtags));
+    }
+
+    #[test]
+    fn unparseable_version_supports_everything() {
+        let dev = caps("unknown-build", None);
+        assert!(dev.supports(Feature::Hashtags));
+        assert!(dev.require(Feature::WorkInProgress).is_ok());
+    }
+
+    #[test]
+    fn freshness() {
+        let c = caps("3.9.1", None);
+        assert!(c.is_fresh(1_000 + CAPABILITIES_TTL_SECS - 1));
+        assert!(!c.is_fresh(1_000 + CAPABILITIES_TTL_SECS));
+    }
+}
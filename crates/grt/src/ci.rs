@@ -0,0 +1,465 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! CI results from change messages: which CI system reported what on a
+//! patchset, and the per-job verdict blocks Zuul and Jenkins post.
+
+use serde::Serialize;
+
+use crate::gerrit::{ChangeInfo, ChangeMessageInfo};
+use crate::reviewers::account_label;
+
+/// Outcome of a CI report or job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CiResult {
+    Passed,
+    Failed,
+    Skipped,
+    Unknown,
+}
+
+/// One job from a CI verdict block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CiJob {
+    pub name: String,
+    pub result: CiResult,
+    /// Result as reported, e.g. `SUCCESS` or `POST_FAILURE`.
+    pub status: String,
+    pub url: Option<String>,
+    pub duration: Option<String>,
+    /// False for jobs marked `(non-voting)`.
+    pub voting: bool,
+}
+
+/// Latest report from one CI system (author and message tag) on the current patchset.
+#[derive(Debug, Serialize)]
+pub struct CiReport {
+    pub author: String,
+    pub tag: Option<String>,
+    pub result: CiResult,
+    pub date: Option<String>,
+    /// First line of the message after the `Patch Set N:` header.
+    pub summary: String,
+    pub jobs: Vec<CiJob>,
+}
+
+/// Verdict lines that Jenkins and Zuul put under the `Patch Set N:` header.
+const BUILD_VERDICTS: &[&str] = &[
+    "build successful",
+    "build succeeded",
+    "build failed",
+    "build unstable",
+    "build aborted",
+    "build started",
+];
+
+/// Whether a change message was posted by an automated system.
+///
+/// Gerrit tags its own messages `autogenerated:gerrit:...`; everything else
+/// tagged `autogenerated:` comes from CI (Zuul, Jenkins, ...). Untagged
+/// messages, as posted by a Jenkins Gerrit Trigger without a message tag,
+/// count when their author is a Gerrit service user or they carry a build
+/// verdict line such as `Build Successful`.
+pub fn is_ci_message(message: &ChangeMessageInfo) -> bool {
+    match message.tag.as_deref().filter(|t| !t.is_empty()) {
+        Some(tag) => tag.starts_with("autogenerated:") && !tag.starts_with("autogenerated:gerrit:"),
+        None => {
+            message.author.as_ref().is_some_and(|a| {
+                a.tags
+                    .as_ref()
+                    .is_some_and(|tags| tags.iter().any(|t| t == "SERVICE_USER"))
+            }) || message.message.as_deref().is_some_and(has_build_verdict)
+        }
+    }
+}
+
+/// Whether a line of `text` is a verdict such as `Build Failed` or
+/// `Build succeeded (check pipeline).`, rather than prose mentioning a build.
+fn has_build_verdict(text: &str) -> bool {
+    text.lines().skip(1).any(|line| {
+        let line = line.trim().trim_end_matches('.').to_ascii_lowercase();
+        BUILD_VERDICTS.iter().any(|verdict| {
+            line.strip_prefix(verdict)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(" ("))
+        })
+    })
+}
+
+/// Classify a CI message by its headline, falling back to its Verified vote.
+pub fn classify_ci_message(text: &str) -> CiResult {
+    let lower = text.to_ascii_lowercase();
+    if lower.contains("build failed") || lower.contains("merge failed") {
+        CiResult::Failed
+    } else if lower.contains("build succeeded") || lower.contains("build successful") {
+        CiResult::Passed
+    } else if lower.contains("verified-") {
+        CiResult::Failed
+    } else if lower.contains("verified+") {
+        CiResult::Passed
+    } else {
+        CiResult::Unknown
+    }
+}
+
+/// Parse the job lines of a CI message.
+///
+/// Zuul posts `- <job> <url> : <RESULT> in <duration>`, optionally followed by
+/// `(non-voting)`; the Jenkins Gerrit Trigger posts `<url> : <RESULT>`, where
+/// the job name is taken from the URL. Other lines are ignored.
+pub fn parse_jobs(text: &str) -> Vec<CiJob> {
+    text.lines().filter_map(parse_job_line).collect()
+}
+
+fn parse_job_line(line: &str) -> Option<CiJob> {
+    let line = line.trim();
+    let line = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .unwrap_or(line);
+    let (left, right) = line.rsplit_once(" : ")?;
+
+    let right = right.trim();
+    let (status, rest) = right.split_once(' ').unwrap_or((right, ""));
+    if status.is_empty() || !status.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+        return None;
+    }
+    let duration = rest
+        .trim()
+        .strip_prefix("in ")
+        .map(|d| d.split(" (").next().unwrap_or(d).trim().to_string())
+        .filter(|d| !d.is_empty());
+
+    let mut words = left.split_whitespace();
+    let first = words.next()?;
+    let second = words.next();
+    if words.next().is_some() {
+        return None;
+    }
+    let (name, url) = match second {
+        Some(url) if is_url(url) => (first.to_string(), Some(url.to_string())),
+        Some(_) => return None,
+        None if is_url(first) => (jenkins_job_name(first), Some(first.to_string())),
+        None => (first.to_string(), None),
+    };
+
+    Some(CiJob {
+        name,
+        result: job_result(status),
+        status: status.to_string(),
+        url,
+        duration,
+        voting: !rest.contains("(non-voting)"),
+    })
+}
+
+fn is_url(word: &str) -> bool {
+    word.starts_with("https://") || word.starts_with("http://")
+}
+
+/// Job name from a Jenkins build URL: the segment after the last `job/`
+/// (`.../job/folder/job/name/12/` gives `name`), else the last non-numeric segment.
+fn jenkins_job_name(url: &str) -> String {
+    let segments: Vec<&str> = url.split('/').skip(3).filter(|s| !s.is_empty()).collect();
+    segments
+        .iter()
+        .rposition(|s| *s == "job")
+        .and_then(|i| segments.get(i + 1))
+        .or_else(|| {
+            segments
+                .iter()
+                .rev()
+                .find(|s| !s.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map_or_else(|| url.to_string(), |s| s.to_string())
+}
+
+/// Map a Zuul or Jenkins job result to an outcome.
+fn job_result(status: &str) -> CiResult {
+    match status {
+        "SUCCESS" => CiResult::Passed,
+        "SKIPPED" | "CANCELED" | "ABORTED" | "NOT_BUILT" => CiResult::Skipped,
+        "TIMED_OUT" | "RETRY_LIMIT" | "ERROR" | "LOST" | "UNSTABLE" | "MERGE_CONFLICT"
+        | "DISK_FULL" => CiResult::Failed,
+        _ if status.contains("FAILURE") => CiResult::Failed,
+        _ => CiResult::Unknown,
+    }
+}
+
+/// The latest CI message from each author and tag on `patchset`, in posting order.
+pub fn latest_reports(messages: &[ChangeMessageInfo], patchset: Option<i32>) -> Vec<CiReport> {
+    let mut latest: Vec<CiReport> = Vec::new();
+    let on_patchset = messages
+        .iter()
+        .filter(|m| patchset.is_none() || m.revision_number == patchset)
+        .filter(|m| is_ci_message(m));
+    for message in on_patchset {
+        let text = message.message.as_deref().unwrap_or("");
+        let jobs = parse_jobs(text);
+        // Without a recognizable headline, the voting jobs decide
+        let result = match classify_ci_message(text) {
+            CiResult::Unknown if jobs.iter().any(|j| j.voting) => {
+                if jobs
+                    .iter()
+                    .any(|j| j.voting && j.result == CiResult::Failed)
+                {
+                    CiResult::Failed
+                } else {
+                    CiResult::Passed
+                }
+            }
+            result => result,
+        };
+        let report = CiReport {
+            author: message
+                .author
+                .as_ref()
+                .map_or_else(|| "unknown".to_string(), account_label),
+            tag: message.tag.clone(),
+            result,
+            date: message.date.clone(),
+            summary: summary_line(text),
+            jobs,
+        };
+        latest.retain(|r| !(r.author == report.author && r.tag == report.tag));
+        latest.push(report);
+    }
+    latest
+}
+
+/// CI reports on the current patchset of a listed change.
+///
+/// Empty unless the change was fetched with its messages (`o=MESSAGES`).
+pub fn change_reports(change: &ChangeInfo) -> Vec<CiReport> {
+    let Some(messages) = change.messages.as_deref() else {
+        return Vec::new();
+    };
+    let patchset = change
+        .current_revision
+        .as_ref()
+        .and_then(|rev| change.revisions.as_ref()?.get(rev)?.number);
+    latest_reports(messages, patchset)
+}
+
+/// Marker for a job or report outcome in text output.
+pub fn result_mark(result: CiResult) -> &'static str {
+    match result {
+        CiResult::Passed => "✔",
+        CiResult::Failed => "✘",
+        CiResult::Skipped => "-",
+        CiResult::Unknown => "?",
+    }
+}
+
+/// One-cell summary of `reports` for list output.
+///
+/// `✘ <job>,<job>` names the failing voting jobs, `✘` or `✔` alone give the
+/// verdict when no jobs were listed or none failed, and `-` means no CI has
+/// reported yet.
+pub fn compact_summary(reports: &[CiReport]) -> String {
+    let failing: Vec<&str> = reports
+        .iter()
+        .flat_map(|r| &r.jobs)
+        .filter(|j| j.voting && j.result == CiResult::Failed)
+        .map(|j| j.name.as_str())
+        .collect();
+    if !failing.is_empty() {
+        format!("{} {}", result_mark(CiResult::Failed), failing.join(","))
+    } else if reports.iter().any(|r| r.result == CiResult::Failed) {
+        result_mark(CiResult::Failed).to_string()
+    } else if reports.iter().any(|r| r.result == CiResult::Passed) {
+        result_mark(CiResult::Passed).to_string()
+    } else {
+        "-".to_string()
+    }
+}
+
+/// First meaningful line of a change message, skipping the `Patch Set N:` header.
+fn summary_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("Patch Set "))
+        .or_else(|| text.lines().map(str::trim).find(|l| !l.is_empty()))
+        .unwrap_or("")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZUUL: &str = "Patch Set 2: Verified-1\n\n\
+        Build failed (check pipeline).  For information on how to proceed, see\n\
+        https://docs.example.org/ci\n\n\
+        https://zuul.example.org/t/main/buildset/abc\n\n\
+        - tox-pep8 https://zuul.example.org/t/main/build/1 : SUCCESS in 3m 12s\n\
+        - tox-py312 https://zuul.example.org/t/main/build/2 : FAILURE in 10m 02s\n\
+        - tempest-full https://zuul.example.org/t/main/build/3 : POST_FAILURE in 1h 02m 03s (non-voting)\n\
+        - docs-build : SKIPPED\n";
+
+    fn message(author: &str, tag: &str, text: &str, ps: i32) -> ChangeMessageInfo {
+        serde_json::from_value(serde_json::json!({
+            "author": {"name": author},
+            "tag": tag,
+            "message": text,
+            "_revision_number": ps
+        }))
+        .unwrap()
+    }
+
+    // === classification ===
+
+    #[test]
+    fn classify_ci_messages() {
+        assert_eq!(
+            classify_ci_message("Patch Set 3: Verified+1\n\nBuild succeeded (gate pipeline)."),
+            CiResult::Passed
+        );
+        assert_eq!(
+            classify_ci_message("Patch Set 3: Verified-2\n\nBuild failed (gate pipeline)."),
+            CiResult::Failed
+        );
+        assert_eq!(
+            classify_ci_message(
+                "Patch Set 3:\n\nMerge Failed.\n\nThis change could not be merged."
+            ),
+            CiResult::Failed
+        );
+        assert_eq!(
+            classify_ci_message("Patch Set 3: Verified+1"),
+            CiResult::Passed
+        );
+        assert_eq!(
+            classify_ci_message("Starting check jobs."),
+            CiResult::Unknown
+        );
+    }
+
+    // === job parsing ===
+
+    #[test]
+    fn parse_zuul_jobs() {
+        let jobs = parse_jobs(ZUUL);
+        assert_eq!(jobs.len(), 4);
+        assert_eq!(jobs[0].name, "tox-pep8");
+        assert_eq!(jobs[0].result, CiResult::Passed);
+        assert_eq!(
+            jobs[0].url.as_deref(),
+            Some("https://zuul.example.org/t/main/build/1")
+        );
+        assert_eq!(jobs[0].duration.as_deref(), Some("3m 12s"));
+        assert_eq!(jobs[1].result, CiResult::Failed);
+        assert!(jobs[1].voting);
+        assert_eq!(jobs[2].status, "POST_FAILURE");
+        assert_eq!(jobs[2].duration.as_deref(), Some("1h 02m 03s"));
+        assert!(!jobs[2].voting);
+        assert_eq!(jobs[3].name, "docs-build");
+        assert_eq!(jobs[3].result, CiResult::Skipped);
+        assert_eq!(jobs[3].url, None);
+    }
+
+    #[test]
+    fn parse_jenkins_jobs() {
+        let text = "Patch Set 1: Verified-1\n\nBuild Failed\n\n\
+            https://ci.example.org/job/unit-tests/42/ : FAILURE\n\
+            https://ci.example.org/job/team/job/lint/7/ : SUCCESS\n";
+        let jobs = parse_jobs(text);
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].name, "unit-tests");
+        assert_eq!(jobs[0].result, CiResult::Failed);
+        assert_eq!(jobs[1].name, "lint");
+        assert_eq!(jobs[1].duration, None);
+    }
+
+    #[test]
+    fn parse_jobs_ignores_prose() {
+        assert!(parse_jobs("Patch Set 1:\n\nNote : see the docs\n- a b c : SUCCESS").is_empty());
+    }
+
+    // === reports ===
+
+    #[test]
+    fn latest_reports_keep_newest_per_system() {
+        let messages = vec![
+            message(
+                "Zuul",
+                "autogenerated:zuul:check",
+                "Patch Set 1: Verified+1\n\nBuild succeeded (check pipeline).",
+                1,
+            ),
+            message("Zuul", "autogenerated:zuul:check", ZUUL, 2),
+            message("Alice", "", "Patch Set 2: Code-Review+1", 2),
+        ];
+        let reports = latest_reports(&messages, Some(2));
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].result, CiResult::Failed);
+        assert_eq!(
+            reports[0].summary,
+            "Build failed (check pipeline).  For information on how to proceed, see"
+        );
+        assert_eq!(reports[0].jobs.len(), 4);
+    }
+
+    #[test]
+    fn untagged_jenkins_message_is_ci() {
+        let text = "Patch Set 1: Verified+1\n\nBuild Successful \n\n\
+            https://ci.example.org/job/unit-tests/42/ : SUCCESS\n";
+        let messages = vec![
+            message("Jenkins", "", text, 1),
+            message(
+                "Alice",
+                "",
+                "Patch Set 1:\n\nBuild failed for me locally, see below",
+                1,
+            ),
+        ];
+        assert!(is_ci_message(&messages[0]));
+        assert!(!is_ci_message(&messages[1]));
+        let reports = latest_reports(&messages, Some(1));
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].author, "Jenkins");
+        assert_eq!(reports[0].result, CiResult::Passed);
+        assert_eq!(reports[0].jobs[0].name, "unit-tests");
+    }
+
+    #[test]
+    fn untagged_message_from_service_user_is_ci() {
+        let bot: ChangeMessageInfo = serde_json::from_value(serde_json::json!({
+            "author": {"name": "CI Bot", "tags": ["SERVICE_USER"]},
+            "message": "Patch Set 1: Verified-1",
+            "_revision_number": 1
+        }))
+        .unwrap();
+        assert!(is_ci_message(&bot));
+        assert!(!is_ci_message(&message(
+            "Alice",
+            "",
+            "Patch Set 1: Verified-1",
+            1
+        )));
+    }
+
+    #[test]
+    fn jobs_decide_result_without_headline() {
+        let text = "Patch Set 1:\n\n- lint https://ci.example.org/1 : SUCCESS in 1m\n";
+        let reports = latest_reports(&[message("CI", "autogenerated:ci", text, 1)], Some(1));
+        assert_eq!(reports[0].result, CiResult::Passed);
+    }
+
+    #[test]
+    fn compact_summary_names_failing_voting_jobs() {
+        let reports = latest_reports(
+            &[message("Zuul", "autogenerated:zuul:check", ZUUL, 2)],
+            None,
+        );
+        assert_eq!(compact_summary(&reports), "✘ tox-py312");
+        assert_eq!(compact_summary(&[]), "-");
+
+        let passed = "Patch Set 2: Verified+1\n\nBuild succeeded (check pipeline).";
+        let reports = latest_reports(
+            &[message("Zuul", "autogenerated:zuul:check", passed, 2)],
+            None,
+        );
+        assert_eq!(compact_summary(&reports), "✔");
+    }
+}
@@ -4,9 +4,41 @@
 use std::collections::HashMap;
 use std::fmt::Write as _;
 
+use anyhow::Result;
 use serde::Serialize;
 
-use crate::gerrit::{ChangeInfo, ChangeMessageInfo, CommentInfo};
+use crate::gerrit::{ChangeInfo, ChangeMessageInfo, CommentInfo, CommentInput};
+use crate::review::OutputFormat;
+use crate::ui::Style;
+
+/// Output format for `grt comments`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CommentsFormat {
+    /// Plain text, easy for humans and LLMs to read
+    Text,
+    /// Structured JSON
+    Json,
+    /// GitHub-flavored Markdown, for issues and docs
+    Markdown,
+    /// SARIF 2.1.0, for code-scanning tools and editors
+    Sarif,
+    /// `file:line: message` lines for Vim's quickfix and Emacs compilation mode
+    Quickfix,
+    /// reviewdog Diagnostic Format (rdjson)
+    Rdjson,
+    /// Commit message, unresolved threads with code context, and the diff,
+    /// as one bundle to paste into an LLM
+    Prompt,
+}
+
+impl From<OutputFormat> for CommentsFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Text => CommentsFormat::Text,
+            OutputFormat::Json => CommentsFormat::Json,
+        }
+    }
+}
 
 /// A thread of comments on a single location in a file.
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
@@ -26,6 +58,9 @@ pub struct ThreadComment {
     pub patch_set: Option<i32>,
     pub date: String,
     pub message: String,
+    /// Bot that posted a robot comment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub robot_id: Option<String>,
 }
 
 /// Structured output for JSON format.
@@ -108,21 +143,12 @@ pub fn build_threads(comments_by_file: &HashMap<String, Vec<CommentInfo>>) -> Ve
         collect_thread(root, &children, &mut thread_comments);
 
         // Thread is resolved if the last comment has unresolved: false
-        let resolved = thread_comments.last().map(|c| !c.4).unwrap_or(false);
-
-        let comments: Vec<ThreadComment> = thread_comments
-            .into_iter()
-            .map(
-                |(author, email, account_id, ps, _unresolved, date, message)| ThreadComment {
-                    author,
-                    author_email: email,
-                    account_id,
-                    patch_set: ps,
-                    date,
-                    message,
-                },
-            )
-            .collect();
+        let resolved = thread_comments
+            .last()
+            .map(|(_, unresolved)| !unresolved)
+            .unwrap_or(false);
+
+        let comments: Vec<ThreadComment> = thread_comments.into_iter().map(|(c, _)| c).collect();
 
         threads.push(CommentThread {
             file: file.to_string(),
@@ -142,34 +168,27 @@ pub fn build_threads(comments_by_file: &HashMap<String, Vec<CommentInfo>>) -> Ve
     threads
 }
 
-/// Recursively collect comments in a thread, depth-first in chronological order.
-#[allow(clippy::type_complexity)]
+/// Recursively collect comments in a thread, depth-first in chronological
+/// order, each with whether it left the thread unresolved.
 fn collect_thread(
     comment: &CommentInfo,
     children: &HashMap<&str, Vec<&CommentInfo>>,
-    result: &mut Vec<(
-        String,
-        Option<String>,
-        Option<i64>,
-        Option<i32>,
-        bool,
-        String,
-        String,
-    )>,
+    result: &mut Vec<(ThreadComment, bool)>,
 ) {
-    let author = comment
-        .author
-        .as_ref()
-        .and_then(|a| a.name.clone())
-        .unwrap_or_else(|| "Unknown".to_string());
-    let email = comment.author.as_ref().and_then(|a| a.email.clone());
-    let account_id = comment.author.as_ref().and_then(|a| a.account_id);
-    let unresolved = comment.unresolved.unwrap_or(true);
-    let date = comment.updated.clone().unwrap_or_default();
-    let message = comment.message.clone().unwrap_or_default();
-    let ps = comment.patch_set;
-
-    result.push((author, email, account_id, ps, unresolved, date, message));
+    let thread_comment = ThreadComment {
+        author: comment
+            .author
+            .as_ref()
+            .and_then(|a| a.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string()),
+        author_email: comment.author.as_ref().and_then(|a| a.email.clone()),
+        account_id: comment.author.as_ref().and_then(|a| a.account_id),
+        patch_set: comment.patch_set,
+        date: comment.updated.clone().unwrap_or_default(),
+        message: comment.message.clone().unwrap_or_default(),
+        robot_id: comment.robot_id.clone(),
+    };
+    result.push((thread_comment, comment.unresolved.unwrap_or(true)));
 
     if let Some(id) = &comment.id {
         if let Some(replies) = children.get(id.as_str()) {
@@ -182,12 +201,50 @@ fn collect_thread(
     }
 }
 
+/// Web UI link to the file and line a thread is attached to.
+///
+/// Patchset-level threads have no file to point at, so they link to the
+/// patchset itself.
+pub fn thread_permalink(
+    gerrit_url: &str,
+    project: &str,
+    number: i64,
+    thread: &CommentThread,
+) -> String {
+    let mut url = format!(
+        "{}/c/{}/+/{}",
+        gerrit_url.trim_end_matches('/'),
+        project,
+        number
+    );
+    if let Some(ps) = thread.comments.first().and_then(|c| c.patch_set) {
+        let _ = write!(url, "/{ps}");
+        if thread.file != "/PATCHSET_LEVEL" {
+            let file: Vec<_> = thread
+                .file
+                .split('/')
+                .map(|segment| urlencoding::encode(segment).into_owned())
+                .collect();
+            let _ = write!(url, "/{}", file.join("/"));
+            if let Some(line) = thread.line {
+                let _ = write!(url, "#{line}");
+            }
+        }
+    }
+    url
+}
+
 /// Format threads and change info as LLM-friendly text.
+///
+/// With an enabled `style`, headings and thread states are colored and each
+/// thread's permalink is rendered as a short OSC 8 link; otherwise the full
+/// URL is printed.
 pub fn format_text(
     change: &ChangeInfo,
     messages: &[ChangeMessageInfo],
     threads: &[CommentThread],
     gerrit_url: &str,
+    style: Style,
 ) -> String {
     let mut out = String::new();
 
@@ -207,7 +264,11 @@ pub fn format_text(
         .and_then(|o| o.email.as_deref())
         .unwrap_or("");
 
-    let _ = writeln!(out, "# Change {number} — {subject}");
+    let _ = writeln!(
+        out,
+        "{}",
+        style.bold(&format!("# Change {number} — {subject}"))
+    );
     let _ = writeln!(
         out,
         "# Project: {project} | Branch: {branch} | Status: {status}"
@@ -241,7 +302,7 @@ pub fn format_text(
             let date = msg.date.as_deref().unwrap_or("");
             let body = msg.message.as_deref().unwrap_or("");
 
-            let _ = writeln!(out, "\n### {author} ({ps}) — {date}");
+            let _ = writeln!(out, "\n### {} ({ps}) — {date}", style.cyan(author));
             let _ = writeln!(out, "{body}");
         }
     }
@@ -254,7 +315,11 @@ pub fn format_text(
         for thread in threads {
             if thread.file != current_file {
                 current_file = &thread.file;
-                let _ = writeln!(out, "\n### File: {current_file}");
+                let _ = writeln!(
+                    out,
+                    "\n{}",
+                    style.bold(&format!("### File: {current_file}"))
+                );
             }
 
             let line_str = thread
@@ -262,20 +327,28 @@ pub fn format_text(
                 .map(|l| format!("Line {l}"))
                 .unwrap_or_else(|| "File-level".to_string());
             let status = if thread.resolved {
-                "RESOLVED"
+                style.green("RESOLVED")
             } else {
-                "UNRESOLVED"
+                style.yellow("UNRESOLVED")
             };
             let count = thread.comments.len();
+            let permalink = thread_permalink(gerrit_url, project, number, thread);
+            let link = style.link(&permalink, "open");
             let _ = writeln!(
                 out,
-                "\n#### {line_str} [{status}] ({count} comment{})",
+                "\n#### {line_str} [{status}] ({count} comment{}) — {link}",
                 if count == 1 { "" } else { "s" }
             );
 
             for c in &thread.comments {
                 let ps_str = c.patch_set.map(|n| format!("PS{n}")).unwrap_or_default();
-                let _ = writeln!(out, "\n> **{}** ({}) — {}", c.author, ps_str, c.date);
+                let _ = writeln!(
+                    out,
+                    "\n> **{}** ({}) — {}",
+                    style.cyan(&c.author),
+                    ps_str,
+                    c.date
+                );
                 for line in c.message.lines() {
                     let _ = writeln!(out, "> {line}");
                 }
@@ -303,6 +376,17 @@ pub fn format_json(
     threads: &[CommentThread],
     gerrit_url: &str,
 ) -> serde_json::Value {
+    serde_json::to_value(comment_output(change, messages, threads, gerrit_url)).unwrap_or_default()
+}
+
+/// Collect change info, review messages and threads into the structure the
+/// JSON, Markdown and SARIF formats are rendered from.
+pub fn comment_output(
+    change: &ChangeInfo,
+    messages: &[ChangeMessageInfo],
+    threads: &[CommentThread],
+    gerrit_url: &str,
+) -> CommentOutput {
     let number = change.number.unwrap_or(0);
     let project = change.project.as_deref().unwrap_or("unknown");
 
@@ -324,7 +408,7 @@ pub fn format_json(
     let unresolved = threads.iter().filter(|t| !t.resolved).count();
     let resolved = threads.iter().filter(|t| t.resolved).count();
 
-    let output = CommentOutput {
+    CommentOutput {
         change: ChangeSummary {
             number,
             subject: change.subject.clone().unwrap_or_default(),
@@ -355,9 +439,170 @@ pub fn format_json(
             unresolved,
             resolved,
         },
+    }
+}
+
+/// Format one change's comments as GitHub-flavored Markdown, for pasting
+/// into issues and docs. Threads are task-list items, checked when resolved.
+pub fn format_markdown(output: &CommentOutput, gerrit_url: &str) -> String {
+    let change = &output.change;
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        "# [Change {}]({}): {}",
+        change.number, change.url, change.subject
+    );
+    let owner = if change.owner_email.is_empty() {
+        change.owner.clone()
+    } else {
+        format!("{} <{}>", change.owner, change.owner_email)
     };
+    let _ = writeln!(out, "\n| Project | Branch | Status | Owner |");
+    let _ = writeln!(out, "|---------|--------|--------|-------|");
+    let _ = writeln!(
+        out,
+        "| {} | {} | {} | {} |",
+        table_cell(&change.project),
+        table_cell(&change.branch),
+        table_cell(&change.status),
+        table_cell(&owner)
+    );
+
+    if !output.review_messages.is_empty() {
+        let _ = writeln!(out, "\n## Review Messages");
+        for msg in &output.review_messages {
+            let ps = msg
+                .patch_set
+                .map(|n| format!(" (Patchset {n})"))
+                .unwrap_or_default();
+            let _ = writeln!(out, "\n**{}**{ps} — {}\n", msg.author, msg.date);
+            write_quote(&mut out, "", &msg.message);
+        }
+    }
+
+    if !output.inline_comments.is_empty() {
+        let _ = writeln!(out, "\n## Inline Comments");
+        let mut current_file = "";
+        for thread in &output.inline_comments {
+            if thread.file != current_file {
+                current_file = &thread.file;
+                let _ = writeln!(out, "\n### `{current_file}`");
+            }
+            let check = if thread.resolved { 'x' } else { ' ' };
+            let location = thread
+                .line
+                .map(|l| format!("Line {l}"))
+                .unwrap_or_else(|| "File-level".to_string());
+            let link = thread_permalink(gerrit_url, &change.project, change.number, thread);
+            let _ = writeln!(out, "\n- [{check}] **{location}** ([open]({link}))");
+            for c in &thread.comments {
+                let ps = c.patch_set.map(|n| format!(" (PS{n})")).unwrap_or_default();
+                let _ = writeln!(out);
+                write_quote(
+                    &mut out,
+                    "  ",
+                    &format!("**{}**{ps} — {}\n\n{}", c.author, c.date, c.message),
+                );
+            }
+        }
+    }
 
-    serde_json::to_value(output).unwrap_or_default()
+    let summary = &output.summary;
+    let _ = writeln!(
+        out,
+        "\n**Summary:** {} thread{}, {} unresolved, {} resolved",
+        summary.total_threads,
+        if summary.total_threads == 1 { "" } else { "s" },
+        summary.unresolved,
+        summary.resolved
+    );
+    out
+}
+
+/// Escape a value for a Markdown table cell.
+fn table_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Write `text` as a Markdown blockquote, each line prefixed by `indent`.
+fn write_quote(out: &mut String, indent: &str, text: &str) {
+    for line in text.lines() {
+        if line.is_empty() {
+            let _ = writeln!(out, "{indent}>");
+        } else {
+            let _ = writeln!(out, "{indent}> {line}");
+        }
+    }
+}
+
+/// SARIF rule ID for threads opened by a human reviewer.
+pub const SARIF_REVIEW_RULE: &str = "gerrit-review-comment";
+
+/// Format comment threads as a SARIF 2.1.0 log, so code-scanning tools and
+/// editors can show them.
+///
+/// Each thread is one result: robot comments use the bot's ID as rule ID,
+/// other threads [`SARIF_REVIEW_RULE`]. Unresolved threads are warnings and
+/// resolved ones notes. Patchset-level and commit message threads have no
+/// file in the tree, so they carry no location.
+pub fn format_sarif(outputs: &[CommentOutput], gerrit_url: &str) -> serde_json::Value {
+    let mut rules: Vec<String> = Vec::new();
+    let mut results = Vec::new();
+    for output in outputs {
+        let change = &output.change;
+        for thread in &output.inline_comments {
+            let Some(root) = thread.comments.first() else {
+                continue;
+            };
+            let rule = root
+                .robot_id
+                .clone()
+                .unwrap_or_else(|| SARIF_REVIEW_RULE.to_string());
+            if !rules.contains(&rule) {
+                rules.push(rule.clone());
+            }
+            let mut text = root.message.clone();
+            for reply in &thread.comments[1..] {
+                let _ = write!(text, "\n\n{}: {}", reply.author, reply.message);
+            }
+            let mut result = serde_json::json!({
+                "ruleId": rule,
+                "level": if thread.resolved { "note" } else { "warning" },
+                "message": {"text": text},
+                "hostedViewerUri": thread_permalink(gerrit_url, &change.project, change.number, thread),
+                "properties": {
+                    "change": change.number,
+                    "patchSet": root.patch_set,
+                    "author": root.author,
+                    "resolved": thread.resolved,
+                },
+            });
+            if !thread.file.starts_with('/') {
+                let mut location = serde_json::json!({
+                    "physicalLocation": {"artifactLocation": {"uri": thread.file}},
+                });
+                if let Some(line) = thread.line.filter(|&l| l > 0) {
+                    location["physicalLocation"]["region"] = serde_json::json!({"startLine": line});
+                }
+                result["locations"] = serde_json::json!([location]);
+            }
+            results.push(result);
+        }
+    }
+    rules.sort();
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {"driver": {
+                "name": "Gerrit",
+                "informationUri": gerrit_url,
+                "rules": rules.iter().map(|id| serde_json::json!({"id": id})).collect::<Vec<_>>(),
+            }},
+            "results": results,
+        }],
+    })
 }
 
 /// Retain threads where at least one comment's author matches `pattern`
@@ -365,14 +610,107 @@ pub fn format_json(
 /// The full thread is preserved when matched.
 pub fn filter_threads_by_author(threads: &mut Vec<CommentThread>, pattern: &str) {
     let pat = pattern.to_lowercase();
+    threads.retain(|t| t.comments.iter().any(|c| written_by(c, &pat)));
+}
+
+/// Retain threads started by a user matching `pattern` (same matching as
+/// [`filter_threads_by_author`], but only the root comment counts).
+pub fn filter_threads_by_starter(threads: &mut Vec<CommentThread>, pattern: &str) {
+    let pat = pattern.to_lowercase();
+    threads.retain(|t| t.comments.first().is_some_and(|c| written_by(c, &pat)));
+}
+
+/// Whether `comment`'s author name or email contains the lower-cased `pat`.
+fn written_by(comment: &ThreadComment, pat: &str) -> bool {
+    comment.author.to_lowercase().contains(pat)
+        || comment
+            .author_email
+            .as_deref()
+            .unwrap_or("")
+            .to_lowercase()
+            .contains(pat)
+}
+
+/// Retain threads on files matching the glob `pattern`.
+///
+/// `*` and `?` do not cross `/`, `**` does. A pattern without `/` is also
+/// tried against the file name alone, so `*.py` matches `nova/api/x.py`.
+pub fn filter_threads_by_file(threads: &mut Vec<CommentThread>, pattern: &str) {
+    threads.retain(|t| file_matches(pattern, &t.file));
+}
+
+/// Whether `path` matches the glob `pattern`, see [`filter_threads_by_file`].
+pub fn file_matches(pattern: &str, path: &str) -> bool {
+    let pat: Vec<char> = pattern.chars().collect();
+    let full: Vec<char> = path.chars().collect();
+    if glob_match(&pat, &full) {
+        return true;
+    }
+    if pattern.contains('/') {
+        return false;
+    }
+    let name: Vec<char> = path.rsplit('/').next().unwrap_or(path).chars().collect();
+    glob_match(&pat, &name)
+}
+
+fn glob_match(pat: &[char], s: &[char]) -> bool {
+    match pat {
+        [] => s.is_empty(),
+        ['*', '*', rest @ ..] => {
+            let rest = rest.strip_prefix(&['/']).unwrap_or(rest);
+            (0..=s.len()).any(|i| glob_match(rest, &s[i..]))
+        }
+        ['*', rest @ ..] => (0..=s.len())
+            .take_while(|&i| i == 0 || s[i - 1] != '/')
+            .any(|i| glob_match(rest, &s[i..])),
+        ['?', rest @ ..] => s.first().is_some_and(|&c| c != '/') && glob_match(rest, &s[1..]),
+        [c, rest @ ..] => s.first() == Some(c) && glob_match(rest, &s[1..]),
+    }
+}
+
+/// Lower bound for `grt comments --since`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Since {
+    /// `YYYY-MM-DD`
+    Date(String),
+    /// A patchset number
+    Patchset(i32),
+}
+
+/// Parse a `--since` value: a patchset number (`3`, `ps3`) or a
+/// `YYYY-MM-DD` date.
+pub fn parse_since(value: &str) -> Result<Since> {
+    let value = value.trim();
+    let number = value
+        .strip_prefix("ps")
+        .or_else(|| value.strip_prefix("PS"))
+        .unwrap_or(value);
+    if let Ok(ps) = number.parse::<i32>() {
+        if ps < 1 {
+            anyhow::bail!("--since patchset must be 1 or greater");
+        }
+        return Ok(Since::Patchset(ps));
+    }
+    let bytes = value.as_bytes();
+    let is_date = bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if !is_date {
+        anyhow::bail!("--since takes a patchset number or a YYYY-MM-DD date, not '{value}'");
+    }
+    Ok(Since::Date(value.to_string()))
+}
+
+/// Retain threads with activity since `since`: a comment on that patchset
+/// or a later one, or a comment posted on or after that date. Unlike
+/// `--after`, replies count, so old threads with new answers stay.
+pub fn filter_threads_since(threads: &mut Vec<CommentThread>, since: &Since) {
     threads.retain(|t| {
-        t.comments.iter().any(|c| {
-            c.author.to_lowercase().contains(&pat)
-                || c.author_email
-                    .as_deref()
-                    .unwrap_or("")
-                    .to_lowercase()
-                    .contains(&pat)
+        t.comments.iter().any(|c| match since {
+            Since::Patchset(ps) => c.patch_set.is_some_and(|p| p >= *ps),
+            Since::Date(date) => c.date.get(..10).unwrap_or(&c.date) >= date.as_str(),
         })
     });
 }
@@ -407,6 +745,196 @@ pub fn filter_threads_by_date(
     });
 }
 
+/// Threads that point at a file in the tree; patchset-level and commit
+/// message threads (`/PATCHSET_LEVEL`, `/COMMIT_MSG`) do not.
+fn on_tree_file(thread: &CommentThread) -> bool {
+    !thread.file.starts_with('/')
+}
+
+/// Format threads as `file:line: warning: author: message` lines for Vim's
+/// quickfix list and Emacs compilation mode, one per thread.
+///
+/// Unresolved threads are `warning`s and resolved ones `note`s. File-level
+/// threads point at line 1, and only the first line of the root comment is
+/// shown, with a count of replies. Paths are relative to the repository root.
+pub fn format_quickfix(outputs: &[CommentOutput]) -> String {
+    let mut out = String::new();
+    for thread in outputs
+        .iter()
+        .flat_map(|o| &o.inline_comments)
+        .filter(|t| on_tree_file(t))
+    {
+        let Some(root) = thread.comments.first() else {
+            continue;
+        };
+        let kind = if thread.resolved { "note" } else { "warning" };
+        let first_line = root.message.lines().next().unwrap_or("");
+        let _ = write!(
+            out,
+            "{}:{}: {kind}: {}: {first_line}",
+            thread.file,
+            thread.line.unwrap_or(1).max(1),
+            root.author
+        );
+        match thread.comments.len() - 1 {
+            0 => {}
+            1 => out.push_str(" (1 reply)"),
+            n => {
+                let _ = write!(out, " ({n} replies)");
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Lines of code shown on each side of a commented line by `--format prompt`.
+pub const PROMPT_CONTEXT_LINES: usize = 3;
+
+/// Numbered lines of `content` around 1-based `line`, the line itself
+/// marked with `>`. `None` if the line is not in the file.
+pub fn code_context(content: &str, line: i32, radius: usize) -> Option<String> {
+    let target = usize::try_from(line).ok().filter(|&l| l >= 1)?;
+    let lines: Vec<&str> = content.lines().collect();
+    if target > lines.len() {
+        return None;
+    }
+    let first = target.saturating_sub(radius).max(1);
+    let last = (target + radius).min(lines.len());
+    let width = last.to_string().len();
+    let mut out = String::new();
+    for n in first..=last {
+        let marker = if n == target { '>' } else { ' ' };
+        let _ = writeln!(out, "{marker} {n:width$} | {}", lines[n - 1]);
+    }
+    Some(out)
+}
+
+/// Patchset a thread was started on, as a Gerrit revision id.
+pub fn thread_revision(thread: &CommentThread) -> String {
+    thread
+        .comments
+        .first()
+        .and_then(|c| c.patch_set)
+        .map(|ps| ps.to_string())
+        .unwrap_or_else(|| "current".to_string())
+}
+
+/// Bundle a change for an LLM: the task, the commit message, each unresolved
+/// thread with the code it is on, and the change's diff.
+///
+/// `contexts` holds the [`code_context`] for each thread in `threads`, or
+/// `None` for file-level threads and files that could not be read.
+pub fn format_prompt(
+    change: &ChangeInfo,
+    commit_message: &str,
+    threads: &[CommentThread],
+    contexts: &[Option<String>],
+    patch: &str,
+    gerrit_url: &str,
+) -> String {
+    let mut out = String::new();
+    let number = change.number.unwrap_or(0);
+    let subject = change.subject.as_deref().unwrap_or("(no subject)");
+    let project = change.project.as_deref().unwrap_or("unknown");
+    let branch = change.branch.as_deref().unwrap_or("unknown");
+
+    let _ = writeln!(out, "# Change {number} — {subject}");
+    let _ = writeln!(out, "# Project: {project} | Branch: {branch}");
+    let _ = writeln!(
+        out,
+        "# URL: {}/c/{project}/+/{number}",
+        gerrit_url.trim_end_matches('/')
+    );
+
+    let _ = writeln!(out, "\n## Task");
+    let _ = writeln!(
+        out,
+        "Address the {} unresolved review comment thread(s) below. Each thread \
+         shows the code it was left on, with the commented line marked `>`. \
+         Reply with a unified diff against the change, and a short note for \
+         each thread explaining the fix or why no change is needed.",
+        threads.len()
+    );
+
+    let _ = writeln!(
+        out,
+        "\n## Commit Message\n\n```\n{}\n```",
+        commit_message.trim_end()
+    );
+
+    let _ = writeln!(out, "\n## Unresolved Comments");
+    if threads.is_empty() {
+        let _ = writeln!(out, "\nNone.");
+    }
+    for (i, thread) in threads.iter().enumerate() {
+        let location = match thread.line {
+            Some(line) => format!("{}:{line}", thread.file),
+            None => thread.file.clone(),
+        };
+        let ps = thread
+            .comments
+            .first()
+            .and_then(|c| c.patch_set)
+            .map(|n| format!(" (patchset {n})"))
+            .unwrap_or_default();
+        let _ = writeln!(out, "\n### {}. {location}{ps}", i + 1);
+        if let Some(Some(context)) = contexts.get(i) {
+            let _ = writeln!(out, "\n```\n{}```", context);
+        }
+        for c in &thread.comments {
+            let _ = writeln!(out, "\n**{}**:", c.author);
+            for line in c.message.lines() {
+                let _ = writeln!(out, "> {line}");
+            }
+        }
+    }
+
+    let _ = writeln!(out, "\n## Diff\n\n```diff\n{}\n```", patch.trim_end());
+    out
+}
+
+/// Format threads in reviewdog's Diagnostic Format, for
+/// `reviewdog -f=rdjson`.
+///
+/// Each thread on a file is one diagnostic carrying the whole conversation;
+/// robot comments name the bot as their source and the thread's permalink
+/// is the diagnostic's code URL.
+pub fn format_rdjson(outputs: &[CommentOutput], gerrit_url: &str) -> serde_json::Value {
+    let mut diagnostics = Vec::new();
+    for output in outputs {
+        let change = &output.change;
+        for thread in output.inline_comments.iter().filter(|t| on_tree_file(t)) {
+            let Some(root) = thread.comments.first() else {
+                continue;
+            };
+            let mut message = format!("{}: {}", root.author, root.message);
+            for reply in &thread.comments[1..] {
+                let _ = write!(message, "\n\n{}: {}", reply.author, reply.message);
+            }
+            let mut location = serde_json::json!({"path": thread.file});
+            if let Some(line) = thread.line.filter(|&l| l > 0) {
+                location["range"] = serde_json::json!({"start": {"line": line}});
+            }
+            let source = root.robot_id.as_deref().unwrap_or("gerrit");
+            diagnostics.push(serde_json::json!({
+                "message": message,
+                "location": location,
+                "severity": if thread.resolved { "INFO" } else { "WARNING" },
+                "source": {"name": source},
+                "code": {
+                    "value": format!("{}", change.number),
+                    "url": thread_permalink(gerrit_url, &change.project, change.number, thread),
+                },
+            }));
+        }
+    }
+    serde_json::json!({
+        "source": {"name": "gerrit", "url": gerrit_url},
+        "diagnostics": diagnostics,
+    })
+}
+
 /// Format multiple change comment outputs as a JSON object with a "changes" array.
 pub fn format_json_multi(outputs: &[CommentOutput]) -> serde_json::Value {
     serde_json::json!({"changes": outputs})
@@ -420,14 +948,66 @@ pub fn format_text_multi(
         &[CommentThread],
     )],
     gerrit_url: &str,
+    style: Style,
 ) -> String {
     changes
         .iter()
-        .map(|(change, messages, threads)| format_text(change, messages, threads, gerrit_url))
+        .map(|(change, messages, threads)| {
+            format_text(change, messages, threads, gerrit_url, style)
+        })
         .collect::<Vec<_>>()
         .join("\n---\n\n")
 }
 
+/// Build a draft comment from `grt comments reply` arguments.
+///
+/// `resolved` is `Some(true)` for `--resolved`, `Some(false)` for
+/// `--unresolved`, and `None` to let Gerrit decide.
+pub fn draft_comment(
+    path: &str,
+    line: Option<u32>,
+    message: &str,
+    in_reply_to: Option<&str>,
+    resolved: Option<bool>,
+) -> Result<CommentInput> {
+    if path.trim().is_empty() {
+        anyhow::bail!("--file must not be empty");
+    }
+    if message.trim().is_empty() {
+        anyhow::bail!("comment message must not be empty");
+    }
+    let line = match line {
+        Some(0) => anyhow::bail!("--line starts at 1; omit it for a file-level comment"),
+        Some(n) => Some(i32::try_from(n).map_err(|_| anyhow::anyhow!("--line {n} is too large"))?),
+        None => None,
+    };
+    Ok(CommentInput {
+        path: path.to_string(),
+        line,
+        message: message.to_string(),
+        in_reply_to: in_reply_to.map(str::to_string),
+        unresolved: resolved.map(|r| !r),
+    })
+}
+
+/// Find a comment by id, returning its file and line.
+pub fn find_comment(
+    comments_by_file: &HashMap<String, Vec<CommentInfo>>,
+    id: &str,
+) -> Option<(String, Option<i32>)> {
+    comments_by_file.iter().find_map(|(file, comments)| {
+        comments
+            .iter()
+            .find(|c| c.id.as_deref() == Some(id))
+            .map(|c| (file.clone(), c.line))
+    })
+}
+
+/// Count draft comments across all files.
+pub fn count_drafts(drafts: &HashMap<String, Vec<CommentInfo>>) -> usize {
+    drafts.values().map(Vec::len).sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -514,9 +1094,12 @@ mod tests {
                         email: self.author_email,
                         username: None,
                         display_name: None,
+                        tags: None,
                     }),
                     patch_set: self.ps,
                     unresolved: self.unresolved,
+                    robot_id: None,
+                    fix_suggestions: None,
                 },
             )
         }
@@ -719,6 +1302,8 @@ mod tests {
             insertions: None,
             deletions: None,
             labels: None,
+            attention_set: None,
+            more_changes: None,
         }
     }
 
@@ -743,11 +1328,18 @@ mod tests {
                 email: Some("alice@example.com".into()),
                 username: None,
                 display_name: None,
+                tags: None,
             }),
             ..test_change(12345)
         };
 
-        let text = format_text(&change, &[], &threads, "https://review.example.com");
+        let text = format_text(
+            &change,
+            &[],
+            &threads,
+            "https://review.example.com",
+            Style::plain(),
+        );
         assert!(text.contains("# Change 12345"));
         assert!(text.contains("UNRESOLVED"));
         assert!(text.contains("Bob"));
@@ -777,7 +1369,13 @@ mod tests {
     #[test]
     fn format_text_no_comments() {
         let change = test_change(1);
-        let text = format_text(&change, &[], &[], "https://review.example.com");
+        let text = format_text(
+            &change,
+            &[],
+            &[],
+            "https://review.example.com",
+            Style::plain(),
+        );
         assert!(text.contains("Total inline comment threads: 0"));
     }
 
@@ -791,6 +1389,193 @@ mod tests {
         assert_eq!(output.summary.total_threads, 1);
     }
 
+    #[test]
+    fn format_markdown_renders_threads_as_tasks() {
+        let items = vec![
+            comment("c1", "src/a.rs")
+                .line(12)
+                .author("Alice")
+                .ps(2)
+                .build(),
+            comment("c2", "src/a.rs")
+                .line(12)
+                .author("Bob")
+                .message("Done\n\nThanks")
+                .reply_to("c1")
+                .unresolved(false)
+                .build(),
+        ];
+        let threads = build_threads(&comments_map(items));
+        let output = comment_output(&test_change(42), &[], &threads, "https://r.example.com");
+        let md = format_markdown(&output, "https://r.example.com");
+        assert_eq!(
+            md,
+            "# [Change 42](https://r.example.com/c/proj/+/42): Test\n\
+             \n\
+             | Project | Branch | Status | Owner |\n\
+             |---------|--------|--------|-------|\n\
+             | proj | main | NEW |  |\n\
+             \n\
+             ## Inline Comments\n\
+             \n\
+             ### `src/a.rs`\n\
+             \n\
+             - [x] **Line 12** ([open](https://r.example.com/c/proj/+/42/2/src/a.rs#12))\n\
+             \n  > **Alice** (PS2) — 2025-02-10 14:00:00\n  >\n  > Comment\n\
+             \n  > **Bob** (PS1) — 2025-02-10 14:00:00\n  >\n  > Done\n  >\n  > Thanks\n\
+             \n\
+             **Summary:** 1 thread, 0 unresolved, 1 resolved\n"
+        );
+    }
+
+    #[test]
+    fn markdown_table_cells_are_escaped() {
+        assert_eq!(table_cell("a|b\nc"), "a\\|b c");
+    }
+
+    #[test]
+    fn format_sarif_results() {
+        let (file, mut robot) = comment("r1", "src/a.rs").line(3).build();
+        robot.robot_id = Some("pylint".to_string());
+        let items = vec![
+            (file, robot),
+            comment("c1", "/PATCHSET_LEVEL")
+                .no_line()
+                .unresolved(false)
+                .build(),
+        ];
+        let threads = build_threads(&comments_map(items));
+        let output = comment_output(&test_change(42), &[], &threads, "https://r.example.com");
+        let sarif = format_sarif(&[output], "https://r.example.com");
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            serde_json::json!([{"id": SARIF_REVIEW_RULE}, {"id": "pylint"}])
+        );
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+
+        // Sorted by file: "/PATCHSET_LEVEL" comes first and has no location
+        assert_eq!(results[0]["ruleId"], SARIF_REVIEW_RULE);
+        assert_eq!(results[0]["level"], "note");
+        assert!(results[0].get("locations").is_none());
+
+        assert_eq!(results[1]["ruleId"], "pylint");
+        assert_eq!(results[1]["level"], "warning");
+        let location = &results[1]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/a.rs");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(results[1]["properties"]["change"], 42);
+    }
+
+    fn quickfix_outputs() -> Vec<CommentOutput> {
+        let (file, mut robot) = comment("r1", "src/b.rs").no_line().build();
+        robot.robot_id = Some("pylint".to_string());
+        let items = vec![
+            comment("c1", "src/a.rs")
+                .line(12)
+                .author("Alice")
+                .message("Rename this\nIt is unclear")
+                .build(),
+            comment("c2", "src/a.rs")
+                .line(12)
+                .author("Bob")
+                .reply_to("c1")
+                .unresolved(false)
+                .build(),
+            (file, robot),
+            comment("c3", "/COMMIT_MSG").line(3).build(),
+        ];
+        let threads = build_threads(&comments_map(items));
+        vec![comment_output(
+            &test_change(42),
+            &[],
+            &threads,
+            "https://r.example.com",
+        )]
+    }
+
+    #[test]
+    fn code_context_marks_line() {
+        let content = "one\ntwo\nthree\nfour\nfive\n";
+        assert_eq!(
+            code_context(content, 2, 1).unwrap(),
+            "  1 | one\n> 2 | two\n  3 | three\n"
+        );
+        assert_eq!(
+            code_context(content, 5, 2).unwrap(),
+            "  3 | three\n  4 | four\n> 5 | five\n"
+        );
+        assert!(code_context(content, 6, 2).is_none());
+        assert!(code_context(content, 0, 2).is_none());
+    }
+
+    #[test]
+    fn format_prompt_bundles_message_threads_and_diff() {
+        let items = vec![
+            comment("c1", "src/a.rs")
+                .line(2)
+                .author("Alice")
+                .message("Rename this")
+                .build(),
+            comment("c2", "src/b.rs").no_line().build(),
+        ];
+        let threads = build_threads(&comments_map(items));
+        assert_eq!(thread_revision(&threads[0]), "1");
+        let contexts = vec![code_context("fn a() {}\nlet x = 1;\n", 2, 1), None];
+        let prompt = format_prompt(
+            &test_change(42),
+            "Fix it\n\nChange-Id: Iabc\n",
+            &threads,
+            &contexts,
+            "diff --git a/src/a.rs b/src/a.rs\n",
+            "https://r.example.com",
+        );
+
+        assert!(prompt.contains("2 unresolved review comment thread(s)"));
+        assert!(prompt.contains("## Commit Message\n\n```\nFix it\n\nChange-Id: Iabc\n```"));
+        assert!(prompt.contains(
+            "### 1. src/a.rs:2 (patchset 1)\n\n```\n  1 | fn a() {}\n> 2 | let x = 1;\n```\n\n**Alice**:\n> Rename this\n"
+        ));
+        assert!(prompt.contains("### 2. src/b.rs (patchset 1)\n\n**Author**:"));
+        assert!(prompt.ends_with("## Diff\n\n```diff\ndiff --git a/src/a.rs b/src/a.rs\n```\n"));
+    }
+
+    #[test]
+    fn format_quickfix_lines() {
+        assert_eq!(
+            format_quickfix(&quickfix_outputs()),
+            "src/a.rs:12: note: Alice: Rename this (1 reply)\n\
+             src/b.rs:1: warning: Author: Comment\n"
+        );
+    }
+
+    #[test]
+    fn format_rdjson_diagnostics() {
+        let rdjson = format_rdjson(&quickfix_outputs(), "https://r.example.com");
+        assert_eq!(rdjson["source"]["name"], "gerrit");
+        let diagnostics = rdjson["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+
+        assert_eq!(diagnostics[0]["location"]["path"], "src/a.rs");
+        assert_eq!(diagnostics[0]["location"]["range"]["start"]["line"], 12);
+        assert_eq!(diagnostics[0]["severity"], "INFO");
+        assert_eq!(
+            diagnostics[0]["message"],
+            "Alice: Rename this\nIt is unclear\n\nBob: Comment"
+        );
+        assert_eq!(
+            diagnostics[0]["code"]["url"],
+            "https://r.example.com/c/proj/+/42/1/src/a.rs#12"
+        );
+
+        assert_eq!(diagnostics[1]["source"]["name"], "pylint");
+        assert_eq!(diagnostics[1]["severity"], "WARNING");
+        assert!(diagnostics[1]["location"].get("range").is_none());
+    }
+
     #[test]
     fn format_json_schema() {
         let change = test_change(1);
@@ -916,7 +1701,198 @@ mod tests {
                 (&change, &messages, &threads),
             ],
             "https://review.example.com",
+            Style::plain(),
         );
         assert!(result.contains("---"), "expected separator between changes");
     }
+
+    #[test]
+    fn thread_permalink_points_at_file_and_line() {
+        let items = vec![comment("c1", "src/main.rs").line(42).ps(3).build()];
+        let threads = build_threads(&comments_map(items));
+        assert_eq!(
+            thread_permalink("https://review.example.com/", "nova", 12345, &threads[0]),
+            "https://review.example.com/c/nova/+/12345/3/src/main.rs#42"
+        );
+    }
+
+    #[test]
+    fn thread_permalink_patchset_level() {
+        let items = vec![comment("c1", "/PATCHSET_LEVEL").ps(2).build()];
+        let threads = build_threads(&comments_map(items));
+        assert_eq!(
+            thread_permalink("https://review.example.com", "nova", 7, &threads[0]),
+            "https://review.example.com/c/nova/+/7/2"
+        );
+    }
+
+    #[test]
+    fn format_text_plain_permalink_without_hyperlinks() {
+        let items = vec![comment("c1", "f.rs").line(10).ps(1).build()];
+        let threads = build_threads(&comments_map(items));
+        let change = test_change(5);
+        let text = format_text(
+            &change,
+            &[],
+            &threads,
+            "https://review.example.com",
+            Style::plain(),
+        );
+        assert!(text.contains("/+/5/1/f.rs#10"), "{text}");
+        assert!(!text.contains('\x1b'), "{text}");
+    }
+
+    #[test]
+    fn format_text_osc8_permalink_with_hyperlinks() {
+        let items = vec![comment("c1", "f.rs").line(10).ps(1).build()];
+        let threads = build_threads(&comments_map(items));
+        let change = test_change(5);
+        let text = format_text(
+            &change,
+            &[],
+            &threads,
+            "https://review.example.com",
+            Style::new(true),
+        );
+        assert!(
+            text.contains("\x1b]8;;https://review.example.com/c/"),
+            "{text}"
+        );
+        assert!(text.contains("\x1b\\open\x1b]8;;\x1b\\"), "{text}");
+    }
+
+    #[test]
+    fn format_text_plain_style_has_no_sgr() {
+        let items = vec![comment("c1", "f.rs").line(10).ps(1).build()];
+        let threads = build_threads(&comments_map(items));
+        let change = test_change(5);
+        let plain = format_text(
+            &change,
+            &[],
+            &threads,
+            "https://review.example.com",
+            Style::plain(),
+        );
+        assert!(!plain.contains("\x1b["), "{plain}");
+        let colored = format_text(
+            &change,
+            &[],
+            &threads,
+            "https://review.example.com",
+            Style::new(true),
+        );
+        assert!(colored.contains("RESOLVED\x1b[0m"), "{colored}");
+        assert!(colored.contains("\x1b[1m# Change 5"), "{colored}");
+    }
+
+    // === draft comments ===
+
+    #[test]
+    fn draft_comment_line_and_resolution() {
+        let input = draft_comment("src/foo.rs", Some(42), "fixed", None, Some(true)).unwrap();
+        assert_eq!(input.path, "src/foo.rs");
+        assert_eq!(input.line, Some(42));
+        assert_eq!(input.unresolved, Some(false));
+
+        let reply = draft_comment("f.rs", None, "ack", Some("c1"), None).unwrap();
+        assert_eq!(reply.line, None);
+        assert_eq!(reply.in_reply_to.as_deref(), Some("c1"));
+        assert_eq!(reply.unresolved, None);
+    }
+
+    #[test]
+    fn draft_comment_rejects_bad_input() {
+        assert!(draft_comment("f.rs", Some(0), "x", None, None).is_err());
+        assert!(draft_comment("f.rs", Some(1), "  ", None, None).is_err());
+        assert!(draft_comment("", Some(1), "x", None, None).is_err());
+    }
+
+    #[test]
+    fn filter_threads_by_starter_ignores_replies() {
+        let items = vec![
+            comment("c1", "f.rs").author("Bob").build(),
+            comment("c2", "f.rs").author("Alice").reply_to("c1").build(),
+            comment("c3", "g.rs").author("Alice").build(),
+        ];
+        let mut threads = build_threads(&comments_map(items));
+        filter_threads_by_starter(&mut threads, "alice");
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].file, "g.rs");
+    }
+
+    #[test]
+    fn file_glob_matching() {
+        assert!(file_matches("*.py", "nova/api/x.py"));
+        assert!(file_matches("nova/api/*.py", "nova/api/x.py"));
+        assert!(!file_matches("nova/*.py", "nova/api/x.py"));
+        assert!(file_matches("nova/**/*.py", "nova/api/x.py"));
+        assert!(file_matches("nova/**/*.py", "nova/x.py"));
+        assert!(file_matches("**/test_?.py", "a/b/test_1.py"));
+        assert!(file_matches("/COMMIT_MSG", "/COMMIT_MSG"));
+        assert!(!file_matches("*.rs", "src/main.py"));
+    }
+
+    #[test]
+    fn filter_threads_by_file_keeps_matching_files() {
+        let items = vec![
+            comment("c1", "src/a.rs").build(),
+            comment("c2", "docs/a.md").build(),
+        ];
+        let mut threads = build_threads(&comments_map(items));
+        filter_threads_by_file(&mut threads, "src/**");
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].file, "src/a.rs");
+    }
+
+    #[test]
+    fn parse_since_values() {
+        assert_eq!(parse_since("3").unwrap(), Since::Patchset(3));
+        assert_eq!(parse_since("ps4").unwrap(), Since::Patchset(4));
+        assert_eq!(
+            parse_since("2025-02-01").unwrap(),
+            Since::Date("2025-02-01".to_string())
+        );
+        assert!(parse_since("0").is_err());
+        assert!(parse_since("yesterday").is_err());
+        assert!(parse_since("2025-2-1").is_err());
+    }
+
+    #[test]
+    fn filter_threads_since_patchset_counts_replies() {
+        let items = vec![
+            comment("c1", "a.rs").ps(1).build(),
+            comment("c2", "a.rs").ps(3).reply_to("c1").build(),
+            comment("c3", "b.rs").ps(2).build(),
+        ];
+        let mut threads = build_threads(&comments_map(items));
+        filter_threads_since(&mut threads, &Since::Patchset(3));
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].file, "a.rs");
+        assert_eq!(threads[0].comments.len(), 2);
+    }
+
+    #[test]
+    fn filter_threads_since_date() {
+        let items = vec![comment("c1", "a.rs").build()];
+        let mut threads = build_threads(&comments_map(items));
+        filter_threads_since(&mut threads, &Since::Date("2025-02-10".to_string()));
+        assert_eq!(threads.len(), 1);
+        filter_threads_since(&mut threads, &Since::Date("2025-02-11".to_string()));
+        assert!(threads.is_empty());
+    }
+
+    #[test]
+    fn find_comment_returns_file_and_line() {
+        let items = vec![
+            comment("c1", "a.rs").line(3).build(),
+            comment("c2", "b.rs").line(7).build(),
+        ];
+        let map = comments_map(items);
+        assert_eq!(
+            find_comment(&map, "c2"),
+            Some(("b.rs".to_string(), Some(7)))
+        );
+        assert_eq!(find_comment(&map, "nope"), None);
+        assert_eq!(count_drafts(&map), 2);
+    }
 }
@@ -2,13 +2,15 @@
 // Copyright (c) 2026 grt contributors
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
+use tracing::debug;
 use url::Url;
 
-use crate::gerrit::AuthType;
+use crate::gerrit::{AuthType, HttpOptions};
+use crate::review::OutputFormat;
 
 /// Configuration for connecting to a Gerrit instance.
 #[derive(Debug, Clone)]
@@ -20,18 +22,52 @@ pub struct GerritConfig {
     /// When `None`, the standard port for `scheme` is used (443 for https, 80 for http).
     pub http_port: Option<u16>,
     pub project: String,
-    pub branch: String,
+    /// Target branch set by a config layer or the CLI; `None` falls back
+    /// to `master` (see [`GerritConfig::branch`]).
+    pub branch: Option<String>,
     pub remote: String,
     pub scheme: String,
     pub default_rebase: bool,
     pub track: bool,
     pub notopic: bool,
     pub usepushurl: bool,
+    /// Send a GPG push certificate (`git push --signed`) on every push.
+    pub signed_push: bool,
     pub ssl_verify: bool,
     pub username: Option<String>,
+    /// Reviewers added to every push unless `--no-default-reviewers` is passed.
+    pub default_reviewers: Vec<String>,
+    /// CC recipients added to every push unless `--no-default-reviewers` is passed.
+    pub default_cc: Vec<String>,
+    /// HTTP transport tuning for the REST client.
+    pub http: HttpOptions,
+    /// Auth type from the repo's grt settings; overrides the one stored
+    /// with the credentials.
+    pub auth_type: Option<AuthType>,
+    /// Format for commands run without `--format`, from the repo's grt settings.
+    pub default_output_format: Option<OutputFormat>,
+    /// `auto`, `always` or `never`, from the repo's grt settings.
+    pub color: Option<String>,
+    /// Commit trailers whose value becomes the default topic, in order of
+    /// preference, from the repo's `topic_from_trailer` setting.
+    pub topic_trailers: Vec<String>,
+    /// Review message for pushes without `--message`, from the repo's
+    /// `push.message_template` setting.
+    pub message_template: Option<String>,
 }
 
 impl GerritConfig {
+    /// The configured target branch, or `master` when no layer set one.
+    pub fn branch(&self) -> &str {
+        self.branch.as_deref().unwrap_or("master")
+    }
+
+    /// The output format to use: `--format` if given, else the repo default, else text.
+    pub fn output_format(&self, flag: Option<OutputFormat>) -> OutputFormat {
+        flag.or_else(|| self.default_output_format.clone())
+            .unwrap_or(OutputFormat::Text)
+    }
+
     /// Construct the base URL for Gerrit REST API requests.
     ///
     /// Uses `http_port` if explicitly set, otherwise the standard port for the scheme.
@@ -85,6 +121,159 @@ impl GerritConfig {
 
         url
     }
+
+    /// Build a remote URL and apply the user's `insteadOf` rewrites to it.
+    ///
+    /// With `for_push`, `pushInsteadOf` rules take precedence, as for a
+    /// remote's push URL.
+    pub fn make_rewritten_remote_url(&self, rewrites: &UrlRewrites, for_push: bool) -> String {
+        alias_url(&self.make_remote_url(), rewrites, for_push)
+    }
+
+    /// Every setting as `(key, value)`, `None` when unset, in display order.
+    ///
+    /// Keys are the field names users see in `grt config show`.
+    pub fn entries(&self) -> Vec<(&'static str, Option<String>)> {
+        let text = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        let list = |v: &[String]| (!v.is_empty()).then(|| v.join(", "));
+        vec![
+            ("host", text(&self.host)),
+            ("project", text(&self.project)),
+            ("branch", Some(self.branch().to_string())),
+            ("remote", Some(self.remote.clone())),
+            ("scheme", Some(self.scheme.clone())),
+            ("ssh_port", self.ssh_port.map(|p| p.to_string())),
+            ("http_port", self.http_port.map(|p| p.to_string())),
+            ("username", self.username.clone()),
+            ("default_rebase", Some(self.default_rebase.to_string())),
+            ("track", Some(self.track.to_string())),
+            ("notopic", Some(self.notopic.to_string())),
+            ("usepushurl", Some(self.usepushurl.to_string())),
+            ("signed_push", Some(self.signed_push.to_string())),
+            ("default_reviewers", list(&self.default_reviewers)),
+            ("default_cc", list(&self.default_cc)),
+            ("ssl_verify", Some(self.ssl_verify.to_string())),
+            ("http1_only", Some(self.http.http1_only.to_string())),
+            (
+                "pool_idle_timeout",
+                self.http.pool_idle_timeout.map(|t| t.as_secs().to_string()),
+            ),
+            ("proxy", self.http.proxy.clone()),
+            (
+                "ssl_ca_path",
+                self.http
+                    .ssl_ca_path
+                    .as_ref()
+                    .map(|p| p.display().to_string()),
+            ),
+            (
+                "auth_type",
+                self.auth_type.map(|t| match t {
+                    AuthType::Basic => "basic".to_string(),
+                    AuthType::Bearer => "bearer".to_string(),
+                }),
+            ),
+            (
+                "default_output_format",
+                self.default_output_format.as_ref().map(|f| match f {
+                    OutputFormat::Text => "text".to_string(),
+                    OutputFormat::Json => "json".to_string(),
+                }),
+            ),
+            ("color", self.color.clone()),
+            (
+                "topic_from_trailer",
+                (!self.topic_trailers.is_empty()).then(|| self.topic_trailers.join("|")),
+            ),
+            ("push.message_template", self.message_template.clone()),
+        ]
+    }
+}
+
+/// A configuration layer, as applied by [`load_config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Built-in default.
+    Default,
+    /// `[gerrit]` in the repository's `.gitreview`.
+    Gitreview,
+    /// `[grt]` in the repository's `.gitreview`.
+    GitreviewGrt,
+    /// The repository's `.grt.toml`.
+    RepoToml,
+    /// The user's `config.toml`, including the selected profile.
+    UserToml,
+    /// git config (`gitreview.*`, `http.*`).
+    GitConfig,
+    /// `GERRIT_*` and other environment variables.
+    Environment,
+    /// Command-line flags.
+    Cli,
+    /// Derived from the Gerrit remote's URL.
+    RemoteUrl,
+    /// The default server in `credentials.toml`.
+    Credentials,
+}
+
+impl ConfigSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConfigSource::Default => "default",
+            ConfigSource::Gitreview => ".gitreview",
+            ConfigSource::GitreviewGrt => ".gitreview [grt]",
+            ConfigSource::RepoToml => ".grt.toml",
+            ConfigSource::UserToml => "config.toml",
+            ConfigSource::GitConfig => "git config",
+            ConfigSource::Environment => "environment",
+            ConfigSource::Cli => "command line",
+            ConfigSource::RemoteUrl => "remote URL",
+            ConfigSource::Credentials => "credentials.toml",
+        }
+    }
+}
+
+impl serde::Serialize for ConfigSource {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.label())
+    }
+}
+
+/// Which layer set each value of a loaded [`GerritConfig`].
+///
+/// Built by comparing [`GerritConfig::entries`] before and after each
+/// layer: a value belongs to the last layer that changed it.
+#[derive(Debug)]
+pub struct ConfigSources {
+    last: Vec<(&'static str, Option<String>)>,
+    sources: HashMap<&'static str, ConfigSource>,
+}
+
+impl ConfigSources {
+    fn new(config: &GerritConfig) -> Self {
+        Self {
+            last: config.entries(),
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Attribute every value that changed since the previous layer to `source`.
+    fn record(&mut self, config: &GerritConfig, source: ConfigSource) {
+        let entries = config.entries();
+        for ((key, before), (_, after)) in self.last.iter().zip(&entries) {
+            if before != after {
+                self.sources.insert(*key, source);
+            }
+        }
+        self.last = entries;
+    }
+
+    /// The layer that set `key`; [`ConfigSource::Default`] when none did.
+    pub fn source(&self, key: &str) -> ConfigSource {
+        self.sources
+            .get(key)
+            .copied()
+            .unwrap_or(ConfigSource::Default)
+    }
 }
 
 impl Default for GerritConfig {
@@ -94,15 +283,24 @@ impl Default for GerritConfig {
             ssh_port: None,
             http_port: None,
             project: String::new(),
-            branch: String::from("master"),
+            branch: None,
             remote: String::from("gerrit"),
             scheme: String::from("ssh"),
             default_rebase: true,
             track: false,
             notopic: false,
             usepushurl: false,
+            signed_push: false,
             ssl_verify: true,
             username: None,
+            default_reviewers: Vec::new(),
+            default_cc: Vec::new(),
+            http: HttpOptions::default(),
+            auth_type: None,
+            default_output_format: None,
+            color: None,
+            topic_trailers: Vec::new(),
+            message_template: None,
         }
     }
 }
@@ -214,6 +412,21 @@ pub struct CliOverrides {
     pub use_pushurl: Option<bool>,
     /// Allow sending credentials over plain HTTP (no TLS).
     pub insecure: bool,
+    /// Named `[profile.<name>]` from config.toml (--profile, overrides `GRT_PROFILE`).
+    pub profile: Option<String>,
+    /// Force HTTP/1.1 for the REST client (--http1).
+    pub http1: bool,
+    /// Let `.gitreview` host/project/branch win over git config (--prefer-gitreview).
+    pub prefer_gitreview: bool,
+}
+
+impl CliOverrides {
+    /// The config.toml profile to apply: `--profile`, else a non-blank `GRT_PROFILE`.
+    pub fn selected_profile(&self, env_value: impl Fn(&str) -> Option<String>) -> Option<String> {
+        self.profile
+            .clone()
+            .or_else(|| env_value("GRT_PROFILE").filter(|v| !v.trim().is_empty()))
+    }
 }
 
 /// A single server entry in `credentials.toml`.
@@ -243,9 +456,19 @@ pub struct LoadedCredentials {
     pub auth_type: AuthType,
 }
 
+/// Map a `credentials.toml` / keyring `auth_type` to [`AuthType`]; anything
+/// but `"bearer"` is Basic.
+pub fn parse_auth_type(value: Option<&str>) -> AuthType {
+    match value {
+        Some("bearer") => AuthType::Bearer,
+        _ => AuthType::Basic,
+    }
+}
+
 /// Load credentials for `host` from `<config_dir>/grt/credentials.toml`.
 ///
-/// Returns `Ok(None)` if the file is missing or no entry matches `host`.
+/// The OS keyring is consulted separately, by [`crate::app::App::authenticate`].
+/// Returns `Ok(None)` if the file is missing or has no entry for `host`.
 /// Returns `Err` if the file has bad permissions (must be `0600` on Unix) or invalid TOML.
 pub fn load_credentials(host: &str, config_dir: &Path) -> Result<Option<LoadedCredentials>> {
     let cred_path = config_dir.join("grt").join("credentials.toml");
@@ -277,14 +500,10 @@ pub fn load_credentials(host: &str, config_dir: &Path) -> Result<Option<LoadedCr
 
     for server in &creds.server {
         if server.name == host {
-            let auth_type = match server.auth_type.as_deref() {
-                Some("bearer") => AuthType::Bearer,
-                _ => AuthType::Basic,
-            };
             return Ok(Some(LoadedCredentials {
                 username: server.username.clone(),
                 password: server.password.clone(),
-                auth_type,
+                auth_type: parse_auth_type(server.auth_type.as_deref()),
             }));
         }
     }
@@ -336,7 +555,15 @@ pub fn load_default_server(config_dir: &Path) -> Result<Option<String>> {
 
 /// Parse a `.gitreview` INI file. Expects a `[gerrit]` section with key=value pairs.
 pub fn parse_gitreview(content: &str) -> Result<HashMap<String, String>> {
-    let mut in_gerrit_section = false;
+    parse_ini_section(content, "gerrit")
+        .ok_or_else(|| anyhow::anyhow!("missing [gerrit] section in .gitreview"))
+}
+
+/// The key=value pairs of `[section]` in an INI file, or `None` if the
+/// section is absent. Keys are lowercased.
+pub fn parse_ini_section(content: &str, section: &str) -> Option<HashMap<String, String>> {
+    let header = format!("[{section}]");
+    let mut in_section = false;
     let mut found_section = false;
     let mut values = HashMap::new();
 
@@ -348,14 +575,14 @@ pub fn parse_gitreview(content: &str) -> Result<HashMap<String, String>> {
         }
 
         if trimmed.starts_with('[') {
-            in_gerrit_section = trimmed.eq_ignore_ascii_case("[gerrit]");
-            if in_gerrit_section {
+            in_section = trimmed.eq_ignore_ascii_case(&header);
+            if in_section {
                 found_section = true;
             }
             continue;
         }
 
-        if in_gerrit_section {
+        if in_section {
             if let Some((key, value)) = trimmed.split_once('=').or_else(|| trimmed.split_once(':'))
             {
                 values.insert(key.trim().to_lowercase(), value.trim().to_string());
@@ -363,11 +590,96 @@ pub fn parse_gitreview(content: &str) -> Result<HashMap<String, String>> {
         }
     }
 
-    if !found_section {
-        anyhow::bail!("missing [gerrit] section in .gitreview");
+    found_section.then_some(values)
+}
+
+/// The keys of a repo-local `.grt.toml` as strings, so they can be applied
+/// like the `.gitreview` `[grt]` section.
+///
+/// Keys in a table are prefixed with its name, so `message_template` under
+/// `[push]` becomes `push.message_template`.
+pub fn grt_toml_settings(table: &toml::Table) -> HashMap<String, String> {
+    let scalar = |value: &toml::Value| match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(_) | toml::Value::Boolean(_) => Some(value.to_string()),
+        _ => None,
+    };
+    let mut settings = HashMap::new();
+    for (key, value) in table {
+        let key = key.to_lowercase();
+        if let toml::Value::Table(inner) = value {
+            for (inner_key, value) in inner {
+                if let Some(value) = scalar(value) {
+                    settings.insert(format!("{key}.{}", inner_key.to_lowercase()), value);
+                }
+            }
+        } else if let Some(value) = scalar(value) {
+            settings.insert(key, value);
+        }
     }
+    settings
+}
 
-    Ok(values)
+/// Apply repo-local grt settings (`.gitreview` `[grt]` or `.grt.toml`).
+///
+/// `source` names the file in error messages. Unknown keys are ignored so
+/// newer settings do not break older grt versions.
+pub fn apply_repo_settings(
+    config: &mut GerritConfig,
+    settings: &HashMap<String, String>,
+    source: &str,
+) -> Result<()> {
+    for (key, value) in settings {
+        let value = value.trim();
+        match key.as_str() {
+            "http_port" => {
+                config.http_port = Some(
+                    value
+                        .parse::<u16>()
+                        .with_context(|| format!("parsing http_port in {source}"))?,
+                );
+            }
+            "auth_type" => {
+                config.auth_type = Some(match value {
+                    "basic" => AuthType::Basic,
+                    "bearer" => AuthType::Bearer,
+                    other => anyhow::bail!(
+                        "invalid auth_type {other:?} in {source}: expected basic or bearer"
+                    ),
+                });
+            }
+            "default_output_format" => {
+                config.default_output_format = Some(match value {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    other => anyhow::bail!(
+                        "invalid default_output_format {other:?} in {source}: expected text or json"
+                    ),
+                });
+            }
+            "color" => {
+                if !matches!(value, "auto" | "always" | "never") {
+                    anyhow::bail!(
+                        "invalid color {value:?} in {source}: expected auto, always or never"
+                    );
+                }
+                config.color = Some(value.to_string());
+            }
+            "topic_from_trailer" | "topic-from-trailer" => {
+                config.topic_trailers = value
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+            "push.message_template" => {
+                config.message_template = (!value.is_empty()).then(|| value.to_string());
+            }
+            other => debug!("ignoring unknown setting {other:?} in {source}"),
+        }
+    }
+    Ok(())
 }
 
 /// Strip a trailing `.git` suffix from a project name.
@@ -387,21 +699,315 @@ fn parse_bool_value(value: &str) -> bool {
     !matches!(value.trim().to_lowercase().as_str(), "0" | "false" | "no")
 }
 
-/// Load configuration by layering sources: .gitreview, grt config, git config, CLI overrides.
+/// Split a reviewer list on commas and whitespace, dropping empty entries.
+///
+/// Used for `gitreview.defaultreviewers` and the `.gitreview`
+/// `defaultreviewers`/`defaultcc` keys, which may be written as
+/// `alice,bob`, `alice bob`, or `alice, bob`.
+pub fn parse_reviewer_list(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Expand a leading `~/` to the home directory, as git does for path values.
+pub fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Apply the `[gerrit]` table of a parsed config.toml, then the
+/// `[gerrit."<host>"]` table for the repository's host, then the selected
+/// profile's `[profile.<name>.gerrit]` table over it.
+///
+/// The host is the one `config` already has from `.gitreview`, else the
+/// `[gerrit]` table's `host`. Errors if `profile` names a profile that is
+/// not defined.
+pub fn apply_toml_config(
+    config: &mut GerritConfig,
+    table: &toml::Table,
+    profile: Option<&str>,
+) -> Result<()> {
+    apply_toml_config_for_host(config, table, profile, None).map(|_| ())
+}
+
+/// [`apply_toml_config`], taking the host table from `host_hint` when given.
+///
+/// The hint is the host a later layer settled on (git config, environment,
+/// command line or remote URL); its table is applied without touching
+/// `config.host`, so those layers still decide the host. Returns the host
+/// whose table was applied, if any.
+fn apply_toml_config_for_host(
+    config: &mut GerritConfig,
+    table: &toml::Table,
+    profile: Option<&str>,
+    host_hint: Option<&str>,
+) -> Result<Option<String>> {
+    let repo_host = config.host.clone();
+    let mut applied = None;
+    if let Some(gerrit) = table.get("gerrit").and_then(|v| v.as_table()) {
+        apply_gerrit_table(config, gerrit)?;
+        let host = match host_hint {
+            Some(hint) => hint.to_string(),
+            None if repo_host.is_empty() => config.host.clone(),
+            None => repo_host,
+        };
+        if let Some(host_table) = host_table(gerrit, &host) {
+            let before = config.host.clone();
+            apply_gerrit_table(config, host_table)
+                .with_context(|| format!("in [gerrit.\"{host}\"] of grt config.toml"))?;
+            // The table is for this host, so a default `host` does not replace it
+            config.host = if host_hint.is_some() {
+                before
+            } else {
+                host.clone()
+            };
+            applied = Some(host);
+        }
+    }
+    if let Some(name) = profile {
+        let profile_table = table
+            .get("profile")
+            .and_then(|v| v.as_table())
+            .and_then(|profiles| profiles.get(name))
+            .and_then(|v| v.as_table())
+            .with_context(|| format!("unknown profile {name:?} in grt config.toml"))?;
+        if let Some(gerrit) = profile_table.get("gerrit").and_then(|v| v.as_table()) {
+            apply_gerrit_table(config, gerrit)
+                .with_context(|| format!("in profile {name:?} of grt config.toml"))?;
+        }
+    }
+    Ok(applied)
+}
+
+/// Whether config.toml has a `[gerrit."<host>"]` table for `host` other than
+/// the one already applied (`applied`), so the layers must be loaded again.
+fn needs_host_table_reload(table: &toml::Table, applied: Option<&str>, host: &str) -> bool {
+    if applied.is_some_and(|applied| applied.eq_ignore_ascii_case(host)) {
+        return false;
+    }
+    table
+        .get("gerrit")
+        .and_then(|v| v.as_table())
+        .and_then(|gerrit| host_table(gerrit, host))
+        .is_some()
+}
+
+/// The `[gerrit."<host>"]` table for `host`; host names match case-insensitively.
+fn host_table<'a>(gerrit: &'a toml::Table, host: &str) -> Option<&'a toml::Table> {
+    if host.is_empty() {
+        return None;
+    }
+    gerrit
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(host))
+        .and_then(|(_, value)| value.as_table())
+}
+
+/// Apply the keys of a `[gerrit]` TOML table to the config.
+fn apply_gerrit_table(config: &mut GerritConfig, gerrit: &toml::Table) -> Result<()> {
+    if let Some(host) = gerrit.get("host").and_then(|v| v.as_str()) {
+        config.host = host.to_string();
+    }
+    if let Some(port) = gerrit.get("port").and_then(|v| v.as_integer()) {
+        config.http_port = Some(port as u16);
+    }
+    if let Some(project) = gerrit.get("project").and_then(|v| v.as_str()) {
+        config.project = strip_git_suffix(project);
+    }
+    if let Some(branch) = gerrit.get("branch").and_then(|v| v.as_str()) {
+        config.branch = Some(branch.to_string());
+    }
+    if let Some(remote) = gerrit.get("remote").and_then(|v| v.as_str()) {
+        config.remote = remote.to_string();
+    }
+    if let Some(scheme) = gerrit.get("scheme").and_then(|v| v.as_str()) {
+        config.scheme = scheme.to_string();
+    }
+    if let Some(http1) = gerrit.get("http1_only").and_then(|v| v.as_bool()) {
+        config.http.http1_only = http1;
+    }
+    if let Some(secs) = gerrit.get("pool_idle_timeout").and_then(|v| v.as_integer()) {
+        config.http.pool_idle_timeout = Some(std::time::Duration::from_secs(secs.max(0) as u64));
+    }
+    if let Some(proxy) = gerrit.get("proxy").and_then(|v| v.as_str()) {
+        config.http.proxy = Some(proxy.to_string());
+    }
+    if let Some(path) = gerrit.get("ssl_ca_path").and_then(|v| v.as_str()) {
+        config.http.ssl_ca_path = Some(expand_tilde(path));
+    }
+    if let Some(reviewers) = gerrit.get("default_reviewers").and_then(|v| v.as_array()) {
+        config.default_reviewers = reviewers
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect();
+    }
+    if let Some(cc) = gerrit.get("default_cc").and_then(|v| v.as_array()) {
+        config.default_cc = cc
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect();
+    }
+    if let Some(verify) = gerrit.get("ssl_verify").and_then(|v| v.as_bool()) {
+        config.ssl_verify = verify;
+    }
+    if let Some(auth_type) = gerrit.get("auth_type").and_then(|v| v.as_str()) {
+        config.auth_type = Some(match auth_type {
+            "basic" => AuthType::Basic,
+            "bearer" => AuthType::Bearer,
+            other => anyhow::bail!("invalid auth_type {other:?}: expected basic or bearer"),
+        });
+    }
+    Ok(())
+}
+
+/// Load configuration by layering sources: .gitreview, grt config, git config,
+/// `GERRIT_*` environment variables, CLI overrides.
 pub fn load_config(
     repo_root: &Path,
     git_config_value: impl Fn(&str) -> Option<String>,
     cli: &CliOverrides,
 ) -> Result<GerritConfig> {
+    load_config_with_env(
+        repo_root,
+        git_config_value,
+        |key| std::env::var(key).ok(),
+        cli,
+    )
+}
+
+/// Re-apply the `.gitreview` host, project and branch on top of `config`.
+fn apply_gitreview_target(config: &mut GerritConfig, values: &HashMap<String, String>) {
+    if let Some(host) = values.get("host") {
+        config.host = host.clone();
+    }
+    if let Some(project) = values.get("project") {
+        config.project = strip_git_suffix(project);
+    }
+    if let Some(branch) = values.get("defaultbranch") {
+        config.branch = Some(branch.clone());
+    }
+}
+
+/// Fill host, port, project and scheme from a remote URL when nothing else set them.
+///
+/// Values that are already configured (project, ports, an explicit scheme
+/// when `keep_scheme`) are kept; URLs that are neither SSH/SCP nor HTTP(S),
+/// such as local paths, and URLs of known non-Gerrit forges such as GitHub
+/// are ignored.
+fn apply_remote_url_fallback(config: &mut GerritConfig, url: &str, keep_scheme: bool) {
+    if !crate::push::looks_like_gerrit_remote(url) {
+        return;
+    }
+    if crate::review_query::is_http_remote(url) {
+        let Ok(parsed) = Url::parse(url) else {
+            return;
+        };
+        let Some(host) = parsed.host_str() else {
+            return;
+        };
+        config.host = host.to_string();
+        if !keep_scheme {
+            config.scheme = parsed.scheme().to_string();
+        }
+        if config.http_port.is_none() {
+            config.http_port = parsed.port();
+        }
+        if config.project.is_empty() {
+            let path = parsed.path().trim_start_matches('/');
+            // Authenticated clone URLs carry Gerrit's `/a/` prefix
+            let path = path.strip_prefix("a/").unwrap_or(path);
+            config.project = strip_git_suffix(path.trim_end_matches('/'));
+        }
+    } else if let Ok((host, username, port, project)) =
+        crate::review_query::ssh::parse_gerrit_ssh_params(url)
+    {
+        if host.is_empty() {
+            return;
+        }
+        config.host = host;
+        if !keep_scheme {
+            config.scheme = "ssh".to_string();
+        }
+        if config.ssh_port.is_none() {
+            config.ssh_port = port;
+        }
+        if config.username.is_none() {
+            config.username = username;
+        }
+        if config.project.is_empty() {
+            config.project = strip_git_suffix(&project);
+        }
+    }
+}
+
+/// [`load_config`] with an injectable environment lookup, for testing.
+pub fn load_config_with_env(
+    repo_root: &Path,
+    git_config_value: impl Fn(&str) -> Option<String>,
+    env_value: impl Fn(&str) -> Option<String>,
+    cli: &CliOverrides,
+) -> Result<GerritConfig> {
+    load_layers(repo_root, git_config_value, env_value, cli).map(|(config, _)| config)
+}
+
+/// [`load_config`], also reporting which layer set each value.
+pub fn load_config_with_sources(
+    repo_root: &Path,
+    git_config_value: impl Fn(&str) -> Option<String>,
+    cli: &CliOverrides,
+) -> Result<(GerritConfig, ConfigSources)> {
+    load_layers(
+        repo_root,
+        git_config_value,
+        |key| std::env::var(key).ok(),
+        cli,
+    )
+}
+
+fn load_layers(
+    repo_root: &Path,
+    git_config_value: impl Fn(&str) -> Option<String>,
+    env_value: impl Fn(&str) -> Option<String>,
+    cli: &CliOverrides,
+) -> Result<(GerritConfig, ConfigSources)> {
+    load_layers_for_host(repo_root, &git_config_value, &env_value, cli, None)
+}
+
+/// Apply every layer, taking config.toml's host table for `host_hint` when set.
+///
+/// The host table is chosen while config.toml is applied, before git config,
+/// the environment, the command line or the remote URL can change the host.
+/// When one of them settles on a host that has its own table, the layers are
+/// loaded again with that table, so its keys keep config.toml's precedence.
+fn load_layers_for_host(
+    repo_root: &Path,
+    git_config_value: &dyn Fn(&str) -> Option<String>,
+    env_value: &dyn Fn(&str) -> Option<String>,
+    cli: &CliOverrides,
+    host_hint: Option<&str>,
+) -> Result<(GerritConfig, ConfigSources)> {
     let mut config = GerritConfig::default();
+    let mut sources = ConfigSources::new(&config);
 
     // Layer 1: .gitreview file
     let gitreview_path = repo_root.join(".gitreview");
-    if gitreview_path.exists() {
-        let content =
-            std::fs::read_to_string(&gitreview_path).context("reading .gitreview file")?;
-        let values = parse_gitreview(&content)?;
-
+    let gitreview_content = if gitreview_path.exists() {
+        Some(std::fs::read_to_string(&gitreview_path).context("reading .gitreview file")?)
+    } else {
+        None
+    };
+    let gitreview = gitreview_content
+        .as_deref()
+        .map(parse_gitreview)
+        .transpose()?;
+    if let Some(values) = &gitreview {
         if let Some(host) = values.get("host") {
             config.host = host.clone();
         }
@@ -412,7 +1018,7 @@ pub fn load_config(
             config.project = strip_git_suffix(project);
         }
         if let Some(branch) = values.get("defaultbranch") {
-            config.branch = branch.clone();
+            config.branch = Some(branch.clone());
         }
         if let Some(remote) = values.get("defaultremote") {
             config.remote = remote.clone();
@@ -432,37 +1038,53 @@ pub fn load_config(
         if let Some(val) = values.get("usepushurl") {
             config.usepushurl = parse_bool_value(val);
         }
+        if let Some(val) = values.get("signedpush") {
+            config.signed_push = parse_bool_value(val);
+        }
+        if let Some(val) = values.get("defaultreviewers") {
+            config.default_reviewers = parse_reviewer_list(val);
+        }
+        if let Some(val) = values.get("defaultcc") {
+            config.default_cc = parse_reviewer_list(val);
+        }
+    }
+    sources.record(&config, ConfigSource::Gitreview);
+
+    // Layer 1b: repo-local grt settings, `[grt]` in .gitreview then .grt.toml
+    if let Some(settings) = gitreview_content
+        .as_deref()
+        .and_then(|content| parse_ini_section(content, "grt"))
+    {
+        apply_repo_settings(&mut config, &settings, ".gitreview [grt]")?;
+    }
+    sources.record(&config, ConfigSource::GitreviewGrt);
+    let grt_toml_path = repo_root.join(".grt.toml");
+    if grt_toml_path.exists() {
+        let content = std::fs::read_to_string(&grt_toml_path).context("reading .grt.toml")?;
+        let table: toml::Table = toml::from_str(&content).context("parsing .grt.toml")?;
+        apply_repo_settings(&mut config, &grt_toml_settings(&table), ".grt.toml")?;
     }
+    sources.record(&config, ConfigSource::RepoToml);
 
-    // Layer 2: grt native TOML config
+    // Layer 2: grt native TOML config, with an optional named profile on top
+    let profile = cli.selected_profile(env_value);
+    let mut user_toml = None;
     if let Some(config_dir) = dirs::config_dir() {
         let toml_path = config_dir.join("grt").join("config.toml");
         if toml_path.exists() {
             let content = std::fs::read_to_string(&toml_path).context("reading grt config.toml")?;
             let table: toml::Table = toml::from_str(&content).context("parsing grt config.toml")?;
-
-            if let Some(gerrit) = table.get("gerrit").and_then(|v| v.as_table()) {
-                if let Some(host) = gerrit.get("host").and_then(|v| v.as_str()) {
-                    config.host = host.to_string();
-                }
-                if let Some(port) = gerrit.get("port").and_then(|v| v.as_integer()) {
-                    config.http_port = Some(port as u16);
-                }
-                if let Some(project) = gerrit.get("project").and_then(|v| v.as_str()) {
-                    config.project = strip_git_suffix(project);
-                }
-                if let Some(branch) = gerrit.get("branch").and_then(|v| v.as_str()) {
-                    config.branch = branch.to_string();
-                }
-                if let Some(remote) = gerrit.get("remote").and_then(|v| v.as_str()) {
-                    config.remote = remote.to_string();
-                }
-                if let Some(scheme) = gerrit.get("scheme").and_then(|v| v.as_str()) {
-                    config.scheme = scheme.to_string();
-                }
-            }
+            let applied =
+                apply_toml_config_for_host(&mut config, &table, profile.as_deref(), host_hint)?;
+            user_toml = Some((table, applied));
+        } else if let Some(ref name) = profile {
+            anyhow::bail!(
+                "unknown profile {name:?}: {} not found",
+                toml_path.display()
+            );
         }
     }
+    sources.record(&config, ConfigSource::UserToml);
 
     // Layer 3: git config (gitreview.*)
     if let Some(host) =
@@ -480,7 +1102,7 @@ pub fn load_config(
         config.project = strip_git_suffix(&project);
     }
     if let Some(branch) = git_config_value("gitreview.branch") {
-        config.branch = branch;
+        config.branch = Some(branch);
     }
     if let Some(remote) = git_config_value("gitreview.remote") {
         config.remote = remote;
@@ -488,18 +1110,70 @@ pub fn load_config(
     if let Some(username) = git_config_value("gitreview.username") {
         config.username = Some(username);
     }
+    if let Some(reviewers) = git_config_value("gitreview.defaultreviewers") {
+        config.default_reviewers = parse_reviewer_list(&reviewers);
+    }
+    if let Some(val) = git_config_value("gitreview.signedpush") {
+        config.signed_push = parse_bool_value(&val);
+    }
 
-    // SSL verification: git config + environment
+    // SSL verification, proxy and CA bundle, as git uses them for its own
+    // HTTP remotes
     if let Some(ssl) = git_config_value("http.sslVerify") {
         if ssl.eq_ignore_ascii_case("false") {
             config.ssl_verify = false;
         }
     }
-    if std::env::var("GIT_SSL_NO_VERIFY").is_ok() {
+    if let Some(proxy) = git_config_value("http.proxy").filter(|v| !v.trim().is_empty()) {
+        config.http.proxy = Some(proxy);
+    }
+    if let Some(path) = git_config_value("http.sslCAInfo").filter(|v| !v.trim().is_empty()) {
+        config.http.ssl_ca_path = Some(expand_tilde(&path));
+    }
+    sources.record(&config, ConfigSource::GitConfig);
+
+    // --prefer-gitreview: the checked-in .gitreview beats the layers above
+    if cli.prefer_gitreview {
+        if let Some(values) = &gitreview {
+            apply_gitreview_target(&mut config, values);
+        }
+    }
+    sources.record(&config, ConfigSource::Gitreview);
+
+    // Layer 4: environment overrides (GERRIT_*), for CI and containers
+    if env_value("GIT_SSL_NO_VERIFY").is_some() {
         config.ssl_verify = false;
     }
+    if let Some(path) = env_value("GIT_SSL_CAINFO").filter(|v| !v.trim().is_empty()) {
+        config.http.ssl_ca_path = Some(expand_tilde(&path));
+    }
+    let env = |key: &str| env_value(key).filter(|v| !v.trim().is_empty());
+    if let Some(host) = env("GERRIT_HOST") {
+        config.host = host;
+    }
+    // SSH port, as set by the Jenkins Gerrit Trigger
+    if let Some(port) = env("GERRIT_PORT") {
+        config.ssh_port = Some(
+            port.trim()
+                .parse::<u16>()
+                .context("parsing GERRIT_PORT from environment")?,
+        );
+    }
+    if let Some(project) = env("GERRIT_PROJECT") {
+        config.project = strip_git_suffix(&project);
+    }
+    if let Some(branch) = env("GERRIT_BRANCH") {
+        config.branch = Some(branch);
+    }
+    if let Some(scheme) = env("GERRIT_SCHEME") {
+        config.scheme = scheme;
+    }
+    if let Some(val) = env("GRT_FORCE_HTTP1") {
+        config.http.http1_only = parse_bool_value(&val);
+    }
+    sources.record(&config, ConfigSource::Environment);
 
-    // Layer 4: CLI overrides (highest precedence)
+    // Layer 5: CLI overrides (highest precedence)
     if let Some(ref host) = cli.host {
         config.host = host.clone();
     }
@@ -510,7 +1184,7 @@ pub fn load_config(
         config.project = strip_git_suffix(project);
     }
     if let Some(ref branch) = cli.branch {
-        config.branch = branch.clone();
+        config.branch = Some(branch.clone());
     }
     if let Some(ref remote) = cli.remote {
         config.remote = remote.clone();
@@ -521,8 +1195,23 @@ pub fn load_config(
     if let Some(use_push) = cli.use_pushurl {
         config.usepushurl = use_push;
     }
+    if cli.http1 {
+        config.http.http1_only = true;
+    }
+    sources.record(&config, ConfigSource::Cli);
 
-    // Layer 5 (fallback): default server from credentials.toml
+    // Layer 6 (fallback): derive the server from the repo's Gerrit remote
+    if config.host.is_empty() {
+        let url = git_config_value(&format!("remote.{}.url", config.remote))
+            .or_else(|| git_config_value("remote.origin.url"));
+        if let Some(url) = url {
+            let keep_scheme = cli.scheme.is_some() || env("GERRIT_SCHEME").is_some();
+            apply_remote_url_fallback(&mut config, &url, keep_scheme);
+        }
+    }
+    sources.record(&config, ConfigSource::RemoteUrl);
+
+    // Layer 7 (fallback): default server from credentials.toml
     if config.host.is_empty() {
         if let Some(config_dir) = dirs::config_dir() {
             if let Ok(Some(default_host)) = load_default_server(&config_dir) {
@@ -530,120 +1219,817 @@ pub fn load_config(
             }
         }
     }
+    sources.record(&config, ConfigSource::Credentials);
+
+    // Default SSH port when using ssh scheme
+    if config.scheme == "ssh" && config.ssh_port.is_none() {
+        config.ssh_port = Some(29418);
+    }
+    // usepushurl pushes through the existing origin remote instead of a
+    // separate Gerrit remote, unless a remote was chosen explicitly
+    if config.usepushurl && sources.source("remote") == ConfigSource::Default {
+        config.remote = String::from("origin");
+    }
+    sources.record(&config, ConfigSource::Default);
+
+    // URL rewriting: insteadOf / pushInsteadOf is handled at the call site
+    // via populate_rewrites() + alias_url() since it needs git config --list output.
+
+    if let (None, Some((table, applied))) = (host_hint, &user_toml) {
+        if needs_host_table_reload(table, applied.as_deref(), &config.host) {
+            let host = config.host.clone();
+            return load_layers_for_host(repo_root, git_config_value, env_value, cli, Some(&host));
+        }
+    }
+    Ok((config, sources))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_gitreview_basic() {
+        let content = "\
+[gerrit]
+host=review.example.com
+port=29418
+project=my/project
+defaultbranch=develop
+";
+        let values = parse_gitreview(content).unwrap();
+        assert_eq!(values.get("host").unwrap(), "review.example.com");
+        assert_eq!(values.get("port").unwrap(), "29418");
+        assert_eq!(values.get("project").unwrap(), "my/project");
+        assert_eq!(values.get("defaultbranch").unwrap(), "develop");
+    }
+
+    #[test]
+    fn parse_gitreview_minimal() {
+        let content = "\
+[gerrit]
+host=review.example.com
+project=my/project
+";
+        let values = parse_gitreview(content).unwrap();
+        assert_eq!(values.get("host").unwrap(), "review.example.com");
+        assert_eq!(values.get("project").unwrap(), "my/project");
+        assert!(!values.contains_key("port"));
+        assert!(!values.contains_key("defaultbranch"));
+    }
+
+    #[test]
+    fn parse_gitreview_crlf() {
+        let content = "[gerrit]\r\nhost=review.example.com\r\nproject=my/project.git\r\n";
+        let values = parse_gitreview(content).unwrap();
+        assert_eq!(values.get("host").unwrap(), "review.example.com");
+        assert_eq!(values.get("project").unwrap(), "my/project.git");
+    }
+
+    #[test]
+    fn parse_gitreview_with_spaces() {
+        let content = "\
+[gerrit]
+host = review.example.com
+port = 29418
+project = my/project
+";
+        let values = parse_gitreview(content).unwrap();
+        assert_eq!(values.get("host").unwrap(), "review.example.com");
+        assert_eq!(values.get("port").unwrap(), "29418");
+        assert_eq!(values.get("project").unwrap(), "my/project");
+    }
+
+    #[test]
+    fn parse_gitreview_missing_section() {
+        let content = "\
+host=review.example.com
+project=my/project
+";
+        let err = parse_gitreview(content).unwrap_err();
+        assert!(
+            err.to_string().contains("[gerrit]"),
+            "error should mention missing section: {err}"
+        );
+    }
+
+    #[test]
+    fn parse_gitreview_empty() {
+        let err = parse_gitreview("").unwrap_err();
+        assert!(
+            err.to_string().contains("[gerrit]"),
+            "error should mention missing section: {err}"
+        );
+    }
+
+    // === repo-local grt settings ===
+
+    #[test]
+    fn parse_ini_section_reads_grt_section() {
+        let content = "[gerrit]\nhost=review.example.com\n\n[grt]\nhttp_port = 8443\nColor=never\n";
+        let values = parse_ini_section(content, "grt").unwrap();
+        assert_eq!(values.get("http_port").unwrap(), "8443");
+        assert_eq!(values.get("color").unwrap(), "never");
+        assert!(!values.contains_key("host"));
+        assert!(parse_ini_section("[gerrit]\nhost=x\n", "grt").is_none());
+    }
+
+    #[test]
+    fn gitreview_grt_section_sets_repo_options() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=review.example.com\nproject=p\n[grt]\nhttp_port=8443\nauth_type=bearer\ndefault_output_format=json\ncolor=never\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.http_port, Some(8443));
+        assert_eq!(config.auth_type, Some(AuthType::Bearer));
+        assert!(matches!(
+            config.default_output_format,
+            Some(OutputFormat::Json)
+        ));
+        assert_eq!(config.color.as_deref(), Some("never"));
+        assert!(matches!(config.output_format(None), OutputFormat::Json));
+        assert!(matches!(
+            config.output_format(Some(OutputFormat::Text)),
+            OutputFormat::Text
+        ));
+    }
+
+    #[test]
+    fn grt_toml_overrides_gitreview_grt_section() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=review.example.com\nproject=p\n[grt]\nhttp_port=8443\ncolor=never\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(".grt.toml"),
+            "http_port = 9443\nfuture_option = true\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.http_port, Some(9443));
+        assert_eq!(config.color.as_deref(), Some("never"));
+    }
+
+    #[test]
+    fn grt_toml_topic_from_trailer() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".grt.toml"),
+            "topic-from-trailer = \"Closes-Bug | JIRA\"\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.topic_trailers, ["Closes-Bug", "JIRA"]);
+        let entries = config.entries();
+        let (_, value) = entries
+            .iter()
+            .find(|(key, _)| *key == "topic_from_trailer")
+            .unwrap();
+        assert_eq!(value.as_deref(), Some("Closes-Bug|JIRA"));
+    }
+
+    #[test]
+    fn grt_toml_push_message_template() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".grt.toml"),
+            "[push]\nmessage_template = \"[{ticket}] {topic}\"\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(
+            config.message_template.as_deref(),
+            Some("[{ticket}] {topic}")
+        );
+    }
+
+    #[test]
+    fn gitreview_grt_push_message_template() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=review.example.com\nproject=my/project\n\n\
+             [grt]\npush.message_template = Pushed to {branch}\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(
+            config.message_template.as_deref(),
+            Some("Pushed to {branch}")
+        );
+    }
+
+    #[test]
+    fn invalid_repo_setting_names_source() {
+        let mut config = GerritConfig::default();
+        let settings = HashMap::from([("auth_type".to_string(), "digest".to_string())]);
+        let err = apply_repo_settings(&mut config, &settings, ".grt.toml").unwrap_err();
+        assert!(err.to_string().contains(".grt.toml"), "{err}");
+
+        let settings = HashMap::from([("http_port".to_string(), "https".to_string())]);
+        assert!(apply_repo_settings(&mut config, &settings, ".grt.toml").is_err());
+    }
+
+    #[test]
+    fn config_defaults() {
+        let config = GerritConfig::default();
+        assert_eq!(config.branch(), "master");
+        assert_eq!(config.remote, "gerrit");
+        assert_eq!(config.scheme, "ssh");
+        assert!(config.default_rebase);
+        assert!(!config.track);
+        assert!(!config.notopic);
+        assert!(!config.usepushurl);
+        assert!(config.ssl_verify);
+        assert!(config.username.is_none());
+        assert!(config.branch.is_none());
+    }
+
+    #[test]
+    fn branch_set_only_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let gitreview = dir.path().join(".gitreview");
+        std::fs::write(&gitreview, "[gerrit]\nhost=h.example.com\nproject=p\n").unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert!(config.branch.is_none());
+        assert_eq!(config.branch(), "master");
+
+        std::fs::write(
+            &gitreview,
+            "[gerrit]\nhost=h.example.com\nproject=p\ndefaultbranch=master\n",
+        )
+        .unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.branch.as_deref(), Some("master"));
+    }
+
+    #[test]
+    fn config_cli_overrides_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let gitreview = dir.path().join(".gitreview");
+        std::fs::write(
+            &gitreview,
+            "[gerrit]\nhost=file.example.com\nproject=file/project\n",
+        )
+        .unwrap();
+
+        let cli = CliOverrides {
+            host: Some("cli.example.com".into()),
+            ..Default::default()
+        };
+
+        let config = load_config_with_env(dir.path(), |_| None, |_| None, &cli).unwrap();
+        assert_eq!(config.host, "cli.example.com");
+        assert_eq!(config.project, "file/project");
+    }
+
+    // === config.toml profiles ===
+
+    const PROFILE_TOML: &str = r#"
+[gerrit]
+host = "default.example.com"
+project = "default/project"
+
+[profile.work.gerrit]
+host = "review.work.example.com"
+
+[profile.oss.gerrit]
+host = "review.opendev.org"
+branch = "master"
+"#;
+
+    #[test]
+    fn toml_without_profile_uses_default_gerrit_table() {
+        let table: toml::Table = toml::from_str(PROFILE_TOML).unwrap();
+        let mut config = GerritConfig::default();
+        apply_toml_config(&mut config, &table, None).unwrap();
+        assert_eq!(config.host, "default.example.com");
+    }
+
+    #[test]
+    fn toml_profile_overrides_default_host() {
+        let table: toml::Table = toml::from_str(PROFILE_TOML).unwrap();
+        let mut config = GerritConfig::default();
+        apply_toml_config(&mut config, &table, Some("work")).unwrap();
+        assert_eq!(config.host, "review.work.example.com");
+        // Keys the profile does not set keep the default table's values
+        assert_eq!(config.project, "default/project");
+    }
+
+    const HOST_TOML: &str = r#"
+[gerrit]
+host = "default.example.com"
+port = 443
+default_reviewers = ["alice"]
+
+[gerrit."review.opendev.org"]
+default_reviewers = ["infra-core"]
+auth_type = "bearer"
+
+[gerrit."gerrit.corp.example.com"]
+port = 8443
+ssl_verify = false
+ssl_ca_path = "/etc/corp-ca.pem"
+"#;
+
+    #[test]
+    fn toml_host_table_selected_by_gitreview_host() {
+        let table: toml::Table = toml::from_str(HOST_TOML).unwrap();
+        let mut config = GerritConfig {
+            host: "Review.OpenDev.org".to_string(),
+            ..Default::default()
+        };
+        apply_toml_config(&mut config, &table, None).unwrap();
+        // The default `host` does not replace the repository's own
+        assert_eq!(config.host, "Review.OpenDev.org");
+        assert_eq!(config.default_reviewers, ["infra-core"]);
+        assert_eq!(config.auth_type, Some(AuthType::Bearer));
+        // Keys the host table does not set keep the default table's values
+        assert_eq!(config.http_port, Some(443));
+    }
+
+    #[test]
+    fn toml_host_table_sets_ssl_options() {
+        let table: toml::Table = toml::from_str(HOST_TOML).unwrap();
+        let mut config = GerritConfig {
+            host: "gerrit.corp.example.com".to_string(),
+            ..Default::default()
+        };
+        apply_toml_config(&mut config, &table, None).unwrap();
+        assert_eq!(config.http_port, Some(8443));
+        assert!(!config.ssl_verify);
+        assert_eq!(
+            config.http.ssl_ca_path,
+            Some(PathBuf::from("/etc/corp-ca.pem"))
+        );
+        assert_eq!(config.default_reviewers, ["alice"]);
+    }
+
+    #[test]
+    fn toml_host_table_for_default_host_without_gitreview() {
+        let table: toml::Table = toml::from_str(
+            "[gerrit]\nhost = \"review.opendev.org\"\n\n[gerrit.\"review.opendev.org\"]\nport = 8080\n",
+        )
+        .unwrap();
+        let mut config = GerritConfig::default();
+        apply_toml_config(&mut config, &table, None).unwrap();
+        assert_eq!(config.http_port, Some(8080));
+    }
+
+    #[test]
+    fn toml_host_table_ignored_for_other_hosts() {
+        let table: toml::Table = toml::from_str(HOST_TOML).unwrap();
+        let mut config = GerritConfig {
+            host: "other.example.org".to_string(),
+            ..Default::default()
+        };
+        apply_toml_config(&mut config, &table, None).unwrap();
+        assert_eq!(config.host, "default.example.com");
+        assert_eq!(config.default_reviewers, ["alice"]);
+        assert_eq!(config.auth_type, None);
+    }
+
+    #[test]
+    fn toml_host_hint_picks_table_without_changing_host() {
+        let table: toml::Table = toml::from_str(HOST_TOML).unwrap();
+        let mut config = GerritConfig {
+            host: "other.example.org".to_string(),
+            ..Default::default()
+        };
+        let applied =
+            apply_toml_config_for_host(&mut config, &table, None, Some("gerrit.corp.example.com"))
+                .unwrap();
+        assert_eq!(applied.as_deref(), Some("gerrit.corp.example.com"));
+        assert_eq!(config.http_port, Some(8443));
+        assert!(!config.ssl_verify);
+        // Later layers decide the host; applying its table does not set it
+        assert_eq!(config.host, "default.example.com");
+    }
+
+    #[test]
+    fn host_table_reload_only_for_another_host_with_a_table() {
+        let table: toml::Table = toml::from_str(HOST_TOML).unwrap();
+        // e.g. GERRIT_HOST or --host named a host with its own table
+        assert!(needs_host_table_reload(
+            &table,
+            None,
+            "gerrit.corp.example.com"
+        ));
+        assert!(needs_host_table_reload(
+            &table,
+            Some("review.opendev.org"),
+            "gerrit.corp.example.com"
+        ));
+        assert!(!needs_host_table_reload(
+            &table,
+            Some("review.opendev.org"),
+            "Review.OpenDev.org"
+        ));
+        assert!(!needs_host_table_reload(&table, None, "other.example.org"));
+        assert!(!needs_host_table_reload(&table, None, ""));
+    }
+
+    #[test]
+    fn toml_default_cc() {
+        let table: toml::Table = toml::from_str(
+            "[gerrit]\ndefault_cc = [\"carol\"]\n\n[gerrit.\"review.opendev.org\"]\ndefault_cc = [\"infra\"]\n",
+        )
+        .unwrap();
+        let mut config = GerritConfig::default();
+        apply_toml_config(&mut config, &table, None).unwrap();
+        assert_eq!(config.default_cc, ["carol"]);
+
+        let mut config = GerritConfig {
+            host: "review.opendev.org".to_string(),
+            ..Default::default()
+        };
+        apply_toml_config(&mut config, &table, None).unwrap();
+        assert_eq!(config.default_cc, ["infra"]);
+    }
+
+    #[test]
+    fn toml_invalid_host_auth_type_is_error() {
+        let table: toml::Table =
+            toml::from_str("[gerrit.\"review.opendev.org\"]\nauth_type = \"token\"\n").unwrap();
+        let mut config = GerritConfig {
+            host: "review.opendev.org".to_string(),
+            ..Default::default()
+        };
+        let err = apply_toml_config(&mut config, &table, None).unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("review.opendev.org"), "{err}");
+        assert!(err.contains("expected basic or bearer"), "{err}");
+    }
+
+    #[test]
+    fn toml_unknown_profile_is_error() {
+        let table: toml::Table = toml::from_str(PROFILE_TOML).unwrap();
+        let mut config = GerritConfig::default();
+        let err = apply_toml_config(&mut config, &table, Some("nope")).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown profile"), "{err:#}");
+    }
+
+    #[test]
+    fn load_config_unknown_profile_from_env_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = load_config_with_env(
+            dir.path(),
+            |_| None,
+            |key| (key == "GRT_PROFILE").then(|| "does-not-exist-profile".to_string()),
+            &CliOverrides::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn selected_profile_flag_then_env() {
+        let env = |key: &str| (key == "GRT_PROFILE").then(|| "work".to_string());
+        let cli = CliOverrides::default();
+        assert_eq!(cli.selected_profile(env).as_deref(), Some("work"));
+        assert_eq!(cli.selected_profile(|_| Some("  ".to_string())), None);
+        assert_eq!(cli.selected_profile(|_| None), None);
+
+        let cli = CliOverrides {
+            profile: Some("oss".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(cli.selected_profile(env).as_deref(), Some("oss"));
+    }
+
+    // === GERRIT_* environment overrides ===
+
+    fn gerrit_env(key: &str) -> Option<String> {
+        match key {
+            "GERRIT_HOST" => Some("env.example.com".into()),
+            "GERRIT_PORT" => Some("29419".into()),
+            "GERRIT_PROJECT" => Some("env/project.git".into()),
+            "GERRIT_BRANCH" => Some("stable".into()),
+            "GERRIT_SCHEME" => Some("https".into()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn env_overrides_gitreview() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=file.example.com\nproject=file/project\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, gerrit_env, &CliOverrides::default())
+                .unwrap();
+        assert_eq!(config.host, "env.example.com");
+        assert_eq!(config.ssh_port, Some(29419));
+        assert_eq!(config.http_port, None);
+        assert_eq!(config.project, "env/project");
+        assert_eq!(config.branch(), "stable");
+        assert_eq!(config.scheme, "https");
+    }
+
+    #[test]
+    fn env_overrides_git_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = load_config_with_env(
+            dir.path(),
+            |key| match key {
+                "gitreview.host" => Some("git.example.com".into()),
+                _ => None,
+            },
+            gerrit_env,
+            &CliOverrides::default(),
+        )
+        .unwrap();
+        assert_eq!(config.host, "env.example.com");
+    }
+
+    #[test]
+    fn cli_overrides_env() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=file.example.com\nproject=file/project\n",
+        )
+        .unwrap();
+
+        let cli = CliOverrides {
+            host: Some("cli.example.com".into()),
+            ..Default::default()
+        };
+        let config = load_config_with_env(dir.path(), |_| None, gerrit_env, &cli).unwrap();
+        assert_eq!(config.host, "cli.example.com");
+        assert_eq!(config.project, "env/project");
+    }
+
+    #[test]
+    fn host_derived_from_ssh_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |key: &str| {
+            (key == "remote.origin.url")
+                .then(|| "ssh://alice@review.example.com:29418/team/project.git".to_string())
+        };
+        let config =
+            load_config_with_env(dir.path(), git, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.host, "review.example.com");
+        assert_eq!(config.ssh_port, Some(29418));
+        assert_eq!(config.project, "team/project");
+        assert_eq!(config.scheme, "ssh");
+        assert_eq!(config.username.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn host_derived_from_https_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |key: &str| {
+            (key == "remote.gerrit.url")
+                .then(|| "https://review.example.com:8443/a/team/project".to_string())
+        };
+        let config =
+            load_config_with_env(dir.path(), git, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.host, "review.example.com");
+        assert_eq!(config.http_port, Some(8443));
+        assert_eq!(config.project, "team/project");
+        assert_eq!(config.scheme, "https");
+    }
+
+    #[test]
+    fn host_not_derived_from_non_gerrit_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |key: &str| {
+            (key == "remote.origin.url").then(|| "git@github.com:team/project.git".to_string())
+        };
+        let config =
+            load_config_with_env(dir.path(), git, |_| None, &CliOverrides::default()).unwrap();
+        assert!(config.host.is_empty());
+        assert!(config.project.is_empty());
+    }
 
-    // Default SSH port when using ssh scheme
-    if config.scheme == "ssh" && config.ssh_port.is_none() {
-        config.ssh_port = Some(29418);
+    #[test]
+    fn remote_url_ignored_when_host_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |key: &str| match key {
+            "gitreview.host" => Some("configured.example.com".to_string()),
+            "remote.origin.url" => Some("ssh://other.example.com:29418/p".to_string()),
+            _ => None,
+        };
+        let config =
+            load_config_with_env(dir.path(), git, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.host, "configured.example.com");
     }
 
-    // URL rewriting: insteadOf / pushInsteadOf is handled at the call site
-    // via populate_rewrites() + alias_url() since it needs git config --list output.
+    #[test]
+    fn git_config_branch_beats_gitreview_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=file.example.com\nproject=file/project\ndefaultbranch=stable\n",
+        )
+        .unwrap();
+        let git = |key: &str| (key == "gitreview.branch").then(|| "main".to_string());
+        let config =
+            load_config_with_env(dir.path(), git, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.branch(), "main");
+    }
 
-    Ok(config)
-}
+    #[test]
+    fn prefer_gitreview_beats_git_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=file.example.com\nproject=file/project\ndefaultbranch=stable\n",
+        )
+        .unwrap();
+        let git = |key: &str| match key {
+            "gitreview.branch" => Some("main".to_string()),
+            "gitreview.host" => Some("git.example.com".to_string()),
+            "gitreview.project" => Some("git/project".to_string()),
+            _ => None,
+        };
+        let cli = CliOverrides {
+            prefer_gitreview: true,
+            ..Default::default()
+        };
+        let config = load_config_with_env(dir.path(), git, |_| None, &cli).unwrap();
+        assert_eq!(config.branch(), "stable");
+        assert_eq!(config.host, "file.example.com");
+        assert_eq!(config.project, "file/project");
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn prefer_gitreview_still_loses_to_cli() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=file.example.com\ndefaultbranch=stable\n",
+        )
+        .unwrap();
+        let cli = CliOverrides {
+            branch: Some("feature".into()),
+            prefer_gitreview: true,
+            ..Default::default()
+        };
+        let git = |key: &str| (key == "gitreview.branch").then(|| "main".to_string());
+        let config = load_config_with_env(dir.path(), git, |_| None, &cli).unwrap();
+        assert_eq!(config.branch(), "feature");
+    }
 
     #[test]
-    fn parse_gitreview_basic() {
-        let content = "\
-[gerrit]
-host=review.example.com
-port=29418
-project=my/project
-defaultbranch=develop
-";
-        let values = parse_gitreview(content).unwrap();
-        assert_eq!(values.get("host").unwrap(), "review.example.com");
-        assert_eq!(values.get("port").unwrap(), "29418");
-        assert_eq!(values.get("project").unwrap(), "my/project");
-        assert_eq!(values.get("defaultbranch").unwrap(), "develop");
+    fn sources_name_the_layer_that_set_each_value() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=file.example.com\nproject=file/project\ndefaultbranch=stable\n\n[grt]\ncolor=never\n",
+        )
+        .unwrap();
+        let git = |key: &str| match key {
+            "gitreview.project" => Some("git/project".to_string()),
+            _ => None,
+        };
+        let env = |key: &str| match key {
+            "GERRIT_SCHEME" => Some("https".to_string()),
+            _ => None,
+        };
+        let cli = CliOverrides {
+            branch: Some("main".to_string()),
+            ..Default::default()
+        };
+        let (config, sources) = load_layers(dir.path(), git, env, &cli).unwrap();
+        assert_eq!(config.project, "git/project");
+        assert_eq!(sources.source("host"), ConfigSource::Gitreview);
+        assert_eq!(sources.source("project"), ConfigSource::GitConfig);
+        assert_eq!(sources.source("color"), ConfigSource::GitreviewGrt);
+        assert_eq!(sources.source("scheme"), ConfigSource::Environment);
+        assert_eq!(sources.source("branch"), ConfigSource::Cli);
+        assert_eq!(sources.source("remote"), ConfigSource::Default);
     }
 
     #[test]
-    fn parse_gitreview_minimal() {
-        let content = "\
-[gerrit]
-host=review.example.com
-project=my/project
-";
-        let values = parse_gitreview(content).unwrap();
-        assert_eq!(values.get("host").unwrap(), "review.example.com");
-        assert_eq!(values.get("project").unwrap(), "my/project");
-        assert!(!values.contains_key("port"));
-        assert!(!values.contains_key("defaultbranch"));
+    fn entries_cover_unset_values() {
+        let entries = GerritConfig::default().entries();
+        let get = |key: &str| entries.iter().find(|(k, _)| *k == key).unwrap().1.clone();
+        assert_eq!(get("host"), None);
+        assert_eq!(get("branch").as_deref(), Some("master"));
+        assert_eq!(get("default_rebase").as_deref(), Some("true"));
+        assert_eq!(get("default_reviewers"), None);
     }
 
     #[test]
-    fn parse_gitreview_with_spaces() {
-        let content = "\
-[gerrit]
-host = review.example.com
-port = 29418
-project = my/project
-";
-        let values = parse_gitreview(content).unwrap();
-        assert_eq!(values.get("host").unwrap(), "review.example.com");
-        assert_eq!(values.get("port").unwrap(), "29418");
-        assert_eq!(values.get("project").unwrap(), "my/project");
+    fn force_http1_from_env_and_cli() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = |key: &str| (key == "GRT_FORCE_HTTP1").then(|| "1".to_string());
+        let config =
+            load_config_with_env(dir.path(), |_| None, env, &CliOverrides::default()).unwrap();
+        assert!(config.http.http1_only);
+
+        let cli = CliOverrides {
+            http1: true,
+            ..Default::default()
+        };
+        let config = load_config_with_env(dir.path(), |_| None, |_| None, &cli).unwrap();
+        assert!(config.http.http1_only);
     }
 
     #[test]
-    fn parse_gitreview_missing_section() {
-        let content = "\
-host=review.example.com
-project=my/project
-";
-        let err = parse_gitreview(content).unwrap_err();
-        assert!(
-            err.to_string().contains("[gerrit]"),
-            "error should mention missing section: {err}"
+    fn http_options_from_toml() {
+        let table: toml::Table =
+            toml::from_str("[gerrit]\nhttp1_only = true\npool_idle_timeout = 20\n").unwrap();
+        let mut config = GerritConfig::default();
+        apply_toml_config(&mut config, &table, None).unwrap();
+        assert!(config.http.http1_only);
+        assert_eq!(
+            config.http.pool_idle_timeout,
+            Some(std::time::Duration::from_secs(20))
         );
     }
 
     #[test]
-    fn parse_gitreview_empty() {
-        let err = parse_gitreview("").unwrap_err();
-        assert!(
-            err.to_string().contains("[gerrit]"),
-            "error should mention missing section: {err}"
+    fn proxy_and_ca_from_toml() {
+        let table: toml::Table = toml::from_str(
+            "[gerrit]\nproxy = \"http://proxy:3128\"\nssl_ca_path = \"/etc/ssl/corp.pem\"\n",
+        )
+        .unwrap();
+        let mut config = GerritConfig::default();
+        apply_toml_config(&mut config, &table, None).unwrap();
+        assert_eq!(config.http.proxy.as_deref(), Some("http://proxy:3128"));
+        assert_eq!(
+            config.http.ssl_ca_path.as_deref(),
+            Some(Path::new("/etc/ssl/corp.pem"))
         );
     }
 
     #[test]
-    fn config_defaults() {
-        let config = GerritConfig::default();
-        assert_eq!(config.branch, "master");
-        assert_eq!(config.remote, "gerrit");
-        assert_eq!(config.scheme, "ssh");
-        assert!(config.default_rebase);
-        assert!(!config.track);
-        assert!(!config.notopic);
-        assert!(!config.usepushurl);
-        assert!(config.ssl_verify);
-        assert!(config.username.is_none());
+    fn proxy_and_ca_from_git_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |key: &str| match key {
+            "http.proxy" => Some("proxy.corp:8080".to_string()),
+            "http.sslCAInfo" => Some("/etc/ssl/git.pem".to_string()),
+            _ => None,
+        };
+        let config =
+            load_config_with_env(dir.path(), git, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.http.proxy.as_deref(), Some("proxy.corp:8080"));
+        assert_eq!(
+            config.http.ssl_ca_path.as_deref(),
+            Some(Path::new("/etc/ssl/git.pem"))
+        );
+
+        // GIT_SSL_CAINFO beats http.sslCAInfo, as in git
+        let env = |key: &str| (key == "GIT_SSL_CAINFO").then(|| "/tmp/env.pem".to_string());
+        let config = load_config_with_env(dir.path(), git, env, &CliOverrides::default()).unwrap();
+        assert_eq!(
+            config.http.ssl_ca_path.as_deref(),
+            Some(Path::new("/tmp/env.pem"))
+        );
     }
 
     #[test]
-    fn config_cli_overrides_file() {
+    fn empty_env_value_is_ignored() {
         let dir = tempfile::tempdir().unwrap();
-        let gitreview = dir.path().join(".gitreview");
         std::fs::write(
-            &gitreview,
-            "[gerrit]\nhost=file.example.com\nproject=file/project\n",
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=file.example.com\n",
         )
         .unwrap();
 
-        let cli = CliOverrides {
-            host: Some("cli.example.com".into()),
-            ..Default::default()
-        };
+        let config = load_config_with_env(
+            dir.path(),
+            |_| None,
+            |key| (key == "GERRIT_HOST").then(String::new),
+            &CliOverrides::default(),
+        )
+        .unwrap();
+        assert_eq!(config.host, "file.example.com");
+    }
 
-        let config = load_config(dir.path(), |_| None, &cli).unwrap();
-        assert_eq!(config.host, "cli.example.com");
-        assert_eq!(config.project, "file/project");
+    #[test]
+    fn invalid_env_port_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = load_config_with_env(
+            dir.path(),
+            |_| None,
+            |key| (key == "GERRIT_PORT").then(|| "abc".to_string()),
+            &CliOverrides::default(),
+        );
+        assert!(result.is_err());
     }
 
     #[test]
@@ -742,7 +2128,8 @@ project=my/project
         )
         .unwrap();
 
-        let config = load_config(dir.path(), |_| None, &CliOverrides::default()).unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
         assert_eq!(config.ssh_port, Some(29418));
         assert_eq!(config.http_port, None);
     }
@@ -931,7 +2318,8 @@ auth_type = "basic"
         )
         .unwrap();
 
-        let config = load_config(dir.path(), |_| None, &CliOverrides::default()).unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
         assert_eq!(
             config.project, "openstack/nova",
             ".git suffix should be stripped from project name"
@@ -974,12 +2362,13 @@ DefaultBranch=develop
         let gitreview = dir.path().join(".gitreview");
         std::fs::write(&gitreview, "[gerrit]\nproject=my/project\n").unwrap();
 
-        let config = load_config(
+        let config = load_config_with_env(
             dir.path(),
             |key| match key {
                 "gitreview.hostname" => Some("fallback.example.com".to_string()),
                 _ => None,
             },
+            |_| None,
             &CliOverrides::default(),
         )
         .unwrap();
@@ -992,13 +2381,14 @@ DefaultBranch=develop
         let gitreview = dir.path().join(".gitreview");
         std::fs::write(&gitreview, "[gerrit]\nproject=my/project\n").unwrap();
 
-        let config = load_config(
+        let config = load_config_with_env(
             dir.path(),
             |key| match key {
                 "gitreview.host" => Some("primary.example.com".to_string()),
                 "gitreview.hostname" => Some("fallback.example.com".to_string()),
                 _ => None,
             },
+            |_| None,
             &CliOverrides::default(),
         )
         .unwrap();
@@ -1015,7 +2405,8 @@ DefaultBranch=develop
         )
         .unwrap();
 
-        let config = load_config(dir.path(), |_| None, &CliOverrides::default()).unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
         assert_eq!(config.scheme, "ssh");
         assert_eq!(
             config.ssh_port,
@@ -1034,7 +2425,8 @@ DefaultBranch=develop
         )
         .unwrap();
 
-        let config = load_config(dir.path(), |_| None, &CliOverrides::default()).unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
         assert_eq!(config.scheme, "https");
         assert_eq!(
             config.ssh_port, None,
@@ -1052,7 +2444,8 @@ DefaultBranch=develop
         )
         .unwrap();
 
-        let config = load_config(dir.path(), |_| None, &CliOverrides::default()).unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
         assert!(config.ssl_verify, "ssl_verify should default to true");
     }
 
@@ -1066,12 +2459,13 @@ DefaultBranch=develop
         )
         .unwrap();
 
-        let config = load_config(
+        let config = load_config_with_env(
             dir.path(),
             |key| match key {
                 "http.sslVerify" => Some("false".to_string()),
                 _ => None,
             },
+            |_| None,
             &CliOverrides::default(),
         )
         .unwrap();
@@ -1114,13 +2508,47 @@ DefaultBranch=develop
         )
         .unwrap();
 
-        let config = load_config(dir.path(), |_| None, &CliOverrides::default()).unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
         assert!(!config.default_rebase, "defaultrebase=0 should be false");
         assert!(config.track, "track=true should be true");
         assert!(config.notopic, "notopic=yes should be true");
         assert!(!config.usepushurl, "usepushurl=false should be false");
     }
 
+    #[test]
+    fn usepushurl_defaults_remote_to_origin() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=review.example.com\nproject=my/project\nusepushurl=true\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert!(config.usepushurl);
+        assert_eq!(config.remote, "origin");
+    }
+
+    #[test]
+    fn usepushurl_keeps_explicit_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=review.example.com\nproject=my/project\n",
+        )
+        .unwrap();
+
+        let cli = CliOverrides {
+            remote: Some("upstream".into()),
+            use_pushurl: Some(true),
+            ..Default::default()
+        };
+        let config = load_config_with_env(dir.path(), |_| None, |_| None, &cli).unwrap();
+        assert_eq!(config.remote, "upstream");
+    }
+
     #[test]
     fn gitreview_username_from_git_config() {
         let dir = tempfile::tempdir().unwrap();
@@ -1131,18 +2559,136 @@ DefaultBranch=develop
         )
         .unwrap();
 
-        let config = load_config(
+        let config = load_config_with_env(
             dir.path(),
             |key| match key {
                 "gitreview.username" => Some("testuser".to_string()),
                 _ => None,
             },
+            |_| None,
             &CliOverrides::default(),
         )
         .unwrap();
         assert_eq!(config.username.as_deref(), Some("testuser"));
     }
 
+    #[test]
+    fn signedpush_from_gitreview_and_git_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=review.example.com\nproject=my/project\nsignedpush=true\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert!(config.signed_push);
+
+        let config = load_config_with_env(
+            dir.path(),
+            |key| (key == "gitreview.signedpush").then(|| "false".to_string()),
+            |_| None,
+            &CliOverrides::default(),
+        )
+        .unwrap();
+        assert!(!config.signed_push, "git config overrides .gitreview");
+    }
+
+    // === default reviewers ===
+
+    #[test]
+    fn parse_reviewer_list_commas_and_spaces() {
+        assert_eq!(
+            parse_reviewer_list("alice, bob carol,,dave"),
+            vec!["alice", "bob", "carol", "dave"]
+        );
+    }
+
+    #[test]
+    fn parse_reviewer_list_empty() {
+        assert!(parse_reviewer_list("  ").is_empty());
+    }
+
+    #[test]
+    fn default_reviewers_from_git_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let gitreview = dir.path().join(".gitreview");
+        std::fs::write(
+            &gitreview,
+            "[gerrit]\nhost=review.example.com\nproject=my/project\n",
+        )
+        .unwrap();
+
+        let config = load_config_with_env(
+            dir.path(),
+            |key| match key {
+                "gitreview.defaultreviewers" => Some("alice,bob".to_string()),
+                _ => None,
+            },
+            |_| None,
+            &CliOverrides::default(),
+        )
+        .unwrap();
+        assert_eq!(config.default_reviewers, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn parse_gitreview_default_reviewers_and_cc() {
+        let content = "\
+[gerrit]
+host=review.example.com
+defaultreviewers=alice, bob
+defaultcc=carol dave
+";
+        let values = parse_gitreview(content).unwrap();
+        assert_eq!(values.get("defaultreviewers").unwrap(), "alice, bob");
+        assert_eq!(values.get("defaultcc").unwrap(), "carol dave");
+    }
+
+    #[test]
+    fn default_reviewers_and_cc_from_gitreview() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=review.example.com\ndefaultreviewers=alice,bob\ndefaultcc=carol\n",
+        )
+        .unwrap();
+
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
+        assert_eq!(config.default_reviewers, vec!["alice", "bob"]);
+        assert_eq!(config.default_cc, vec!["carol"]);
+    }
+
+    #[test]
+    fn default_reviewers_git_config_overrides_gitreview() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitreview"),
+            "[gerrit]\nhost=review.example.com\ndefaultreviewers=alice\n",
+        )
+        .unwrap();
+
+        let config = load_config_with_env(
+            dir.path(),
+            |key| match key {
+                "gitreview.defaultreviewers" => Some("zed".to_string()),
+                _ => None,
+            },
+            |_| None,
+            &CliOverrides::default(),
+        )
+        .unwrap();
+        assert_eq!(config.default_reviewers, vec!["zed"]);
+    }
+
+    #[test]
+    fn default_reviewers_empty_by_default() {
+        let config = GerritConfig::default();
+        assert!(config.default_reviewers.is_empty());
+    }
+
     // === URL rewriting tests ===
 
     #[test]
@@ -1331,6 +2877,34 @@ DefaultBranch=develop
         );
     }
 
+    #[test]
+    fn make_rewritten_remote_url_applies_instead_of() {
+        let config = GerritConfig {
+            host: "review.example.com".into(),
+            scheme: "ssh".into(),
+            ssh_port: Some(29418),
+            project: "openstack/nova".into(),
+            username: Some("alice".into()),
+            ..Default::default()
+        };
+        let rewrites = populate_rewrites(
+            "url.gerrit:.insteadof=ssh://alice@review.example.com:29418/\n\
+             url.ssh://push.example.com/.pushinsteadof=ssh://alice@review.example.com:29418/\n",
+        );
+        assert_eq!(
+            config.make_rewritten_remote_url(&rewrites, false),
+            "gerrit:openstack/nova"
+        );
+        assert_eq!(
+            config.make_rewritten_remote_url(&rewrites, true),
+            "ssh://push.example.com/openstack/nova"
+        );
+        assert_eq!(
+            config.make_rewritten_remote_url(&UrlRewrites::default(), false),
+            config.make_remote_url()
+        );
+    }
+
     #[test]
     fn load_default_server_returns_none_when_no_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -1428,7 +3002,8 @@ default = true
         // which we cannot easily override in a unit test. Instead, verify that
         // load_config with an empty repo root and no git config values returns
         // an empty host when no credentials default is available.
-        let config = load_config(dir.path(), |_| None, &CliOverrides::default()).unwrap();
+        let config =
+            load_config_with_env(dir.path(), |_| None, |_| None, &CliOverrides::default()).unwrap();
         // host will be empty unless a real credentials.toml with default=true exists
         // in the user's config dir — just verify the call succeeds without error.
         let _ = config.host;
@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Dependency chain of a change for `grt deps`, built from Gerrit's related
+//! changes of its current revision.
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::gerrit::RelatedChangeAndCommitInfo;
+
+/// State of a change in the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DepState {
+    Open,
+    Merged,
+    Abandoned,
+    Unknown,
+}
+
+impl DepState {
+    pub fn from_status(status: Option<&str>) -> Self {
+        match status {
+            Some("NEW") => DepState::Open,
+            Some("MERGED") => DepState::Merged,
+            Some("ABANDONED") => DepState::Abandoned,
+            _ => DepState::Unknown,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DepState::Open => "open",
+            DepState::Merged => "merged",
+            DepState::Abandoned => "abandoned",
+            DepState::Unknown => "unknown",
+        }
+    }
+}
+
+/// One change in the chain, newest first.
+#[derive(Debug, Serialize)]
+pub struct DepNode {
+    pub number: Option<i64>,
+    /// Patchset that is part of the chain.
+    pub patchset: Option<i32>,
+    pub current_patchset: Option<i32>,
+    pub state: DepState,
+    pub subject: String,
+    pub commit: Option<String>,
+    /// The change `grt deps` was asked about.
+    pub selected: bool,
+    /// Whether the next (older) entry is this commit's parent.
+    pub direct_parent: bool,
+}
+
+impl DepNode {
+    /// Whether the chain is built on a patchset that has since been replaced.
+    pub fn outdated(&self) -> bool {
+        matches!((self.patchset, self.current_patchset), (Some(ps), Some(cur)) if ps < cur)
+    }
+}
+
+/// Build the chain from related changes, marking `selected` by number.
+pub fn build_chain(related: &[RelatedChangeAndCommitInfo], selected: Option<i64>) -> Vec<DepNode> {
+    let commits: Vec<Option<&str>> = related
+        .iter()
+        .map(|r| r.commit.as_ref().and_then(|c| c.commit.as_deref()))
+        .collect();
+    related
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let commit = r.commit.as_ref();
+            let next = commits.get(i + 1).copied().flatten();
+            let direct_parent = match (commit, next) {
+                (Some(c), Some(next)) => {
+                    c.parents.iter().any(|p| p.commit.as_deref() == Some(next))
+                }
+                _ => false,
+            };
+            DepNode {
+                number: r.change_number,
+                patchset: r.revision_number,
+                current_patchset: r.current_revision_number,
+                state: DepState::from_status(r.status.as_deref()),
+                subject: commit
+                    .and_then(|c| c.subject.clone())
+                    .unwrap_or_else(|| "-".to_string()),
+                commit: commit.and_then(|c| c.commit.clone()),
+                selected: selected.is_some() && r.change_number == selected,
+                direct_parent,
+            }
+        })
+        .collect()
+}
+
+/// Render the chain as a vertical graph, newest on top.
+///
+/// `@` marks the selected change and `o` the others. Consecutive entries are
+/// joined by `|` when the lower one is the parent of the upper one, and by
+/// `:` when other commits sit between them.
+pub fn format_graph(chain: &[DepNode]) -> String {
+    let mut out = String::new();
+    let labels: Vec<String> = chain
+        .iter()
+        .map(|node| match (node.number, node.patchset) {
+            (Some(n), Some(ps)) => format!("{n},{ps}"),
+            (Some(n), None) => n.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect();
+    let width = labels.iter().map(String::len).max().unwrap_or(0);
+    for (i, (node, label)) in chain.iter().zip(&labels).enumerate() {
+        if i > 0 {
+            let joint = if chain[i - 1].direct_parent { "|" } else { ":" };
+            let _ = writeln!(out, "{joint}");
+        }
+        let marker = if node.selected { '@' } else { 'o' };
+        let _ = write!(
+            out,
+            "{marker} {label:width$}  {:9}  {}",
+            node.state.as_str(),
+            node.subject
+        );
+        if node.outdated() {
+            let _ = write!(
+                out,
+                "  (outdated: patchset {} is current)",
+                node.current_patchset.unwrap_or(0)
+            );
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn related(json: &str) -> Vec<RelatedChangeAndCommitInfo> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn chain() -> Vec<RelatedChangeAndCommitInfo> {
+        related(
+            r#"[
+            {"_change_number":3,"_revision_number":1,"_current_revision_number":1,"status":"NEW",
+             "commit":{"commit":"c3","parents":[{"commit":"c2"}],"subject":"Add tests"}},
+            {"_change_number":2,"_revision_number":2,"_current_revision_number":3,"status":"NEW",
+             "commit":{"commit":"c2","parents":[{"commit":"c1"}],"subject":"Implement feature"}},
+            {"_change_number":1,"_revision_number":4,"_current_revision_number":4,"status":"MERGED",
+             "commit":{"commit":"c1","parents":[{"commit":"c0"}],"subject":"Refactor"}}
+        ]"#,
+        )
+    }
+
+    #[test]
+    fn build_chain_marks_selected_and_parents() {
+        let nodes = build_chain(&chain(), Some(2));
+        assert_eq!(nodes.len(), 3);
+        assert!(nodes[1].selected);
+        assert!(!nodes[0].selected);
+        assert!(nodes[0].direct_parent);
+        assert!(nodes[1].direct_parent);
+        assert!(!nodes[2].direct_parent);
+        assert_eq!(nodes[2].state, DepState::Merged);
+        assert!(nodes[1].outdated());
+        assert!(!nodes[0].outdated());
+    }
+
+    #[test]
+    fn format_graph_renders_chain() {
+        let text = format_graph(&build_chain(&chain(), Some(2)));
+        assert_eq!(
+            text,
+            "o 3,1  open       Add tests\n\
+             |\n\
+             @ 2,2  open       Implement feature  (outdated: patchset 3 is current)\n\
+             |\n\
+             o 1,4  merged     Refactor\n"
+        );
+    }
+
+    #[test]
+    fn gap_in_chain_uses_dotted_joint() {
+        let nodes = build_chain(
+            &related(
+                r#"[
+                {"_change_number":5,"status":"ABANDONED","commit":{"commit":"b","parents":[{"commit":"x"}],"subject":"B"}},
+                {"_change_number":4,"status":"NEW","commit":{"commit":"a","subject":"A"}}
+            ]"#,
+            ),
+            None,
+        );
+        let text = format_graph(&nodes);
+        assert!(text.contains("o 5  abandoned  B\n:\no 4  open"), "{text}");
+    }
+
+    #[test]
+    fn state_from_status() {
+        assert_eq!(DepState::from_status(Some("NEW")), DepState::Open);
+        assert_eq!(DepState::from_status(None), DepState::Unknown);
+    }
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! `grt diff`: show a change's patch with the local git.
+
+use crate::hook;
+use crate::ui::ColorWhen;
+
+/// Pager to force on `git diff`, if any.
+///
+/// git already honours `$GIT_PAGER` and `core.pager`, so those win. Only
+/// when neither is set and `delta` is installed is `delta` used instead of
+/// git's default pager.
+pub fn diff_pager(
+    git_pager: Option<&str>,
+    core_pager: Option<&str>,
+    delta_available: bool,
+) -> Option<String> {
+    let configured = |p: Option<&str>| p.is_some_and(|p| !p.trim().is_empty());
+    if configured(git_pager) || configured(core_pager) || !delta_available {
+        return None;
+    }
+    Some("delta".to_string())
+}
+
+/// Arguments for `git diff` between `from` and `to`.
+pub fn diff_args(pager: Option<&str>, color: ColorWhen, from: &str, to: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(pager) = pager {
+        args.push("-c".to_string());
+        args.push(format!("core.pager={pager}"));
+    }
+    args.push("diff".to_string());
+    args.push(format!("--color={}", color.as_str()));
+    args.push(from.to_string());
+    args.push(to.to_string());
+    args
+}
+
+/// Whether an executable called `name` is on `PATH`.
+pub fn on_path(name: &str) -> bool {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path_var).any(|dir| {
+        let path = dir.join(name);
+        hook::is_executable(&path)
+            || (cfg!(windows) && hook::is_executable(&path.with_extension("exe")))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_used_when_no_pager_configured() {
+        assert_eq!(diff_pager(None, None, true).as_deref(), Some("delta"));
+        assert_eq!(diff_pager(Some(""), None, true).as_deref(), Some("delta"));
+    }
+
+    #[test]
+    fn configured_pager_wins_over_delta() {
+        assert_eq!(diff_pager(Some("less -R"), None, true), None);
+        assert_eq!(diff_pager(None, Some("bat"), true), None);
+    }
+
+    #[test]
+    fn no_pager_forced_without_delta() {
+        assert_eq!(diff_pager(None, None, false), None);
+    }
+
+    #[test]
+    fn diff_args_with_pager() {
+        assert_eq!(
+            diff_args(Some("delta"), ColorWhen::Auto, "abc^", "abc"),
+            vec![
+                "-c",
+                "core.pager=delta",
+                "diff",
+                "--color=auto",
+                "abc^",
+                "abc"
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_args_without_pager() {
+        assert_eq!(
+            diff_args(None, ColorWhen::Never, "base", "abc")[..2],
+            ["diff", "--color=never"]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
@@ -20,6 +20,56 @@ pub enum ExportTarget {
         #[arg(long)]
         clean: bool,
     },
+
+    /// Write man pages for grt and its subcommands
+    Man {
+        /// Directory to write the pages to (created if missing)
+        #[arg(short, long, value_name = "DIR", default_value = "man")]
+        out_dir: PathBuf,
+    },
+
+    /// Print the recommended git aliases as `git config` commands
+    Aliases {
+        /// Print a `[alias]` section for a gitconfig file instead
+        #[arg(long)]
+        gitconfig: bool,
+    },
+}
+
+/// Git aliases recommended by `grt export aliases`: alias name and the grt
+/// subcommand it runs.
+pub const GIT_ALIASES: &[(&str, &str)] = &[
+    ("review", "review"),
+    ("gerrit-comments", "comments"),
+    ("gerrit-status", "status"),
+];
+
+/// Render [`GIT_ALIASES`] as `git config --global` commands, or with
+/// `gitconfig` as an `[alias]` section.
+pub fn format_aliases(gitconfig: bool) -> String {
+    let mut out = String::new();
+    if gitconfig {
+        out.push_str("[alias]\n");
+    }
+    for (alias, command) in GIT_ALIASES {
+        if gitconfig {
+            out.push_str(&format!("\t{alias} = !grt {command}\n"));
+        } else {
+            out.push_str(&format!(
+                "git config --global alias.{alias} '!grt {command}'\n"
+            ));
+        }
+    }
+    out
+}
+
+/// Write a man page for `cmd` and each visible subcommand into `out_dir`
+/// (`grt.1`, `grt-review.1`, ...).
+pub fn write_man_pages(cmd: clap::Command, out_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(out_dir)
+        .with_context(|| format!("creating directory {}", out_dir.display()))?;
+    clap_mangen::generate_to(cmd, out_dir)
+        .with_context(|| format!("writing man pages to {}", out_dir.display()))
 }
 
 /// Resolve the target path for the git-review symlink (`~/.local/bin/git-review`).
@@ -40,8 +90,18 @@ fn is_in_path(dir: &std::path::Path) -> bool {
     false
 }
 
-pub fn cmd_export(args: &ExportArgs) -> Result<()> {
+/// Run `grt export`; `cli` is grt's command definition, for man pages.
+pub fn cmd_export(args: &ExportArgs, cli: clap::Command) -> Result<()> {
     match &args.target {
+        ExportTarget::Man { out_dir } => {
+            write_man_pages(cli, out_dir)?;
+            eprintln!("Wrote man pages to {}", out_dir.display());
+            Ok(())
+        }
+        ExportTarget::Aliases { gitconfig } => {
+            print!("{}", format_aliases(*gitconfig));
+            Ok(())
+        }
         ExportTarget::GitReview { clean } => {
             let symlink_path = git_review_symlink_path()?;
 
@@ -139,4 +199,54 @@ mod tests {
         let cli = TestCli::parse_from(["test", "git-review", "--clean"]);
         assert!(matches!(cli.cmd, ExportTarget::GitReview { clean: true }));
     }
+
+    #[test]
+    fn export_args_parse_man_and_aliases() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct TestCli {
+            #[command(subcommand)]
+            cmd: ExportTarget,
+        }
+
+        let cli = TestCli::parse_from(["test", "man"]);
+        assert!(
+            matches!(cli.cmd, ExportTarget::Man { ref out_dir } if out_dir == Path::new("man"))
+        );
+        let cli = TestCli::parse_from(["test", "man", "-o", "/tmp/pages"]);
+        assert!(
+            matches!(cli.cmd, ExportTarget::Man { ref out_dir } if out_dir == Path::new("/tmp/pages"))
+        );
+        let cli = TestCli::parse_from(["test", "aliases", "--gitconfig"]);
+        assert!(matches!(cli.cmd, ExportTarget::Aliases { gitconfig: true }));
+    }
+
+    #[test]
+    fn aliases_as_git_config_commands() {
+        let out = format_aliases(false);
+        assert!(out.starts_with("git config --global alias.review '!grt review'\n"));
+        assert!(out.contains("git config --global alias.gerrit-comments '!grt comments'\n"));
+        assert_eq!(out.lines().count(), GIT_ALIASES.len());
+    }
+
+    #[test]
+    fn aliases_as_gitconfig_section() {
+        let out = format_aliases(true);
+        assert!(out.starts_with("[alias]\n\treview = !grt review\n"));
+        assert!(out.contains("\tgerrit-comments = !grt comments\n"));
+    }
+
+    #[test]
+    fn man_pages_for_command_and_subcommands() {
+        let cmd = clap::Command::new("grt")
+            .about("test")
+            .subcommand(clap::Command::new("review").about("Push changes"));
+        let tmp = tempfile::tempdir().unwrap();
+        let out_dir = tmp.path().join("man");
+        write_man_pages(cmd, &out_dir).unwrap();
+        let page = std::fs::read_to_string(out_dir.join("grt-review.1")).unwrap();
+        assert!(page.contains("Push changes"), "{page}");
+        assert!(out_dir.join("grt.1").exists());
+    }
 }
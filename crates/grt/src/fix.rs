@@ -0,0 +1,479 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Applying fixes suggested in review comments (`grt fix`).
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::path::{Component, Path};
+
+use anyhow::{bail, Context, Result};
+
+use crate::gerrit::{CommentInfo, CommentRange};
+
+/// One suggested fix, flattened out of the comment that carries it.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub comment_id: Option<String>,
+    /// Robot ID for robot comments, otherwise the commenter's name.
+    pub author: String,
+    /// File the comment is on (replacements may touch other files).
+    pub path: String,
+    pub line: Option<i32>,
+    pub description: String,
+    pub replacements: Vec<Replacement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub path: String,
+    pub start_line: i32,
+    pub start_character: i32,
+    pub end_line: i32,
+    pub end_character: i32,
+    pub text: String,
+}
+
+impl Replacement {
+    fn new(path: &str, range: &CommentRange, text: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            start_line: range.start_line,
+            start_character: range.start_character,
+            end_line: range.end_line,
+            end_character: range.end_character,
+            text: text.to_string(),
+        }
+    }
+}
+
+impl Suggestion {
+    /// Files this suggestion edits, sorted and deduplicated.
+    pub fn files(&self) -> Vec<&str> {
+        let files: BTreeSet<&str> = self.replacements.iter().map(|r| r.path.as_str()).collect();
+        files.into_iter().collect()
+    }
+}
+
+/// Collect the fix suggestions from a comments map (file path → comments),
+/// optionally restricted to one patchset, ordered by file and line.
+pub fn collect_suggestions(
+    comments: &HashMap<String, Vec<CommentInfo>>,
+    patchset: Option<i32>,
+) -> Vec<Suggestion> {
+    let mut out = Vec::new();
+    for (file, file_comments) in comments {
+        for comment in file_comments {
+            if patchset.is_some_and(|ps| comment.patch_set.is_some_and(|cps| cps != ps)) {
+                continue;
+            }
+            let Some(fixes) = &comment.fix_suggestions else {
+                continue;
+            };
+            let author = comment
+                .robot_id
+                .clone()
+                .or_else(|| comment.author.as_ref().and_then(|a| a.name.clone()))
+                .unwrap_or_else(|| "Unknown".to_string());
+            for fix in fixes {
+                if fix.replacements.is_empty() {
+                    continue;
+                }
+                out.push(Suggestion {
+                    comment_id: comment.id.clone(),
+                    author: author.clone(),
+                    path: comment.path.clone().unwrap_or_else(|| file.clone()),
+                    line: comment.line,
+                    description: fix
+                        .description
+                        .clone()
+                        .unwrap_or_else(|| "suggested fix".to_string()),
+                    replacements: fix
+                        .replacements
+                        .iter()
+                        .map(|r| Replacement::new(&r.path, &r.range, &r.replacement))
+                        .collect(),
+                });
+            }
+        }
+    }
+    out.sort_by(|a, b| {
+        (
+            a.path.as_str(),
+            a.line.unwrap_or(0),
+            a.comment_id.as_deref(),
+        )
+            .cmp(&(
+                b.path.as_str(),
+                b.line.unwrap_or(0),
+                b.comment_id.as_deref(),
+            ))
+    });
+    out
+}
+
+/// Numbered listing of suggestions for the picker and `--list`.
+pub fn format_suggestions(suggestions: &[Suggestion]) -> String {
+    let mut out = String::new();
+    for (i, s) in suggestions.iter().enumerate() {
+        let location = match s.line {
+            Some(line) => format!("{}:{line}", s.path),
+            None => s.path.clone(),
+        };
+        let _ = writeln!(
+            out,
+            "[{}] {location} ({}): {}",
+            i + 1,
+            s.author,
+            s.description.lines().next().unwrap_or_default()
+        );
+        for r in &s.replacements {
+            let _ = writeln!(
+                out,
+                "      {}:{}:{}-{}:{}",
+                r.path, r.start_line, r.start_character, r.end_line, r.end_character
+            );
+            for line in r.text.lines() {
+                let _ = writeln!(out, "      + {line}");
+            }
+            if r.text.is_empty() {
+                out.push_str("      (delete)\n");
+            }
+        }
+    }
+    out
+}
+
+/// Parse a picker answer into 0-based indices.
+///
+/// Accepts `all`/`a`, an empty answer or `none`/`n` (nothing), or a
+/// comma-separated list of 1-based numbers and ranges such as `1,3-4`.
+pub fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "all" | "a" => return Ok((0..count).collect()),
+        "" | "none" | "n" => return Ok(Vec::new()),
+        _ => {}
+    }
+    let mut picked = BTreeSet::new();
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (lo, hi) = match part.split_once('-') {
+            Some((lo, hi)) => (lo.trim(), hi.trim()),
+            None => (part, part),
+        };
+        let parse = |n: &str| -> Result<usize> {
+            let n: usize = n
+                .parse()
+                .with_context(|| format!("invalid selection '{part}'"))?;
+            if n == 0 || n > count {
+                bail!("selection {n} out of range (1-{count})");
+            }
+            Ok(n - 1)
+        };
+        let (lo, hi) = (parse(lo)?, parse(hi)?);
+        if lo > hi {
+            bail!("invalid range '{part}'");
+        }
+        picked.extend(lo..=hi);
+    }
+    Ok(picked.into_iter().collect())
+}
+
+/// Byte offset of (`line`, `character`) in `content`; lines are 1-based,
+/// characters 0-based. Line `n + 1` column 0 addresses the end of a file
+/// with `n` lines.
+fn offset(content: &str, line: i32, character: i32) -> Option<usize> {
+    if line < 1 || character < 0 {
+        return None;
+    }
+    let mut start = 0;
+    for _ in 1..line {
+        start += content[start..].find('\n')? + 1;
+    }
+    let line_end = content[start..]
+        .find('\n')
+        .map_or(content.len(), |i| start + i);
+    let pos = content[start..line_end]
+        .char_indices()
+        .map(|(i, _)| start + i)
+        .chain(std::iter::once(line_end))
+        .nth(character as usize)?;
+    Some(pos)
+}
+
+/// Apply replacements to `content`. Ranges refer to the original content
+/// and may not overlap.
+pub fn apply_replacements(content: &str, replacements: &[&Replacement]) -> Result<String> {
+    let mut spans = Vec::with_capacity(replacements.len());
+    for r in replacements {
+        let start = offset(content, r.start_line, r.start_character);
+        let end = offset(content, r.end_line, r.end_character);
+        let (Some(start), Some(end)) = (start, end) else {
+            bail!(
+                "{}: range {}:{}-{}:{} is outside the file",
+                r.path,
+                r.start_line,
+                r.start_character,
+                r.end_line,
+                r.end_character
+            );
+        };
+        if start > end {
+            bail!("{}: range ends before it starts", r.path);
+        }
+        spans.push((start, end, r.text.as_str()));
+    }
+    spans.sort_by_key(|&(start, end, _)| (start, end));
+    for pair in spans.windows(2) {
+        if pair[1].0 < pair[0].1 {
+            bail!(
+                "{}: suggested fixes overlap; apply them one at a time",
+                replacements[0].path
+            );
+        }
+    }
+    let mut out = String::with_capacity(content.len());
+    let mut pos = 0;
+    for (start, end, text) in spans {
+        out.push_str(&content[pos..start]);
+        out.push_str(text);
+        pos = end;
+    }
+    out.push_str(&content[pos..]);
+    Ok(out)
+}
+
+/// Reject paths that would escape the working tree, and Gerrit's magic
+/// files, which have no counterpart on disk.
+fn check_path(path: &str) -> Result<()> {
+    if path.starts_with('/') {
+        bail!("cannot apply a fix to {path}: not a file in the tree");
+    }
+    if Path::new(path)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        bail!("refusing to apply a fix outside the working tree: {path}");
+    }
+    Ok(())
+}
+
+/// Apply the chosen suggestions under `root`, returning the files changed.
+///
+/// Every file is checked and rewritten in memory first, so a bad range in
+/// one suggestion leaves the working tree untouched.
+pub fn apply_suggestions(root: &Path, suggestions: &[&Suggestion]) -> Result<Vec<String>> {
+    let mut by_file: HashMap<&str, Vec<&Replacement>> = HashMap::new();
+    for s in suggestions {
+        for r in &s.replacements {
+            check_path(&r.path)?;
+            by_file.entry(r.path.as_str()).or_default().push(r);
+        }
+    }
+    let mut files: Vec<&str> = by_file.keys().copied().collect();
+    files.sort_unstable();
+
+    let mut updated = Vec::with_capacity(files.len());
+    for file in &files {
+        let path = root.join(file);
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        updated.push((path, apply_replacements(&content, &by_file[file])?));
+    }
+    for (path, content) in updated {
+        std::fs::write(&path, content).with_context(|| format!("writing {}", path.display()))?;
+    }
+    Ok(files.into_iter().map(str::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gerrit::{AccountInfo, FixReplacementInfo, FixSuggestionInfo};
+
+    fn repl(path: &str, sl: i32, sc: i32, el: i32, ec: i32, text: &str) -> Replacement {
+        Replacement {
+            path: path.to_string(),
+            start_line: sl,
+            start_character: sc,
+            end_line: el,
+            end_character: ec,
+            text: text.to_string(),
+        }
+    }
+
+    fn comment(id: &str, line: i32, ps: i32, fixes: Option<Vec<FixSuggestionInfo>>) -> CommentInfo {
+        CommentInfo {
+            id: Some(id.to_string()),
+            path: Some("src/a.rs".to_string()),
+            line: Some(line),
+            range: None,
+            in_reply_to: None,
+            message: Some("fix this".to_string()),
+            updated: None,
+            author: Some(AccountInfo {
+                account_id: Some(1),
+                name: Some("Alice".to_string()),
+                email: None,
+                username: None,
+                display_name: None,
+                tags: None,
+            }),
+            patch_set: Some(ps),
+            unresolved: Some(true),
+            robot_id: None,
+            fix_suggestions: fixes,
+        }
+    }
+
+    fn fix(description: &str, line: i32, text: &str) -> FixSuggestionInfo {
+        FixSuggestionInfo {
+            fix_id: None,
+            description: Some(description.to_string()),
+            replacements: vec![FixReplacementInfo {
+                path: "src/a.rs".to_string(),
+                range: CommentRange {
+                    start_line: line,
+                    start_character: 0,
+                    end_line: line,
+                    end_character: 3,
+                },
+                replacement: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn replaces_within_a_line() {
+        let r = repl("f", 2, 4, 2, 7, "baz");
+        let out = apply_replacements("one\nlet bar = 1;\n", &[&r]).unwrap();
+        assert_eq!(out, "one\nlet baz = 1;\n");
+    }
+
+    #[test]
+    fn replaces_across_lines_and_at_eof() {
+        let join = repl("f", 1, 3, 2, 0, " ");
+        let append = repl("f", 4, 0, 4, 0, "four\n");
+        let out = apply_replacements("one\ntwo\nthree\n", &[&append, &join]).unwrap();
+        assert_eq!(out, "one two\nthree\nfour\n");
+    }
+
+    #[test]
+    fn character_offsets_count_chars() {
+        let r = repl("f", 1, 3, 1, 4, "e");
+        assert_eq!(apply_replacements("caf\u{e9}!", &[&r]).unwrap(), "cafe!");
+    }
+
+    #[test]
+    fn rejects_overlapping_and_out_of_range() {
+        let a = repl("f", 1, 0, 1, 3, "x");
+        let b = repl("f", 1, 2, 1, 4, "y");
+        assert!(apply_replacements("abcd\n", &[&a, &b])
+            .unwrap_err()
+            .to_string()
+            .contains("overlap"));
+        let far = repl("f", 9, 0, 9, 1, "z");
+        assert!(apply_replacements("abcd\n", &[&far])
+            .unwrap_err()
+            .to_string()
+            .contains("outside the file"));
+        let wide = repl("f", 1, 0, 1, 10, "z");
+        assert!(apply_replacements("abcd\n", &[&wide]).is_err());
+    }
+
+    #[test]
+    fn parse_selection_forms() {
+        assert_eq!(parse_selection("all", 3).unwrap(), vec![0, 1, 2]);
+        assert!(parse_selection("", 3).unwrap().is_empty());
+        assert!(parse_selection("n", 3).unwrap().is_empty());
+        assert_eq!(parse_selection("3, 1", 3).unwrap(), vec![0, 2]);
+        assert_eq!(parse_selection("1-2,2", 3).unwrap(), vec![0, 1]);
+        assert!(parse_selection("4", 3).is_err());
+        assert!(parse_selection("0", 3).is_err());
+        assert!(parse_selection("x", 3).is_err());
+        assert!(parse_selection("3-1", 3).is_err());
+    }
+
+    #[test]
+    fn collects_and_orders_suggestions() {
+        let mut map = HashMap::new();
+        map.insert(
+            "src/a.rs".to_string(),
+            vec![
+                comment("c2", 20, 2, Some(vec![fix("second", 20, "b")])),
+                comment("c1", 10, 2, Some(vec![fix("first\nmore", 10, "a")])),
+                comment("c0", 5, 1, Some(vec![fix("old", 5, "z")])),
+                comment("c3", 30, 2, None),
+            ],
+        );
+        let all = collect_suggestions(&map, None);
+        assert_eq!(all.len(), 3);
+        let current = collect_suggestions(&map, Some(2));
+        let descriptions: Vec<&str> = current.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(descriptions, ["first\nmore", "second"]);
+        assert_eq!(current[0].author, "Alice");
+        assert_eq!(current[0].files(), ["src/a.rs"]);
+
+        let listing = format_suggestions(&current);
+        assert!(listing.starts_with("[1] src/a.rs:10 (Alice): first\n"));
+        assert!(listing.contains("      src/a.rs:10:0-10:3\n      + a\n"));
+    }
+
+    #[test]
+    fn robot_id_names_the_author() {
+        let mut c = comment("c1", 1, 1, Some(vec![fix("lint", 1, "x")]));
+        c.robot_id = Some("clippy".to_string());
+        let map = HashMap::from([("src/a.rs".to_string(), vec![c])]);
+        assert_eq!(collect_suggestions(&map, None)[0].author, "clippy");
+    }
+
+    #[test]
+    fn apply_writes_files_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/a.rs"), "foo();\nbar();\n").unwrap();
+        let good = Suggestion {
+            comment_id: None,
+            author: "A".to_string(),
+            path: "src/a.rs".to_string(),
+            line: Some(1),
+            description: "rename".to_string(),
+            replacements: vec![repl("src/a.rs", 1, 0, 1, 3, "baz")],
+        };
+        let bad = Suggestion {
+            replacements: vec![repl("src/a.rs", 7, 0, 7, 1, "x")],
+            ..good.clone()
+        };
+        assert!(apply_suggestions(dir.path(), &[&good, &bad]).is_err());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/a.rs")).unwrap(),
+            "foo();\nbar();\n"
+        );
+
+        let files = apply_suggestions(dir.path(), &[&good]).unwrap();
+        assert_eq!(files, ["src/a.rs"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/a.rs")).unwrap(),
+            "baz();\nbar();\n"
+        );
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_tree() {
+        let escape = Suggestion {
+            comment_id: None,
+            author: "A".to_string(),
+            path: "x".to_string(),
+            line: None,
+            description: "d".to_string(),
+            replacements: vec![repl("../etc/passwd", 1, 0, 1, 0, "x")],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        assert!(apply_suggestions(dir.path(), &[&escape]).is_err());
+        let magic = Suggestion {
+            replacements: vec![repl("/COMMIT_MSG", 1, 0, 1, 0, "x")],
+            ..escape.clone()
+        };
+        assert!(apply_suggestions(dir.path(), &[&magic]).is_err());
+    }
+}
@@ -1,11 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 use url::Url;
@@ -13,6 +16,80 @@ use url::Url;
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 const MAX_RETRIES: u32 = 3;
+/// Changes requested per page by [`GerritClient::query_changes_paginated`].
+pub const QUERY_PAGE_SIZE: usize = 100;
+
+/// Configure the reqwest client builder shared by all Gerrit clients.
+///
+/// `https` is whether the Gerrit base URL uses TLS; it picks which proxy
+/// environment variable applies.
+fn client_builder(
+    ssl_verify: bool,
+    http: &HttpOptions,
+    https: bool,
+) -> Result<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(format!("grt/{}", env!("CARGO_PKG_VERSION")));
+
+    if !ssl_verify {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if http.http1_only {
+        builder = builder.http1_only();
+    }
+    if let Some(timeout) = http.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(timeout);
+    }
+    if let Some(proxy) = resolve_proxy(http.proxy.as_deref(), https, |key| std::env::var(key).ok())
+    {
+        let proxy = reqwest::Proxy::all(&proxy)
+            .with_context(|| format!("invalid proxy URL {proxy:?}"))?
+            .no_proxy(reqwest::NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = &http.ssl_ca_path {
+        let pem =
+            std::fs::read(path).with_context(|| format!("reading CA bundle {}", path.display()))?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("parsing CA bundle {}", path.display()))?;
+        if certs.is_empty() {
+            anyhow::bail!("CA bundle {} contains no certificates", path.display());
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    Ok(builder)
+}
+
+/// Proxy to use for the REST API, as git picks it: a configured proxy
+/// (`http.proxy`) wins, else `https_proxy` for TLS or `http_proxy` for plain
+/// HTTP, then `all_proxy`. Upper-case variants are accepted except
+/// `HTTP_PROXY`, which curl ignores too since CGI servers set it from
+/// request headers. A value without a scheme is taken as an HTTP proxy.
+pub fn resolve_proxy(
+    configured: Option<&str>,
+    https: bool,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let non_empty = |v: String| (!v.trim().is_empty()).then(|| v.trim().to_string());
+    let from_env: &[&str] = if https {
+        &["https_proxy", "HTTPS_PROXY", "all_proxy", "ALL_PROXY"]
+    } else {
+        &["http_proxy", "all_proxy", "ALL_PROXY"]
+    };
+    let proxy = configured
+        .map(str::to_string)
+        .and_then(non_empty)
+        .or_else(|| from_env.iter().find_map(|key| env(key).and_then(non_empty)))?;
+    if proxy.contains("://") {
+        Some(proxy)
+    } else {
+        Some(format!("http://{proxy}"))
+    }
+}
 
 /// Typed errors from the Gerrit REST API.
 #[derive(Debug, thiserror::Error)]
@@ -68,12 +145,39 @@ impl std::fmt::Debug for Credentials {
     }
 }
 
+/// HTTP transport tuning for [`GerritClient`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpOptions {
+    /// Force HTTP/1.1 instead of negotiating HTTP/2 (some proxies stall on HTTP/2).
+    pub http1_only: bool,
+    /// How long idle pooled connections are kept; `None` keeps reqwest's default.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Proxy from configuration (`http.proxy`); the environment is consulted
+    /// when unset, see [`resolve_proxy`].
+    pub proxy: Option<String>,
+    /// PEM bundle of extra CA certificates to trust (`http.sslCAInfo`).
+    pub ssl_ca_path: Option<PathBuf>,
+}
+
+/// Source of replacement credentials when Gerrit answers 401 mid-session,
+/// e.g. a git credential helper that holds a newer token.
+pub trait CredentialRefresher: std::fmt::Debug + Send + Sync {
+    /// Tell the source that `creds` were refused.
+    fn reject(&self, creds: &Credentials);
+    /// Fetch credentials again; `None` when the source has nothing.
+    fn fill(&self) -> Option<Credentials>;
+    /// Tell the source that `creds` worked.
+    fn approve(&self, creds: &Credentials);
+}
+
 /// Client for the Gerrit REST API.
 #[derive(Debug)]
 pub struct GerritClient {
     client: reqwest::Client,
     base_url: Url,
-    credentials: Option<Credentials>,
+    /// Behind a lock so a refresh after a 401 applies to later requests.
+    credentials: RwLock<Option<Credentials>>,
+    refresher: Option<Arc<dyn CredentialRefresher>>,
 }
 
 impl GerritClient {
@@ -81,32 +185,47 @@ impl GerritClient {
     ///
     /// When `ssl_verify` is `false`, TLS certificate verification is disabled.
     pub fn new(base_url: Url, credentials: Option<Credentials>, ssl_verify: bool) -> Result<Self> {
-        let mut builder = reqwest::Client::builder()
-            .connect_timeout(CONNECT_TIMEOUT)
-            .timeout(REQUEST_TIMEOUT)
-            .user_agent(format!("grt/{}", env!("CARGO_PKG_VERSION")));
-
-        if !ssl_verify {
-            builder = builder.danger_accept_invalid_certs(true);
-        }
+        Self::with_http_options(base_url, credentials, ssl_verify, &HttpOptions::default())
+    }
 
-        let client = builder.build().context("building HTTP client")?;
+    /// Create a new Gerrit REST client with explicit HTTP transport options.
+    pub fn with_http_options(
+        base_url: Url,
+        credentials: Option<Credentials>,
+        ssl_verify: bool,
+        http: &HttpOptions,
+    ) -> Result<Self> {
+        let client = client_builder(ssl_verify, http, base_url.scheme() == "https")?
+            .build()
+            .context("building HTTP client")?;
 
         Ok(Self {
             client,
             base_url,
-            credentials,
+            credentials: RwLock::new(credentials),
+            refresher: None,
         })
     }
 
     /// Set or replace the credentials used for authentication.
     pub fn set_credentials(&mut self, creds: Credentials) {
-        self.credentials = Some(creds);
+        *self
+            .credentials
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner()) = Some(creds);
     }
 
     /// Return the current credentials, if any.
-    pub fn credentials(&self) -> Option<&Credentials> {
-        self.credentials.as_ref()
+    pub fn credentials(&self) -> Option<Credentials> {
+        self.credentials
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Ask `refresher` for new credentials once when a request gets a 401.
+    pub fn set_credential_refresher(&mut self, refresher: Arc<dyn CredentialRefresher>) {
+        self.refresher = Some(refresher);
     }
 
     /// Build the full URL for an API endpoint path.
@@ -115,7 +234,11 @@ impl GerritClient {
     /// which would discard any sub-path prefix (e.g. `/gerrit/`).
     fn api_url(&self, path: &str) -> Result<Url> {
         // Gerrit authenticated endpoints use /a/ prefix
-        let prefix = if self.credentials.is_some() { "/a" } else { "" };
+        let prefix = if self.credentials().is_some() {
+            "/a"
+        } else {
+            ""
+        };
         let full_path = format!("{}{}", prefix, path);
 
         // Split off any query string so set_path doesn't percent-encode `?`.
@@ -137,7 +260,7 @@ impl GerritClient {
     /// Build authorization headers if credentials are available.
     fn auth_headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
-        if let Some(ref creds) = self.credentials {
+        if let Some(ref creds) = self.credentials() {
             let header_value = match creds.auth_type {
                 AuthType::Bearer => format!("Bearer {}", creds.password),
                 AuthType::Basic => {
@@ -152,12 +275,24 @@ impl GerritClient {
         headers
     }
 
-    /// Perform a single GET request returning a typed error.
-    async fn get_once(&self, url: &Url) -> std::result::Result<String, GerritError> {
-        let resp = self
+    /// Perform a single request returning a typed error.
+    ///
+    /// `body` is sent as JSON when present. The XSSI prefix is stripped from
+    /// the response; an empty body (e.g. `204 No Content`) yields `""`.
+    async fn send_once(
+        &self,
+        method: Method,
+        url: &Url,
+        body: Option<&serde_json::Value>,
+    ) -> std::result::Result<String, GerritError> {
+        let mut request = self
             .client
-            .get(url.clone())
-            .headers(self.auth_headers())
+            .request(method, url.clone())
+            .headers(self.auth_headers());
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let resp = request
             .send()
             .await
             .map_err(|e| GerritError::Network(e.to_string()))?;
@@ -181,21 +316,64 @@ impl GerritClient {
         Ok(strip_xssi_prefix(&body))
     }
 
-    /// Perform a GET request with retry on transient errors.
+    /// Perform a single request, refreshing credentials once on a 401.
+    ///
+    /// Gerrit rejects the request before acting on it, so repeating it is
+    /// safe even for POST. The refused credentials are rejected with the
+    /// refresher; replacements are approved once they work.
+    async fn send_authed(
+        &self,
+        method: Method,
+        url: &Url,
+        body: Option<&serde_json::Value>,
+    ) -> std::result::Result<String, GerritError> {
+        let err = match self.send_once(method.clone(), url, body).await {
+            Err(err @ GerritError::AuthFailed { status: 401 }) => err,
+            other => return other,
+        };
+        let (Some(refresher), Some(stale)) = (&self.refresher, self.credentials()) else {
+            return Err(err);
+        };
+        refresher.reject(&stale);
+        let Some(fresh) = refresher.fill() else {
+            return Err(err);
+        };
+        if fresh.username == stale.username && fresh.password == stale.password {
+            return Err(err);
+        }
+        tracing::debug!("retrying {method} {url} with refreshed credentials");
+        *self.credentials.write().unwrap_or_else(|e| e.into_inner()) = Some(fresh.clone());
+        let result = self.send_once(method, url, body).await;
+        match &result {
+            Ok(_) => refresher.approve(&fresh),
+            Err(GerritError::AuthFailed { status: 401 }) => refresher.reject(&fresh),
+            Err(_) => {}
+        }
+        result
+    }
+
+    /// Perform a request with retry on transient errors.
     ///
     /// Retries up to 3 times with exponential backoff (1s, 2s, 4s) on
     /// 5xx server errors and network failures. Does not retry on 4xx.
-    async fn get(&self, path: &str) -> Result<String> {
+    /// Only used for idempotent methods (GET, PUT, DELETE).
+    async fn send_with_retry(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<String> {
         let url = self.api_url(path)?;
         let mut last_err = None;
 
         for attempt in 0..=MAX_RETRIES {
-            match self.get_once(&url).await {
+            match self.send_authed(method.clone(), &url, body).await {
                 Ok(body) => return Ok(body),
                 Err(e) if e.is_retryable() && attempt < MAX_RETRIES => {
                     let delay = Duration::from_secs(1 << attempt);
                     warn!(
-                        "request to {} failed (attempt {}/{}): {}, retrying in {}s",
+                        "{} {} failed (attempt {}/{}): {}, retrying in {}s",
+                        method,
                         path,
                         attempt + 1,
                         MAX_RETRIES + 1,
@@ -214,6 +392,34 @@ impl GerritClient {
         Err(last_err.unwrap()).context(format!("Gerrit API request to {path} (exhausted retries)"))
     }
 
+    /// Perform a GET request with retry on transient errors.
+    async fn get(&self, path: &str) -> Result<String> {
+        self.send_with_retry(Method::GET, path, None).await
+    }
+
+    /// Perform a POST request with a JSON body, returning the response body.
+    ///
+    /// Not retried: POST endpoints (review, submit, abandon) are not
+    /// idempotent, and a retry after a lost response could apply twice.
+    pub async fn post_json<T: Serialize>(&self, path: &str, body: &T) -> Result<String> {
+        let url = self.api_url(path)?;
+        let body = serde_json::to_value(body).context("encoding request body")?;
+        self.send_authed(Method::POST, &url, Some(&body))
+            .await
+            .with_context(|| format!("Gerrit API request to {path}"))
+    }
+
+    /// Perform a PUT request with a JSON body, retrying transient errors.
+    pub async fn put_json<T: Serialize>(&self, path: &str, body: &T) -> Result<String> {
+        let body = serde_json::to_value(body).context("encoding request body")?;
+        self.send_with_retry(Method::PUT, path, Some(&body)).await
+    }
+
+    /// Perform a DELETE request, retrying transient errors.
+    pub async fn delete(&self, path: &str) -> Result<String> {
+        self.send_with_retry(Method::DELETE, path, None).await
+    }
+
     /// Get the Gerrit server version.
     pub async fn get_version(&self) -> Result<String> {
         let body = self.get("/config/server/version").await?;
@@ -221,21 +427,101 @@ impl GerritClient {
         Ok(version)
     }
 
+    /// Get the server's configuration summary (`/config/server/info`).
+    pub async fn get_server_info(&self) -> Result<ServerInfo> {
+        let body = self.get("/config/server/info").await?;
+        serde_json::from_str(&body).context("parsing server info")
+    }
+
     /// Get the authenticated user's account info.
     pub async fn get_self_account(&self) -> Result<AccountInfo> {
         let body = self.get("/accounts/self").await?;
         serde_json::from_str(&body).context("parsing account info")
     }
 
+    /// Return true if a group named `name` exists and is visible to the caller.
+    pub async fn group_exists(&self, name: &str) -> Result<bool> {
+        let path = format!("/groups/{}", urlencoding::encode(name));
+        match self.get(&path).await {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.downcast_ref::<GerritError>(), Some(GerritError::NotFound)) => {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     /// Query changes using Gerrit query syntax.
+    ///
+    /// Fetches every page, so results are not cut off at the server's
+    /// default query limit.
     pub async fn query_changes(&self, query: &str) -> Result<Vec<ChangeInfo>> {
+        self.query_changes_paginated(query, None, |_| {}).await
+    }
+
+    /// Query changes page by page (`n=`/`S=`), following `_more_changes`.
+    ///
+    /// Stops after `limit` changes when set; `None` fetches every page.
+    /// `on_page` is called with the running total after each page.
+    pub async fn query_changes_paginated(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        on_page: impl FnMut(usize),
+    ) -> Result<Vec<ChangeInfo>> {
+        self.query_changes_with_options(query, limit, &[], on_page)
+            .await
+    }
+
+    /// Like [`Self::query_changes_paginated`], requesting extra `o=` options
+    /// (e.g. `MESSAGES`) on top of `CURRENT_REVISION` and `DETAILED_ACCOUNTS`.
+    pub async fn query_changes_with_options(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        options: &[&str],
+        mut on_page: impl FnMut(usize),
+    ) -> Result<Vec<ChangeInfo>> {
+        if limit == Some(0) {
+            return Ok(Vec::new());
+        }
         let encoded_query = urlencoding::encode(query);
-        let path = format!(
-            "/changes/?q={}&o=CURRENT_REVISION&o=DETAILED_ACCOUNTS",
-            encoded_query
-        );
+        let mut changes: Vec<ChangeInfo> = Vec::new();
+        loop {
+            let page_size = match limit {
+                Some(limit) => QUERY_PAGE_SIZE.min(limit - changes.len()),
+                None => QUERY_PAGE_SIZE,
+            };
+            let mut path = format!(
+                "/changes/?q={}&n={}&S={}&o=CURRENT_REVISION&o=DETAILED_ACCOUNTS",
+                encoded_query,
+                page_size,
+                changes.len()
+            );
+            for option in options {
+                path.push_str(&format!("&o={option}"));
+            }
+            let body = self.get(&path).await?;
+            let page: Vec<ChangeInfo> =
+                serde_json::from_str(&body).context("parsing change list")?;
+            let more = page.last().and_then(|c| c.more_changes).unwrap_or(false);
+            let page_len = page.len();
+            changes.extend(page);
+            on_page(changes.len());
+
+            let at_limit = limit.is_some_and(|limit| changes.len() >= limit);
+            if !more || page_len == 0 || at_limit {
+                break;
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Get basic change information (project, number, status, subject).
+    pub async fn get_change(&self, change_id: &str) -> Result<ChangeInfo> {
+        let path = format!("/changes/{}", urlencoding::encode(change_id));
         let body = self.get(&path).await?;
-        serde_json::from_str(&body).context("parsing change list")
+        serde_json::from_str(&body).context("parsing change")
     }
 
     /// Get detailed change information.
@@ -248,6 +534,29 @@ impl GerritClient {
         serde_json::from_str(&body).context("parsing change detail")
     }
 
+    /// Get a change with detailed labels, messages and submit requirements.
+    ///
+    /// Servers older than 3.5 reject `SUBMIT_REQUIREMENTS` with 400, so the
+    /// request is repeated without it and only labels are available.
+    pub async fn get_change_status(&self, change_id: &str) -> Result<ChangeStatusInfo> {
+        let base = format!(
+            "/changes/{}/detail?o=CURRENT_REVISION&o=DETAILED_ACCOUNTS&o=DETAILED_LABELS&o=MESSAGES&o=SUBMITTABLE",
+            urlencoding::encode(change_id)
+        );
+        let body = match self.get(&format!("{base}&o=SUBMIT_REQUIREMENTS")).await {
+            Err(e)
+                if matches!(
+                    e.downcast_ref::<GerritError>(),
+                    Some(GerritError::ServerError { status: 400, .. })
+                ) =>
+            {
+                self.get(&base).await?
+            }
+            other => other?,
+        };
+        serde_json::from_str(&body).context("parsing change status")
+    }
+
     /// Get change detail with ALL_REVISIONS (needed for download/cherry-pick).
     pub async fn get_change_all_revisions(&self, change_id: &str) -> Result<ChangeInfo> {
         let path = format!(
@@ -283,6 +592,333 @@ impl GerritClient {
         serde_json::from_str(&body).context("parsing revision comments")
     }
 
+    /// Post a review on a revision (`POST /changes/{id}/revisions/{rev}/review`).
+    pub async fn set_review(
+        &self,
+        change_id: &str,
+        revision: &str,
+        input: &ReviewInput,
+    ) -> Result<()> {
+        let path = format!(
+            "/changes/{}/revisions/{}/review",
+            urlencoding::encode(change_id),
+            urlencoding::encode(revision)
+        );
+        self.post_json(&path, input).await?;
+        Ok(())
+    }
+
+    /// Add a reviewer, account or group (`POST /changes/{id}/reviewers`).
+    pub async fn add_reviewer(&self, change_id: &str, reviewer: &str) -> Result<()> {
+        let result = self
+            .post_reviewer(change_id, reviewer, ReviewerState::Reviewer)
+            .await?;
+        match result.error {
+            Some(error) => anyhow::bail!("adding reviewer {reviewer}: {error}"),
+            None => Ok(()),
+        }
+    }
+
+    /// Add a reviewer or CC (`POST /changes/{id}/reviewers`).
+    ///
+    /// Gerrit reports unknown accounts in the result's `error` field rather
+    /// than as an HTTP error, so callers must check it.
+    pub async fn post_reviewer(
+        &self,
+        change_id: &str,
+        reviewer: &str,
+        state: ReviewerState,
+    ) -> Result<AddReviewerResult> {
+        let path = format!("/changes/{}/reviewers", urlencoding::encode(change_id));
+        let input = serde_json::json!({ "reviewer": reviewer, "state": state.as_str() });
+        let body = self.post_json(&path, &input).await?;
+        serde_json::from_str(&body).context("parsing add reviewer result")
+    }
+
+    /// Remove a reviewer or CC (`DELETE /changes/{id}/reviewers/{account}`).
+    pub async fn remove_reviewer(&self, change_id: &str, account: &str) -> Result<()> {
+        let path = format!(
+            "/changes/{}/reviewers/{}",
+            urlencoding::encode(change_id),
+            urlencoding::encode(account)
+        );
+        self.delete(&path).await?;
+        Ok(())
+    }
+
+    /// Reviewers of a change keyed by state (`REVIEWER`, `CC`, `REMOVED`).
+    pub async fn get_reviewers_by_state(
+        &self,
+        change_id: &str,
+    ) -> Result<BTreeMap<String, Vec<AccountInfo>>> {
+        #[derive(Deserialize)]
+        struct Reviewers {
+            #[serde(default)]
+            reviewers: BTreeMap<String, Vec<AccountInfo>>,
+        }
+        let path = format!(
+            "/changes/{}?o=DETAILED_LABELS&o=DETAILED_ACCOUNTS",
+            urlencoding::encode(change_id)
+        );
+        let body = self.get(&path).await?;
+        let parsed: Reviewers = serde_json::from_str(&body).context("parsing change reviewers")?;
+        Ok(parsed.reviewers)
+    }
+
+    /// Suggest accounts and groups matching `query` (`GET /changes/{id}/suggest_reviewers`).
+    pub async fn suggest_reviewers(
+        &self,
+        change_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SuggestedReviewerInfo>> {
+        let path = format!(
+            "/changes/{}/suggest_reviewers?q={}&n={limit}",
+            urlencoding::encode(change_id),
+            urlencoding::encode(query)
+        );
+        let body = self.get(&path).await?;
+        serde_json::from_str(&body).context("parsing reviewer suggestions")
+    }
+
+    /// Submit a change (`POST /changes/{id}/submit`), returning the updated change.
+    pub async fn submit_change(&self, change_id: &str) -> Result<ChangeInfo> {
+        let path = format!("/changes/{}/submit", urlencoding::encode(change_id));
+        let body = self.post_json(&path, &serde_json::json!({})).await?;
+        serde_json::from_str(&body).context("parsing submitted change")
+    }
+
+    /// Get the commit of a revision (`GET /changes/{id}/revisions/{rev}/commit`).
+    pub async fn get_revision_commit(&self, change_id: &str, revision: &str) -> Result<CommitInfo> {
+        let path = format!(
+            "/changes/{}/revisions/{}/commit",
+            urlencoding::encode(change_id),
+            urlencoding::encode(revision)
+        );
+        let body = self.get(&path).await?;
+        serde_json::from_str(&body).context("parsing revision commit")
+    }
+
+    /// Get a revision as a patch (`GET /changes/{id}/revisions/{rev}/patch`).
+    ///
+    /// Gerrit sends the patch base64-encoded; it is returned decoded.
+    pub async fn get_revision_patch(&self, change_id: &str, revision: &str) -> Result<String> {
+        let path = format!(
+            "/changes/{}/revisions/{}/patch",
+            urlencoding::encode(change_id),
+            urlencoding::encode(revision)
+        );
+        let body = self.get(&path).await?;
+        let bytes = base64_decode(&body).context("decoding patch")?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Get a file's content at a revision
+    /// (`GET /changes/{id}/revisions/{rev}/files/{file}/content`), decoded.
+    pub async fn get_file_content(
+        &self,
+        change_id: &str,
+        revision: &str,
+        file: &str,
+    ) -> Result<String> {
+        let path = format!(
+            "/changes/{}/revisions/{}/files/{}/content",
+            urlencoding::encode(change_id),
+            urlencoding::encode(revision),
+            urlencoding::encode(file)
+        );
+        let body = self.get(&path).await?;
+        let bytes = base64_decode(&body).context("decoding file content")?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Abandon a change (`POST /changes/{id}/abandon`), returning the updated change.
+    pub async fn abandon_change(
+        &self,
+        change_id: &str,
+        message: Option<&str>,
+    ) -> Result<ChangeInfo> {
+        self.change_action(change_id, "abandon", message).await
+    }
+
+    /// Restore an abandoned change (`POST /changes/{id}/restore`), returning the updated change.
+    pub async fn restore_change(
+        &self,
+        change_id: &str,
+        message: Option<&str>,
+    ) -> Result<ChangeInfo> {
+        self.change_action(change_id, "restore", message).await
+    }
+
+    /// Mark a change work in progress (`POST /changes/{id}/wip`).
+    pub async fn set_work_in_progress(&self, change_id: &str, message: Option<&str>) -> Result<()> {
+        self.wip_action(change_id, "wip", message).await
+    }
+
+    /// Mark a work-in-progress change ready for review (`POST /changes/{id}/ready`).
+    pub async fn set_ready_for_review(&self, change_id: &str, message: Option<&str>) -> Result<()> {
+        self.wip_action(change_id, "ready", message).await
+    }
+
+    /// Cherry-pick a revision onto another branch on the server
+    /// (`POST /changes/{id}/revisions/{rev}/cherrypick`), returning the new change.
+    pub async fn cherry_pick_revision(
+        &self,
+        change_id: &str,
+        revision: &str,
+        input: &CherryPickInput,
+    ) -> Result<ChangeInfo> {
+        let path = format!(
+            "/changes/{}/revisions/{}/cherrypick",
+            urlencoding::encode(change_id),
+            urlencoding::encode(revision)
+        );
+        let body = self
+            .post_json(&path, input)
+            .await
+            .with_context(|| format!("cherry-picking onto {}", input.destination))?;
+        serde_json::from_str(&body).context("parsing cherry-picked change")
+    }
+
+    /// Create a draft comment on a revision (`PUT /changes/{id}/revisions/{rev}/drafts`).
+    pub async fn create_draft(
+        &self,
+        change_id: &str,
+        revision: &str,
+        input: &CommentInput,
+    ) -> Result<CommentInfo> {
+        let path = format!(
+            "/changes/{}/revisions/{}/drafts",
+            urlencoding::encode(change_id),
+            urlencoding::encode(revision)
+        );
+        let body = self.put_json(&path, input).await?;
+        serde_json::from_str(&body).context("parsing draft comment")
+    }
+
+    /// The caller's draft comments on all revisions, keyed by file path.
+    pub async fn get_drafts(&self, change_id: &str) -> Result<HashMap<String, Vec<CommentInfo>>> {
+        let path = format!("/changes/{}/drafts", urlencoding::encode(change_id));
+        let body = self.get(&path).await?;
+        serde_json::from_str(&body).context("parsing draft comments")
+    }
+
+    /// Set a change's topic (`PUT /changes/{id}/topic`); `None` clears it.
+    pub async fn set_topic(&self, change_id: &str, topic: Option<&str>) -> Result<()> {
+        let path = format!("/changes/{}/topic", urlencoding::encode(change_id));
+        match topic {
+            Some(topic) => {
+                self.put_json(&path, &serde_json::json!({ "topic": topic }))
+                    .await?
+            }
+            None => self.delete(&path).await?,
+        };
+        Ok(())
+    }
+
+    /// Changes related to the current revision by git ancestry, descendants
+    /// first; empty when the change stands alone.
+    pub async fn get_related_changes(
+        &self,
+        change_id: &str,
+    ) -> Result<Vec<RelatedChangeAndCommitInfo>> {
+        let path = format!(
+            "/changes/{}/revisions/current/related",
+            urlencoding::encode(change_id)
+        );
+        let body = self.get(&path).await?;
+        let related: RelatedChangesInfo =
+            serde_json::from_str(&body).context("parsing related changes")?;
+        Ok(related.changes)
+    }
+
+    /// Get a change's hashtags.
+    pub async fn get_hashtags(&self, change_id: &str) -> Result<Vec<String>> {
+        let path = format!("/changes/{}/hashtags", urlencoding::encode(change_id));
+        let body = self.get(&path).await?;
+        serde_json::from_str(&body).context("parsing hashtags")
+    }
+
+    /// Add and remove hashtags (`POST /changes/{id}/hashtags`); returns the
+    /// change's hashtags afterwards.
+    pub async fn set_hashtags(
+        &self,
+        change_id: &str,
+        add: &[String],
+        remove: &[String],
+    ) -> Result<Vec<String>> {
+        let path = format!("/changes/{}/hashtags", urlencoding::encode(change_id));
+        let input = serde_json::json!({ "add": add, "remove": remove });
+        let body = self.post_json(&path, &input).await?;
+        serde_json::from_str(&body).context("parsing hashtags")
+    }
+
+    /// Add `user` to the attention set (`POST /changes/{id}/attention`).
+    pub async fn add_to_attention_set(
+        &self,
+        change_id: &str,
+        user: &str,
+        reason: &str,
+    ) -> Result<AccountInfo> {
+        let path = format!("/changes/{}/attention", urlencoding::encode(change_id));
+        let input = serde_json::json!({ "user": user, "reason": reason });
+        let body = self
+            .post_json(&path, &input)
+            .await
+            .with_context(|| format!("adding {user} to the attention set"))?;
+        serde_json::from_str(&body).context("parsing attention set account")
+    }
+
+    /// Remove `user` from the attention set.
+    ///
+    /// Uses `POST /changes/{id}/attention/{account}/delete`, the form of the
+    /// endpoint that accepts a reason.
+    pub async fn remove_from_attention_set(
+        &self,
+        change_id: &str,
+        user: &str,
+        reason: &str,
+    ) -> Result<()> {
+        let path = format!(
+            "/changes/{}/attention/{}/delete",
+            urlencoding::encode(change_id),
+            urlencoding::encode(user)
+        );
+        let input = serde_json::json!({ "reason": reason });
+        self.post_json(&path, &input)
+            .await
+            .with_context(|| format!("removing {user} from the attention set"))?;
+        Ok(())
+    }
+
+    /// POST a change action that takes an optional message (abandon, restore).
+    async fn change_action(
+        &self,
+        change_id: &str,
+        action: &str,
+        message: Option<&str>,
+    ) -> Result<ChangeInfo> {
+        let path = format!("/changes/{}/{action}", urlencoding::encode(change_id));
+        let input = match message {
+            Some(message) => serde_json::json!({ "message": message }),
+            None => serde_json::json!({}),
+        };
+        let body = self.post_json(&path, &input).await?;
+        serde_json::from_str(&body).with_context(|| format!("parsing change after {action}"))
+    }
+
+    /// POST a `WorkInProgressInput` to `wip` or `ready`; Gerrit answers with
+    /// no body.
+    async fn wip_action(&self, change_id: &str, action: &str, message: Option<&str>) -> Result<()> {
+        let path = format!("/changes/{}/{action}", urlencoding::encode(change_id));
+        let input = match message {
+            Some(message) => serde_json::json!({ "message": message }),
+            None => serde_json::json!({}),
+        };
+        self.post_json(&path, &input).await?;
+        Ok(())
+    }
+
     /// Get robot comments on a change.
     pub async fn get_robot_comments(
         &self,
@@ -317,6 +953,30 @@ fn base64_encode(input: &str) -> String {
     String::from_utf8(buf).unwrap()
 }
 
+/// Decode standard base64, ignoring whitespace and padding.
+fn base64_decode(input: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for c in input.bytes() {
+        if c.is_ascii_whitespace() || c == b'=' {
+            continue;
+        }
+        let value = BASE64_CHARS
+            .iter()
+            .position(|&b| b == c)
+            .with_context(|| format!("invalid base64 character {:?}", c as char))?;
+        acc = (acc << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
 /// Minimal base64 encoder (avoids adding a dependency just for this).
 struct Base64Encoder<W: std::io::Write> {
     writer: W,
@@ -387,7 +1047,7 @@ impl<W: std::io::Write> std::io::Write for Base64Encoder<W> {
 
 // ---- Gerrit API response types ----
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountInfo {
     #[serde(rename = "_account_id")]
     pub account_id: Option<i64>,
@@ -395,6 +1055,8 @@ pub struct AccountInfo {
     pub email: Option<String>,
     pub username: Option<String>,
     pub display_name: Option<String>,
+    /// Account tags, e.g. `SERVICE_USER` for bots.
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -417,6 +1079,24 @@ pub struct ChangeInfo {
     pub insertions: Option<i64>,
     pub deletions: Option<i64>,
     pub labels: Option<HashMap<String, LabelInfo>>,
+    /// Users expected to act on the change, keyed by account id (Gerrit >= 3.3).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attention_set: Option<HashMap<String, AttentionSetInfo>>,
+    /// Set on the last change of a page when more results are available.
+    #[serde(
+        rename = "_more_changes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub more_changes: Option<bool>,
+}
+
+/// An entry of a change's attention set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttentionSetInfo {
+    pub account: AccountInfo,
+    pub last_update: Option<String>,
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -430,6 +1110,10 @@ pub struct RevisionInfo {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CommitInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<CommitInfo>,
     pub subject: Option<String>,
     pub message: Option<String>,
     pub author: Option<GitPersonInfo>,
@@ -455,9 +1139,33 @@ pub struct CommentInfo {
     pub author: Option<AccountInfo>,
     pub patch_set: Option<i32>,
     pub unresolved: Option<bool>,
+    /// Set on robot comments: the ID of the bot that posted them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub robot_id: Option<String>,
+    /// Edits the commenter proposes; mostly posted by robots, or by humans
+    /// through the web UI's "suggest fix" on newer releases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix_suggestions: Option<Vec<FixSuggestionInfo>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixSuggestionInfo {
+    pub fix_id: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub replacements: Vec<FixReplacementInfo>,
+}
+
+/// Replace `range` of `path` (in the revision the comment was made on)
+/// with `replacement`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixReplacementInfo {
+    pub path: String,
+    pub range: CommentRange,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommentRange {
     pub start_line: i32,
     pub start_character: i32,
@@ -469,6 +1177,42 @@ pub struct CommentRange {
 pub struct LabelInfo {
     pub all: Option<Vec<ApprovalInfo>>,
     pub default_value: Option<i32>,
+    /// Set when the label has reached its maximum value.
+    pub approved: Option<AccountInfo>,
+    /// Set when the label has a blocking minimum value.
+    pub rejected: Option<AccountInfo>,
+    pub recommended: Option<AccountInfo>,
+    pub disliked: Option<AccountInfo>,
+    pub optional: Option<bool>,
+}
+
+/// Outcome of one submit requirement (Gerrit 3.5+).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitRequirementResultInfo {
+    pub name: String,
+    pub description: Option<String>,
+    /// `SATISFIED`, `UNSATISFIED`, `OVERRIDDEN`, `NOT_APPLICABLE`, `ERROR` or `FORCED`.
+    pub status: String,
+    pub is_legacy: Option<bool>,
+    pub submittability_expression_result: Option<SubmitRequirementExpressionInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitRequirementExpressionInfo {
+    pub expression: Option<String>,
+    pub fulfilled: Option<bool>,
+    /// Atoms of the expression that did not match, e.g. `label:Verified=MAX`.
+    pub failing_atoms: Option<Vec<String>>,
+}
+
+/// A change with the fields needed to judge whether it can be submitted.
+#[derive(Debug, Deserialize)]
+pub struct ChangeStatusInfo {
+    #[serde(flatten)]
+    pub change: ChangeInfo,
+    #[serde(default)]
+    pub submit_requirements: Vec<SubmitRequirementResultInfo>,
+    pub submittable: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -480,10 +1224,157 @@ pub struct ApprovalInfo {
     pub value: Option<i32>,
 }
 
+/// Whether an account is added as a reviewer or only CC'd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewerState {
+    Reviewer,
+    Cc,
+}
+
+impl ReviewerState {
+    /// Value of the `state` field in `ReviewerInput`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReviewerState::Reviewer => "REVIEWER",
+            ReviewerState::Cc => "CC",
+        }
+    }
+}
+
+/// Response to `POST /changes/{id}/reviewers`.
+#[derive(Debug, Default, Deserialize)]
+pub struct AddReviewerResult {
+    pub input: Option<String>,
+    /// Set instead of an HTTP error when the reviewer could not be added.
+    pub error: Option<String>,
+    #[serde(default)]
+    pub reviewers: Vec<AccountInfo>,
+    #[serde(default)]
+    pub ccs: Vec<AccountInfo>,
+}
+
+/// Response of `GET /changes/{id}/revisions/{rev}/related`.
+#[derive(Debug, Deserialize)]
+pub struct RelatedChangesInfo {
+    #[serde(default)]
+    pub changes: Vec<RelatedChangeAndCommitInfo>,
+}
+
+/// One change in a chain of related changes.
+#[derive(Debug, Deserialize)]
+pub struct RelatedChangeAndCommitInfo {
+    pub project: Option<String>,
+    pub change_id: Option<String>,
+    pub commit: Option<CommitInfo>,
+    #[serde(rename = "_change_number")]
+    pub change_number: Option<i64>,
+    /// Patchset of this change that is part of the chain.
+    #[serde(rename = "_revision_number")]
+    pub revision_number: Option<i32>,
+    #[serde(rename = "_current_revision_number")]
+    pub current_revision_number: Option<i32>,
+    pub status: Option<String>,
+}
+
+/// The part of `GET /config/server/info` used to detect server capabilities.
+#[derive(Debug, Deserialize)]
+pub struct ServerInfo {
+    /// Sent by 2.x servers only; NoteDb is always on from 3.0.
+    pub note_db_enabled: Option<bool>,
+}
+
+/// An entry from `GET /changes/{id}/suggest_reviewers`: an account or a group.
+#[derive(Debug, Deserialize)]
+pub struct SuggestedReviewerInfo {
+    pub account: Option<AccountInfo>,
+    pub group: Option<GroupBaseInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GroupBaseInfo {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Request body for posting a review.
+#[derive(Debug, Default, Serialize)]
+pub struct ReviewInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Coarse notification level (`NONE`, `OWNER`, `OWNER_REVIEWERS`, `ALL`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify: Option<String>,
+    /// Additional recipients keyed by recipient type (`TO`, `CC`, `BCC`).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub notify_details: BTreeMap<String, NotifyInfo>,
+    /// Votes keyed by label name (e.g. `Code-Review` → `1`).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, i32>,
+    /// What to do with the caller's drafts (`KEEP`, `PUBLISH`, `PUBLISH_ALL_REVISIONS`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drafts: Option<String>,
+}
+
+/// Request body for cherry-picking a revision on the server.
+#[derive(Debug, Default, Serialize)]
+pub struct CherryPickInput {
+    /// Branch to cherry-pick onto.
+    pub destination: String,
+    /// Commit message; Gerrit keeps the original one when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// Add the original change's reviewers to the new change.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub keep_reviewers: bool,
+    /// Create the change with conflict markers instead of failing.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub allow_conflicts: bool,
+}
+
+/// Request body for creating a draft comment.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CommentInput {
+    pub path: String,
+    /// Line to comment on; `None` for a file-level comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<i32>,
+    pub message: String,
+    /// Id of the comment this replies to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_reply_to: Option<String>,
+    /// `None` inherits the parent's state (or unresolved-false without a parent).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unresolved: Option<bool>,
+}
+
+impl ReviewInput {
+    /// Add accounts to notify under the given recipient type (`TO`, `CC`, `BCC`).
+    pub fn add_notify_recipients(&mut self, recipient_type: &str, accounts: &[String]) {
+        if accounts.is_empty() {
+            return;
+        }
+        self.notify_details
+            .entry(recipient_type.to_string())
+            .or_default()
+            .accounts
+            .extend(accounts.iter().cloned());
+    }
+}
+
+/// Accounts to notify for one recipient type.
+#[derive(Debug, Default, Serialize)]
+pub struct NotifyInfo {
+    pub accounts: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChangeMessageInfo {
     pub id: Option<String>,
     pub author: Option<AccountInfo>,
+    /// e.g. `autogenerated:zuul:check` for CI results.
+    pub tag: Option<String>,
     pub date: Option<String>,
     pub message: Option<String>,
     #[serde(rename = "_revision_number")]
@@ -603,6 +1494,53 @@ mod tests {
         assert_eq!(msg.revision_number, Some(3));
     }
 
+    #[test]
+    fn serialize_review_input_empty() {
+        let input = ReviewInput::default();
+        assert_eq!(serde_json::to_string(&input).unwrap(), "{}");
+    }
+
+    #[test]
+    fn serialize_comment_input_skips_unset_fields() {
+        let input = CommentInput {
+            path: "src/lib.rs".into(),
+            message: "Done".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&input).unwrap(),
+            serde_json::json!({ "path": "src/lib.rs", "message": "Done" })
+        );
+    }
+
+    #[test]
+    fn serialize_review_input_with_notify_details() {
+        let mut input = ReviewInput {
+            notify: Some("NONE".into()),
+            ..Default::default()
+        };
+        input.add_notify_recipients("TO", &["alice@example.com".into()]);
+        input.add_notify_recipients("CC", &["bob".into(), "carol".into()]);
+        let json: serde_json::Value = serde_json::to_value(&input).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "notify": "NONE",
+                "notify_details": {
+                    "CC": { "accounts": ["bob", "carol"] },
+                    "TO": { "accounts": ["alice@example.com"] }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn review_input_ignores_empty_recipients() {
+        let mut input = ReviewInput::default();
+        input.add_notify_recipients("TO", &[]);
+        assert!(input.notify_details.is_empty());
+    }
+
     #[test]
     fn base64_encode_basic() {
         assert_eq!(base64_encode("user:pass"), "dXNlcjpwYXNz");
@@ -611,6 +1549,105 @@ mod tests {
         assert_eq!(base64_encode("abc"), "YWJj");
     }
 
+    #[test]
+    fn base64_decode_round_trips() {
+        for input in ["", "a", "ab", "abc", "user:pass", "line one\nline two\n"] {
+            let decoded = base64_decode(&base64_encode(input)).unwrap();
+            assert_eq!(decoded, input.as_bytes());
+        }
+        assert_eq!(base64_decode("YWJj\nZA==").unwrap(), b"abcd");
+        assert!(base64_decode("YW!j").is_err());
+    }
+
+    #[test]
+    fn client_builder_accepts_http1_only() {
+        let http = HttpOptions {
+            http1_only: true,
+            pool_idle_timeout: Some(Duration::from_secs(15)),
+            ..Default::default()
+        };
+        assert!(client_builder(true, &http, true).unwrap().build().is_ok());
+    }
+
+    #[test]
+    fn client_builder_accepts_proxy() {
+        let http = HttpOptions {
+            proxy: Some("proxy.example.com:3128".into()),
+            ..Default::default()
+        };
+        assert!(client_builder(true, &http, true).unwrap().build().is_ok());
+    }
+
+    #[test]
+    fn client_builder_reports_bad_ca_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = HttpOptions {
+            ssl_ca_path: Some(dir.path().join("missing.pem")),
+            ..Default::default()
+        };
+        let err = client_builder(true, &missing, true).unwrap_err();
+        assert!(format!("{err:#}").contains("reading CA bundle"), "{err:#}");
+
+        let empty = dir.path().join("empty.pem");
+        std::fs::write(&empty, "not a certificate\n").unwrap();
+        let http = HttpOptions {
+            ssl_ca_path: Some(empty),
+            ..Default::default()
+        };
+        assert!(client_builder(true, &http, true).is_err());
+    }
+
+    #[test]
+    fn configured_proxy_wins_over_env() {
+        let env = |key: &str| (key == "https_proxy").then(|| "http://env:8080".to_string());
+        assert_eq!(
+            resolve_proxy(Some("http://git:3128"), true, env).as_deref(),
+            Some("http://git:3128")
+        );
+        assert_eq!(
+            resolve_proxy(None, true, env).as_deref(),
+            Some("http://env:8080")
+        );
+        assert_eq!(
+            resolve_proxy(Some("  "), true, env).as_deref(),
+            Some("http://env:8080")
+        );
+    }
+
+    #[test]
+    fn proxy_env_depends_on_scheme() {
+        let env = |key: &str| match key {
+            "HTTPS_PROXY" => Some("https-proxy:1".to_string()),
+            "HTTP_PROXY" => Some("ignored:2".to_string()),
+            "ALL_PROXY" => Some("http://all:3".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            resolve_proxy(None, true, env).as_deref(),
+            Some("http://https-proxy:1")
+        );
+        assert_eq!(
+            resolve_proxy(None, false, env).as_deref(),
+            Some("http://all:3")
+        );
+        assert_eq!(resolve_proxy(None, false, |_| None), None);
+    }
+
+    #[test]
+    fn with_http_options_builds_client() {
+        let http = HttpOptions {
+            http1_only: true,
+            ..Default::default()
+        };
+        let client = GerritClient::with_http_options(
+            Url::parse("https://example.com").unwrap(),
+            None,
+            true,
+            &http,
+        );
+        assert!(client.is_ok());
+    }
+
     #[test]
     fn auth_headers_basic() {
         let creds = Credentials {
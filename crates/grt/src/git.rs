@@ -5,6 +5,11 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 
+/// HEAD is on an unborn branch: the repository has no commits yet.
+#[derive(Debug, thiserror::Error)]
+#[error("repository has no commits yet")]
+pub struct NoCommitsError;
+
 /// Wrapper around a gix repository providing read operations.
 pub struct GitRepo {
     repo: gix::Repository,
@@ -18,6 +23,9 @@ impl GitRepo {
     }
 
     /// Return the worktree root path.
+    ///
+    /// In a linked worktree (`git worktree add`) this is the linked checkout,
+    /// not the main one.
     pub fn root(&self) -> Result<PathBuf> {
         self.repo
             .workdir()
@@ -25,6 +33,18 @@ impl GitRepo {
             .ok_or_else(|| anyhow::anyhow!("repository is bare (no worktree)"))
     }
 
+    /// Return the git directory shared by all worktrees (e.g. `.git`).
+    pub fn common_dir(&self) -> PathBuf {
+        self.repo.common_dir().to_path_buf()
+    }
+
+    /// Return true when opened from a worktree created by `git worktree add`,
+    /// whose private git dir (`.git/worktrees/<name>`) holds only its HEAD,
+    /// index and in-progress state.
+    pub fn is_linked_worktree(&self) -> bool {
+        self.repo.git_dir() != self.repo.common_dir()
+    }
+
     /// Return the current branch name (the short ref, e.g. "main").
     /// Returns an error if HEAD is detached.
     pub fn current_branch(&self) -> Result<String> {
@@ -46,35 +66,48 @@ impl GitRepo {
     }
 
     /// Return the path to the hooks directory, respecting `core.hooksPath`.
+    ///
+    /// Without `core.hooksPath` this is `hooks` in the common git dir, which
+    /// git uses for every worktree of the repository.
     pub fn hooks_dir(&self) -> Result<PathBuf> {
         if let Some(custom) = self.config_value("core.hooksPath") {
-            let custom_path = Path::new(&custom);
+            let custom_path = crate::config::expand_tilde(&custom);
             if custom_path.is_absolute() {
-                return Ok(custom_path.to_path_buf());
+                return Ok(custom_path);
             }
             // Relative to worktree root
             let root = self.root()?;
             return Ok(root.join(custom_path));
         }
 
-        let git_dir = self.repo.git_dir().to_path_buf();
-        Ok(git_dir.join("hooks"))
+        Ok(self.common_dir().join("hooks"))
+    }
+
+    /// Return true if the hooks directory lies outside both the common git
+    /// dir and the worktree, e.g. a shared `core.hooksPath = ~/.githooks`.
+    ///
+    /// Installing a hook there affects every repository that shares it.
+    pub fn hooks_dir_outside_repo(&self) -> Result<bool> {
+        let hooks_dir = self.hooks_dir()?;
+        let inside = path_is_within(&hooks_dir, &self.common_dir())
+            || self
+                .root()
+                .map(|root| path_is_within(&hooks_dir, &root))
+                .unwrap_or(false);
+        Ok(!inside)
     }
 
     /// Get the upstream tracking remote and branch for the current branch.
     ///
     /// Reads `branch.<name>.remote` and `branch.<name>.merge` from git config.
-    /// Returns `Some((remote, branch))` if both are configured, `None` otherwise.
+    /// Returns `None` if `merge` is not configured. When `merge` is set but
+    /// `remote` is not, the remote is reported as [`LOCAL_REMOTE`] (`.`), the
+    /// same value git uses for a branch that tracks another local branch.
     pub fn upstream_branch(&self) -> Result<Option<(String, String)>> {
         let branch = self.current_branch()?;
         let remote_key = format!("branch.{branch}.remote");
         let merge_key = format!("branch.{branch}.merge");
 
-        let remote = match self.config_value(&remote_key) {
-            Some(r) => r,
-            None => return Ok(None),
-        };
-
         let merge = match self.config_value(&merge_key) {
             Some(m) => {
                 // Convert refs/heads/main -> main
@@ -83,6 +116,10 @@ impl GitRepo {
             None => return Ok(None),
         };
 
+        let remote = self
+            .config_value(&remote_key)
+            .unwrap_or_else(|| LOCAL_REMOTE.to_string());
+
         Ok(Some((remote, merge)))
     }
 
@@ -106,13 +143,32 @@ impl GitRepo {
     }
 
     /// Return the message of the HEAD commit.
+    ///
+    /// Fails with [`NoCommitsError`] on an unborn branch so callers can give
+    /// a clearer hint than a generic read error.
     pub fn head_commit_message(&self) -> Result<String> {
+        if self.repo.head().context("reading HEAD")?.is_unborn() {
+            return Err(NoCommitsError.into());
+        }
         let head = self.repo.head_commit().context("reading HEAD commit")?;
         let message = head.message_raw().context("reading commit message")?;
         Ok(message.to_string())
     }
 }
 
+/// Remote name git uses for a branch whose upstream is another local branch.
+pub const LOCAL_REMOTE: &str = ".";
+
+/// Return true if `path` is `base` or a descendant of it.
+///
+/// Both paths are canonicalized when they exist so symlinks (e.g. `/tmp` on
+/// macOS) do not cause false negatives.
+fn path_is_within(path: &Path, base: &Path) -> bool {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let base = base.canonicalize().unwrap_or_else(|_| base.to_path_buf());
+    path.starts_with(base)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +242,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn head_commit_message_without_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        git_cmd(&["init", "--initial-branch=master"], dir.path())
+            .output()
+            .expect("git init failed");
+        let repo = GitRepo::open(dir.path()).unwrap();
+        let err = repo.head_commit_message().unwrap_err();
+        assert!(err.is::<NoCommitsError>(), "{err:#}");
+        assert_eq!(err.to_string(), "repository has no commits yet");
+    }
+
     #[test]
     fn upstream_branch_configured() {
         let dir = tempfile::tempdir().unwrap();
@@ -205,6 +273,50 @@ mod tests {
         assert_eq!(result, Some(("origin".into(), "develop".into())));
     }
 
+    #[test]
+    fn upstream_branch_local_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        git_cmd(&["config", "branch.master.remote", "."], dir.path())
+            .output()
+            .unwrap();
+        git_cmd(
+            &["config", "branch.master.merge", "refs/heads/develop"],
+            dir.path(),
+        )
+        .output()
+        .unwrap();
+        let repo = GitRepo::open(dir.path()).unwrap();
+        let result = repo.upstream_branch().unwrap();
+        assert_eq!(result, Some((LOCAL_REMOTE.into(), "develop".into())));
+    }
+
+    #[test]
+    fn upstream_branch_merge_only() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        git_cmd(
+            &["config", "branch.master.merge", "refs/heads/stable"],
+            dir.path(),
+        )
+        .output()
+        .unwrap();
+        let repo = GitRepo::open(dir.path()).unwrap();
+        let result = repo.upstream_branch().unwrap();
+        assert_eq!(result, Some((".".into(), "stable".into())));
+    }
+
+    #[test]
+    fn upstream_branch_remote_only() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        git_cmd(&["config", "branch.master.remote", "origin"], dir.path())
+            .output()
+            .unwrap();
+        let repo = GitRepo::open(dir.path()).unwrap();
+        assert_eq!(repo.upstream_branch().unwrap(), None);
+    }
+
     #[test]
     fn upstream_branch_not_configured() {
         let dir = tempfile::tempdir().unwrap();
@@ -245,4 +357,113 @@ mod tests {
             "hooks dir should end with 'hooks': {hooks:?}"
         );
     }
+
+    #[test]
+    fn hooks_dir_inside_repo_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        let repo = GitRepo::open(dir.path()).unwrap();
+        assert!(!repo.hooks_dir_outside_repo().unwrap());
+    }
+
+    #[test]
+    fn hooks_dir_relative_hooks_path_is_inside() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        git_cmd(&["config", "core.hooksPath", ".githooks"], dir.path())
+            .output()
+            .unwrap();
+        let repo = GitRepo::open(dir.path()).unwrap();
+        assert!(!repo.hooks_dir_outside_repo().unwrap());
+    }
+
+    /// Add a linked worktree of `main` at `linked` on a new `feature` branch.
+    fn add_worktree(main: &Path, linked: &Path) {
+        let status = git_cmd(
+            &["worktree", "add", "-b", "feature", linked.to_str().unwrap()],
+            main,
+        )
+        .status()
+        .expect("git worktree add failed");
+        assert!(status.success());
+    }
+
+    #[test]
+    fn linked_worktree_root_and_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main");
+        let linked = dir.path().join("linked");
+        std::fs::create_dir(&main).unwrap();
+        init_repo(&main);
+        add_worktree(&main, &linked);
+
+        let repo = GitRepo::open(&linked).unwrap();
+        assert!(repo.is_linked_worktree());
+        assert_eq!(
+            repo.root().unwrap().canonicalize().unwrap(),
+            linked.canonicalize().unwrap()
+        );
+        assert_eq!(repo.current_branch().unwrap(), "feature");
+        assert_eq!(
+            repo.common_dir().canonicalize().unwrap(),
+            main.join(".git").canonicalize().unwrap()
+        );
+
+        let main_repo = GitRepo::open(&main).unwrap();
+        assert!(!main_repo.is_linked_worktree());
+        assert_eq!(main_repo.current_branch().unwrap(), "master");
+    }
+
+    #[test]
+    fn linked_worktree_shares_hooks_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main");
+        let linked = dir.path().join("linked");
+        std::fs::create_dir(&main).unwrap();
+        init_repo(&main);
+        add_worktree(&main, &linked);
+
+        // Hooks run from the main repository's hooks dir, not the linked
+        // worktree's private git dir
+        let repo = GitRepo::open(&linked).unwrap();
+        let hooks = repo.hooks_dir().unwrap();
+        assert!(hooks.ends_with("hooks"), "{hooks:?}");
+        assert_eq!(
+            hooks.parent().unwrap().canonicalize().unwrap(),
+            main.join(".git").canonicalize().unwrap()
+        );
+        assert!(!repo.hooks_dir_outside_repo().unwrap());
+    }
+
+    #[test]
+    fn linked_worktree_relative_hooks_path_uses_linked_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main");
+        let linked = dir.path().join("linked");
+        std::fs::create_dir(&main).unwrap();
+        init_repo(&main);
+        add_worktree(&main, &linked);
+        git_cmd(&["config", "core.hooksPath", ".githooks"], &main)
+            .output()
+            .unwrap();
+
+        let repo = GitRepo::open(&linked).unwrap();
+        assert!(repo.hooks_dir().unwrap().starts_with(repo.root().unwrap()));
+        assert!(!repo.hooks_dir_outside_repo().unwrap());
+    }
+
+    #[test]
+    fn hooks_dir_absolute_hooks_path_outside_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        git_cmd(
+            &["config", "core.hooksPath", shared.path().to_str().unwrap()],
+            dir.path(),
+        )
+        .output()
+        .unwrap();
+        let repo = GitRepo::open(dir.path()).unwrap();
+        assert!(repo.hooks_dir_outside_repo().unwrap());
+    }
 }
@@ -0,0 +1,440 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Review history of the current branch's commits for `grt log`: a timeline
+//! of patchset uploads, votes and comments per change, built from Gerrit's
+//! change messages.
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::ci;
+use crate::gerrit::{AccountInfo, ChangeInfo, ChangeMessageInfo};
+
+/// Where a local commit stands relative to the target branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitState {
+    /// Not on the remote branch yet.
+    Unpushed,
+    /// Already on the remote branch.
+    Merged,
+}
+
+impl CommitState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommitState::Unpushed => "unpushed",
+            CommitState::Merged => "merged",
+        }
+    }
+}
+
+/// What a change message records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    /// A new patchset was uploaded.
+    Upload,
+    /// Votes and/or comments from a reviewer.
+    Review,
+    /// A result posted by a CI system.
+    Ci,
+    Merged,
+    Abandoned,
+    Restored,
+    Other,
+}
+
+/// One entry of a change's timeline.
+#[derive(Debug, Serialize)]
+pub struct Event {
+    pub date: Option<String>,
+    pub author: String,
+    pub patchset: Option<i32>,
+    pub kind: EventKind,
+    /// Votes cast, e.g. `Code-Review+2`; a removed vote is `-Code-Review`.
+    pub votes: Vec<String>,
+    /// Inline comments published with the message.
+    pub comments: u32,
+    /// First line of the free-form text, if any.
+    pub text: Option<String>,
+}
+
+/// A local commit and what happened to its change on Gerrit.
+#[derive(Debug, Serialize)]
+pub struct ChangeHistory {
+    pub change_id: String,
+    pub subject: String,
+    pub state: CommitState,
+    /// `None` when the Change-Id is not known to Gerrit.
+    pub number: Option<i64>,
+    pub status: Option<String>,
+    pub patchsets: Option<i32>,
+    pub events: Vec<Event>,
+}
+
+/// Build the history of the change for a local commit.
+///
+/// `info` is the change as fetched with its messages, or `None` when Gerrit
+/// does not know the Change-Id.
+pub fn build_history(
+    change_id: &str,
+    subject: &str,
+    state: CommitState,
+    info: Option<&ChangeInfo>,
+) -> ChangeHistory {
+    let events: Vec<Event> = info
+        .and_then(|info| info.messages.as_deref())
+        .unwrap_or(&[])
+        .iter()
+        .map(parse_event)
+        .collect();
+    let current = info.and_then(|info| {
+        let rev = info.current_revision.as_ref()?;
+        info.revisions.as_ref()?.get(rev)?.number
+    });
+    ChangeHistory {
+        change_id: change_id.to_string(),
+        subject: subject.to_string(),
+        state,
+        number: info.and_then(|info| info.number),
+        status: info.and_then(|info| info.status.clone()),
+        patchsets: current.or_else(|| events.iter().filter_map(|e| e.patchset).max()),
+        events,
+    }
+}
+
+/// Classify a change message and pick out its votes and comment count.
+///
+/// Gerrit writes `Uploaded patch set N[: <votes or note>].` for uploads and
+/// `Patch Set N: <votes>` followed by `(K comments)` and the reviewer's text
+/// for reviews.
+pub fn parse_event(message: &ChangeMessageInfo) -> Event {
+    let text = message.message.as_deref().unwrap_or("").trim();
+    let mut lines = text.lines();
+    let header = lines.next().unwrap_or("").trim();
+    let body: Vec<&str> = lines.map(str::trim).filter(|l| !l.is_empty()).collect();
+
+    let comments = body.iter().find_map(|l| comment_count(l)).unwrap_or(0);
+    let remark = body
+        .iter()
+        .find(|l| comment_count(l).is_none())
+        .map(|l| l.to_string());
+
+    let (kind, rest) = if let Some(rest) = strip_patchset_prefix(header, "Uploaded patch set ") {
+        (EventKind::Upload, rest)
+    } else if let Some(rest) = strip_patchset_prefix(header, "Patch Set ") {
+        let kind = if ci::is_ci_message(message) {
+            EventKind::Ci
+        } else {
+            EventKind::Review
+        };
+        (kind, rest)
+    } else {
+        let lower = header.to_ascii_lowercase();
+        let kind = if lower.starts_with("change has been successfully") {
+            EventKind::Merged
+        } else if lower.starts_with("abandoned") {
+            EventKind::Abandoned
+        } else if lower.starts_with("restored") {
+            EventKind::Restored
+        } else {
+            EventKind::Other
+        };
+        return Event {
+            date: message.date.clone(),
+            author: author_name(message.author.as_ref()),
+            patchset: message.revision_number,
+            kind,
+            votes: Vec::new(),
+            comments,
+            text: (!header.is_empty()).then(|| header.to_string()),
+        };
+    };
+
+    let rest = rest.trim().trim_end_matches('.');
+    let votes: Vec<String> = rest
+        .split_whitespace()
+        .filter(|w| is_vote(w))
+        .map(String::from)
+        .collect();
+    // Whatever follows the votes on the header is a note, e.g. "Patch Set 1 was rebased"
+    let note = rest
+        .split_whitespace()
+        .filter(|w| !is_vote(w))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Event {
+        date: message.date.clone(),
+        author: author_name(message.author.as_ref()),
+        patchset: message.revision_number,
+        kind,
+        votes,
+        comments,
+        text: (!note.is_empty()).then_some(note).or(remark),
+    }
+}
+
+/// The rest of `header` after `prefix` and a patchset number and colon.
+fn strip_patchset_prefix<'a>(header: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = header.strip_prefix(prefix)?;
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let rest = &rest[digits..];
+    Some(rest.strip_prefix(':').unwrap_or(rest))
+}
+
+/// `Code-Review+2`, `Verified-1`, or `-Code-Review` for a removed vote.
+fn is_vote(word: &str) -> bool {
+    if let Some(label) = word.strip_prefix('-') {
+        return label.starts_with(|c: char| c.is_ascii_alphabetic())
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    }
+    let Some(pos) = word.rfind(['+', '-']) else {
+        return false;
+    };
+    let (label, value) = (&word[..pos], &word[pos + 1..]);
+    !label.is_empty()
+        && label.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !value.is_empty()
+        && value.chars().all(|c| c.is_ascii_digit())
+}
+
+/// `K` from a `(K comment)` or `(K comments)` line.
+fn comment_count(line: &str) -> Option<u32> {
+    let inner = line.strip_prefix('(')?.strip_suffix(')')?;
+    let (count, word) = inner.split_once(' ')?;
+    if !matches!(
+        word,
+        "comment" | "comments" | "inline comment" | "inline comments"
+    ) {
+        return None;
+    }
+    count.parse().ok()
+}
+
+fn author_name(account: Option<&AccountInfo>) -> String {
+    account
+        .and_then(|a| {
+            a.name
+                .clone()
+                .or_else(|| a.display_name.clone())
+                .or_else(|| a.username.clone())
+                .or_else(|| a.email.clone())
+        })
+        .unwrap_or_else(|| "Gerrit".to_string())
+}
+
+/// `2026-03-01 12:34` from Gerrit's `2026-03-01 12:34:56.000000000`.
+fn short_date(date: Option<&str>) -> &str {
+    date.map_or("", |d| d.get(..16).unwrap_or(d))
+}
+
+fn describe(event: &Event) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    match event.kind {
+        EventKind::Upload => parts.push("uploaded".to_string()),
+        EventKind::Merged => parts.push("merged".to_string()),
+        EventKind::Abandoned => parts.push("abandoned".to_string()),
+        EventKind::Restored => parts.push("restored".to_string()),
+        EventKind::Review | EventKind::Ci | EventKind::Other => {}
+    }
+    if !event.votes.is_empty() {
+        parts.push(event.votes.join(" "));
+    }
+    if event.comments > 0 {
+        let noun = if event.comments == 1 {
+            "comment"
+        } else {
+            "comments"
+        };
+        parts.push(format!("({} {noun})", event.comments));
+    }
+    let text = match event.kind {
+        // The header already said what happened
+        EventKind::Merged | EventKind::Abandoned | EventKind::Restored => None,
+        // CI text is a report headline; the verdict is in the votes
+        EventKind::Ci if !event.votes.is_empty() => None,
+        _ => event.text.as_deref(),
+    };
+    if let Some(text) = text {
+        parts.push(text.to_string());
+    }
+    parts.join("  ")
+}
+
+/// Render the histories as one block per change, oldest event first.
+pub fn format_text(histories: &[ChangeHistory]) -> String {
+    let mut out = String::new();
+    for (i, history) in histories.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let Some(number) = history.number else {
+            let _ = writeln!(
+                out,
+                "{}  {}  (not on Gerrit, {})",
+                history.change_id,
+                history.subject,
+                history.state.as_str()
+            );
+            continue;
+        };
+        let mut notes: Vec<String> = Vec::new();
+        if let Some(status) = &history.status {
+            notes.push(status.to_ascii_lowercase());
+        }
+        if let Some(ps) = history.patchsets {
+            let noun = if ps == 1 { "patchset" } else { "patchsets" };
+            notes.push(format!("{ps} {noun}"));
+        }
+        // A merged change needs no reminder that its commit is on the branch
+        if history.state == CommitState::Unpushed {
+            notes.push("local commit unpushed".to_string());
+        }
+        let _ = writeln!(out, "{number}  {}  ({})", history.subject, notes.join(", "));
+
+        let width = history
+            .events
+            .iter()
+            .map(|e| e.author.chars().count())
+            .max()
+            .unwrap_or(0);
+        for event in &history.events {
+            let ps = event
+                .patchset
+                .map_or_else(|| "-".to_string(), |ps| format!("PS{ps}"));
+            let line = format!(
+                "  {:16}  {:4}  {:width$}  {}",
+                short_date(event.date.as_deref()),
+                ps,
+                event.author,
+                describe(event)
+            );
+            let _ = writeln!(out, "{}", line.trim_end());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(json: serde_json::Value) -> ChangeMessageInfo {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn upload_events() {
+        let event = parse_event(&message(serde_json::json!({
+            "author": {"name": "Alice"},
+            "date": "2026-03-01 12:34:56.000000000",
+            "message": "Uploaded patch set 1.",
+            "_revision_number": 1
+        })));
+        assert_eq!(event.kind, EventKind::Upload);
+        assert_eq!(event.author, "Alice");
+        assert_eq!(event.patchset, Some(1));
+        assert!(event.votes.is_empty());
+        assert_eq!(event.text, None);
+
+        let event = parse_event(&message(serde_json::json!({
+            "message": "Uploaded patch set 3: Patch Set 2 was rebased.",
+            "_revision_number": 3
+        })));
+        assert_eq!(event.kind, EventKind::Upload);
+        assert_eq!(event.text.as_deref(), Some("Patch Set 2 was rebased"));
+
+        let event = parse_event(&message(serde_json::json!({
+            "message": "Uploaded patch set 2: Code-Review+2.",
+            "_revision_number": 2
+        })));
+        assert_eq!(event.votes, ["Code-Review+2"]);
+        assert_eq!(event.text, None);
+    }
+
+    #[test]
+    fn review_event_with_votes_and_comments() {
+        let event = parse_event(&message(serde_json::json!({
+            "author": {"username": "bob"},
+            "message": "Patch Set 2: Code-Review-1 -Verified\n\n(3 comments)\n\nPlease add a test.",
+            "_revision_number": 2
+        })));
+        assert_eq!(event.kind, EventKind::Review);
+        assert_eq!(event.author, "bob");
+        assert_eq!(event.votes, ["Code-Review-1", "-Verified"]);
+        assert_eq!(event.comments, 3);
+        assert_eq!(event.text.as_deref(), Some("Please add a test."));
+    }
+
+    #[test]
+    fn ci_and_state_events() {
+        let event = parse_event(&message(serde_json::json!({
+            "author": {"name": "Zuul"},
+            "tag": "autogenerated:zuul:check",
+            "message": "Patch Set 2: Verified+1\n\nBuild succeeded (check pipeline).",
+            "_revision_number": 2
+        })));
+        assert_eq!(event.kind, EventKind::Ci);
+        assert_eq!(event.votes, ["Verified+1"]);
+
+        let event = parse_event(&message(serde_json::json!({
+            "message": "Change has been successfully merged by Alice",
+            "_revision_number": 2
+        })));
+        assert_eq!(event.kind, EventKind::Merged);
+        assert_eq!(event.author, "Gerrit");
+
+        let event = parse_event(&message(
+            serde_json::json!({"message": "Abandoned\n\nDuplicate"}),
+        ));
+        assert_eq!(event.kind, EventKind::Abandoned);
+    }
+
+    #[test]
+    fn history_and_text() {
+        let info: ChangeInfo = serde_json::from_value(serde_json::json!({
+            "_number": 12345,
+            "status": "MERGED",
+            "current_revision": "abc",
+            "revisions": {"abc": {"_number": 2}},
+            "messages": [
+                {"author": {"name": "Alice"}, "date": "2026-03-01 12:34:56.000000000",
+                 "message": "Uploaded patch set 1.", "_revision_number": 1},
+                {"author": {"name": "Bob"}, "date": "2026-03-02 09:10:00.000000000",
+                 "message": "Patch Set 1: Code-Review+2\n\n(1 comment)", "_revision_number": 1}
+            ]
+        }))
+        .unwrap();
+        let histories = vec![
+            build_history("I1", "Fix login", CommitState::Merged, Some(&info)),
+            build_history("I2", "Add tests", CommitState::Unpushed, None),
+        ];
+        assert_eq!(histories[0].patchsets, Some(2));
+        assert_eq!(
+            format_text(&histories),
+            "12345  Fix login  (merged, 2 patchsets)\n\
+             \x20 2026-03-01 12:34  PS1   Alice  uploaded\n\
+             \x20 2026-03-02 09:10  PS1   Bob    Code-Review+2  (1 comment)\n\
+             \n\
+             I2  Add tests  (not on Gerrit, unpushed)\n"
+        );
+    }
+
+    #[test]
+    fn vote_words() {
+        assert!(is_vote("Code-Review+2"));
+        assert!(is_vote("Verified-1"));
+        assert!(is_vote("-Code-Review"));
+        assert!(!is_vote("rebased"));
+        assert!(!is_vote("Patch"));
+        assert!(!is_vote("-"));
+        assert!(!is_vote("-1"));
+        assert!(!is_vote("+1"));
+    }
+}
@@ -1,20 +1,305 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
-use std::path::Path;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use serde::Serialize;
 
 const COMMIT_MSG_HOOK: &str = include_str!("../resources/commit-msg");
 
+/// Version of the bundled hook; bump it with the `grt-hook-version` line in
+/// `resources/commit-msg` whenever the hook changes.
+pub const HOOK_VERSION: u32 = 1;
+
+const HOOK_VERSION_MARKER: &str = "# grt-hook-version:";
+
+/// Who a commit-msg hook came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookKind {
+    Missing,
+    /// A copy grt installed, possibly an older version.
+    Grt,
+    /// A Gerrit hook from elsewhere, e.g. downloaded from the server.
+    Gerrit,
+    /// Some other script; it may not add Change-Ids.
+    Foreign,
+}
+
+impl HookKind {
+    pub fn describe(self) -> &'static str {
+        match self {
+            HookKind::Missing => "not installed",
+            HookKind::Grt => "grt-managed copy",
+            HookKind::Gerrit => "upstream Gerrit copy",
+            HookKind::Foreign => "foreign script",
+        }
+    }
+}
+
+/// The `grt-hook-version` of a grt-installed hook. Copies installed before
+/// the marker existed are identical to the bundled hook minus the marker
+/// lines and count as version 0.
+pub fn hook_version(content: &str) -> Option<u32> {
+    let marked = content.lines().find_map(|line| {
+        line.strip_prefix(HOOK_VERSION_MARKER)
+            .and_then(|v| v.trim().parse().ok())
+    });
+    if marked.is_some() {
+        return marked;
+    }
+    (content == unmarked_hook()).then_some(0)
+}
+
+/// The bundled hook as it was shipped before it carried a version marker.
+fn unmarked_hook() -> String {
+    COMMIT_MSG_HOOK.replacen(&format!("#\n{HOOK_VERSION_MARKER} {HOOK_VERSION}\n"), "", 1)
+}
+
+/// Classify a commit-msg hook by its contents.
+pub fn classify_hook(content: &str) -> HookKind {
+    if hook_version(content).is_some() {
+        HookKind::Grt
+    } else if content.contains("Gerrit Code Review") && content.contains("Change-Id") {
+        HookKind::Gerrit
+    } else {
+        HookKind::Foreign
+    }
+}
+
+/// Classify the commit-msg hook in `hooks_dir`.
+pub fn installed_hook_kind(hooks_dir: &Path) -> HookKind {
+    installed_hook(hooks_dir).0
+}
+
+/// Kind and, for grt's own copies, version of the hook in `hooks_dir`.
+pub fn installed_hook(hooks_dir: &Path) -> (HookKind, Option<u32>) {
+    match std::fs::read(hooks_dir.join("commit-msg")) {
+        Ok(bytes) => {
+            let content = String::from_utf8_lossy(&bytes);
+            (classify_hook(&content), hook_version(&content))
+        }
+        Err(_) => (HookKind::Missing, None),
+    }
+}
+
+/// Whether `hooks_dir` holds a grt hook older than the bundled one.
+pub fn hook_is_outdated(hooks_dir: &Path) -> bool {
+    matches!(installed_hook(hooks_dir), (HookKind::Grt, Some(v)) if v < HOOK_VERSION)
+}
+
+/// What `grt setup --update-hook` did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookUpdate {
+    /// An older grt hook was replaced.
+    Updated { from: u32 },
+    /// The hook is already the bundled version (or newer).
+    Current,
+    /// No hook was installed, so the bundled one was.
+    Installed,
+}
+
+/// Replace an outdated grt-managed hook with the bundled one.
+///
+/// Hooks grt did not install are left alone; replacing them takes
+/// `--force-hook`.
+pub fn update_hook(hooks_dir: &Path) -> Result<HookUpdate> {
+    match installed_hook(hooks_dir) {
+        (HookKind::Missing, _) => {
+            install_hook(hooks_dir)?;
+            Ok(HookUpdate::Installed)
+        }
+        (HookKind::Grt, Some(from)) if from < HOOK_VERSION => {
+            install_hook(hooks_dir)?;
+            Ok(HookUpdate::Updated { from })
+        }
+        (HookKind::Grt, _) => Ok(HookUpdate::Current),
+        (kind, _) => anyhow::bail!(
+            "{} holds a commit-msg hook grt did not install ({}); use --force-hook to replace it",
+            hooks_dir.display(),
+            kind.describe()
+        ),
+    }
+}
+
+/// Where the commit-msg hook resolves for a repository (`grt hooks status`).
+#[derive(Debug, Clone, Serialize)]
+pub struct HookStatus {
+    pub hooks_dir: PathBuf,
+    /// Raw `core.hooksPath`, when set.
+    pub hooks_path: Option<String>,
+    /// Whether the hooks directory is shared outside the repository.
+    pub shared: bool,
+    pub hook: PathBuf,
+    pub kind: HookKind,
+    /// `grt-hook-version` of a grt-managed hook (0 for unmarked copies).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    pub outdated: bool,
+    pub executable: bool,
+}
+
+impl HookStatus {
+    pub fn new(hooks_dir: PathBuf, hooks_path: Option<String>, shared: bool) -> Self {
+        let hook = hooks_dir.join("commit-msg");
+        let (kind, version) = installed_hook(&hooks_dir);
+        Self {
+            kind,
+            version,
+            outdated: kind == HookKind::Grt && version.is_some_and(|v| v < HOOK_VERSION),
+            executable: is_executable(&hook),
+            hooks_dir,
+            hooks_path,
+            shared,
+            hook,
+        }
+    }
+}
+
+/// Text report for `grt hooks status`.
+pub fn format_hook_status(status: &HookStatus) -> String {
+    let source = match (&status.hooks_path, status.shared) {
+        (Some(path), true) => format!("core.hooksPath = {path}, shared"),
+        (Some(path), false) => format!("core.hooksPath = {path}"),
+        (None, _) => "default".to_string(),
+    };
+    let kind = match status.version {
+        Some(version) => format!("{} (version {version})", status.kind.describe()),
+        None => status.kind.describe().to_string(),
+    };
+    let mut out = format!(
+        "hooks directory: {} ({source})\ncommit-msg:      {}\nkind:            {kind}\n",
+        status.hooks_dir.display(),
+        status.hook.display(),
+    );
+    if status.outdated {
+        let _ = writeln!(
+            out,
+            "warning:         outdated (current is version {HOOK_VERSION}); run `grt setup --update-hook`"
+        );
+    }
+    if status.kind != HookKind::Missing && !status.executable {
+        out.push_str("warning:         hook is not executable; git will skip it\n");
+    }
+    if status.kind == HookKind::Foreign {
+        out.push_str(
+            "warning:         commits may lack Change-Ids; `grt hooks install --force` replaces it\n",
+        );
+    }
+    out
+}
+
+/// Where `grt hooks install --global` puts the hook when no directory is
+/// given: the global `core.hooksPath` if set, else git's XDG config
+/// directory (`~/.config/git/hooks`).
+pub fn default_global_hooks_dir(
+    global_hooks_path: Option<&str>,
+    home: Option<&Path>,
+) -> Option<PathBuf> {
+    if let Some(path) = global_hooks_path.filter(|p| !p.trim().is_empty()) {
+        return Some(match (path.strip_prefix("~/"), home) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(path),
+        });
+    }
+    let xdg = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|h| h.join(".config")))?;
+    Some(xdg.join("git").join("hooks"))
+}
+
+/// Custom scripts `grt review` runs around a push, as git-review does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomScript {
+    /// Runs before the push; a non-zero exit aborts it.
+    PreReview,
+    /// Runs after a successful push.
+    PostReview,
+}
+
+impl CustomScript {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            CustomScript::PreReview => "pre-review",
+            CustomScript::PostReview => "post-review",
+        }
+    }
+}
+
+/// Where `script` may live: the user's `<config_dir>/grt/hooks/`, then the
+/// repository's hooks directory.
+pub fn custom_script_paths(
+    script: CustomScript,
+    config_dir: Option<&Path>,
+    hooks_dir: &Path,
+) -> Vec<PathBuf> {
+    config_dir
+        .map(|dir| dir.join("grt").join("hooks"))
+        .into_iter()
+        .chain(std::iter::once(hooks_dir.to_path_buf()))
+        .map(|dir| dir.join(script.file_name()))
+        .collect()
+}
+
+/// Run every executable `script` found in `paths` from `work_dir`, with
+/// `env` added to its environment. Returns how many ran.
+pub fn run_custom_scripts(
+    script: CustomScript,
+    paths: &[PathBuf],
+    env: &[(&str, String)],
+    work_dir: &Path,
+) -> Result<usize> {
+    let mut ran = 0;
+    for path in paths.iter().filter(|p| is_executable(p)) {
+        tracing::debug!("running custom script {}", path.display());
+        let status = std::process::Command::new(path)
+            .current_dir(work_dir)
+            .env("GRT_HOOK", script.file_name())
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .status()
+            .with_context(|| format!("running custom script {}", path.display()))?;
+        if !status.success() {
+            anyhow::bail!(
+                "custom script {} failed (exit {}); use --no-custom-script to skip it",
+                path.display(),
+                status.code().unwrap_or(-1)
+            );
+        }
+        ran += 1;
+    }
+    Ok(ran)
+}
+
+/// Whether `path` is a file the current user may execute.
+pub fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
 /// Ensure the Gerrit commit-msg hook is installed in the repository's hooks directory.
 /// Does not overwrite an existing hook.
 pub fn ensure_hook_installed(hooks_dir: &Path) -> Result<()> {
-    let hook_path = hooks_dir.join("commit-msg");
-
-    if hook_path.exists() {
+    if hooks_dir.join("commit-msg").exists() {
         return Ok(());
     }
+    install_hook(hooks_dir)
+}
+
+/// Write the bundled commit-msg hook into `hooks_dir`, replacing any hook
+/// already there.
+pub fn install_hook(hooks_dir: &Path) -> Result<()> {
+    let hook_path = hooks_dir.join("commit-msg");
 
     // Create hooks directory if it doesn't exist
     if !hooks_dir.exists() {
@@ -41,33 +326,15 @@ pub fn ensure_hook_installed(hooks_dir: &Path) -> Result<()> {
 
 /// Propagate the commit-msg hook to all submodules recursively.
 pub fn propagate_hook_to_submodules(work_dir: &Path) -> Result<()> {
-    use std::process::Command;
-    let output = Command::new("git")
-        .args([
-            "submodule",
-            "foreach",
-            "--recursive",
-            "echo $toplevel/$sm_path",
-        ])
-        .current_dir(work_dir)
-        .output()
-        .context("listing submodules")?;
-
-    if !output.status.success() {
-        // No submodules or git submodule not available — not an error
-        return Ok(());
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    for line in stdout.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with("Entering") {
-            continue;
-        }
-        let submodule_path = Path::new(line);
-        let hooks_dir = submodule_path.join(".git").join("hooks");
-        if let Err(e) = ensure_hook_installed(&hooks_dir) {
-            tracing::warn!("failed to install hook in submodule {}: {}", line, e);
+    for submodule_path in crate::subprocess::list_submodules(work_dir)? {
+        let result = crate::subprocess::git_common_dir(&submodule_path)
+            .and_then(|git_dir| ensure_hook_installed(&git_dir.join("hooks")));
+        if let Err(e) = result {
+            tracing::warn!(
+                "failed to install hook in submodule {}: {}",
+                submodule_path.display(),
+                e
+            );
         }
     }
 
@@ -202,6 +469,73 @@ fn fetch_hook_scp(url: &str, hook_path: &Path) -> Result<()> {
 mod tests {
     use super::*;
 
+    fn write_script(path: &Path, body: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    #[test]
+    fn custom_script_paths_user_then_repo() {
+        let paths = custom_script_paths(
+            CustomScript::PreReview,
+            Some(Path::new("/home/u/.config")),
+            Path::new("/repo/.git/hooks"),
+        );
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/home/u/.config/grt/hooks/pre-review"),
+                PathBuf::from("/repo/.git/hooks/pre-review"),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_custom_scripts_passes_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("hooks").join("post-review");
+        write_script(&script, "echo \"$GRT_HOOK $GRT_BRANCH\" > out");
+        let paths = vec![dir.path().join("missing"), script];
+        let env = [("GRT_BRANCH", "main".to_string())];
+
+        let ran = run_custom_scripts(CustomScript::PostReview, &paths, &env, dir.path()).unwrap();
+
+        assert_eq!(ran, 1);
+        let out = std::fs::read_to_string(dir.path().join("out")).unwrap();
+        assert_eq!(out.trim(), "post-review main");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_custom_scripts_fails_on_nonzero_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("pre-review");
+        write_script(&script, "exit 3");
+
+        let err = run_custom_scripts(CustomScript::PreReview, &[script], &[], dir.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("exit 3"), "{err}");
+        assert!(err.contains("--no-custom-script"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_executable_script_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("pre-review");
+        std::fs::write(&script, "#!/bin/sh\nexit 1\n").unwrap();
+
+        let ran = run_custom_scripts(CustomScript::PreReview, &[script], &[], dir.path()).unwrap();
+        assert_eq!(ran, 0);
+    }
+
     #[test]
     fn install_hook_creates_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -258,6 +592,98 @@ mod tests {
         assert!(hooks_dir.join("commit-msg").exists());
     }
 
+    #[test]
+    fn install_hook_replaces_existing() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("commit-msg"), "#!/bin/sh\n# custom hook\n").unwrap();
+        assert_eq!(installed_hook_kind(dir.path()), HookKind::Foreign);
+        install_hook(dir.path()).unwrap();
+        assert_eq!(installed_hook_kind(dir.path()), HookKind::Grt);
+    }
+
+    #[test]
+    fn classify_hooks() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(installed_hook_kind(dir.path()), HookKind::Missing);
+        assert_eq!(classify_hook(COMMIT_MSG_HOOK), HookKind::Grt);
+        let upstream = unmarked_hook().replace("set -u", "set -eu");
+        assert_eq!(classify_hook(&upstream), HookKind::Gerrit);
+        assert_eq!(
+            classify_hook("#!/bin/sh\nexec commitlint --edit \"$1\"\n"),
+            HookKind::Foreign
+        );
+    }
+
+    #[test]
+    fn hook_status_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = HookStatus::new(dir.path().to_path_buf(), None, false);
+        assert_eq!(missing.kind, HookKind::Missing);
+        let text = format_hook_status(&missing);
+        assert!(text.contains("(default)"));
+        assert!(text.contains("kind:            not installed\n"));
+
+        install_hook(dir.path()).unwrap();
+        let shared = HookStatus::new(
+            dir.path().to_path_buf(),
+            Some("~/.githooks".to_string()),
+            true,
+        );
+        assert_eq!(shared.kind, HookKind::Grt);
+        let text = format_hook_status(&shared);
+        assert!(text.contains("(core.hooksPath = ~/.githooks, shared)"));
+        assert!(text.contains("grt-managed copy"));
+        assert!(!text.contains("warning"));
+    }
+
+    #[test]
+    fn bundled_hook_carries_current_version() {
+        assert_eq!(hook_version(COMMIT_MSG_HOOK), Some(HOOK_VERSION));
+        // Copies installed before the marker existed
+        assert_eq!(hook_version(&unmarked_hook()), Some(0));
+        assert_eq!(classify_hook(&unmarked_hook()), HookKind::Grt);
+        assert_eq!(hook_version("#!/bin/sh\n"), None);
+    }
+
+    #[test]
+    fn update_hook_replaces_only_outdated_grt_hooks() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(update_hook(dir.path()).unwrap(), HookUpdate::Installed);
+        assert_eq!(update_hook(dir.path()).unwrap(), HookUpdate::Current);
+        assert!(!hook_is_outdated(dir.path()));
+
+        std::fs::write(dir.path().join("commit-msg"), unmarked_hook()).unwrap();
+        assert!(hook_is_outdated(dir.path()));
+        let status = HookStatus::new(dir.path().to_path_buf(), None, false);
+        assert!(status.outdated);
+        assert!(format_hook_status(&status).contains("grt setup --update-hook"));
+        assert_eq!(
+            update_hook(dir.path()).unwrap(),
+            HookUpdate::Updated { from: 0 }
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("commit-msg")).unwrap(),
+            COMMIT_MSG_HOOK
+        );
+
+        std::fs::write(dir.path().join("commit-msg"), "#!/bin/sh\n# mine\n").unwrap();
+        assert!(!hook_is_outdated(dir.path()));
+        assert!(update_hook(dir.path()).is_err());
+    }
+
+    #[test]
+    fn global_hooks_dir_prefers_core_hooks_path() {
+        let home = Path::new("/home/u");
+        assert_eq!(
+            default_global_hooks_dir(Some("~/.githooks"), Some(home)),
+            Some(PathBuf::from("/home/u/.githooks"))
+        );
+        assert_eq!(
+            default_global_hooks_dir(Some("/srv/hooks"), Some(home)),
+            Some(PathBuf::from("/srv/hooks"))
+        );
+    }
+
     #[test]
     fn propagate_hook_no_submodules() {
         let dir = tempfile::tempdir().unwrap();
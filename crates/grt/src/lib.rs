@@ -2,15 +2,36 @@
 // Copyright (c) 2026 grt contributors
 
 pub mod app;
+pub mod attention;
+pub mod backport;
+pub mod batch;
+pub mod cache;
+pub mod capabilities;
+pub mod ci;
 pub mod comments;
 pub mod config;
+pub mod deps;
+pub mod diff;
 pub mod export;
+pub mod fix;
 pub mod gerrit;
 pub mod git;
+pub mod history;
 pub mod hook;
 pub mod list;
+pub mod open;
+pub mod preview;
 pub mod push;
+pub mod query;
 pub mod rebase;
 pub mod review;
 pub mod review_query;
+pub mod reviewers;
+pub mod secrets;
+pub mod settings;
+pub mod status;
+pub mod submit;
 pub mod subprocess;
+pub mod tui;
+pub mod ui;
+pub mod workspace;
@@ -1,28 +1,144 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
+use serde::Serialize;
+
+use crate::ci::{self, CiReport};
 use crate::gerrit::ChangeInfo;
+use crate::ui::Style;
+
+/// Default cap on the number of changes `-l` fetches.
+pub const DEFAULT_LIST_LIMIT: usize = 500;
+
+/// Resolve the maximum number of changes to list.
+///
+/// `--no-limit` removes the cap (`None`); otherwise `--limit` or
+/// [`DEFAULT_LIST_LIMIT`] applies.
+pub fn resolve_list_limit(limit: Option<usize>, no_limit: bool) -> Option<usize> {
+    if no_limit {
+        None
+    } else {
+        Some(limit.unwrap_or(DEFAULT_LIST_LIMIT))
+    }
+}
+
+/// Cut `changes` down to `limit`, returning true if any were dropped.
+///
+/// Callers fetch one change past the limit, so a list holding exactly
+/// `limit` changes is not reported as truncated.
+pub fn truncate_to_limit<T>(changes: &mut Vec<T>, limit: Option<usize>) -> bool {
+    match limit {
+        Some(limit) if changes.len() > limit => {
+            changes.truncate(limit);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Extra predicates for `grt review -l`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListFilters {
+    /// Only the caller's own changes (`owner:self`).
+    pub mine: bool,
+    /// Only work-in-progress changes (`is:wip`).
+    pub wip: bool,
+    /// Only private changes (`is:private`).
+    pub private: bool,
+    /// Only changes with this user as a reviewer (`reviewer:<user>`).
+    pub reviewer: Option<String>,
+}
 
 /// Build the Gerrit query string for listing open changes.
 ///
 /// Always includes `status:open`. Adds `project:<project>` when non-empty,
-/// and `branch:<branch>` when provided.
-pub fn build_list_query(project: &str, branch: Option<&str>) -> String {
+/// a branch predicate (see [`branch_predicate`]) when provided, and one
+/// predicate per set filter.
+pub fn build_list_query(project: &str, branch: Option<&str>, filters: &ListFilters) -> String {
     let mut query = "status:open".to_string();
     if !project.is_empty() {
         query.push_str(&format!(" project:{project}"));
     }
     if let Some(branch) = branch {
-        query.push_str(&format!(" branch:{branch}"));
+        query.push(' ');
+        query.push_str(&branch_predicate(branch));
+    }
+    if filters.mine {
+        query.push_str(" owner:self");
+    }
+    if filters.wip {
+        query.push_str(" is:wip");
+    }
+    if filters.private {
+        query.push_str(" is:private");
+    }
+    if let Some(reviewer) = &filters.reviewer {
+        query.push_str(&format!(" reviewer:{}", query_value(reviewer)));
     }
     query
 }
 
+/// Quote `value` for a Gerrit search predicate when it needs it.
+///
+/// Full names contain spaces, and a `"` or parenthesis would end the
+/// predicate early, so such values are double-quoted with `\` and `"`
+/// backslash-escaped.
+fn query_value(value: &str) -> String {
+    if !value.contains(|c: char| c.is_whitespace() || matches!(c, '"' | '(' | ')')) {
+        return value.to_string();
+    }
+    quote_query_value(value)
+}
+
+/// Double-quote `value` for a Gerrit search, backslash-escaping `\` and `"`.
+fn quote_query_value(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// Characters that mark a branch argument as a pattern rather than a name.
+const REGEX_METACHARS: &[char] = &[
+    '^', '$', '*', '?', '+', '[', ']', '(', ')', '{', '}', '|', '\\',
+];
+
+/// Build the Gerrit `branch:` predicate for a branch argument.
+///
+/// Plain names produce an exact match (`branch:main`). Arguments starting
+/// with `^` are passed through as Gerrit regexes. Other arguments containing
+/// pattern characters are anchored with `^`; shell-style globs using only
+/// `*`/`?` are translated first, so `stable/*` becomes `branch:"^stable/.*"`.
+/// Regexes are always quoted, since the query parser would otherwise read
+/// `(`, `)` and `|` as grouping and `OR`.
+pub fn branch_predicate(branch: &str) -> String {
+    if branch.starts_with('^') {
+        return format!("branch:{}", quote_query_value(branch));
+    }
+    if !branch.contains(REGEX_METACHARS) {
+        return format!("branch:{branch}");
+    }
+    // `.*` already reads as a regex; anything else with only `*`/`?` is a glob
+    let is_glob = !branch.contains(".*")
+        && !branch.contains(|c: char| REGEX_METACHARS.contains(&c) && c != '*' && c != '?');
+    if is_glob {
+        let mut regex = String::from("^");
+        for c in branch.chars() {
+            match c {
+                '*' => regex.push_str(".*"),
+                '?' => regex.push('.'),
+                '.' => regex.push_str("\\."),
+                c => regex.push(c),
+            }
+        }
+        return format!("branch:{}", quote_query_value(&regex));
+    }
+    format!("branch:{}", quote_query_value(&format!("^{branch}")))
+}
+
 /// Format a list of changes for brief output (`-l`).
 ///
 /// Columns: right-aligned number, left-aligned branch, subject.
 /// Returns a "no changes" message if the list is empty.
-pub fn format_reviews_text(changes: &[ChangeInfo]) -> String {
+pub fn format_reviews_text(changes: &[ChangeInfo], style: Style) -> String {
     if changes.is_empty() {
         return "No changes found for review\n".to_string();
     }
@@ -42,8 +158,8 @@ pub fn format_reviews_text(changes: &[ChangeInfo]) -> String {
         use std::fmt::Write;
         let _ = writeln!(
             output,
-            "{num:>num_width$}  {branch:<branch_width$}  {subject}",
-            num_width = num_width,
+            "{}  {branch:<branch_width$}  {subject}",
+            style.yellow(&format!("{num:>num_width$}")),
             branch_width = branch_width
         );
     }
@@ -53,11 +169,32 @@ pub fn format_reviews_text(changes: &[ChangeInfo]) -> String {
     output
 }
 
+/// A listed change in `--format json`: the change plus its CI reports.
+#[derive(Debug, Serialize)]
+pub struct ListEntry<'a> {
+    #[serde(flatten)]
+    pub change: &'a ChangeInfo,
+    /// Empty (and omitted) unless the change was fetched with its messages.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ci: Vec<CiReport>,
+}
+
+impl<'a> ListEntry<'a> {
+    pub fn new(change: &'a ChangeInfo) -> Self {
+        ListEntry {
+            change,
+            ci: ci::change_reports(change),
+        }
+    }
+}
+
 /// Format a list of changes for verbose output (`-ll`).
 ///
-/// Columns: right-aligned number, left-aligned branch, left-aligned topic, subject.
+/// Columns: right-aligned number, left-aligned branch, left-aligned topic,
+/// the owner with `show_owner`, CI results (see [`ci::compact_summary`]) when
+/// the changes carry their messages, subject.
 /// Returns a "no changes" message if the list is empty.
-pub fn format_reviews_verbose(changes: &[ChangeInfo]) -> String {
+pub fn format_reviews_verbose(changes: &[ChangeInfo], style: Style, show_owner: bool) -> String {
     if changes.is_empty() {
         return "No changes found for review\n".to_string();
     }
@@ -73,20 +210,53 @@ pub fn format_reviews_verbose(changes: &[ChangeInfo]) -> String {
         .map(|c| c.topic.as_deref().unwrap_or("-").len())
         .max()
         .unwrap_or(1);
+    let owner_width = changes
+        .iter()
+        .map(|c| owner_name(c).len())
+        .max()
+        .unwrap_or(1);
+    let show_ci = changes.iter().any(|c| c.messages.is_some());
+    let ci_cells: Vec<String> = changes
+        .iter()
+        .map(|c| ci::compact_summary(&ci::change_reports(c)))
+        .collect();
+    let ci_width = ci_cells
+        .iter()
+        .map(|c| c.chars().count())
+        .max()
+        .unwrap_or(1);
 
     let mut output = String::new();
-    for change in changes {
+    for (change, ci_cell) in changes.iter().zip(&ci_cells) {
         let num = change.number.unwrap_or(0);
         let branch = change.branch.as_deref().unwrap_or("-");
         let topic = change.topic.as_deref().unwrap_or("-");
         let subject = change.subject.as_deref().unwrap_or("-");
+        let owner = if show_owner {
+            format!("{:<owner_width$}  ", owner_name(change))
+        } else {
+            String::new()
+        };
+        let ci_column = if show_ci {
+            let cell = format!("{ci_cell:<ci_width$}");
+            let cell = if ci_cell.starts_with(ci::result_mark(ci::CiResult::Failed)) {
+                style.red(&cell)
+            } else if ci_cell.starts_with(ci::result_mark(ci::CiResult::Passed)) {
+                style.green(&cell)
+            } else {
+                cell
+            };
+            format!("{cell}  ")
+        } else {
+            String::new()
+        };
         use std::fmt::Write;
         let _ = writeln!(
             output,
-            "{num:>num_width$}  {branch:<branch_width$}  {topic:<topic_width$}  {subject}",
-            num_width = num_width,
-            branch_width = branch_width,
-            topic_width = topic_width
+            "{}  {branch:<branch_width$}  {}  {owner}{ci_column}{subject}",
+            style.yellow(&format!("{num:>num_width$}")),
+            style.cyan(&format!("{topic:<topic_width$}")),
+            branch_width = branch_width
         );
     }
     use std::fmt::Write;
@@ -95,6 +265,20 @@ pub fn format_reviews_verbose(changes: &[ChangeInfo]) -> String {
     output
 }
 
+/// Short name of the change owner: username, else name, else email.
+fn owner_name(change: &ChangeInfo) -> &str {
+    change
+        .owner
+        .as_ref()
+        .and_then(|o| {
+            o.username
+                .as_deref()
+                .or(o.name.as_deref())
+                .or(o.email.as_deref())
+        })
+        .unwrap_or("-")
+}
+
 /// Compute the maximum display width of change numbers in the list.
 fn max_number_width(changes: &[ChangeInfo]) -> usize {
     changes
@@ -135,46 +319,189 @@ mod tests {
             insertions: None,
             deletions: None,
             labels: None,
+            attention_set: None,
+            more_changes: None,
         }
     }
 
+    // === resolve_list_limit ===
+
+    #[test]
+    fn list_limit_defaults_to_cap() {
+        assert_eq!(resolve_list_limit(None, false), Some(DEFAULT_LIST_LIMIT));
+    }
+
+    #[test]
+    fn list_limit_explicit() {
+        assert_eq!(resolve_list_limit(Some(25), false), Some(25));
+    }
+
+    #[test]
+    fn list_no_limit_removes_cap() {
+        assert_eq!(resolve_list_limit(None, true), None);
+    }
+
+    // === truncate_to_limit ===
+
+    #[test]
+    fn truncate_exactly_limit_is_not_truncated() {
+        let mut changes = vec![1, 2, 3];
+        assert!(!truncate_to_limit(&mut changes, Some(3)));
+        assert_eq!(changes, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn truncate_drops_extra_change() {
+        let mut changes = vec![1, 2, 3, 4];
+        assert!(truncate_to_limit(&mut changes, Some(3)));
+        assert_eq!(changes, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn truncate_without_limit_keeps_all() {
+        let mut changes = vec![1, 2, 3];
+        assert!(!truncate_to_limit(&mut changes, None));
+        assert_eq!(changes.len(), 3);
+    }
+
     // === build_list_query ===
 
     #[test]
     fn query_with_project() {
-        let q = build_list_query("my/project", None);
+        let q = build_list_query("my/project", None, &ListFilters::default());
         assert_eq!(q, "status:open project:my/project");
     }
 
     #[test]
     fn query_with_project_and_branch() {
-        let q = build_list_query("my/project", Some("main"));
+        let q = build_list_query("my/project", Some("main"), &ListFilters::default());
         assert_eq!(q, "status:open project:my/project branch:main");
     }
 
     #[test]
     fn query_empty_project() {
-        let q = build_list_query("", None);
+        let q = build_list_query("", None, &ListFilters::default());
         assert_eq!(q, "status:open");
     }
 
     #[test]
     fn query_empty_project_with_branch() {
-        let q = build_list_query("", Some("develop"));
+        let q = build_list_query("", Some("develop"), &ListFilters::default());
         assert_eq!(q, "status:open branch:develop");
     }
 
+    #[test]
+    fn query_with_regex_branch() {
+        let q = build_list_query("my/project", Some("^stable/.*"), &ListFilters::default());
+        assert_eq!(q, r#"status:open project:my/project branch:"^stable/.*""#);
+    }
+
+    #[test]
+    fn query_with_filters() {
+        let filters = ListFilters {
+            mine: true,
+            wip: true,
+            private: true,
+            reviewer: Some("alice".to_string()),
+        };
+        let q = build_list_query("my/project", None, &filters);
+        assert_eq!(
+            q,
+            "status:open project:my/project owner:self is:wip is:private reviewer:alice"
+        );
+    }
+
+    #[test]
+    fn query_quotes_reviewer_with_spaces() {
+        let filters = ListFilters {
+            reviewer: Some("Jane Doe".to_string()),
+            ..Default::default()
+        };
+        let q = build_list_query("", Some("main"), &filters);
+        assert_eq!(q, "status:open branch:main reviewer:\"Jane Doe\"");
+    }
+
+    #[test]
+    fn query_escapes_quotes_in_reviewer() {
+        let filters = ListFilters {
+            reviewer: Some("Jane \"JD\" Doe".to_string()),
+            ..Default::default()
+        };
+        let q = build_list_query("", None, &filters);
+        assert_eq!(q, r#"status:open reviewer:"Jane \"JD\" Doe""#);
+
+        let filters = ListFilters {
+            reviewer: Some(r#"a"b\c"#.to_string()),
+            ..Default::default()
+        };
+        let q = build_list_query("", None, &filters);
+        assert_eq!(q, r#"status:open reviewer:"a\"b\\c""#);
+    }
+
+    // === branch_predicate ===
+
+    #[test]
+    fn branch_predicate_exact() {
+        assert_eq!(branch_predicate("main"), "branch:main");
+        assert_eq!(branch_predicate("stable/2024.1"), "branch:stable/2024.1");
+    }
+
+    #[test]
+    fn branch_predicate_regex_passthrough() {
+        assert_eq!(branch_predicate("^stable/.*"), r#"branch:"^stable/.*""#);
+    }
+
+    #[test]
+    fn branch_predicate_quotes_grouping_and_alternation() {
+        assert_eq!(
+            branch_predicate("^(master|stable/.*)"),
+            r#"branch:"^(master|stable/.*)""#
+        );
+        let q = build_list_query("p", Some("stable/(2024|2025).*"), &ListFilters::default());
+        assert_eq!(q, r#"status:open project:p branch:"^stable/(2024|2025).*""#);
+    }
+
+    #[test]
+    fn branch_predicate_escapes_quote_and_backslash() {
+        assert_eq!(branch_predicate(r#"^a"b\d"#), r#"branch:"^a\"b\\d""#);
+    }
+
+    #[test]
+    fn branch_predicate_unanchored_regex_gets_anchor() {
+        assert_eq!(
+            branch_predicate("stable/(2024|2025).*"),
+            r#"branch:"^stable/(2024|2025).*""#
+        );
+    }
+
+    #[test]
+    fn branch_predicate_glob_translated() {
+        assert_eq!(branch_predicate("stable/*"), r#"branch:"^stable/.*""#);
+        assert_eq!(branch_predicate("v1.?"), r#"branch:"^v1\\..""#);
+    }
+
+    #[test]
+    fn branch_predicate_dot_star_is_regex() {
+        assert_eq!(
+            branch_predicate("stable/2024.*"),
+            r#"branch:"^stable/2024.*""#
+        );
+    }
+
     // === format_reviews_text (brief) ===
 
     #[test]
     fn text_empty_returns_no_changes_message() {
-        assert_eq!(format_reviews_text(&[]), "No changes found for review\n");
+        assert_eq!(
+            format_reviews_text(&[], Style::plain()),
+            "No changes found for review\n"
+        );
     }
 
     #[test]
     fn text_single_change() {
         let changes = vec![make_change(12345, "main", "Fix the bug", None)];
-        let output = format_reviews_text(&changes);
+        let output = format_reviews_text(&changes, Style::plain());
         assert!(output.contains("12345  main  Fix the bug"));
         assert!(output.contains("Found 1 item(s) for review"));
     }
@@ -185,7 +512,7 @@ mod tests {
             make_change(12345, "main", "Fix the bug", None),
             make_change(99, "feature/long-branch", "Add feature", None),
         ];
-        let output = format_reviews_text(&changes);
+        let output = format_reviews_text(&changes, Style::plain());
         let lines: Vec<&str> = output.lines().collect();
         // 2 data lines + 1 summary line
         assert_eq!(lines.len(), 3);
@@ -206,7 +533,7 @@ mod tests {
             make_change(100, "main", "Second", None),
             make_change(99999, "main", "Third", None),
         ];
-        let output = format_reviews_text(&changes);
+        let output = format_reviews_text(&changes, Style::plain());
         let lines: Vec<&str> = output.lines().collect();
         // 3 data lines + 1 summary line
         assert_eq!(lines.len(), 4);
@@ -217,17 +544,33 @@ mod tests {
         assert_eq!(lines[3], "Found 3 item(s) for review");
     }
 
+    #[test]
+    fn text_colored_number_keeps_alignment() {
+        let changes = vec![
+            make_change(7, "main", "Fix", None),
+            make_change(123, "main", "Add", None),
+        ];
+        let output = format_reviews_text(&changes, Style::new(true));
+        assert!(
+            output.starts_with("\x1b[33m  7\x1b[0m  main  Fix\n"),
+            "{output}"
+        );
+    }
+
     // === format_reviews_verbose ===
 
     #[test]
     fn verbose_empty_returns_no_changes_message() {
-        assert_eq!(format_reviews_verbose(&[]), "No changes found for review\n");
+        assert_eq!(
+            format_reviews_verbose(&[], Style::plain(), false),
+            "No changes found for review\n"
+        );
     }
 
     #[test]
     fn verbose_single_change_with_topic() {
         let changes = vec![make_change(12345, "main", "Fix the bug", Some("my-topic"))];
-        let output = format_reviews_verbose(&changes);
+        let output = format_reviews_verbose(&changes, Style::plain(), false);
         assert!(output.contains("12345  main  my-topic  Fix the bug"));
         assert!(output.contains("Found 1 item(s) for review"));
     }
@@ -238,7 +581,7 @@ mod tests {
             make_change(12345, "main", "Fix the bug", Some("bugfix")),
             make_change(99, "develop", "Add feature", Some("new-feature")),
         ];
-        let output = format_reviews_verbose(&changes);
+        let output = format_reviews_verbose(&changes, Style::plain(), false);
         let lines: Vec<&str> = output.lines().collect();
         // 2 data lines + 1 summary line
         assert_eq!(lines.len(), 3);
@@ -257,7 +600,7 @@ mod tests {
             make_change(100, "main", "Has topic", Some("my-topic")),
             make_change(200, "main", "No topic", None),
         ];
-        let output = format_reviews_verbose(&changes);
+        let output = format_reviews_verbose(&changes, Style::plain(), false);
         let lines: Vec<&str> = output.lines().collect();
         // 2 data lines + 1 summary line
         assert_eq!(lines.len(), 3);
@@ -268,4 +611,78 @@ mod tests {
         assert!(lines[1].contains("No topic"));
         assert_eq!(lines[2], "Found 2 item(s) for review");
     }
+
+    #[test]
+    fn verbose_owner_column() {
+        let mut mine = make_change(100, "main", "Mine", Some("t"));
+        mine.owner = Some(
+            serde_json::from_value(serde_json::json!({"_account_id": 1, "username": "alice"}))
+                .unwrap(),
+        );
+        let mut theirs = make_change(200, "main", "Theirs", Some("t"));
+        theirs.owner = Some(
+            serde_json::from_value(serde_json::json!({"_account_id": 2, "name": "Bob Jones"}))
+                .unwrap(),
+        );
+        let output = format_reviews_verbose(&[mine, theirs], Style::plain(), true);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "100  main  t  alice      Mine");
+        assert_eq!(lines[1], "200  main  t  Bob Jones  Theirs");
+    }
+
+    #[test]
+    fn verbose_ci_column() {
+        let message = |text: &str| {
+            serde_json::json!({
+                "author": {"name": "Zuul"},
+                "tag": "autogenerated:zuul:check",
+                "message": text,
+                "_revision_number": 1
+            })
+        };
+        let mut failing = make_change(100, "main", "Broken", Some("t"));
+        failing.messages = Some(
+            serde_json::from_value(serde_json::json!([message(
+                "Patch Set 1: Verified-1\n\nBuild failed (check pipeline).\n\n\
+                 - lint https://zuul.example.org/build/1 : SUCCESS in 1m\n\
+                 - unit https://zuul.example.org/build/2 : FAILURE in 5m\n"
+            )]))
+            .unwrap(),
+        );
+        let mut passing = make_change(200, "main", "Fine", Some("t"));
+        passing.messages = Some(
+            serde_json::from_value(serde_json::json!([message(
+                "Patch Set 1: Verified+1\n\nBuild succeeded (check pipeline)."
+            )]))
+            .unwrap(),
+        );
+        let pending = make_change(300, "main", "New", Some("t"));
+        let output = format_reviews_verbose(&[failing, passing, pending], Style::plain(), false);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "100  main  t  ✘ unit  Broken");
+        assert_eq!(lines[1], "200  main  t  ✔       Fine");
+        assert_eq!(lines[2], "300  main  t  -       New");
+    }
+
+    #[test]
+    fn json_entry_carries_ci_reports() {
+        let mut change = make_change(100, "main", "Broken", None);
+        change.messages = Some(
+            serde_json::from_value(serde_json::json!([{
+                "author": {"name": "Zuul"},
+                "tag": "autogenerated:zuul:check",
+                "message": "Patch Set 1: Verified-1\n\n- unit https://zuul.example.org/build/2 : FAILURE in 5m",
+                "_revision_number": 1
+            }]))
+            .unwrap(),
+        );
+        let json = serde_json::to_value(ListEntry::new(&change)).unwrap();
+        assert_eq!(json["_number"], 100);
+        assert_eq!(json["ci"][0]["result"], "failed");
+        assert_eq!(json["ci"][0]["jobs"][0]["name"], "unit");
+
+        let plain = make_change(200, "main", "Plain", None);
+        let json = serde_json::to_value(ListEntry::new(&plain)).unwrap();
+        assert!(json.get("ci").is_none());
+    }
 }
@@ -1,23 +1,46 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use tracing::debug;
 
 use grt::app::App;
-use grt::comments;
-use grt::config::CliOverrides;
+use grt::attention;
+use grt::backport;
+use grt::batch::{self, BatchOutcome};
+use grt::cache::{self, CacheMode, CachedComments};
+use grt::capabilities::{Capabilities, Feature};
+use grt::comments::{self, CommentsFormat};
+use grt::config::{self, CliOverrides};
+use grt::deps;
+use grt::diff;
 use grt::export::{self, ExportArgs};
-use grt::gerrit::GerritError;
+use grt::fix;
+use grt::gerrit::{CherryPickInput, GerritError, ReviewerState};
+use grt::git::{GitRepo, NoCommitsError};
+use grt::history;
 use grt::hook;
+use grt::list;
+use grt::open;
+use grt::preview;
 use grt::push::{self, ChangeIdStatus, PushOptions, PushResult};
+use grt::query::{self, QueryFormat};
 use grt::rebase;
 use grt::review::{self, OutputFormat, ReviewArgs};
 use grt::review_query;
+use grt::reviewers;
+use grt::secrets;
+use grt::settings::{self, SetTarget};
+use grt::status;
+use grt::submit;
 use grt::subprocess;
+use grt::tui;
+use grt::ui::{self, ColorWhen, Style};
+use grt::workspace;
 
 /// grt — CLI/TUI tool for Git and Gerrit workflows
 #[derive(Parser, Debug)]
@@ -27,14 +50,18 @@ struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     verbose: u8,
 
-    /// Run as if started in <PATH>
+    /// Run as if started in <PATH>; repeatable, each relative to the previous
     #[arg(short = 'C', long = "directory", global = true)]
-    directory: Option<PathBuf>,
+    directory: Vec<PathBuf>,
 
-    /// Disable colored output
+    /// Disable colored output (same as `--color never`)
     #[arg(long, global = true)]
     no_color: bool,
 
+    /// When to color output: auto (terminals only), always or never
+    #[arg(long, global = true, value_enum, value_name = "WHEN")]
+    color: Option<ColorWhen>,
+
     /// Allow sending credentials over plain HTTP (no TLS)
     #[arg(long, global = true)]
     insecure: bool,
@@ -43,10 +70,63 @@ struct Cli {
     #[arg(long, global = true)]
     server: Option<String>,
 
+    /// Use the named `[profile.<NAME>]` from config.toml (overrides GRT_PROFILE)
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Force HTTP/1.1 for the Gerrit REST API (overrides GRT_FORCE_HTTP1)
+    #[arg(long, global = true)]
+    http1: bool,
+
+    /// Let .gitreview host/project/branch win over git config (CLI flags still win)
+    #[arg(long, global = true)]
+    prefer_gitreview: bool,
+
+    /// Print the meaning of a grt exit code and exit; takes no subcommand
+    /// (see [`parse_invocation`])
+    #[arg(long, hide = true, value_name = "CODE")]
+    explain_exit: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// What the `grt` command line asks for.
+#[derive(Debug)]
+enum Invocation {
+    /// `grt --explain-exit CODE`
+    ExplainExit(String),
+    Run(Box<Cli>),
+}
+
+/// Parse grt's command line.
+///
+/// `--explain-exit` is the one way to run without a subcommand, so the
+/// subcommand is only required once it is known to be absent. The flag is
+/// refused next to a subcommand here rather than with clap's
+/// `args_conflicts_with_subcommands`, which would also refuse global flags
+/// such as `grt -v push`.
+fn parse_invocation<I, T>(args: I) -> Result<Invocation, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let mut cmd = Cli::command().subcommand_required(false);
+    let matches = cmd.try_get_matches_from_mut(args)?;
+    if let Some(code) = matches.get_one::<String>("explain_exit") {
+        if let Some(name) = matches.subcommand_name() {
+            return Err(cmd.error(
+                clap::error::ErrorKind::ArgumentConflict,
+                format!("--explain-exit cannot be used with a subcommand ('{name}')"),
+            ));
+        }
+        return Ok(Invocation::ExplainExit(code.clone()));
+    }
+    Cli::from_arg_matches(&matches)
+        .map(|cli| Invocation::Run(Box::new(cli)))
+        .map_err(|e| e.format(&mut cmd))
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Push changes to Gerrit for review (git-review compatible)
@@ -58,14 +138,86 @@ enum Commands {
     /// Retrieve review comments from Gerrit
     Comments(CommentsArgs),
 
+    /// Show labels, submit requirements and CI results for a change (defaults to HEAD's Change-Id)
+    Status(StatusArgs),
+
+    /// Open a change in the web browser (defaults to HEAD's Change-Id)
+    Open(OpenArgs),
+
+    /// Show the dependency chain of a change (defaults to HEAD's Change-Id)
+    Deps(DepsArgs),
+
+    /// Show the review history of the current branch's unpushed and recently merged commits
+    Log(LogArgs),
+
+    /// Search changes with Gerrit query syntax
+    Query(QueryArgs),
+
+    /// Show the diff of a change's patchset against its parent
+    Diff(DiffArgs),
+
+    /// Apply fixes suggested in review comments to the working tree (defaults to HEAD's Change-Id)
+    Fix(FixArgs),
+
+    /// Cherry-pick a change onto other branches and push the backports for review
+    Backport(BackportArgs),
+
+    /// Cherry-pick a change onto another branch on the Gerrit server, without a local fetch
+    CherrypickRemote(CherrypickRemoteArgs),
+
+    /// Rebase all unpushed commits onto the latest target branch
+    RebaseChain(RebaseChainArgs),
+
     /// Set up current repo for Gerrit (hook, remote, connectivity)
     Setup(SetupArgs),
 
+    /// Install the commit-msg hook or show where it resolves
+    Hooks(HooksArgs),
+
+    /// Show the effective configuration and where each value comes from, or set a value
+    Config(ConfigArgs),
+
+    /// Store or remove Gerrit HTTP credentials in the OS keyring
+    Auth(AuthArgs),
+
+    /// Submit changes in Gerrit
+    Submit(SubmitArgs),
+
+    /// Check out a change on a branch that follows it across patchsets
+    Checkout(CheckoutArgs),
+
+    /// Abandon a change (defaults to HEAD's Change-Id)
+    Abandon(ChangeStateArgs),
+
+    /// Restore an abandoned change (defaults to HEAD's Change-Id)
+    Restore(ChangeStateArgs),
+
+    /// Mark an existing change work in progress (defaults to HEAD's Change-Id)
+    Wip(WipArgs),
+
+    /// Mark a work-in-progress change ready for review (defaults to HEAD's Change-Id)
+    Ready(WipArgs),
+
+    /// Set or clear the topic of an existing change (defaults to HEAD's Change-Id)
+    Topic(TopicArgs),
+
+    /// Add, remove, list or look up reviewers of an existing change
+    Reviewers(ReviewersArgs),
+
+    /// Add, remove or list hashtags of an existing change
+    Hashtags(HashtagsArgs),
+
+    /// Show or change who is in the attention set of a change
+    Attention(AttentionArgs),
+
+    /// Browse open changes interactively (preview, download, cherry-pick, abandon)
+    Tui(TuiArgs),
+
     /// Export grt functionality (e.g., create git-review symlink)
     Export(ExportArgs),
 
     /// Show grt and Gerrit server versions
-    Version,
+    Version(VersionArgs),
 
     /// Generate shell completions
     Completions {
@@ -103,7 +255,7 @@ struct GitReviewCli {
     review: ReviewArgs,
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone, Default)]
 struct PushArgs {
     /// Target branch (defaults to config or "main")
     branch: Option<String>,
@@ -136,6 +288,22 @@ struct PushArgs {
     #[arg(short = 'r', long, value_delimiter = ',')]
     reviewers: Vec<String>,
 
+    /// Do not add the configured default reviewers and CC recipients
+    #[arg(long)]
+    no_default_reviewers: bool,
+
+    /// Add reviewers from CODEOWNERS for the files changed by the push
+    #[arg(long)]
+    codeowners: bool,
+
+    /// Keep yourself in the reviewer list instead of dropping it
+    #[arg(long)]
+    allow_self_review: bool,
+
+    /// Move yourself to CC when dropped from the reviewer list
+    #[arg(long, conflicts_with = "allow_self_review")]
+    cc_self: bool,
+
     /// Add CC recipients (comma-separated or repeated)
     #[arg(long, value_delimiter = ',')]
     cc: Vec<String>,
@@ -144,23 +312,64 @@ struct PushArgs {
     #[arg(long, value_delimiter = ',')]
     hashtags: Vec<String>,
 
-    /// Review message
+    /// Review message; `{change}`, `{branch}`, `{patchset}`, `{topic}` and
+    /// `{ticket}` are expanded (default: the `push.message_template` setting)
     #[arg(short, long)]
     message: Option<String>,
 
+    /// Push without a review message, ignoring `push.message_template`
+    #[arg(long, conflicts_with = "message")]
+    no_message: bool,
+
+    /// Fail if the message contains a placeholder that cannot be expanded
+    #[arg(long)]
+    strict_message: bool,
+
     /// Notification setting (NONE, OWNER, etc.)
     #[arg(long)]
     notify: Option<String>,
 
+    /// Vote at push time: `LABEL`, `LABEL+N` or `LABEL-N` (comma-separated or repeated)
+    #[arg(long = "label", value_name = "VOTE", value_delimiter = ',')]
+    labels: Vec<String>,
+
+    /// Base the pushed changes on this commit (any revision git can resolve)
+    #[arg(long, value_name = "COMMIT")]
+    base: Option<String>,
+
+    /// Create changes for commits that are already merged into the branch
+    #[arg(long)]
+    merged: bool,
+
+    /// Publish your draft comments on the updated changes
+    #[arg(long)]
+    publish_comments: bool,
+
+    /// Append a raw push option (`KEY=VALUE` or `KEY`) to the refspec
+    #[arg(long = "push-option", value_name = "OPTION")]
+    push_options: Vec<String>,
+
+    /// Write `change_id=`, `change_number=` and `change_url=` lines to PATH after pushing
+    #[arg(long, value_name = "PATH")]
+    change_id_file: Option<PathBuf>,
+
+    /// Notify these accounts as TO recipients (posted as a review after push)
+    #[arg(long, value_delimiter = ',')]
+    notify_to: Vec<String>,
+
+    /// Notify these accounts as CC recipients (posted as a review after push)
+    #[arg(long, value_delimiter = ',')]
+    notify_cc: Vec<String>,
+
     /// Disable automatic rebase
     #[arg(long)]
     no_rebase: bool,
 
-    /// Force rebase before pushing
+    /// Rebase and push the rebased commits (undone if nothing is pushed)
     #[arg(long)]
     force_rebase: bool,
 
-    /// Keep rebase state on failure (don't abort)
+    /// Keep rebase state on conflict, and keep --force-rebase commits when nothing is pushed
     #[arg(long)]
     keep_rebase: bool,
 
@@ -168,6 +377,10 @@ struct PushArgs {
     #[arg(long)]
     dry_run: bool,
 
+    /// Print only the computed refspec and exit
+    #[arg(long, visible_alias = "debug-refspec")]
+    print_refspec: bool,
+
     /// Skip confirmation prompt
     #[arg(short, long)]
     yes: bool,
@@ -180,17 +393,48 @@ struct PushArgs {
     #[arg(long)]
     no_thin: bool,
 
-    /// Color for git push (e.g. always, never, auto). Set by caller, not CLI.
-    #[arg(skip)]
-    pub color_remote: Option<String>,
-
-    /// Output format
-    #[arg(long, value_enum, default_value = "text")]
-    format: OutputFormat,
+    /// GPG-sign the push (`git push --signed`), signing HEAD first if needed
+    /// (default: the `signedpush` setting)
+    #[arg(
+        short = 'S',
+        long,
+        visible_alias = "signed-push",
+        conflicts_with = "no_sign"
+    )]
+    sign: bool,
+
+    /// Do not sign the push even if `signedpush` is set
+    #[arg(long)]
+    no_sign: bool,
+
+    /// Push the superproject and every submodule with its own `.gitreview`,
+    /// each to its configured target, after showing a combined plan
+    #[arg(
+        long,
+        conflicts_with_all = ["branch", "remote", "change_id_file", "print_refspec"]
+    )]
+    recurse: bool,
+
+    /// Push every local branch matching this glob (e.g. `fix/*`) that has
+    /// unpushed commits with Change-Ids, each to the branch it tracks
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        conflicts_with_all = ["branch", "remote", "recurse", "change_id_file", "print_refspec", "base"]
+    )]
+    all_branches: Option<String>,
+
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
 }
 
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 struct CommentsArgs {
+    #[command(subcommand)]
+    action: Option<CommentsAction>,
+
     /// Change number or Change-Id (auto-detected from HEAD if omitted)
     change: Option<String>,
 
@@ -206,9 +450,9 @@ struct CommentsArgs {
     #[arg(long, conflicts_with = "unresolved")]
     resolved: bool,
 
-    /// Output format
-    #[arg(long, value_enum, default_value = "text")]
-    format: OutputFormat,
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<CommentsFormat>,
 
     /// Show comments from all revisions
     #[arg(long)]
@@ -222,6 +466,20 @@ struct CommentsArgs {
     #[arg(long)]
     comment_by: Option<String>,
 
+    /// Show only threads started by this user (name or email substring match)
+    #[arg(long)]
+    author: Option<String>,
+
+    /// Show only threads on files matching this glob (`*` stays within a
+    /// directory, `**` crosses them; `*.py` also matches by file name)
+    #[arg(long, value_name = "GLOB")]
+    file: Option<String>,
+
+    /// Show only threads with activity since a patchset (e.g. 3) or date
+    /// (YYYY-MM-DD). A patchset implies --all-revisions
+    #[arg(long, value_name = "PATCHSET|DATE")]
+    since: Option<String>,
+
     /// Only show threads with 2+ comments (i.e., that received replies)
     #[arg(long)]
     has_replies: bool,
@@ -242,6 +500,14 @@ struct CommentsArgs {
     #[arg(long)]
     project: Option<String>,
 
+    /// Remote to resolve the Gerrit server from
+    #[arg(long)]
+    remote: Option<String>,
+
+    /// Branch to restrict cross-change search mode to
+    #[arg(long)]
+    branch: Option<String>,
+
     /// Show only comments no older than this age (e.g., 30d, 2w, 1m, 1y).
     /// In cross-change search mode this is also passed to Gerrit as -age:<value>.
     #[arg(long)]
@@ -251,6 +517,100 @@ struct CommentsArgs {
     /// Useful for finding stale unresolved threads.
     #[arg(long)]
     min_age: Option<String>,
+
+    /// Read comments from the local cache without contacting Gerrit
+    #[arg(long, conflicts_with = "refresh")]
+    cached: bool,
+
+    /// Always fetch from Gerrit; never fall back to the local cache
+    #[arg(long)]
+    refresh: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum CommentsAction {
+    /// Create a draft inline comment, or a draft reply to a thread
+    Reply(ReplyArgs),
+
+    /// Publish your draft comments as a review
+    Publish(PublishArgs),
+}
+
+#[derive(Parser, Debug)]
+struct ReplyArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    change: Option<String>,
+
+    /// File to comment on (taken from the parent comment with --in-reply-to)
+    #[arg(long)]
+    file: Option<String>,
+
+    /// Line to comment on; omit for a file-level comment
+    #[arg(long)]
+    line: Option<u32>,
+
+    /// Comment text
+    #[arg(short, long)]
+    message: String,
+
+    /// Id of the comment to reply to (shown by `grt comments --format json`)
+    #[arg(long)]
+    in_reply_to: Option<String>,
+
+    /// Mark the thread resolved
+    #[arg(long, conflicts_with = "unresolved")]
+    resolved: bool,
+
+    /// Mark the thread unresolved
+    #[arg(long)]
+    unresolved: bool,
+
+    /// Revision to comment on
+    #[arg(long, default_value = "current")]
+    revision: String,
+}
+
+#[derive(Parser, Debug)]
+struct PublishArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    change: Option<String>,
+
+    /// Review message posted with the drafts
+    #[arg(short, long)]
+    message: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+struct AuthArgs {
+    #[command(subcommand)]
+    action: AuthAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum AuthAction {
+    /// Verify credentials for a Gerrit host and store them in the OS keyring
+    Login {
+        /// Gerrit host (default: the host configured for this repo)
+        host: Option<String>,
+
+        /// HTTP username (prompted for when omitted)
+        #[arg(long)]
+        username: Option<String>,
+
+        /// The secret is a bearer token rather than an HTTP password
+        #[arg(long)]
+        bearer: bool,
+
+        /// Read the password or token from stdin instead of prompting
+        #[arg(long)]
+        password_stdin: bool,
+    },
+
+    /// Remove the credentials stored in the OS keyring for a Gerrit host
+    Logout {
+        /// Gerrit host (default: the host configured for this repo)
+        host: Option<String>,
+    },
 }
 
 #[derive(Parser, Debug)]
@@ -263,6 +623,10 @@ struct SetupArgs {
     #[arg(long)]
     force_hook: bool,
 
+    /// Replace a commit-msg hook installed by an older grt
+    #[arg(long, conflicts_with_all = ["force_hook", "remote_hook"])]
+    update_hook: bool,
+
     /// Download hook from remote Gerrit server instead of using vendored copy
     #[arg(long)]
     remote_hook: bool,
@@ -276,1450 +640,5623 @@ struct SetupArgs {
     http: bool,
 }
 
-/// CLI personality based on argv[0].
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Personality {
-    /// Normal `grt` invocation with subcommands.
-    Grt,
-    /// Busybox-style `git-review` invocation with flat flags.
-    GitReview,
+#[derive(Parser, Debug)]
+struct ConfigArgs {
+    #[command(subcommand)]
+    action: ConfigAction,
 }
 
-/// Detect CLI personality from argv[0].
-fn detect_personality(argv0: &str) -> Personality {
-    let basename = Path::new(argv0)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("");
-    if basename == "git-review" {
-        Personality::GitReview
-    } else {
-        Personality::Grt
-    }
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// Print every setting with the layer that set it
+    Show {
+        /// Output format (default: the repo's default_output_format, else text)
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Write a setting to the layer it belongs to
+    Set {
+        /// Setting name as shown by `grt config show`
+        key: String,
+
+        /// New value
+        value: String,
+    },
 }
 
-fn init_tracing(verbosity: u8) {
-    let level = match verbosity {
-        0 => "warn",
-        1 => "info",
-        2 => "debug",
-        _ => "trace",
-    };
+#[derive(Parser, Debug)]
+struct HooksArgs {
+    #[command(subcommand)]
+    action: HooksAction,
+}
 
-    let filter = format!("grt={level}");
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(&filter)),
-        )
-        .with_target(false)
-        .without_time()
-        .init();
+#[derive(Subcommand, Debug)]
+enum HooksAction {
+    /// Install the bundled commit-msg hook
+    Install {
+        /// Install into a global hooks directory instead of this repository's
+        #[arg(long)]
+        global: bool,
+
+        /// Global hooks directory (default: the global core.hooksPath, else ~/.config/git/hooks)
+        #[arg(long, requires = "global")]
+        dir: Option<PathBuf>,
+
+        /// Point the global core.hooksPath at the directory
+        #[arg(long, requires = "global")]
+        set_hooks_path: bool,
+
+        /// Replace an existing commit-msg hook
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show where the commit-msg hook resolves for this repository and what it is
+    Status {
+        /// Output format (default: text)
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
 }
 
-/// Map an error to an exit code for git-review compatibility.
-fn exit_code_for_error(err: &anyhow::Error) -> i32 {
-    // Check for GerritError in the error chain
-    if let Some(gerrit_err) = err.downcast_ref::<GerritError>() {
-        return match gerrit_err {
-            GerritError::AuthFailed { .. } => 1,
-            GerritError::NotFound => 1,
-            GerritError::ServerError { .. } => 1,
-            GerritError::Network(_) => 40,
-        };
-    }
+#[derive(Parser, Debug)]
+struct SubmitArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    #[arg(conflicts_with = "topic")]
+    change: Option<String>,
 
-    // Check for common error patterns in the message
-    let msg = format!("{err:#}");
-    if msg.contains("git config") || msg.contains("no Gerrit host configured") {
-        return 128;
-    }
-    if msg.contains("argument") || msg.contains("CHANGE,PS") || msg.contains("malformed") {
-        return 3;
-    }
-    if msg.contains("hook") {
-        return 2;
-    }
+    /// Submit all open changes in this topic together
+    #[arg(long)]
+    topic: Option<String>,
 
-    1 // generic error
+    /// Only consider changes in this project (with --topic)
+    #[arg(long, requires = "topic")]
+    project: Option<String>,
+
+    /// Vote Auto-Submit+1 so Gerrit submits the change once it is approved
+    #[arg(long, conflicts_with = "topic")]
+    when_ready: bool,
 }
 
-fn cmd_completions(shell: clap_complete::Shell) {
-    let mut cmd = Cli::command();
-    clap_complete::generate(shell, &mut cmd, "grt", &mut std::io::stdout());
+#[derive(Parser, Debug)]
+struct ChangeStateArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    change: Option<String>,
+
+    /// Message to attach; prompted for on a terminal when omitted
+    #[arg(short, long)]
+    message: Option<String>,
 }
 
-/// Resolve color.remote value from CLI flags.
-fn resolve_color_remote(no_color: bool, color: Option<&str>) -> String {
-    if no_color {
-        return "never".to_string();
-    }
-    match color {
-        Some("always") | Some("never") | Some("auto") => color.unwrap().to_string(),
-        _ => "always".to_string(),
-    }
+#[derive(Parser, Debug)]
+struct WipArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    change: Option<String>,
+
+    /// Message to add to the change
+    #[arg(short, long)]
+    message: Option<String>,
 }
 
-/// Prompt the user for their Gerrit username on stderr, read from stdin.
-/// Returns an error if stdin is not a tty or input is empty.
-fn prompt_for_username() -> Result<String> {
-    use std::io::IsTerminal as _;
+#[derive(Parser, Debug)]
+struct TopicArgs {
+    /// `[CHANGE] TOPIC`: change number, Change-Id, or URL (default: HEAD's
+    /// Change-Id), then the new topic. With --clear, only the optional CHANGE.
+    #[arg(value_name = "ARGS", num_args = 0..=2)]
+    args: Vec<String>,
 
-    if !std::io::stdin().is_terminal() {
-        anyhow::bail!("stdin is not a tty; set gitreview.username in git config");
-    }
-    eprint!("Enter your Gerrit username: ");
-    let mut line = String::new();
-    std::io::stdin()
-        .read_line(&mut line)
-        .context("reading username from stdin")?;
-    let username = line.trim().to_string();
-    if username.is_empty() {
-        anyhow::bail!("Gerrit username cannot be empty");
-    }
-    Ok(username)
+    /// Remove the change's topic
+    #[arg(long)]
+    clear: bool,
 }
 
-/// Check if the configured remote exists, and create it if possible.
-///
-/// - If remote exists with a tracking branch: no-op
-/// - If remote exists but no tracking branch: run `git remote update`
-/// - If remote doesn't exist and config has enough info: create it
-fn check_and_create_remote(app: &mut App) -> Result<()> {
-    let remote = app.config.remote.clone();
-    let root = app.require_git()?.root()?;
+#[derive(Parser, Debug)]
+struct ReviewersArgs {
+    #[command(subcommand)]
+    action: ReviewersAction,
+}
 
-    match subprocess::check_remote_exists(&remote, &root)? {
-        Some(_url) => {
-            // Remote exists — check if tracking branch exists
-            let tracking_ref = format!("refs/remotes/{}/{}", remote, app.config.branch);
-            let has_tracking =
-                subprocess::git_output(&["show-ref", "--verify", "--quiet", &tracking_ref], &root)
-                    .is_ok();
+#[derive(Subcommand, Debug)]
+enum ReviewersAction {
+    /// Add reviewers, or CCs with --cc
+    Add {
+        /// `[CHANGE] USER...`: change number, Change-Id, or URL (default:
+        /// HEAD's Change-Id), then accounts or groups to add
+        #[arg(value_name = "ARGS", required = true)]
+        args: Vec<String>,
+
+        /// Add as CC instead of reviewer
+        #[arg(long)]
+        cc: bool,
+    },
 
-            if !has_tracking {
-                tracing::info!("Remote '{remote}' exists but has no tracking branch, updating...");
-                subprocess::git_remote_update(&remote, &root)?;
-            }
-        }
-        None => {
-            // Remote doesn't exist — try to create from config
-            if app.config.host.is_empty() {
-                return Ok(()); // Not enough config to auto-create
-            }
+    /// Remove reviewers or CCs
+    Remove {
+        /// `[CHANGE] USER...`: change (default: HEAD's Change-Id), then accounts to remove
+        #[arg(value_name = "ARGS", required = true)]
+        args: Vec<String>,
+    },
 
-            // For SSH remotes, prompt for username if not configured
-            if app.config.scheme.starts_with("ssh") && app.config.username.is_none() {
-                let username = prompt_for_username()?;
-                app.config.username = Some(username.clone());
-                // Persist to git config so it's not asked again
-                subprocess::git_exec(&["config", "gitreview.username", &username], &root)?;
-            }
+    /// List reviewers and CCs
+    List {
+        /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+        change: Option<String>,
+    },
 
-            let url = app.config.make_remote_url();
-            tracing::info!("Creating remote '{remote}' with URL {url}...");
-            subprocess::git_remote_add(&remote, &url, &root)?;
+    /// Suggest accounts and groups matching a name or email
+    Suggest {
+        /// `[CHANGE] QUERY`: change (default: HEAD's Change-Id), then the text to match
+        #[arg(value_name = "ARGS", required = true, num_args = 1..=2)]
+        args: Vec<String>,
 
-            // Set push URL if usepushurl is configured
-            if app.config.usepushurl {
-                let push_url = app.config.make_remote_url();
-                subprocess::git_remote_set_push_url(&remote, &push_url, &root)?;
-            }
-        }
-    }
+        /// Maximum number of suggestions
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+}
 
-    Ok(())
+#[derive(Parser, Debug)]
+struct HashtagsArgs {
+    #[command(subcommand)]
+    action: HashtagsAction,
 }
 
-#[tokio::main]
-async fn main() {
-    let argv0 = std::env::args().next().unwrap_or_default();
-    let personality = detect_personality(&argv0);
+#[derive(Subcommand, Debug)]
+enum HashtagsAction {
+    /// Add hashtags
+    Add {
+        /// `[CHANGE] TAG...`: change number, Change-Id, or URL (default:
+        /// HEAD's Change-Id), then hashtags (comma-separated or repeated)
+        #[arg(value_name = "ARGS", required = true)]
+        args: Vec<String>,
+    },
 
-    let result = match personality {
-        Personality::GitReview => {
-            let cli = GitReviewCli::parse();
-            init_tracing(cli.verbose);
+    /// Remove hashtags
+    Remove {
+        /// `[CHANGE] TAG...`: change (default: HEAD's Change-Id), then hashtags to remove
+        #[arg(value_name = "ARGS", required = true)]
+        args: Vec<String>,
+    },
 
-            if cli.license {
-                println!("Licensed under Apache-2.0 OR MIT");
-                return;
-            }
+    /// List hashtags
+    List {
+        /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+        change: Option<String>,
+    },
+}
 
-            let work_dir = std::env::current_dir().expect("cannot determine current directory");
-            let color = resolve_color_remote(cli.no_color, cli.color.as_deref());
-            cmd_review(&work_dir, cli.review, false, None, Some(color)).await
-        }
-        Personality::Grt => {
-            let cli = Cli::parse();
-            init_tracing(cli.verbose);
+#[derive(Parser, Debug)]
+struct AttentionArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    change: Option<String>,
 
-            let work_dir = cli.directory.unwrap_or_else(|| {
-                std::env::current_dir().expect("cannot determine current directory")
-            });
+    /// Add a user to the attention set (repeatable)
+    #[arg(long, value_name = "USER")]
+    add: Vec<String>,
 
-            let insecure = cli.insecure;
-            let server = cli.server.clone();
-            let color = resolve_color_remote(cli.no_color, None);
-            match cli.command {
-                Commands::Review(args) => {
-                    cmd_review(&work_dir, args, insecure, server, Some(color)).await
-                }
-                Commands::Push(args) => {
-                    let mut push_args = args;
-                    push_args.color_remote = Some(color);
-                    cmd_push(&work_dir, push_args, insecure, server).await
-                }
-                Commands::Comments(args) => cmd_comments(&work_dir, args, insecure, server).await,
-                Commands::Setup(args) => cmd_setup(&work_dir, args, insecure, server).await,
-                Commands::Export(args) => export::cmd_export(&args),
-                Commands::Version => cmd_version(&work_dir).await,
-                Commands::Completions { shell } => {
-                    cmd_completions(shell);
-                    Ok(())
-                }
-            }
-        }
-    };
+    /// Remove a user from the attention set (repeatable)
+    #[arg(long, value_name = "USER")]
+    remove: Vec<String>,
 
-    if let Err(err) = result {
-        eprintln!("error: {err:#}");
-        std::process::exit(exit_code_for_error(&err));
-    }
-}
+    /// Reason shown on the change for the update
+    #[arg(long)]
+    reason: Option<String>,
 
-/// Dispatch `grt review` / `git-review` based on which mode flag is set.
-async fn cmd_review(
-    work_dir: &Path,
-    args: ReviewArgs,
-    insecure: bool,
-    server: Option<String>,
-    color_remote: Option<String>,
-) -> Result<()> {
-    // Setup mode: run setup, but continue if --finish is also set
-    if args.setup {
-        cmd_setup(
-            work_dir,
-            SetupArgs {
-                remote: args.remote.clone(),
-                force_hook: false,
-                remote_hook: args.remote_hook,
-                ssh: false,
-                http: false,
-            },
-            insecure,
-            server.clone(),
-        )
-        .await?;
-        if !args.finish {
-            return Ok(());
-        }
-        // Fall through to finish logic below
-    }
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+}
 
-    // Warn about flags that are parsed but not yet fully implemented
-    review::warn_unused_flags(&args);
+#[derive(Parser, Debug)]
+struct CheckoutArgs {
+    /// Change number, Change-Id, or URL, optionally with a patchset (CHANGE[,PS])
+    change: String,
 
-    // Create a single App instance for all mode dispatches
-    let cli_overrides = CliOverrides {
-        host: server,
-        remote: args.remote.clone(),
-        use_pushurl: args.use_pushurl.then_some(true),
-        insecure,
-        ..Default::default()
-    };
-    let mut app = App::new(work_dir, &cli_overrides)?;
+    /// Git remote to fetch the change from
+    #[arg(long)]
+    remote: Option<String>,
 
-    // Ensure remote exists (auto-create if possible)
-    check_and_create_remote(&mut app)?;
+    /// Fail if the fetched commit does not match the revision Gerrit reported
+    #[arg(long)]
+    verify: bool,
 
-    // Resolve branch via --track if no explicit branch given (before all mode dispatches)
-    let branch = if args.track && args.branch.is_none() {
-        match app.require_git()?.upstream_branch()? {
-            Some((_remote, merge_branch)) => {
-                tracing::debug!("--track resolved upstream branch to {}", merge_branch);
-                Some(merge_branch)
-            }
-            None => None,
-        }
-    } else {
-        args.branch.clone()
-    };
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+}
 
-    // Download mode
-    if let Some(ref change_arg) = args.download {
-        return review::cmd_review_download(&mut app, change_arg, &args.format).await;
-    }
+#[derive(Parser, Debug)]
+struct StatusArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    change: Option<String>,
 
-    // Cherry-pick modes
-    if let Some(ref change_arg) = args.cherrypick {
-        return review::cmd_review_cherrypick(&mut app, change_arg).await;
-    }
-    if let Some(ref change_arg) = args.cherrypickindicate {
-        return review::cmd_review_cherrypickindicate(&mut app, change_arg).await;
-    }
-    if let Some(ref change_arg) = args.cherrypickonly {
-        return review::cmd_review_cherrypickonly(&mut app, change_arg).await;
-    }
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+}
 
-    // Compare mode
-    if let Some(ref compare_arg) = args.compare {
-        let compare_branch = branch.as_deref().unwrap_or(&app.config.branch).to_string();
-        return review::cmd_review_compare(
-            &mut app,
-            compare_arg,
-            &compare_branch,
-            args.no_rebase,
-            args.force_rebase,
-        )
-        .await;
-    }
+#[derive(Parser, Debug)]
+struct OpenArgs {
+    /// Change number, Change-Id, or URL, optionally `CHANGE,PS` (default: HEAD's Change-Id)
+    change: Option<String>,
 
-    // List mode
-    if args.list > 0 {
-        return review::cmd_review_list(&mut app, branch.as_deref(), args.list >= 2, &args.format)
-            .await;
-    }
+    /// Print the URL instead of launching a browser
+    #[arg(long)]
+    print: bool,
+}
 
-    // Pre-push: --update runs `git remote update`
-    if args.update {
-        let remote = args.remote.as_deref().unwrap_or(&app.config.remote);
-        let root = app.require_git()?.root()?;
-        tracing::info!("Updating remote {remote}...");
-        subprocess::git_remote_update(remote, &root)?;
-    }
+#[derive(Parser, Debug)]
+struct DiffArgs {
+    /// Change number, Change-Id, or URL, optionally `CHANGE,PS` (default: latest patchset)
+    change: String,
 
-    // Pre-push: --new-changeid strips Change-Id and amends
-    if args.new_changeid {
-        let root = app.require_git()?.root()?;
-        tracing::info!("Regenerating Change-Id...");
-        subprocess::git_regenerate_changeid(&root)?;
-    }
+    /// Diff against the target branch instead of the patchset's parent
+    #[arg(long)]
+    against_target: bool,
+}
 
-    // Capture current branch name for --finish (only when not dry-run) (Task B2)
-    let current_branch_name = if args.finish && !args.dry_run {
-        Some(
-            app.require_git()?
-                .current_branch_or_default(&app.config.branch),
-        )
-    } else {
-        None
-    };
+#[derive(Parser, Debug)]
+struct FixArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    change: Option<String>,
 
-    // Default topic to current branch name (Task H3)
-    let topic = if args.no_topic {
-        None
-    } else {
-        args.topic
-            .clone()
-            .or_else(|| app.git.as_ref()?.current_branch().ok())
-    };
+    /// Apply every suggestion without prompting
+    #[arg(long, conflicts_with = "list")]
+    all: bool,
 
-    // Default mode: push
-    cmd_push(
-        work_dir,
-        PushArgs {
-            branch,
-            remote: args.remote.clone(),
-            topic,
-            wip: args.wip,
-            ready: args.ready,
-            private: args.private,
-            remove_private: args.remove_private,
-            reviewers: args.reviewers,
-            cc: args.cc,
-            hashtags: args.hashtags,
-            message: args.message,
-            notify: args.notify.map(|n| n.to_string()),
-            no_rebase: args.no_rebase,
-            force_rebase: args.force_rebase,
-            keep_rebase: args.keep_rebase,
-            dry_run: args.dry_run,
-            yes: args.yes,
-            new_changeid: false, // already handled above
-            no_thin: args.no_thin,
-            format: args.format.clone(),
-            color_remote: color_remote.clone(),
-        },
-        insecure,
-        None,
-    )
-    .await?;
+    /// Only list the suggested fixes
+    #[arg(long)]
+    list: bool,
 
-    // Post-push: --finish checks out default branch and deletes topic branch (Task B2)
-    if let Some(topic_branch) = current_branch_name {
-        if !args.dry_run {
-            let default_branch = app.config.branch.clone();
-            let root = app.require_git()?.root()?;
-            tracing::info!(
-                "Finishing: checking out {} and deleting {}...",
-                default_branch,
-                topic_branch
-            );
-            subprocess::git_checkout(&default_branch, &root)?;
-            subprocess::git_delete_branch(&topic_branch, &root)?;
-        }
-    }
+    /// Amend HEAD with the fixed files afterwards
+    #[arg(long, conflicts_with = "list")]
+    amend: bool,
 
-    Ok(())
+    /// Apply even when HEAD is not the change's current patchset or the
+    /// working tree has uncommitted changes
+    #[arg(long)]
+    force: bool,
 }
 
-async fn cmd_push(
-    work_dir: &Path,
-    args: PushArgs,
-    insecure: bool,
-    server: Option<String>,
-) -> Result<()> {
-    let cli_overrides = CliOverrides {
-        host: server,
-        remote: args.remote.clone(),
-        branch: args.branch.clone(),
-        insecure,
-        ..Default::default()
-    };
-    let mut app = App::new(work_dir, &cli_overrides)?;
-    let root = app.require_git()?.root()?;
+#[derive(Parser, Debug)]
+struct BackportArgs {
+    /// Change number, Change-Id, or URL to backport (`CHANGE,PS` picks a patchset)
+    change: String,
 
-    // Ensure remote exists (auto-create if possible)
-    check_and_create_remote(&mut app)?;
+    /// Branch to backport to (repeat for several)
+    #[arg(long = "to", value_name = "BRANCH", required = true)]
+    to: Vec<String>,
 
-    // Ensure commit-msg hook is installed
-    let hooks_dir = app.require_git()?.hooks_dir()?;
-    hook::ensure_hook_installed(&hooks_dir)?;
-    debug!("commit-msg hook verified at {:?}", hooks_dir);
+    /// Give each backport a new Change-Id instead of keeping the original one
+    #[arg(long)]
+    new_change_id: bool,
 
-    // Propagate hook to submodules (non-fatal)
-    if let Err(e) = hook::propagate_hook_to_submodules(&root) {
-        tracing::warn!("failed to propagate hook to submodules: {e}");
-    }
+    /// Topic for the pushed backports
+    #[arg(long)]
+    topic: Option<String>,
 
-    let branch = args.branch.unwrap_or_else(|| app.config.branch.clone());
-    let remote = args.remote.unwrap_or_else(|| app.config.remote.clone());
+    /// Add reviewers (comma-separated or repeated)
+    #[arg(short = 'r', long, value_delimiter = ',')]
+    reviewers: Vec<String>,
 
-    // Check Change-Id status with better error handling (Task M15)
-    let commit_msg = app.require_git()?.head_commit_message()?;
-    let hook_installed = app
-        .require_git()?
-        .hooks_dir()
-        .map(|d| d.join("commit-msg").exists())
-        .unwrap_or(false);
-    match push::check_change_id_status(&commit_msg, hook_installed) {
-        ChangeIdStatus::Present(_) => {}
-        ChangeIdStatus::MissingCanAutoAmend => {
-            eprintln!("No Change-Id found; amending commit to add one...");
-            subprocess::git_exec(&["commit", "--amend", "--no-edit"], &root)?;
-        }
-        ChangeIdStatus::MissingNeedHook => {
-            anyhow::bail!("HEAD commit is missing a Change-Id trailer. Run `grt setup` to install the commit-msg hook, then amend the commit");
-        }
-    }
+    /// Prepare the backport branches without pushing them
+    #[arg(long)]
+    no_push: bool,
 
-    // Pre-push rebase (test rebase to detect conflicts)
-    let should_rebase = !args.no_rebase && (app.config.default_rebase || args.force_rebase);
-    let rebase_orig_head = if should_rebase {
-        match rebase::rebase_changes(&remote, &branch, args.keep_rebase, &root)? {
-            rebase::RebaseResult::Success { orig_head } => Some(orig_head),
-            rebase::RebaseResult::Failed => return Ok(()),
-            rebase::RebaseResult::Skipped => None,
-        }
-    } else {
-        None
-    };
+    /// Show what would be pushed without pushing
+    #[arg(long, conflicts_with = "no_push")]
+    dry_run: bool,
+}
 
-    // Undo rebase BEFORE push (unless --force-rebase) so we push original SHAs.
-    // This matches git-review's behavior: test rebase detects conflicts, but we
-    // push the original commits so unchanged commits don't get new patchsets.
-    if let Some(ref orig_head) = rebase_orig_head {
-        if !args.force_rebase {
-            rebase::undo_rebase(orig_head, &root)?;
-        }
-    }
+#[derive(Parser, Debug)]
+struct CherrypickRemoteArgs {
+    /// Change number, Change-Id, or URL to cherry-pick (`CHANGE,PS` picks a patchset)
+    change: String,
 
-    // Count unpushed commits
-    let count = subprocess::count_unpushed_commits(&remote, &branch, &root)?;
-    if count == 0 {
-        eprintln!("No unpushed commits found.");
-        return Ok(());
-    }
+    /// Branch to cherry-pick onto
+    #[arg(long, value_name = "BRANCH")]
+    branch: String,
 
-    if count > 1 && !args.yes {
-        let commits = subprocess::list_unpushed_commits(&remote, &branch, &root)?;
-        eprintln!(
-            "You are about to submit multiple commits to {remote}/{branch}:\n\n{commits}\n\nContinue? [y/N] "
-        );
-        let mut input = String::new();
-        std::io::stdin()
-            .read_line(&mut input)
-            .context("reading user input")?;
-        if !input.trim().eq_ignore_ascii_case("y") {
-            eprintln!("Push cancelled.");
-            return Ok(());
-        }
-    }
+    /// Commit message for the new change (default: the original message)
+    #[arg(short, long)]
+    message: Option<String>,
 
-    let opts = PushOptions {
-        branch: branch.clone(),
-        topic: args.topic,
-        wip: args.wip,
-        ready: args.ready,
-        private: args.private,
-        remove_private: args.remove_private,
-        reviewers: args.reviewers,
-        cc: args.cc,
-        hashtags: args.hashtags,
-        message: args.message,
-        notify: args.notify,
-    };
+    /// Topic for the new change
+    #[arg(long)]
+    topic: Option<String>,
 
-    let refspec = push::build_refspec(&opts)?;
+    /// Add the original change's reviewers to the new change
+    #[arg(long)]
+    keep_reviewers: bool,
 
-    let color_val = args.color_remote.as_deref().unwrap_or("always");
-    let color_config = format!("color.remote={}", color_val);
+    /// Create the change with conflict markers instead of failing on conflicts
+    #[arg(long)]
+    allow_conflicts: bool,
 
-    // Dry-run: show full command with all flags (Task L13)
-    if args.dry_run {
-        let mut dry_args: Vec<&str> = vec!["git", "-c", &color_config, "push", "--no-follow-tags"];
-        if args.no_thin {
-            dry_args.push("--no-thin");
-        }
-        dry_args.push(&remote);
-        dry_args.push(&refspec);
-        println!("{}", dry_args.join(" "));
-        return Ok(());
-    }
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+}
 
-    // Build push args with --no-follow-tags, color remote, and optional --no-thin
-    // (Tasks M6, M7, L13, L15)
-    let mut push_args: Vec<&str> = vec!["-c", &color_config, "push", "--no-follow-tags"];
-    if args.no_thin {
-        push_args.push("--no-thin");
-    }
-    push_args.push(&remote);
-    push_args.push(&refspec);
+#[derive(Parser, Debug)]
+struct RebaseChainArgs {
+    /// Target branch (default: the configured branch, else the remote's default
+    /// branch, else "master"; the same as `grt push`)
+    branch: Option<String>,
 
-    eprintln!("Pushing {count} commit(s) to {remote}/{branch}...");
+    /// Remote to rebase onto
+    #[arg(long)]
+    remote: Option<String>,
 
-    // Catch push errors and suggest --no-thin for "Missing tree" (Task L14)
-    if let Err(e) = subprocess::git_exec(&push_args, &root) {
-        let msg = format!("{e:#}");
-        if msg.contains("Missing tree") || msg.contains("missing tree") {
-            eprintln!("hint: Consider trying again with --no-thin");
-        }
-        return Err(e);
-    }
+    /// Hand the series to `git rebase -i` instead
+    #[arg(short, long, conflicts_with_all = ["keep_rebase", "format"])]
+    interactive: bool,
 
-    match args.format {
-        OutputFormat::Json => {
-            // Re-read commit message to get Change-Id (may have been added by amend)
-            let commit_msg = app.require_git()?.head_commit_message().unwrap_or_default();
-            let result = PushResult {
-                commits: count,
-                remote: remote.clone(),
-                branch: branch.clone(),
-                change_id: push::extract_change_id(&commit_msg),
-                refspec: refspec.clone(),
-            };
-            println!("{}", serde_json::to_string_pretty(&result)?);
-        }
-        OutputFormat::Text => {
-            eprintln!("Push successful.");
-        }
-    }
+    /// Leave the rebase in progress on conflict instead of aborting it
+    #[arg(long)]
+    keep_rebase: bool,
 
-    Ok(())
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
 }
 
-async fn cmd_comments(
-    work_dir: &Path,
-    args: CommentsArgs,
-    insecure: bool,
-    server: Option<String>,
-) -> Result<()> {
-    let cli_overrides = CliOverrides {
-        host: server,
-        project: args.project.clone(),
-        insecure,
-        ..Default::default()
-    };
-    let mut app = App::new(work_dir, &cli_overrides)?;
-    app.authenticate_and_verify().await?;
-
-    // Resolve --age / --max-age into YYYY-MM-DD date bounds used by both modes.
-    // --age N   → keep threads newer than N ago  → `after` lower bound
-    // --max-age N → keep threads older than N ago → `before` upper bound
-    let age_after: Option<String> = args.age.as_deref().map(parse_age_to_date).transpose()?;
-    let age_before: Option<String> = args.min_age.as_deref().map(parse_age_to_date).transpose()?;
+#[derive(Parser, Debug)]
+struct DepsArgs {
+    /// Change number, Change-Id, or URL (default: HEAD's Change-Id)
+    change: Option<String>,
 
-    // Search mode: no change given, but --project or --age provided
-    let search_mode = args.change.is_none() && (args.project.is_some() || args.age.is_some());
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+}
 
-    if search_mode {
-        let mut query_parts = Vec::new();
-        if let Some(ref proj) = args.project {
-            query_parts.push(format!("project:{proj}"));
-        }
-        if let Some(ref age) = args.age {
-            // -age:N = changes active within the last N (newer than N ago)
-            query_parts.push(format!("-age:{age}"));
-        }
-        if let Some(ref min_age) = args.min_age {
-            // age:N = changes last modified more than N ago (older than N ago)
-            query_parts.push(format!("age:{min_age}"));
-        }
-        // Use commentby: for server-side pre-filtering — unlike reviewer: it matches
-        // anyone who has posted a comment, including CI bots that are not formal reviewers.
-        if let Some(ref pat) = args.comment_by {
-            query_parts.push(format!("commentby:{pat}"));
-        }
-        let query = query_parts.join(" ");
+#[derive(Parser, Debug)]
+struct LogArgs {
+    /// Target branch the commits are compared with (default: the configured branch)
+    #[arg(long)]
+    branch: Option<String>,
 
-        let changes = app.gerrit.query_changes(&query).await?;
-        let gerrit_url = app.config.gerrit_base_url()?.to_string();
+    /// How many commits already on the target branch to include
+    #[arg(long, default_value_t = 5)]
+    merged: usize,
 
-        let mut outputs: Vec<comments::CommentOutput> = Vec::new();
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+}
 
-        for change in &changes {
-            let change_id = match change.number {
-                Some(n) => n.to_string(),
-                None => continue,
-            };
+#[derive(Parser, Debug)]
+struct QueryArgs {
+    /// Gerrit query, e.g. "owner:self status:open label:Verified-1"
+    query: String,
 
-            let change_detail = app.gerrit.get_change_detail(&change_id).await?;
-            // In search mode always fetch all revisions — a commenter may have
-            // reviewed an earlier patchset that is no longer the current one.
-            let mut all_comments = app.gerrit.get_change_comments(&change_id).await?;
+    /// Columns to print with text and tsv output, comma-separated
+    /// (default: number,project,branch,status,owner,subject)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    columns: Vec<query::Column>,
 
-            // Always include robot comments in search mode: CI bots comment via
-            // the robot comments endpoint and would be invisible otherwise.
-            if let Ok(robot) = app.gerrit.get_robot_comments(&change_id).await {
-                for (file, rc) in robot {
-                    all_comments.entry(file).or_default().extend(rc);
-                }
-            }
+    /// Output format (default: the repo's default_output_format, else text)
+    #[arg(long, value_enum)]
+    format: Option<QueryFormat>,
 
-            let mut threads = comments::build_threads(&all_comments);
+    /// Return at most N changes (default: 500)
+    #[arg(long, value_name = "N", conflicts_with = "no_limit")]
+    limit: Option<usize>,
 
-            // Apply filters.
-            // In search mode --age drives the Gerrit query (change activity window),
-            // so do NOT apply it as a per-comment date filter here.  Use explicit
-            // --after / --before for per-comment date filtering in search mode.
-            if let Some(ref pat) = args.comment_by {
-                comments::filter_threads_by_author(&mut threads, pat);
-            }
-            if args.has_replies {
-                comments::filter_threads_has_replies(&mut threads);
-            }
-            comments::filter_threads_by_date(
-                &mut threads,
-                args.after.as_deref(),
-                args.before.as_deref(),
-            );
+    /// Fetch every matching change
+    #[arg(long)]
+    no_limit: bool,
+}
 
-            // Label filter
-            if let Some(ref label_arg) = args.label {
-                apply_label_filter(&change_detail, label_arg, &mut threads)?;
-            }
+#[derive(Parser, Debug)]
+struct TuiArgs {
+    /// Only show changes targeting this branch
+    #[arg(long)]
+    branch: Option<String>,
 
-            if args.unresolved {
-                threads.retain(|t| !t.resolved);
-            }
-            if args.resolved {
-                threads.retain(|t| t.resolved);
-            }
+    /// Git remote used for downloads and cherry-picks
+    #[arg(long)]
+    remote: Option<String>,
 
-            if threads.is_empty() {
-                continue; // all threads filtered out for this change
-            }
+    /// Maximum number of changes to load (default: 500)
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+}
 
-            let messages = change_detail.messages.as_deref().unwrap_or(&[]);
-            match args.format {
-                OutputFormat::Json => {
-                    let json =
-                        comments::format_json(&change_detail, messages, &threads, &gerrit_url);
-                    if let Ok(output) = serde_json::from_value(json) {
-                        outputs.push(output);
-                    }
-                }
-                OutputFormat::Text => {
-                    let text =
-                        comments::format_text(&change_detail, messages, &threads, &gerrit_url);
-                    print!("{text}");
-                    println!("\n---\n");
-                }
-            }
-        }
+#[derive(Parser, Debug)]
+struct VersionArgs {
+    /// Print versions and build metadata as JSON
+    #[arg(long)]
+    json: bool,
+}
 
-        if matches!(args.format, OutputFormat::Json) {
-            let multi = comments::format_json_multi(&outputs);
-            println!("{}", serde_json::to_string_pretty(&multi)?);
-        }
+/// Global flags shared by every subcommand that talks to Gerrit.
+#[derive(Debug, Clone, Default)]
+struct GlobalOpts {
+    insecure: bool,
+    server: Option<String>,
+    profile: Option<String>,
+    http1: bool,
+    no_color: bool,
+    color: Option<ColorWhen>,
+    prefer_gitreview: bool,
+}
 
-        return Ok(());
+impl GlobalOpts {
+    /// When to color: the global flags, then `NO_COLOR`, then the repo's `color` setting.
+    fn color(&self, app: &App) -> ColorWhen {
+        ui::resolve_color(self.no_color, self.color, app.config.color.as_deref())
     }
 
-    // Single-change mode
-    let change_id = match args.change {
-        Some(id) => id,
-        None => {
-            let msg = app.require_git()?.head_commit_message()?;
-            push::extract_change_id(&msg)
-                .context("no Change-Id found in HEAD commit. Specify a change number explicitly")?
+    /// Config overrides from the global flags; commands add their own on top.
+    fn cli_overrides(&self) -> CliOverrides {
+        CliOverrides {
+            host: self.server.clone(),
+            profile: self.profile.clone(),
+            insecure: self.insecure,
+            http1: self.http1,
+            prefer_gitreview: self.prefer_gitreview,
+            ..Default::default()
         }
-    };
+    }
+}
 
-    debug!("fetching comments for change: {}", change_id);
+/// CLI personality based on argv[0].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Personality {
+    /// Normal `grt` invocation with subcommands.
+    Grt,
+    /// Busybox-style `git-review` invocation with flat flags.
+    GitReview,
+}
 
-    let change = app.gerrit.get_change_detail(&change_id).await?;
-    let change_comments = if args.all_revisions {
-        app.gerrit.get_change_comments(&change_id).await?
-    } else if let Some(ref rev) = args.revision {
-        app.gerrit.get_revision_comments(&change_id, rev).await?
-    } else if let Some(ref current_rev) = change.current_revision {
-        app.gerrit
-            .get_revision_comments(&change_id, current_rev)
-            .await?
+/// Detect CLI personality from argv[0].
+fn detect_personality(argv0: &str) -> Personality {
+    let basename = Path::new(argv0)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("");
+    if basename == "git-review" {
+        Personality::GitReview
     } else {
-        app.gerrit.get_change_comments(&change_id).await?
+        Personality::Grt
+    }
+}
+
+fn init_tracing(verbosity: u8) {
+    let level = match verbosity {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
     };
 
-    let mut all_comments = change_comments;
+    let filter = format!("grt={level}");
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(&filter)),
+        )
+        .with_target(false)
+        .without_time()
+        .init();
+}
+
+/// Process exit codes, mirroring git-review.
+///
+/// Named `ExitReason` rather than `ExitCode` so it does not clash with
+/// [`std::process::ExitCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitReason {
+    Success,
+    Generic,
+    Hook,
+    MalformedInput,
+    Network,
+    GitConfig,
+}
 
-    if !args.exclude_robot_comments {
-        if let Ok(robot) = app.gerrit.get_robot_comments(&change_id).await {
-            for (file, robot_comments) in robot {
-                all_comments.entry(file).or_default().extend(robot_comments);
-            }
+impl ExitReason {
+    const ALL: [ExitReason; 6] = [
+        ExitReason::Success,
+        ExitReason::Generic,
+        ExitReason::Hook,
+        ExitReason::MalformedInput,
+        ExitReason::Network,
+        ExitReason::GitConfig,
+    ];
+
+    /// The integer passed to `std::process::exit`.
+    fn code(self) -> i32 {
+        match self {
+            ExitReason::Success => 0,
+            ExitReason::Generic => 1,
+            ExitReason::Hook => 2,
+            ExitReason::MalformedInput => 3,
+            ExitReason::Network => 40,
+            ExitReason::GitConfig => 128,
         }
     }
 
-    let mut threads = comments::build_threads(&all_comments);
-
-    // Apply filters
-    if let Some(ref pat) = args.comment_by {
-        comments::filter_threads_by_author(&mut threads, pat);
-    }
-    if args.has_replies {
-        comments::filter_threads_has_replies(&mut threads);
+    /// Human-readable meaning, as documented in the CLI reference.
+    fn describe(self) -> &'static str {
+        match self {
+            ExitReason::Success => "Success",
+            ExitReason::Generic => "Generic error (auth failed, not found, server error)",
+            ExitReason::Hook => "Hook-related error",
+            ExitReason::MalformedInput => {
+                "Malformed input (bad argument format, e.g. invalid CHANGE,PS)"
+            }
+            ExitReason::Network => "Network/connectivity error",
+            ExitReason::GitConfig => "Git config error (no Gerrit host configured)",
+        }
     }
-    let after = args.after.as_deref().or(age_after.as_deref());
-    let before = args.before.as_deref().or(age_before.as_deref());
-    comments::filter_threads_by_date(&mut threads, after, before);
 
-    // Label filter
-    if let Some(ref label_arg) = args.label {
-        apply_label_filter(&change, label_arg, &mut threads)?;
+    fn from_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.code() == code)
     }
+}
 
-    if args.unresolved {
-        threads.retain(|t| !t.resolved);
-    }
-    if args.resolved {
-        threads.retain(|t| t.resolved);
+/// Map an error to an exit code for git-review compatibility.
+fn exit_code_for_error(err: &anyhow::Error) -> ExitReason {
+    // Check for GerritError in the error chain
+    if let Some(gerrit_err) = err.downcast_ref::<GerritError>() {
+        return match gerrit_err {
+            GerritError::AuthFailed { .. } => ExitReason::Generic,
+            GerritError::NotFound => ExitReason::Generic,
+            GerritError::ServerError { .. } => ExitReason::Generic,
+            GerritError::Network(_) => ExitReason::Network,
+        };
     }
 
-    let messages = change.messages.as_deref().unwrap_or(&[]);
-    let gerrit_url = app.config.gerrit_base_url()?.to_string();
-
-    match args.format {
-        OutputFormat::Text => {
-            let text = comments::format_text(&change, messages, &threads, &gerrit_url);
-            print!("{text}");
-        }
-        OutputFormat::Json => {
-            let json = comments::format_json(&change, messages, &threads, &gerrit_url);
-            println!("{}", serde_json::to_string_pretty(&json)?);
-        }
+    // Check for common error patterns in the message
+    let msg = format!("{err:#}");
+    if msg.contains("git config") || msg.contains("no Gerrit host configured") {
+        return ExitReason::GitConfig;
+    }
+    if msg.contains("argument") || msg.contains("CHANGE,PS") || msg.contains("malformed") {
+        return ExitReason::MalformedInput;
+    }
+    if msg.contains("hook") {
+        return ExitReason::Hook;
     }
 
-    Ok(())
+    ExitReason::Generic
 }
 
-/// Resolve the transport scheme for `grt setup`.
-///
-/// SSH is the default and is used whenever `--http` is not passed.
-/// `--ssh` is accepted for explicitness but has the same effect as omitting both flags.
-fn setup_scheme(_ssh: bool, http: bool) -> &'static str {
-    if http {
-        "https"
-    } else {
-        "ssh"
+/// Describe what a grt exit code means.
+fn explain_exit(value: &str) -> Result<String> {
+    let code: i32 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid exit code argument: {value:?}"))?;
+    match ExitReason::from_code(code) {
+        Some(exit) => Ok(format!("{code}: {}", exit.describe())),
+        None => Ok(format!("{code}: not an exit code used by grt")),
     }
 }
 
-/// Return true when setup should run HTTP connectivity and auth checks.
-/// SSH-only setup skips REST API calls to avoid prompting for HTTPS credentials.
-fn setup_needs_http_check(scheme: &str) -> bool {
-    scheme != "ssh"
+fn cmd_completions(shell: clap_complete::Shell) {
+    let mut cmd = Cli::command();
+    clap_complete::generate(shell, &mut cmd, "grt", &mut std::io::stdout());
 }
 
-/// Parse an age string like "1d", "2w", "3m", "1y" into a YYYY-MM-DD date
-/// representing that many days/weeks/months/years before today.
-///
-/// Returned date can be used as an `after` or `before` bound with
-/// `filter_threads_by_date`.
-fn parse_age_to_date(age: &str) -> Result<String> {
-    use std::time::{SystemTime, UNIX_EPOCH};
+/// Prompt the user for their Gerrit username on stderr, read from stdin.
+/// Returns an error if stdin is not a tty or input is empty.
+fn prompt_for_username() -> Result<String> {
+    use std::io::IsTerminal as _;
 
-    let (count_str, unit) = age
-        .find(|c: char| c.is_alphabetic())
-        .map(|i| (&age[..i], &age[i..]))
-        .context("age must be a number followed by a unit (d, w, m, y)")?;
-    let count: u64 = count_str
-        .parse()
-        .context("age count must be a positive integer")?;
-    if count == 0 {
-        anyhow::bail!("age count must be greater than zero");
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("stdin is not a tty; set gitreview.username in git config");
+    }
+    eprint!("Enter your Gerrit username: ");
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .context("reading username from stdin")?;
+    let username = line.trim().to_string();
+    if username.is_empty() {
+        anyhow::bail!("Gerrit username cannot be empty");
     }
+    Ok(username)
+}
 
-    let days: u64 = match unit {
-        "d" => count,
-        "w" => count * 7,
-        "m" => count * 30,
-        "y" => count * 365,
-        other => anyhow::bail!("unknown age unit '{}'; use d, w, m, or y", other),
+async fn cmd_auth(work_dir: &Path, args: AuthArgs, globals: &GlobalOpts) -> Result<()> {
+    let host_overrides = |host: Option<String>| {
+        let mut overrides = globals.cli_overrides();
+        if host.is_some() {
+            overrides.host = host;
+        }
+        overrides
     };
+    match args.action {
+        AuthAction::Login {
+            host,
+            username,
+            bearer,
+            password_stdin,
+        } => {
+            if !secrets::available() {
+                anyhow::bail!(
+                    "grt was built without keyring support; add the server to credentials.toml"
+                );
+            }
+            let mut app = App::new(work_dir, &host_overrides(host))?;
+            let host = app.config.host.clone();
+            let username = match username {
+                Some(username) => username,
+                None => prompt_for_login_username(&host)?,
+            };
+            let kind = if bearer { "token" } else { "HTTP password" };
+            let password =
+                read_login_secret(&format!("{kind} for {username}@{host}"), password_stdin)?;
+            let creds = config::LoadedCredentials {
+                username,
+                password,
+                auth_type: if bearer {
+                    grt::gerrit::AuthType::Bearer
+                } else {
+                    grt::gerrit::AuthType::Basic
+                },
+            };
 
-    let now_secs = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .context("system time before Unix epoch")?
-        .as_secs();
-    let cutoff_secs = now_secs.saturating_sub(days * 86_400);
-
-    // Convert epoch seconds to YYYY-MM-DD without external deps
-    let date = epoch_secs_to_date(cutoff_secs);
-    Ok(date)
-}
-
-/// Convert Unix epoch seconds to a "YYYY-MM-DD" string using the proleptic
-/// Gregorian calendar.  Accurate for dates in the range 1970–9999.
-fn epoch_secs_to_date(secs: u64) -> String {
-    let days_since_epoch = secs / 86_400;
-    // Algorithm from http://howardhinnant.github.io/date_algorithms.html
-    // civil_from_days (public domain)
-    let z = days_since_epoch as i64 + 719_468;
-    let era = z.div_euclid(146_097);
-    let doe = z.rem_euclid(146_097) as u64; // [0, 146096]
-    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
-    let y = yoe as i64 + era * 400;
-    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
-    let mp = (5 * doy + 2) / 153; // [0, 11]
-    let d = doy - (153 * mp + 2) / 5 + 1; // [1, 31]
-    let m = if mp < 10 { mp + 3 } else { mp - 9 }; // [1, 12]
-    let y = if m <= 2 { y + 1 } else { y };
-    format!("{:04}-{:02}-{:02}", y, m, d)
+            // Only keep credentials that Gerrit accepts
+            let account = app.verify_credentials(creds.clone()).await?;
+            secrets::store(&host, &creds)
+                .with_context(|| format!("storing credentials for {host} in the OS keyring"))?;
+            println!(
+                "Logged in to {host} as {}; credentials stored in the OS keyring",
+                account
+                    .username
+                    .as_deref()
+                    .or(account.name.as_deref())
+                    .unwrap_or(&creds.username)
+            );
+        }
+        AuthAction::Logout { host } => {
+            let host = match host {
+                Some(host) => host,
+                None => App::new(work_dir, &globals.cli_overrides())?.config.host,
+            };
+            if secrets::delete(&host)? {
+                println!("Removed credentials for {host} from the OS keyring");
+            } else {
+                println!("No credentials for {host} in the OS keyring");
+            }
+        }
+    }
+    Ok(())
 }
 
-/// Parse a label filter like "Code-Review=-1" and retain only threads
-/// where at least one commenter's account_id voted that label value.
-fn apply_label_filter(
-    change: &grt::gerrit::ChangeInfo,
-    label_arg: &str,
-    threads: &mut Vec<grt::comments::CommentThread>,
-) -> Result<()> {
-    let (label_name, value_str) = label_arg
-        .split_once('=')
-        .context("--label must be in format 'LabelName=value' (e.g., Code-Review=-1)")?;
-    let target_value: i32 = value_str
-        .parse()
-        .context("label value must be an integer")?;
-
-    let voter_ids: std::collections::HashSet<i64> = change
-        .labels
-        .as_ref()
-        .and_then(|labels| labels.get(label_name))
-        .and_then(|info| info.all.as_ref())
-        .map(|approvals| {
-            approvals
-                .iter()
-                .filter(|a| a.value == Some(target_value))
-                .filter_map(|a| a.account_id)
-                .collect()
-        })
-        .unwrap_or_default();
-
-    threads.retain(|t| {
-        !voter_ids.is_empty()
-            && t.comments.iter().any(|c| {
-                c.account_id
-                    .map(|id| voter_ids.contains(&id))
-                    .unwrap_or(false)
-            })
-    });
+/// Prompt for the username to log in with, on stderr.
+fn prompt_for_login_username(host: &str) -> Result<String> {
+    use std::io::IsTerminal as _;
 
-    Ok(())
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("stdin is not a tty; pass --username");
+    }
+    eprint!("Username for {host}: ");
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .context("reading username from stdin")?;
+    let username = line.trim().to_string();
+    if username.is_empty() {
+        anyhow::bail!("username cannot be empty");
+    }
+    Ok(username)
 }
 
-async fn cmd_setup(
-    work_dir: &Path,
-    args: SetupArgs,
-    insecure: bool,
-    server: Option<String>,
-) -> Result<()> {
-    let scheme = Some(setup_scheme(args.ssh, args.http).to_string());
+/// Read the password or token, from stdin with `--password-stdin`, otherwise
+/// from a prompt that does not echo.
+fn read_login_secret(what: &str, from_stdin: bool) -> Result<String> {
+    use std::io::{IsTerminal as _, Read as _};
 
-    let cli_overrides = CliOverrides {
-        host: server,
-        remote: args.remote.clone(),
-        scheme,
-        insecure,
-        ..Default::default()
+    let secret = if from_stdin {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("reading password from stdin")?;
+        input.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("stdin is not a tty; pass --password-stdin");
+        }
+        rpassword::prompt_password(format!("{what}: ")).context("reading password")?
     };
-    let mut app = App::new(work_dir, &cli_overrides)?;
-    let root = app.require_git()?.root()?;
+    if secret.is_empty() {
+        anyhow::bail!("{what} cannot be empty");
+    }
+    Ok(secret)
+}
 
-    eprintln!("Setting up grt for Gerrit...");
+/// Confirm installing the commit-msg hook into a hooks directory that lives
+/// outside this repository (a shared `core.hooksPath`, e.g. `~/.githooks`).
+///
+/// `--force-hook` skips the prompt. Without a tty the install is refused,
+/// since writing there affects every repository sharing the directory.
+fn confirm_shared_hooks_dir(hooks_dir: &Path, force: bool) -> Result<bool> {
+    use std::io::IsTerminal as _;
 
-    // 1. Install commit-msg hook
-    let hooks_dir = app.require_git()?.hooks_dir()?;
-    let hook_path = hooks_dir.join("commit-msg");
-    if args.force_hook && hook_path.exists() {
-        std::fs::remove_file(&hook_path).context("removing existing commit-msg hook")?;
-    }
-    if args.remote_hook {
-        // Download hook from remote Gerrit server (HTTP or SCP based on remote URL)
-        let remote_name = args.remote.as_deref().unwrap_or(&app.config.remote);
-        let remote_url = review_query::resolve_remote_url(
-            remote_name,
-            &root,
-            Some(&app.config.make_remote_url()),
-        )?
-        .or_else(|| app.config.gerrit_base_url().ok().map(|u| u.to_string()))
-        .context("no remote URL for hook download")?;
-        hook::fetch_remote_hook(&remote_url, &hooks_dir).await?;
-    } else {
-        hook::ensure_hook_installed(&hooks_dir)?;
-        eprintln!("  commit-msg hook: installed at {}", hook_path.display());
+    if force {
+        return Ok(true);
     }
-
-    // Propagate hook to submodules
-    if let Err(e) = hook::propagate_hook_to_submodules(&root) {
-        tracing::warn!("failed to propagate hook to submodules: {e}");
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "hooks directory {} is outside this repository (core.hooksPath); \
+             re-run with --force-hook to install the commit-msg hook there",
+            hooks_dir.display()
+        );
     }
+    eprint!(
+        "Hooks directory {} is shared outside this repository (core.hooksPath).\n\
+         Install the Gerrit commit-msg hook there anyway? [y/N] ",
+        hooks_dir.display()
+    );
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .context("reading user input")?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
 
-    // 2. Ensure remote exists (create if missing, prompt for SSH username if needed)
-    check_and_create_remote(&mut app)?;
-    let remote = args.remote.unwrap_or_else(|| app.config.remote.clone());
-    match subprocess::git_output(&["remote", "get-url", &remote], &root) {
-        Ok(url) => eprintln!("  remote '{remote}': {}", url.trim()),
-        Err(_) => eprintln!("  remote '{remote}': NOT FOUND"),
+/// Load the configuration with the layer of each value. Unlike `App::new`,
+/// this works without a Gerrit host, so a missing one can be diagnosed.
+fn load_config_sources(
+    work_dir: &Path,
+    cli: &CliOverrides,
+) -> Result<(config::GerritConfig, config::ConfigSources)> {
+    match GitRepo::open(work_dir) {
+        Ok(git) => config::load_config_with_sources(&git.root()?, |key| git.config_value(key), cli),
+        Err(_) => config::load_config_with_sources(work_dir, |_| None, cli),
     }
+}
 
-    // 3. Test connectivity and auth (HTTP/HTTPS only — skip for SSH-only setup)
-    let use_http = setup_needs_http_check(&app.config.scheme);
-    if use_http {
-        eprintln!("  Gerrit host: {}", app.config.host);
-        match app.gerrit.get_version().await {
-            Ok(version) => {
-                eprintln!("  connectivity: OK (Gerrit {version})");
+fn cmd_config(work_dir: &Path, args: ConfigArgs, globals: &GlobalOpts) -> Result<()> {
+    let cli = globals.cli_overrides();
+    match args.action {
+        ConfigAction::Show { format } => {
+            let (config, sources) = load_config_sources(work_dir, &cli)?;
+            let entries = settings::config_entries(&config, &sources);
+            match config.output_format(format) {
+                OutputFormat::Text => print!("{}", settings::format_entries(&entries)),
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&entries)?),
             }
-            Err(e) => {
-                eprintln!("  connectivity: FAILED ({e})");
-                eprintln!("  Trying with authentication...");
-                if app.authenticate().is_ok() {
-                    match app.gerrit.get_version().await {
-                        Ok(version) => {
-                            eprintln!("  connectivity: OK (Gerrit {version}, authenticated)")
-                        }
-                        Err(e) => eprintln!("  connectivity: FAILED with auth ({e})"),
+        }
+        ConfigAction::Set { key, value } => {
+            let request = settings::plan_set(&key, &value)?;
+            let written_to = match request.target {
+                SetTarget::GitConfig(git_key) => {
+                    let root = GitRepo::open(work_dir)
+                        .context("setting a repository value needs a git repository")?
+                        .root()?;
+                    subprocess::git_output(&["config", git_key, &request.value], &root)?;
+                    format!("git config ({git_key})")
+                }
+                SetTarget::UserToml(toml_key) => {
+                    let path = dirs::config_dir()
+                        .context("cannot determine the user config directory")?
+                        .join("grt")
+                        .join("config.toml");
+                    let profile = cli.selected_profile(|key| std::env::var(key).ok());
+                    let table = settings::user_toml_table(profile.as_deref());
+                    settings::write_toml_setting(&path, &table, toml_key, request.toml_value)?;
+                    format!("{} [{}]", path.display(), table.join("."))
+                }
+                SetTarget::UserHostToml(toml_key) => {
+                    let (config, _) = load_config_sources(work_dir, &cli)?;
+                    if config.host.is_empty() {
+                        anyhow::bail!(
+                            "{} is set per Gerrit server, and no host is configured here",
+                            request.key
+                        );
                     }
-                } else {
-                    eprintln!("  authentication: FAILED (check git credentials)");
+                    let path = dirs::config_dir()
+                        .context("cannot determine the user config directory")?
+                        .join("grt")
+                        .join("config.toml");
+                    let table = settings::user_host_toml_table(&config.host);
+                    settings::write_toml_setting(&path, &table, toml_key, request.toml_value)?;
+                    format!("{} [gerrit.\"{}\"]", path.display(), config.host)
+                }
+                SetTarget::RepoToml => {
+                    let root = GitRepo::open(work_dir)
+                        .context("setting a repository value needs a git repository")?
+                        .root()?;
+                    let path = root.join(".grt.toml");
+                    // `push.message_template` goes to `message_template` under `[push]`
+                    let (table, key) = match request.key.split_once('.') {
+                        Some((table, key)) => (vec![table], key),
+                        None => (vec![], request.key),
+                    };
+                    settings::write_toml_setting(&path, &table, key, request.toml_value)?;
+                    path.display().to_string()
+                }
+            };
+            eprintln!("Set {} = {} in {written_to}", request.key, request.value);
+
+            // A higher layer may still win, e.g. GERRIT_HOST over git config
+            if let Ok((config, sources)) = load_config_sources(work_dir, &cli) {
+                let source = sources.source(request.key);
+                if source != request.target.source() {
+                    let style = Style::stderr(ui::resolve_color(
+                        globals.no_color,
+                        globals.color,
+                        config.color.as_deref(),
+                    ));
+                    eprintln!(
+                        "{} {} is still taken from {}, which overrides it",
+                        style.warning(),
+                        request.key,
+                        source.label()
+                    );
                 }
             }
         }
+    }
+    Ok(())
+}
 
-        // 4. Verify auth
-        match app.gerrit.get_self_account().await {
-            Ok(account) => {
-                let name = account.name.as_deref().unwrap_or("unknown");
-                let email = account.email.as_deref().unwrap_or("unknown");
-                eprintln!("  authenticated as: {name} <{email}>");
+fn cmd_hooks(work_dir: &Path, args: HooksArgs) -> Result<()> {
+    match args.action {
+        HooksAction::Install {
+            global: true,
+            dir,
+            set_hooks_path,
+            force,
+        } => install_global_hook(work_dir, dir, set_hooks_path, force),
+        HooksAction::Install { force, .. } => {
+            let git = GitRepo::open(work_dir)?;
+            let hooks_dir = git.hooks_dir()?;
+            if git.hooks_dir_outside_repo()? && !confirm_shared_hooks_dir(&hooks_dir, force)? {
+                eprintln!(
+                    "commit-msg hook: skipped (shared hooks directory {})",
+                    hooks_dir.display()
+                );
+                return Ok(());
             }
-            Err(_) => {
-                if app.authenticate().is_ok() {
-                    match app.gerrit.get_self_account().await {
-                        Ok(account) => {
-                            let name = account.name.as_deref().unwrap_or("unknown");
-                            let email = account.email.as_deref().unwrap_or("unknown");
-                            eprintln!("  authenticated as: {name} <{email}>");
-                        }
-                        Err(e) => eprintln!("  auth check: FAILED ({e})"),
-                    }
-                }
+            install_hook_into(&hooks_dir, force)
+        }
+        HooksAction::Status { format } => {
+            let git = GitRepo::open(work_dir)?;
+            let status = hook::HookStatus::new(
+                git.hooks_dir()?,
+                git.config_value("core.hooksPath"),
+                git.hooks_dir_outside_repo()?,
+            );
+            match format.unwrap_or(OutputFormat::Text) {
+                OutputFormat::Text => print!("{}", hook::format_hook_status(&status)),
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&status)?),
             }
+            Ok(())
+        }
+    }
+}
+
+/// Install the bundled hook into `hooks_dir`, leaving a different existing
+/// hook alone unless `force`.
+fn install_hook_into(hooks_dir: &Path, force: bool) -> Result<()> {
+    let hook_path = hooks_dir.join("commit-msg");
+    match hook::installed_hook_kind(hooks_dir) {
+        hook::HookKind::Grt if !force => {
+            eprintln!(
+                "commit-msg hook: already installed at {}",
+                hook_path.display()
+            );
+            return Ok(());
+        }
+        kind @ (hook::HookKind::Gerrit | hook::HookKind::Foreign) if !force => anyhow::bail!(
+            "{} already has a commit-msg hook ({}); pass --force to replace it",
+            hooks_dir.display(),
+            kind.describe()
+        ),
+        _ => {}
+    }
+    hook::install_hook(hooks_dir)?;
+    eprintln!("commit-msg hook: installed at {}", hook_path.display());
+    Ok(())
+}
+
+fn install_global_hook(
+    work_dir: &Path,
+    dir: Option<PathBuf>,
+    set_hooks_path: bool,
+    force: bool,
+) -> Result<()> {
+    let configured =
+        subprocess::git_output(&["config", "--global", "--get", "core.hooksPath"], work_dir).ok();
+    let home = dirs::home_dir();
+    let hooks_dir = match dir {
+        Some(dir) => config::expand_tilde(&dir.to_string_lossy()),
+        None => hook::default_global_hooks_dir(configured.as_deref(), home.as_deref())
+            .context("cannot determine a global hooks directory; pass --dir")?,
+    };
+    install_hook_into(&hooks_dir, force)?;
+
+    let active = configured
+        .as_deref()
+        .is_some_and(|path| config::expand_tilde(path) == hooks_dir);
+    if set_hooks_path && !active {
+        let value = hooks_dir.to_string_lossy();
+        subprocess::git_exec(&["config", "--global", "core.hooksPath", &value], work_dir)?;
+        eprintln!("core.hooksPath: set globally to {value}");
+    } else if !active {
+        eprintln!(
+            "note: git only runs hooks from {} once the global core.hooksPath points there; \
+             re-run with --set-hooks-path to set it (git then ignores each repository's .git/hooks)",
+            hooks_dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Check if the configured remote exists, and create it if possible.
+///
+/// - If remote exists with a tracking branch: no-op
+/// - If remote exists but no tracking branch: run `git remote update`
+/// - If remote exists and `usepushurl` is set: point its push URL at Gerrit
+/// - If remote doesn't exist and config has enough info: create it
+fn check_and_create_remote(app: &mut App) -> Result<()> {
+    let remote = app.config.remote.clone();
+    let root = app.require_git()?.root()?;
+
+    match subprocess::check_remote_exists(&remote, &root)? {
+        Some(_url) => {
+            // With usepushurl the existing remote doubles as the Gerrit remote
+            if app.config.usepushurl && !app.config.host.is_empty() {
+                if let Some(push_url) = review_query::ensure_push_url(&remote, &root, &app.config)?
+                {
+                    tracing::info!("Set push URL of remote '{remote}' to {push_url}");
+                }
+            }
+
+            // Remote exists — check if tracking branch exists
+            let tracking_ref = format!("refs/remotes/{}/{}", remote, app.config.branch());
+            let has_tracking =
+                subprocess::git_output(&["show-ref", "--verify", "--quiet", &tracking_ref], &root)
+                    .is_ok();
+
+            if !has_tracking {
+                tracing::info!("Remote '{remote}' exists but has no tracking branch, updating...");
+                subprocess::git_remote_update(&remote, &root)?;
+            }
+        }
+        None => {
+            // Remote doesn't exist — try to create from config
+            if app.config.host.is_empty() {
+                return Ok(()); // Not enough config to auto-create
+            }
+
+            // For SSH remotes, prompt for username if not configured
+            if app.config.scheme.starts_with("ssh") && app.config.username.is_none() {
+                let username = prompt_for_username()?;
+                app.config.username = Some(username.clone());
+                // Persist to git config so it's not asked again
+                subprocess::git_exec(&["config", "gitreview.username", &username], &root)?;
+            }
+
+            // Honour the user's url.<base>.insteadOf rules in the stored URL
+            let rewrites = subprocess::git_config_list(&root)
+                .map(|out| config::populate_rewrites(&out))
+                .unwrap_or_default();
+            let url = app.config.make_rewritten_remote_url(&rewrites, false);
+            tracing::info!("Creating remote '{remote}' with URL {url}...");
+            subprocess::git_remote_add(&remote, &url, &root)?;
+
+            // Set push URL if usepushurl is configured
+            if app.config.usepushurl {
+                let push_url = app.config.make_rewritten_remote_url(&rewrites, true);
+                subprocess::git_remote_set_push_url(&remote, &push_url, &root)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply `-C` directories cumulatively, like git.
+///
+/// Each relative path is resolved against the result of the previous one;
+/// an absolute path starts over. Empty values are ignored, as git does.
+fn resolve_work_dir(base: PathBuf, dirs: &[PathBuf]) -> PathBuf {
+    dirs.iter()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .fold(base, |acc, dir| acc.join(dir))
+}
+
+#[tokio::main]
+async fn main() {
+    let argv0 = std::env::args().next().unwrap_or_default();
+    let personality = detect_personality(&argv0);
+
+    let result = match personality {
+        Personality::GitReview => {
+            let cli = GitReviewCli::parse();
+            init_tracing(cli.verbose);
+
+            if cli.license {
+                println!("Licensed under Apache-2.0 OR MIT");
+                return;
+            }
+
+            let work_dir = std::env::current_dir().expect("cannot determine current directory");
+            let color = cli.color.as_deref().and_then(|value| {
+                let when = ColorWhen::parse(value);
+                if when.is_none() {
+                    eprintln!(
+                        "warning: ignoring --color {value:?}: expected auto, always or never"
+                    );
+                }
+                when
+            });
+            let globals = GlobalOpts {
+                no_color: cli.no_color,
+                color,
+                ..GlobalOpts::default()
+            };
+            cmd_review(&work_dir, cli.review, &globals).await
+        }
+        Personality::Grt => {
+            let cli = match parse_invocation(std::env::args_os()).unwrap_or_else(|e| e.exit()) {
+                Invocation::ExplainExit(value) => {
+                    match explain_exit(&value) {
+                        Ok(text) => println!("{text}"),
+                        Err(err) => {
+                            eprintln!("error: {err:#}");
+                            std::process::exit(ExitReason::MalformedInput.code());
+                        }
+                    }
+                    return;
+                }
+                Invocation::Run(cli) => cli,
+            };
+            init_tracing(cli.verbose);
+
+            let work_dir = resolve_work_dir(
+                std::env::current_dir().expect("cannot determine current directory"),
+                &cli.directory,
+            );
+
+            let globals = GlobalOpts {
+                insecure: cli.insecure,
+                server: cli.server.clone(),
+                profile: cli.profile.clone(),
+                http1: cli.http1,
+                no_color: cli.no_color,
+                color: cli.color,
+                prefer_gitreview: cli.prefer_gitreview,
+            };
+            match cli.command {
+                Commands::Review(args) => cmd_review(&work_dir, args, &globals).await,
+                Commands::Push(args) if args.recurse => {
+                    cmd_push_recurse(&work_dir, args, &globals).await
+                }
+                Commands::Push(args) if args.all_branches.is_some() => {
+                    cmd_push_all_branches(&work_dir, args, &globals).await
+                }
+                Commands::Push(args) => cmd_push(&work_dir, args, &globals).await.map(|_| ()),
+                Commands::Comments(args) => cmd_comments(&work_dir, args, &globals).await,
+                Commands::Status(args) => cmd_status(&work_dir, args, &globals).await,
+                Commands::Open(args) => cmd_open(&work_dir, args, &globals).await,
+                Commands::Deps(args) => cmd_deps(&work_dir, args, &globals).await,
+                Commands::Log(args) => cmd_log(&work_dir, args, &globals).await,
+                Commands::Query(args) => cmd_query(&work_dir, args, &globals).await,
+                Commands::Diff(args) => cmd_diff(&work_dir, args, &globals).await,
+                Commands::Fix(args) => cmd_fix(&work_dir, args, &globals).await,
+                Commands::Backport(args) => cmd_backport(&work_dir, args, &globals).await,
+                Commands::CherrypickRemote(args) => {
+                    cmd_cherrypick_remote(&work_dir, args, &globals).await
+                }
+                Commands::RebaseChain(args) => cmd_rebase_chain(&work_dir, args, &globals),
+                Commands::Setup(args) => cmd_setup(&work_dir, args, &globals).await,
+                Commands::Hooks(args) => cmd_hooks(&work_dir, args),
+                Commands::Config(args) => cmd_config(&work_dir, args, &globals),
+                Commands::Submit(args) => cmd_submit(&work_dir, args, &globals).await,
+                Commands::Checkout(args) => cmd_checkout(&work_dir, args, &globals).await,
+                Commands::Abandon(args) => {
+                    cmd_change_state(&work_dir, args, &globals, ChangeState::Abandon).await
+                }
+                Commands::Restore(args) => {
+                    cmd_change_state(&work_dir, args, &globals, ChangeState::Restore).await
+                }
+                Commands::Wip(args) => cmd_wip(&work_dir, args, &globals, true).await,
+                Commands::Ready(args) => cmd_wip(&work_dir, args, &globals, false).await,
+                Commands::Topic(args) => cmd_topic(&work_dir, args, &globals).await,
+                Commands::Reviewers(args) => cmd_reviewers(&work_dir, args, &globals).await,
+                Commands::Hashtags(args) => cmd_hashtags(&work_dir, args, &globals).await,
+                Commands::Attention(args) => cmd_attention(&work_dir, args, &globals).await,
+                Commands::Tui(args) => cmd_tui(&work_dir, args, &globals).await,
+                Commands::Export(args) => export::cmd_export(&args, Cli::command()),
+                Commands::Version(args) => cmd_version(&work_dir, &args, &globals).await,
+                Commands::Auth(args) => cmd_auth(&work_dir, args, &globals).await,
+                Commands::Completions { shell } => {
+                    cmd_completions(shell);
+                    Ok(())
+                }
+            }
+        }
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: {err:#}");
+        std::process::exit(exit_code_for_error(&err).code());
+    }
+}
+
+/// Dispatch `grt review` / `git-review` based on which mode flag is set.
+async fn cmd_review(work_dir: &Path, args: ReviewArgs, globals: &GlobalOpts) -> Result<()> {
+    // Setup mode: run setup, but continue if --finish is also set
+    if args.setup {
+        cmd_setup(
+            work_dir,
+            SetupArgs {
+                remote: args.remote.clone(),
+                force_hook: false,
+                update_hook: false,
+                remote_hook: args.remote_hook,
+                ssh: false,
+                http: false,
+            },
+            globals,
+        )
+        .await?;
+        if !args.finish {
+            return Ok(());
+        }
+        // Fall through to finish logic below
+    }
+
+    // Warn about flags that are parsed but not yet fully implemented
+    review::warn_unused_flags(&args);
+
+    // Create a single App instance for all mode dispatches
+    let cli_overrides = CliOverrides {
+        remote: args.remote.clone(),
+        use_pushurl: args.use_pushurl.then_some(true),
+        ..globals.cli_overrides()
+    };
+    let mut app = App::new(work_dir, &cli_overrides)?;
+    let format = app.config.output_format(args.format.clone());
+
+    // Ensure remote exists (auto-create if possible)
+    check_and_create_remote(&mut app)?;
+
+    // Resolve branch via --track (or `track` in .gitreview) if no explicit
+    // branch given (before all mode dispatches)
+    let tracked = if args.use_track(app.config.track) && args.branch.is_none() {
+        match app.require_git()?.upstream_branch()? {
+            Some((remote, merge_branch)) => {
+                if remote == grt::git::LOCAL_REMOTE {
+                    tracing::debug!(
+                        "--track: upstream {} is a local branch; using its name as the target",
+                        merge_branch
+                    );
+                } else {
+                    tracing::debug!("--track resolved upstream branch to {}", merge_branch);
+                }
+                Some(merge_branch)
+            }
+            None => None,
+        }
+    } else {
+        None
+    };
+    let branch = args.branch.clone().or_else(|| tracked.clone());
+
+    // Download mode
+    if let Some(ref change_arg) = args.download {
+        return review::cmd_review_download(
+            &mut app,
+            change_arg,
+            &format,
+            args.verify,
+            args.download_branch.as_deref(),
+            args.detach,
+        )
+        .await;
+    }
+
+    // Cherry-pick modes
+    if let Some(ref change_arg) = args.cherrypick {
+        return review::cmd_review_cherrypick(
+            &mut app,
+            change_arg,
+            args.verify,
+            args.onto,
+            args.abort_on_conflict,
+        )
+        .await;
+    }
+    if let Some(ref change_arg) = args.cherrypickindicate {
+        return review::cmd_review_cherrypickindicate(
+            &mut app,
+            change_arg,
+            args.verify,
+            args.onto,
+            args.abort_on_conflict,
+        )
+        .await;
+    }
+    if let Some(ref change_arg) = args.cherrypickonly {
+        return review::cmd_review_cherrypickonly(
+            &mut app,
+            change_arg,
+            args.verify,
+            args.onto,
+            args.abort_on_conflict,
+        )
+        .await;
+    }
+
+    // Compare mode
+    if let Some(ref compare_arg) = args.compare {
+        let remote = app.config.remote.clone();
+        let compare_branch = target_branch(&app, args.branch.clone(), tracked, &remote)?;
+        return review::cmd_review_compare(
+            &mut app,
+            compare_arg,
+            &compare_branch,
+            args.no_rebase,
+            args.force_rebase,
+            args.interdiff,
+            args.difftool,
+        )
+        .await;
+    }
+
+    // List mode
+    if args.list > 0 {
+        let limit = list::resolve_list_limit(args.limit, args.no_limit);
+        let style = Style::stdout(globals.color(&app));
+        let filters = list::ListFilters {
+            mine: args.mine,
+            wip: args.wip,
+            private: args.private,
+            reviewer: args.reviewer.clone(),
+        };
+        return review::cmd_review_list(
+            &mut app,
+            branch.as_deref(),
+            &filters,
+            args.list >= 2 || args.owner,
+            args.owner,
+            &format,
+            style,
+            limit,
+            CacheMode::from_flags(args.cached, args.refresh),
+        )
+        .await;
+    }
+
+    // Pre-push: --update runs `git remote update`
+    if args.update {
+        let remote = args.remote.as_deref().unwrap_or(&app.config.remote);
+        let root = app.require_git()?.root()?;
+        tracing::info!("Updating remote {remote}...");
+        subprocess::git_remote_update(remote, &root)?;
+    }
+
+    // Pre-push: --new-changeid strips Change-Id and amends
+    if args.new_changeid {
+        let root = app.require_git()?.root()?;
+        tracing::info!("Regenerating Change-Id...");
+        subprocess::git_regenerate_changeid(&root)?;
+    }
+
+    // Capture current branch name for --finish (only when not dry-run) (Task B2)
+    let current_branch_name = if args.finish && !args.dry_run {
+        Some(
+            app.require_git()?
+                .current_branch_or_default(app.config.branch()),
+        )
+    } else {
+        None
+    };
+
+    // Default topic to HEAD's `topic_from_trailer` trailer, else the current
+    // branch name, unless `notopic` is set (Task H3)
+    let topic = args.push_topic(app.config.notopic, || {
+        let git = app.git.as_ref()?;
+        git.head_commit_message()
+            .ok()
+            .and_then(|msg| push::topic_from_trailers(&msg, &app.config.topic_trailers))
+            .or_else(|| git.current_branch().ok())
+    });
+
+    // Custom pre-review/post-review scripts, as git-review runs them
+    let custom_scripts = if args.no_custom_script || args.dry_run || args.print_refspec {
+        None
+    } else {
+        let remote = app.config.remote.clone();
+        let env = vec![
+            (
+                "GRT_BRANCH",
+                target_branch(&app, branch.clone(), None, &remote)?,
+            ),
+            ("GRT_REMOTE", remote),
+            ("GRT_TOPIC", topic.clone().unwrap_or_default()),
+        ];
+        Some((app.require_git()?.hooks_dir()?, env))
+    };
+    if let Some((hooks_dir, env)) = &custom_scripts {
+        run_custom_script(hook::CustomScript::PreReview, hooks_dir, env, work_dir)?;
+    }
+
+    // Default mode: push
+    let pushed = cmd_push(
+        work_dir,
+        PushArgs {
+            branch,
+            remote: args.remote.clone(),
+            topic,
+            wip: args.wip,
+            ready: args.ready,
+            private: args.private,
+            remove_private: args.remove_private,
+            reviewers: args.reviewers,
+            no_default_reviewers: args.no_default_reviewers,
+            codeowners: args.codeowners,
+            allow_self_review: args.allow_self_review,
+            cc_self: args.cc_self,
+            cc: args.cc,
+            hashtags: args.hashtags,
+            message: args.message,
+            no_message: false,
+            strict_message: args.strict_message,
+            notify: args.notify.map(|n| n.to_string()),
+            labels: args.labels,
+            base: args.base,
+            merged: args.merged,
+            publish_comments: args.publish_comments,
+            push_options: args.push_options,
+            change_id_file: None,
+            notify_to: args.notify_to,
+            notify_cc: args.notify_cc,
+            no_rebase: args.no_rebase,
+            force_rebase: args.force_rebase,
+            keep_rebase: args.keep_rebase,
+            dry_run: args.dry_run,
+            print_refspec: args.print_refspec,
+            yes: args.yes,
+            new_changeid: false, // already handled above
+            no_thin: args.no_thin,
+            sign: false,
+            no_sign: false,
+            recurse: false,
+            all_branches: None,
+            format: args.format.clone(),
+        },
+        globals,
+    )
+    .await?;
+
+    if !pushed {
+        return Ok(());
+    }
+    if let Some((hooks_dir, env)) = &custom_scripts {
+        run_custom_script(hook::CustomScript::PostReview, hooks_dir, env, work_dir)?;
+    }
+
+    // Post-push: --finish checks out default branch and deletes topic branch (Task B2)
+    if let Some(topic_branch) = current_branch_name {
+        if !args.dry_run {
+            let default_branch = app.config.branch().to_string();
+            let root = app.require_git()?.root()?;
+            tracing::info!(
+                "Finishing: checking out {} and deleting {}...",
+                default_branch,
+                topic_branch
+            );
+            subprocess::git_checkout(&default_branch, &root)?;
+            subprocess::git_delete_branch(&topic_branch, &root)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a custom `grt review` script from the user's and the repo's hooks
+/// directories.
+fn run_custom_script(
+    script: hook::CustomScript,
+    hooks_dir: &Path,
+    env: &[(&str, String)],
+    work_dir: &Path,
+) -> Result<()> {
+    let config_dir = dirs::config_dir();
+    let paths = hook::custom_script_paths(script, config_dir.as_deref(), hooks_dir);
+    hook::run_custom_scripts(script, &paths, env, work_dir)?;
+    Ok(())
+}
+
+/// Resolve the target branch for `remote`; see [`push::resolve_target_branch`].
+fn target_branch(
+    app: &App,
+    positional: Option<String>,
+    tracked: Option<String>,
+    remote: &str,
+) -> Result<String> {
+    let root = app.require_git()?.root()?;
+    Ok(push::resolve_target_branch(push::TargetBranchFacts {
+        positional,
+        tracked,
+        configured: app.config.branch.clone(),
+        remote_default: subprocess::git_remote_default_branch(remote, &root),
+        fallback: app.config.branch().to_string(),
+    }))
+}
+
+/// Gather the values for `--message` placeholders.
+///
+/// `{patchset}` needs a Gerrit lookup, so it is only resolved when the
+/// message uses it: an existing change gets its latest patchset plus one,
+/// an unknown change gets 1, and a failed lookup leaves it unresolved.
+async fn message_context(
+    app: &mut App,
+    branch: &str,
+    topic: Option<&str>,
+    message: &str,
+    remote_url: Option<&str>,
+) -> Result<push::MessageContext> {
+    let root = app.require_git()?.root()?;
+    let commit_msg = app.require_git()?.head_commit_message()?;
+    let change = push::extract_change_id(&commit_msg);
+    let local_branch = app.require_git()?.current_branch().ok();
+    let ticket = push::find_ticket(
+        &commit_msg,
+        &app.config.topic_trailers,
+        local_branch.as_deref(),
+    );
+    let mut patchset = None;
+    let lookup = match (&change, remote_url) {
+        (Some(change_id), Some(url)) if message.contains("{patchset}") => Some((change_id, url)),
+        _ => None,
+    };
+    if let Some((change_id, url)) = lookup {
+        if review_query::is_http_remote(url) {
+            app.authenticate_and_verify().await?;
+        }
+        patchset = match review_query::get_change_all_revisions(url, change_id, &app.gerrit, &root)
+            .await
+        {
+            Ok(info) => info
+                .revisions
+                .unwrap_or_default()
+                .values()
+                .filter_map(|r| r.number)
+                .max()
+                .map(|n| n + 1),
+            Err(e) if matches!(e.downcast_ref::<GerritError>(), Some(GerritError::NotFound)) => {
+                Some(1)
+            }
+            Err(e) => {
+                debug!("could not look up patchset for {change_id}: {e:#}");
+                None
+            }
+        };
+    }
+    Ok(push::MessageContext {
+        change,
+        branch: Some(branch.to_string()),
+        patchset,
+        topic: topic.map(String::from),
+        ticket,
+    })
+}
+
+/// Write HEAD's Change-Id (and the pushed change, if known) for `--change-id-file`.
+fn write_change_id_file(
+    app: &mut App,
+    path: &Path,
+    pushed: Option<&push::PushedChange>,
+) -> Result<()> {
+    let commit_msg = app.require_git()?.head_commit_message()?;
+    let change_id = push::extract_change_id(&commit_msg)
+        .context("no Change-Id in HEAD commit; cannot write --change-id-file")?;
+    std::fs::write(path, push::change_id_file_contents(&change_id, pushed))
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+/// Username and email of the authenticated account, for self-review filtering.
+async fn self_account_ids(app: &mut App) -> Result<Vec<String>> {
+    app.authenticate()?;
+    let account = app.gerrit.get_self_account().await?;
+    Ok([account.username, account.email]
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Warn about `@group` reviewers/CCs that Gerrit does not know.
+async fn warn_unknown_groups(app: &mut App, groups: &[String]) -> Result<()> {
+    app.authenticate_and_verify().await?;
+    for group in groups {
+        if !app.gerrit.group_exists(group).await? {
+            eprintln!("warning: Gerrit group '{group}' was not found; the push may be rejected");
+        }
+    }
+    Ok(())
+}
+
+/// `grt push --recurse`: push the superproject and each submodule with its
+/// own `.gitreview`, each with its own configuration, after one combined
+/// confirmation.
+async fn cmd_push_recurse(work_dir: &Path, args: PushArgs, globals: &GlobalOpts) -> Result<()> {
+    let app = App::new(work_dir, &globals.cli_overrides())?;
+    if matches!(
+        app.config.output_format(args.format.clone()),
+        OutputFormat::Json
+    ) {
+        anyhow::bail!("--recurse does not support --format json; push each project separately");
+    }
+    let status_style = Style::stderr(globals.color(&app));
+    let root = app.require_git()?.root()?;
+    let projects = workspace::gerrit_projects(&root, &subprocess::list_submodules(&root)?);
+    if projects.is_empty() {
+        anyhow::bail!(
+            "no .gitreview found in {} or its submodules",
+            root.display()
+        );
+    }
+
+    let mut plan = Vec::new();
+    for dir in &projects {
+        let app = App::new(dir, &globals.cli_overrides())?;
+        let remote = app.config.remote.clone();
+        let branch = target_branch(&app, None, None, &remote)?;
+        let commits = subprocess::count_unpushed_commits(&remote, &branch, dir).ok();
+        plan.push(workspace::PlanEntry {
+            path: workspace::display_path(&root, dir),
+            remote,
+            branch,
+            commits,
+        });
+    }
+    eprintln!("Projects to push:\n\n{}", workspace::format_plan(&plan));
+
+    let pending: Vec<_> = projects
+        .iter()
+        .zip(&plan)
+        .filter(|(_, entry)| entry.pending())
+        .collect();
+    if pending.is_empty() {
+        eprintln!("No unpushed commits found.");
+        return Ok(());
+    }
+    if !args.yes && !args.dry_run {
+        eprint!("Push {} project(s)? [y/N] ", pending.len());
+        let mut input = String::new();
+        std::io::stdin()
+            .read_line(&mut input)
+            .context("reading user input")?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            eprintln!("Push cancelled.");
+            return Ok(());
+        }
+    }
+
+    // Keep going after a failure so one broken project does not strand the rest
+    let mut failed = Vec::new();
+    for (dir, entry) in pending {
+        eprintln!("\n{}", status_style.bold(&format!("== {} ==", entry.path)));
+        let project_args = PushArgs {
+            recurse: false,
+            yes: true,
+            ..args.clone()
+        };
+        if let Err(e) = cmd_push(dir, project_args, globals).await {
+            eprintln!("{} {}: {e:#}", status_style.warning(), entry.path);
+            failed.push(entry.path.clone());
+        }
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("push failed in: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// `grt push --all-branches PATTERN`: push each matching local branch that
+/// has unpushed commits with Change-Ids to the branch it tracks, after one
+/// combined confirmation, and print a summary table.
+async fn cmd_push_all_branches(
+    work_dir: &Path,
+    args: PushArgs,
+    globals: &GlobalOpts,
+) -> Result<()> {
+    let pattern = args.all_branches.clone().unwrap_or_default();
+    let app = App::new(work_dir, &globals.cli_overrides())?;
+    if matches!(
+        app.config.output_format(args.format.clone()),
+        OutputFormat::Json
+    ) {
+        anyhow::bail!("--all-branches does not support --format json; push each branch separately");
+    }
+    let status_style = Style::stderr(globals.color(&app));
+    let root = app.require_git()?.root()?;
+    let entries = batch::collect_branches(&pattern, &app.config.remote, &root)?;
+    if entries.is_empty() {
+        anyhow::bail!("no local branches match {pattern}");
+    }
+    eprintln!("Branches to push:\n\n{}", batch::format_plan(&entries));
+
+    let pending = entries.iter().filter(|e| e.skip_reason().is_none()).count();
+    if pending == 0 {
+        eprintln!("No branches to push.");
+        return Ok(());
+    }
+    if !subprocess::check_worktree_clean(&root)? {
+        anyhow::bail!("Cannot switch branches: working tree has uncommitted changes.");
+    }
+    if !args.yes && !args.dry_run {
+        eprint!("Push {pending} branch(es)? [y/N] ");
+        let mut input = String::new();
+        std::io::stdin()
+            .read_line(&mut input)
+            .context("reading user input")?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            eprintln!("Push cancelled.");
+            return Ok(());
+        }
+    }
+
+    // Keep going after a failure so one broken branch does not strand the rest
+    let orig_ref = subprocess::git_head_restore_ref(&root)?;
+    let mut outcomes = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        if let Some(reason) = entry.skip_reason() {
+            outcomes.push(BatchOutcome::Skipped(reason));
+            continue;
+        }
+        eprintln!(
+            "\n{}",
+            status_style.bold(&format!("== {} ==", entry.branch))
+        );
+        let branch_args = PushArgs {
+            branch: entry.target.clone(),
+            remote: Some(entry.remote.clone()),
+            all_branches: None,
+            yes: true,
+            ..args.clone()
+        };
+        let result = match subprocess::git_checkout(&entry.branch, &root) {
+            Ok(()) => cmd_push(&root, branch_args, globals).await,
+            Err(e) => Err(e),
+        };
+        // A dry run pushes nothing but is reported as such in the summary
+        outcomes.push(match result {
+            Ok(true) => BatchOutcome::Pushed,
+            Ok(false) if args.dry_run => BatchOutcome::Pushed,
+            Ok(false) => BatchOutcome::Skipped("nothing to push"),
+            Err(e) => {
+                eprintln!("{} {}: {e:#}", status_style.warning(), entry.branch);
+                BatchOutcome::Failed(format!("{e:#}"))
+            }
+        });
+    }
+    subprocess::git_checkout(&orig_ref, &root)?;
+
+    eprintln!(
+        "\nSummary:\n\n{}",
+        batch::format_summary(&entries, &outcomes, args.dry_run)
+    );
+    let failed = outcomes
+        .iter()
+        .filter(|o| matches!(o, BatchOutcome::Failed(_)))
+        .count();
+    if failed > 0 {
+        anyhow::bail!("push failed for {failed} branch(es)");
+    }
+    Ok(())
+}
+
+/// Confirmation prompt for a stacked push, listing the per-commit preview.
+fn stack_confirmation(remote: &str, branch: &str, table: &str) -> String {
+    format!("You are about to submit multiple commits to {remote}/{branch}:\n\n{table}\nContinue? [y/N] ")
+}
+
+/// Per-commit table for a stacked push: commit and ref names (like
+/// git-review's `git log --oneline --decorate` list), subject, Change-Id,
+/// and whether each commit becomes a new change or a new patchset.
+///
+/// Gerrit is only consulted over HTTP; otherwise (or if the lookup fails) the
+/// status column is left blank. A retried stack push that partially landed
+/// shows the already-created changes as new patchsets.
+async fn stack_preview(
+    app: &mut App,
+    remote: &str,
+    branch: &str,
+    root: &Path,
+    query_gerrit: bool,
+) -> Result<String> {
+    let commits = subprocess::list_unpushed_log(remote, branch, root)?;
+    let messages: Vec<String> = commits.iter().map(|c| c.message.clone()).collect();
+    let open = if query_gerrit {
+        match open_change_numbers(app, &messages, branch).await {
+            Ok(open) => Some(open),
+            Err(e) => {
+                debug!("could not check for existing changes: {e:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let rows = preview::build_preview(&commits, open.as_ref());
+    Ok(preview::format_preview(&rows))
+}
+
+/// Map the Change-Ids in `messages` that have open changes on `branch` to their numbers.
+async fn open_change_numbers(
+    app: &mut App,
+    messages: &[String],
+    branch: &str,
+) -> Result<HashMap<String, i64>> {
+    let ids: Vec<String> = messages
+        .iter()
+        .filter_map(|m| push::extract_change_id(m))
+        .collect();
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    app.authenticate_and_verify().await?;
+    let query = push::open_changes_query(&ids, &app.config.project, branch);
+    let mut numbers: HashMap<String, i64> = app
+        .gerrit
+        .query_changes(&query)
+        .await?
+        .into_iter()
+        .filter_map(|c| Some((c.change_id?, c.number?)))
+        .collect();
+    let existing = push::already_pushed_change_ids(&ids, |id| numbers.contains_key(id));
+    numbers.retain(|id, _| existing.contains(id));
+    Ok(numbers)
+}
+
+/// Push the current branch for review. Returns whether anything was pushed:
+/// `false` for a dry run, `--print-refspec`, a cancelled prompt or nothing to push.
+async fn cmd_push(work_dir: &Path, args: PushArgs, globals: &GlobalOpts) -> Result<bool> {
+    let cli_overrides = CliOverrides {
+        remote: args.remote.clone(),
+        branch: args.branch.clone(),
+        ..globals.cli_overrides()
+    };
+    let mut app = App::new(work_dir, &cli_overrides)?;
+    let root = app.require_git()?.root()?;
+    let status_style = Style::stderr(globals.color(&app));
+
+    // Ensure remote exists (auto-create if possible)
+    check_and_create_remote(&mut app)?;
+
+    // Ensure commit-msg hook is installed, but never write into a shared
+    // core.hooksPath implicitly — that is `grt setup --force-hook`'s job.
+    let hooks_dir = app.require_git()?.hooks_dir()?;
+    if !hooks_dir.join("commit-msg").exists() && app.require_git()?.hooks_dir_outside_repo()? {
+        tracing::warn!(
+            "not installing commit-msg hook into shared hooks directory {}; \
+             run `grt setup --force-hook` to install it there",
+            hooks_dir.display()
+        );
+    } else {
+        hook::ensure_hook_installed(&hooks_dir)?;
+        debug!("commit-msg hook verified at {:?}", hooks_dir);
+    }
+    if hook::hook_is_outdated(&hooks_dir) {
+        tracing::warn!(
+            "the commit-msg hook in {} is from an older grt; run `grt setup --update-hook` to update it",
+            hooks_dir.display()
+        );
+    }
+
+    // Propagate hook to submodules (non-fatal)
+    if let Err(e) = hook::propagate_hook_to_submodules(&root) {
+        tracing::warn!("failed to propagate hook to submodules: {e}");
+    }
+
+    let remote = args.remote.unwrap_or_else(|| app.config.remote.clone());
+    let branch = target_branch(&app, args.branch, None, &remote)?;
+
+    // Pushing refs/for/ to a mirror fails with an opaque git error; say why up front
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)
+        .ok()
+        .flatten();
+    if let Some(ref url) = remote_url {
+        if !push::looks_like_gerrit_remote(url) {
+            eprintln!(
+                "{} remote '{remote}' ({url}) does not look like a Gerrit server; \
+                 refs/for/ pushes require a Gerrit remote. Check the host in .gitreview \
+                 or pass --remote",
+                status_style.warning()
+            );
+        }
+    }
+
+    // Old servers answer unknown push options with an opaque error
+    if !args.print_refspec && !args.dry_run {
+        if args.wip || args.ready {
+            require_feature(&app, Feature::WorkInProgress).await?;
+        }
+        if !args.hashtags.is_empty() {
+            require_feature(&app, Feature::Hashtags).await?;
+        }
+    }
+
+    // Check Change-Id status with better error handling (Task M15)
+    let commit_msg = match app.require_git()?.head_commit_message() {
+        Err(e) if e.is::<NoCommitsError>() => {
+            anyhow::bail!("repository has no commits yet; commit your change before pushing")
+        }
+        other => other?,
+    };
+    let hook_installed = app
+        .require_git()?
+        .hooks_dir()
+        .map(|d| d.join("commit-msg").exists())
+        .unwrap_or(false);
+    match push::check_change_id_status(&commit_msg, hook_installed) {
+        ChangeIdStatus::Present(_) => {}
+        ChangeIdStatus::MissingCanAutoAmend => {
+            eprintln!("No Change-Id found; amending commit to add one...");
+            subprocess::git_exec(&["commit", "--amend", "--no-edit"], &root)?;
+            let amended_msg = app.require_git()?.head_commit_message()?;
+            push::verify_amend_added_change_id(&commit_msg, &amended_msg)?;
+        }
+        ChangeIdStatus::MissingNeedHook => {
+            anyhow::bail!("HEAD commit is missing a Change-Id trailer. Run `grt setup` to install the commit-msg hook, then amend the commit");
+        }
+    }
+
+    // Pre-push rebase: -R skips it, -F pushes the rebased commits, and
+    // otherwise `defaultrebase` runs a test rebase to detect conflicts.
+    // --print-refspec only reports, so it never touches the branch.
+    let mode = rebase::rebase_mode(
+        args.no_rebase || args.print_refspec,
+        args.force_rebase,
+        app.config.default_rebase,
+    );
+    let rebase_orig_head = if mode == rebase::RebaseMode::Skip {
+        None
+    } else {
+        match rebase::rebase_changes(&remote, &branch, args.keep_rebase, &root)? {
+            rebase::RebaseResult::Success { orig_head } => Some(orig_head),
+            rebase::RebaseResult::Failed => anyhow::bail!(
+                "could not rebase onto {remote}/{branch}; fix the conflicts or use -R to push without rebasing"
+            ),
+            rebase::RebaseResult::Skipped => None,
+        }
+    };
+
+    // Undo a test rebase BEFORE push so we push original SHAs. This matches
+    // git-review's behavior: the rebase detects conflicts, but unchanged
+    // commits don't get new patchsets. A forced rebase is kept for the push
+    // and only undone if nothing gets pushed.
+    let forced_orig_head = match (mode, rebase_orig_head) {
+        (rebase::RebaseMode::Force, orig_head) => orig_head,
+        (_, Some(orig_head)) => {
+            rebase::undo_rebase(&orig_head, &root)?;
+            None
+        }
+        (_, None) => None,
+    };
+
+    // Count unpushed commits
+    let count = subprocess::count_unpushed_commits(&remote, &branch, &root)?;
+    let format = app.config.output_format(args.format.clone());
+    if count == 0 {
+        eprintln!("No unpushed commits found.");
+        rebase::undo_unpushed_rebase(forced_orig_head.as_deref(), args.keep_rebase, &root)?;
+        if matches!(format, OutputFormat::Json) {
+            let result = push_result(&app, 0, &remote, &branch, "", false);
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        return Ok(false);
+    }
+
+    // Stacked push: show what each commit becomes on Gerrit before asking
+    if count > 1 && !args.print_refspec {
+        let http = remote_url
+            .as_deref()
+            .is_some_and(review_query::is_http_remote);
+        let table = stack_preview(&mut app, &remote, &branch, &root, http).await?;
+        if args.yes {
+            eprintln!("Commits to push to {remote}/{branch}:\n\n{table}");
+        } else {
+            eprint!("{}", stack_confirmation(&remote, &branch, &table));
+            let mut input = String::new();
+            std::io::stdin()
+                .read_line(&mut input)
+                .context("reading user input")?;
+            if !input.trim().eq_ignore_ascii_case("y") {
+                eprintln!("Push cancelled.");
+                rebase::undo_unpushed_rebase(forced_orig_head.as_deref(), args.keep_rebase, &root)?;
+                return Ok(false);
+            }
+        }
+    }
+
+    let mut reviewers = args.reviewers;
+    let mut cc = args.cc;
+    push::apply_default_recipients(
+        &mut reviewers,
+        &mut cc,
+        &app.config,
+        args.no_default_reviewers,
+    );
+    if args.codeowners {
+        match review::read_codeowners(&root) {
+            Some(content) => {
+                let rules = review::parse_codeowners(&content);
+                let files = subprocess::list_unpushed_files(&remote, &branch, &root)?;
+                let owners = review::codeowners_for_files(&rules, &files);
+                if !owners.is_empty() {
+                    eprintln!("Adding CODEOWNERS reviewers: {}", owners.join(", "));
+                }
+                push::merge_default_reviewers(&mut reviewers, &owners);
+            }
+            None => eprintln!(
+                "{} --codeowners given but no CODEOWNERS file was found",
+                status_style.warning()
+            ),
+        }
+    }
+
+    // `@group` entries: best-effort check over HTTP so typos fail before the push
+    let mut groups = push::take_group_markers(&mut reviewers);
+    groups.extend(push::take_group_markers(&mut cc));
+    if !groups.is_empty()
+        && !args.print_refspec
+        && remote_url
+            .as_deref()
+            .is_some_and(review_query::is_http_remote)
+    {
+        if let Err(e) = warn_unknown_groups(&mut app, &groups).await {
+            debug!("could not verify reviewer groups: {e:#}");
+        }
+    }
+
+    // Gerrit rejects self-review; drop ourselves once we know who we are
+    if !reviewers.is_empty()
+        && !args.allow_self_review
+        && !args.print_refspec
+        && remote_url
+            .as_deref()
+            .is_some_and(review_query::is_http_remote)
+    {
+        match self_account_ids(&mut app).await {
+            Ok(ids) => {
+                let removed = push::strip_self_reviewer(&mut reviewers, &ids);
+                if !removed.is_empty() {
+                    eprintln!(
+                        "Dropping yourself from reviewers: {} (use --allow-self-review to keep)",
+                        removed.join(", ")
+                    );
+                    if args.cc_self {
+                        push::merge_default_reviewers(&mut cc, &removed);
+                    }
+                }
+            }
+            Err(e) => debug!("could not resolve own account: {e:#}"),
+        }
+    }
+
+    let cc_before = cc.len();
+    let (reviewers, cc) = push::reconcile_reviewers_cc(reviewers, cc);
+    if cc.len() < cc_before {
+        eprintln!(
+            "{} recipients listed as both reviewer and CC are added as reviewers only",
+            status_style.warning()
+        );
+    }
+
+    let message = if args.no_message {
+        None
+    } else {
+        args.message.or_else(|| app.config.message_template.clone())
+    };
+    let message = match message {
+        Some(msg) if msg.contains('{') => {
+            let ctx = message_context(
+                &mut app,
+                &branch,
+                args.topic.as_deref(),
+                &msg,
+                remote_url.as_deref(),
+            )
+            .await?;
+            Some(push::expand_message_template(
+                &msg,
+                &ctx,
+                args.strict_message,
+            )?)
+        }
+        other => other,
+    };
+
+    let mut opts = PushOptions {
+        branch: branch.clone(),
+        topic: args.topic,
+        wip: args.wip,
+        ready: args.ready,
+        private: args.private,
+        remove_private: args.remove_private,
+        reviewers,
+        cc,
+        hashtags: args.hashtags,
+        message,
+        notify: args.notify.clone(),
+        labels: args.labels,
+        base: args
+            .base
+            .as_deref()
+            .map(|rev| resolve_base_commit(rev, &root))
+            .transpose()?,
+        merged: args.merged,
+        publish_comments: args.publish_comments,
+        raw_options: args.push_options,
+    };
+
+    // Very long messages overflow the refspec; post them through the review API instead
+    let (refspec, deferred_message) = push::build_refspec_deferring_message(&mut opts)?;
+    if deferred_message.is_some() && count > 1 {
+        // In the refspec the message reaches every change; posted, only HEAD's
+        rebase::undo_unpushed_rebase(forced_orig_head.as_deref(), args.keep_rebase, &root)?;
+        anyhow::bail!(
+            "--message is too long for the push refspec (limit {} characters) and \
+             could only be posted to HEAD's change of the {count} being pushed; \
+             shorten it or push the commits one at a time",
+            push::MAX_REFSPEC_LEN
+        );
+    }
+    if deferred_message.is_some() {
+        eprintln!(
+            "{} --message is too long for the push refspec (limit {} characters); \
+             it will be posted as a review message after the push",
+            status_style.warning(),
+            push::MAX_REFSPEC_LEN
+        );
+    }
+
+    if args.print_refspec {
+        push::write_refspec(&mut std::io::stdout().lock(), &refspec)?;
+        return Ok(false);
+    }
+
+    // Build push args with --no-follow-tags, color remote, and optional
+    // --no-thin / --signed (Tasks M6, M7, L13, L15)
+    let sign = !args.no_sign && (args.sign || app.config.signed_push);
+    let push_flags = push::GitPushFlags {
+        // Like git-review, remote messages are colored unless color is turned off
+        color: ui::color_preference(globals.no_color, globals.color, app.config.color.as_deref())
+            .map_or("always", ColorWhen::as_str)
+            .to_string(),
+        no_thin: args.no_thin,
+        signed: sign,
+        // git's stderr is captured, so it only reports progress when asked
+        progress: std::io::IsTerminal::is_terminal(&std::io::stderr()),
+    };
+    let push_args = push::build_push_args(&push_flags, &remote, &refspec);
+
+    // Dry-run: show full command with all flags (Task L13)
+    if args.dry_run {
+        match format {
+            OutputFormat::Json => {
+                eprintln!("git {}", push_args.join(" "));
+                let result = push_result(&app, count, &remote, &branch, &refspec, true);
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            OutputFormat::Text => {
+                println!("git {}", push_args.join(" "));
+                let options = push::describe_refspec(&refspec);
+                if !options.is_empty() {
+                    println!("push options:");
+                    for (key, value) in options {
+                        if value.is_empty() {
+                            println!("  {key}");
+                        } else {
+                            println!("  {key}: {value}");
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(path) = &args.change_id_file {
+            write_change_id_file(&mut app, path, None)?;
+        }
+        rebase::undo_unpushed_rebase(forced_orig_head.as_deref(), args.keep_rebase, &root)?;
+        return Ok(false);
+    }
+
+    eprintln!("Pushing {count} commit(s) to {remote}/{branch}...");
+
+    // Catch push errors and suggest a fix for known failures (Task L14).
+    // stderr is captured (and still shown) for the change URLs in Gerrit's
+    // `remote:` lines and to explain push-certificate rejections.
+    let push_args: Vec<&str> = push_args.iter().map(String::as_str).collect();
+    let result = subprocess::git_exec_capture_stderr(&push_args, &root);
+    let push_output = match result {
+        Ok(output) => output,
+        Err(e) => {
+            let mut msg = format!("{e:#}");
+            if let Some(git_err) = e.downcast_ref::<subprocess::GitCommandError>() {
+                msg.push('\n');
+                msg.push_str(&git_err.stderr);
+            }
+            if let Some(hint) = push::push_failure_hint(&msg, sign) {
+                eprintln!("hint: {hint}");
+            }
+            if let Err(undo_err) =
+                rebase::undo_unpushed_rebase(forced_orig_head.as_deref(), args.keep_rebase, &root)
+            {
+                eprintln!(
+                    "{} could not undo rebase: {undo_err:#}",
+                    status_style.warning()
+                );
+            }
+            return Err(e);
+        }
+    };
+    let pushed = push::parse_pushed_changes(&push_output);
+    let head_message = app.require_git()?.head_commit_message()?;
+    let head_change = push::head_pushed_change(&pushed, &head_message).cloned();
+    if let Some(path) = &args.change_id_file {
+        write_change_id_file(&mut app, path, head_change.as_ref())?;
+    }
+
+    // Per-recipient notification and over-long messages go through the review
+    // API, since the refspec only supports the coarse notify= level and is
+    // length-limited.
+    if !args.notify_to.is_empty() || !args.notify_cc.is_empty() || deferred_message.is_some() {
+        let commit_msg = app.require_git()?.head_commit_message()?;
+        let change_id = push::extract_change_id(&commit_msg)
+            .context("no Change-Id in HEAD commit; cannot post review message")?;
+        let mut input = grt::gerrit::ReviewInput {
+            message: deferred_message,
+            notify: args.notify.clone(),
+            ..Default::default()
+        };
+        input.add_notify_recipients("TO", &args.notify_to);
+        input.add_notify_recipients("CC", &args.notify_cc);
+        // SSH remotes post through `gerrit review`; the REST API needs auth
+        let url = remote_url.unwrap_or_else(|| app.config.make_remote_url());
+        if review_query::is_http_remote(&url) {
+            app.authenticate_and_verify().await?;
+        }
+        let revision = subprocess::git_rev_parse_head(&root)?;
+        review_query::post_review(&url, &change_id, &revision, &input, &app.gerrit, &root)
+            .await
+            .context("posting review message")?;
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let mut result = push_result(&app, count, &remote, &branch, &refspec, false);
+            if let Some(head) = head_change {
+                result.change_number = Some(head.number);
+                result.change_url = Some(head.url);
+            }
+            result.changes = pushed;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        OutputFormat::Text => match head_change {
+            Some(head) => eprintln!(
+                "{} change {}: {}",
+                status_style.green("Push successful:"),
+                head.number,
+                head.url
+            ),
+            None => eprintln!("{}", status_style.green("Push successful.")),
+        },
+    }
+
+    Ok(true)
+}
+
+/// Resolve `--base` to the full SHA-1 Gerrit expects in `base=`.
+fn resolve_base_commit(rev: &str, root: &Path) -> Result<String> {
+    subprocess::git_output(
+        &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+        root,
+    )
+    .with_context(|| format!("--base {rev} is not a commit"))
+}
+
+/// The `--format json` result of a push.
+fn push_result(
+    app: &App,
+    commits: usize,
+    remote: &str,
+    branch: &str,
+    refspec: &str,
+    dry_run: bool,
+) -> PushResult {
+    // Re-read commit message to get Change-Id (may have been added by amend)
+    let commit_msg = app
+        .require_git()
+        .and_then(|git| git.head_commit_message())
+        .unwrap_or_default();
+    PushResult {
+        commits,
+        remote: remote.to_string(),
+        branch: branch.to_string(),
+        change_id: push::extract_change_id(&commit_msg),
+        refspec: refspec.to_string(),
+        dry_run,
+        change_number: None,
+        change_url: None,
+        changes: Vec::new(),
+    }
+}
+
+/// Build the config overrides for `grt comments` from its flags and the globals.
+fn comments_cli_overrides(args: &CommentsArgs, globals: &GlobalOpts) -> CliOverrides {
+    CliOverrides {
+        project: args.project.clone(),
+        remote: args.remote.clone(),
+        branch: args.branch.clone(),
+        ..globals.cli_overrides()
+    }
+}
+
+async fn cmd_comments(work_dir: &Path, mut args: CommentsArgs, globals: &GlobalOpts) -> Result<()> {
+    match args.action {
+        Some(CommentsAction::Reply(reply)) => {
+            return cmd_comments_reply(work_dir, reply, globals).await
+        }
+        Some(CommentsAction::Publish(publish)) => {
+            return cmd_comments_publish(work_dir, publish, globals).await
+        }
+        None => {}
+    }
+    let cli_overrides = comments_cli_overrides(&args, globals);
+    let mut app = App::new(work_dir, &cli_overrides)?;
+    let style = Style::stdout(globals.color(&app));
+    let format = args
+        .format
+        .unwrap_or_else(|| app.config.output_format(None).into());
+
+    // Resolve --age / --max-age into YYYY-MM-DD date bounds used by both modes.
+    // --age N   → keep threads newer than N ago  → `after` lower bound
+    // --max-age N → keep threads older than N ago → `before` upper bound
+    let age_after: Option<String> = args.age.as_deref().map(parse_age_to_date).transpose()?;
+    let age_before: Option<String> = args.min_age.as_deref().map(parse_age_to_date).transpose()?;
+    let since = args
+        .since
+        .as_deref()
+        .map(comments::parse_since)
+        .transpose()?;
+    // Comments on older patchsets are only fetched with --all-revisions
+    if matches!(since, Some(comments::Since::Patchset(_))) && args.revision.is_none() {
+        args.all_revisions = true;
+    }
+
+    // Search mode: no change given, but --project or --age provided
+    let search_mode = args.change.is_none() && (args.project.is_some() || args.age.is_some());
+
+    if search_mode {
+        if format == CommentsFormat::Prompt {
+            anyhow::bail!("--format prompt needs a single change");
+        }
+        if args.cached {
+            anyhow::bail!(
+                "--cached needs a single change; cross-change search always queries Gerrit"
+            );
+        }
+        app.authenticate_and_verify().await?;
+        let mut query_parts = Vec::new();
+        if let Some(ref proj) = args.project {
+            query_parts.push(format!("project:{proj}"));
+        }
+        if let Some(ref branch) = args.branch {
+            query_parts.push(list::branch_predicate(branch));
+        }
+        if let Some(ref age) = args.age {
+            // -age:N = changes active within the last N (newer than N ago)
+            query_parts.push(format!("-age:{age}"));
+        }
+        if let Some(ref min_age) = args.min_age {
+            // age:N = changes last modified more than N ago (older than N ago)
+            query_parts.push(format!("age:{min_age}"));
+        }
+        // Use commentby: for server-side pre-filtering — unlike reviewer: it matches
+        // anyone who has posted a comment, including CI bots that are not formal reviewers.
+        if let Some(ref pat) = args.comment_by {
+            query_parts.push(format!("commentby:{pat}"));
+        }
+        let query = query_parts.join(" ");
+
+        let changes = app.gerrit.query_changes(&query).await?;
+        let gerrit_url = app.config.gerrit_base_url()?.to_string();
+        let robot_comments_supported = server_capabilities(&app)
+            .await
+            .is_none_or(|caps| caps.supports(Feature::RobotComments));
+
+        let mut outputs: Vec<comments::CommentOutput> = Vec::new();
+
+        for change in &changes {
+            let change_id = match change.number {
+                Some(n) => n.to_string(),
+                None => continue,
+            };
+
+            let change_detail = app.gerrit.get_change_detail(&change_id).await?;
+            // In search mode always fetch all revisions — a commenter may have
+            // reviewed an earlier patchset that is no longer the current one.
+            let mut all_comments = app.gerrit.get_change_comments(&change_id).await?;
+
+            // Always include robot comments in search mode: CI bots comment via
+            // the robot comments endpoint and would be invisible otherwise.
+            if robot_comments_supported {
+                if let Ok(robot) = app.gerrit.get_robot_comments(&change_id).await {
+                    for (file, rc) in robot {
+                        all_comments.entry(file).or_default().extend(rc);
+                    }
+                }
+            }
+
+            let mut threads = comments::build_threads(&all_comments);
+
+            // Apply filters.
+            // In search mode --age drives the Gerrit query (change activity window),
+            // so do NOT apply it as a per-comment date filter here.  Use explicit
+            // --after / --before for per-comment date filtering in search mode.
+            if let Some(ref pat) = args.comment_by {
+                comments::filter_threads_by_author(&mut threads, pat);
+            }
+            filter_threads_by_scope(&mut threads, &args, since.as_ref());
+            if args.has_replies {
+                comments::filter_threads_has_replies(&mut threads);
+            }
+            comments::filter_threads_by_date(
+                &mut threads,
+                args.after.as_deref(),
+                args.before.as_deref(),
+            );
+
+            // Label filter
+            if let Some(ref label_arg) = args.label {
+                apply_label_filter(&change_detail, label_arg, &mut threads)?;
+            }
+
+            if args.unresolved {
+                threads.retain(|t| !t.resolved);
+            }
+            if args.resolved {
+                threads.retain(|t| t.resolved);
+            }
+
+            if threads.is_empty() {
+                continue; // all threads filtered out for this change
+            }
+
+            let messages = change_detail.messages.as_deref().unwrap_or(&[]);
+            if format == CommentsFormat::Text {
+                let text =
+                    comments::format_text(&change_detail, messages, &threads, &gerrit_url, style);
+                print!("{text}");
+                println!("\n---\n");
+            } else {
+                outputs.push(comments::comment_output(
+                    &change_detail,
+                    messages,
+                    &threads,
+                    &gerrit_url,
+                ));
+            }
+        }
+
+        if format != CommentsFormat::Text {
+            print_comment_outputs(format, &outputs, &gerrit_url)?;
+        }
+        return Ok(());
+    }
+
+    // Single-change mode
+    let change_id = match args.change.clone() {
+        Some(id) => id,
+        None => {
+            let msg = match app.require_git()?.head_commit_message() {
+                Err(e) if e.is::<NoCommitsError>() => anyhow::bail!(
+                    "repository has no commits yet, so there is no Change-Id to look up. \
+                     Specify a change number explicitly"
+                ),
+                other => other?,
+            };
+            push::extract_change_id(&msg)
+                .context("no Change-Id found in HEAD commit. Specify a change number explicitly")?
+        }
+    };
+
+    // Every successful fetch refreshes the offline cache for this change
+    let cache_mode = CacheMode::from_flags(args.cached, args.refresh);
+    if format == CommentsFormat::Prompt && cache_mode == CacheMode::Offline {
+        anyhow::bail!("--format prompt fetches the diff from Gerrit and cannot use --cached");
+    }
+    let revision = match (&args.revision, args.all_revisions) {
+        (_, true) => None,
+        (Some(rev), false) => Some(rev.as_str()),
+        (None, false) => Some("current"),
+    };
+    let key = cache::comments_key(&change_id, revision);
+    let cache = app.cache().ok();
+
+    let data = if cache_mode == CacheMode::Offline {
+        cached_comments(cache.as_ref(), &key)?
+    } else {
+        match fetch_comments(&mut app, &change_id, &args).await {
+            Ok(data) => {
+                if let Some(cache) = &cache {
+                    if let Err(e) = cache.store_comments(&key, &data) {
+                        debug!("could not update comment cache: {e:#}");
+                    }
+                }
+                data
+            }
+            Err(e) if cache_mode == CacheMode::Auto && cache::is_offline_error(&e) => {
+                eprintln!("warning: {e:#}; showing cached comments");
+                cached_comments(cache.as_ref(), &key)?
+            }
+            Err(e) => return Err(e),
+        }
+    };
+
+    let change = data.change;
+    let mut all_comments = data.comments;
+    if !args.exclude_robot_comments {
+        for (file, robot_comments) in data.robot_comments {
+            all_comments.entry(file).or_default().extend(robot_comments);
+        }
+    }
+
+    let mut threads = comments::build_threads(&all_comments);
+
+    // Apply filters
+    if let Some(ref pat) = args.comment_by {
+        comments::filter_threads_by_author(&mut threads, pat);
+    }
+    filter_threads_by_scope(&mut threads, &args, since.as_ref());
+    if args.has_replies {
+        comments::filter_threads_has_replies(&mut threads);
+    }
+    let after = args.after.as_deref().or(age_after.as_deref());
+    let before = args.before.as_deref().or(age_before.as_deref());
+    comments::filter_threads_by_date(&mut threads, after, before);
+
+    // Label filter
+    if let Some(ref label_arg) = args.label {
+        apply_label_filter(&change, label_arg, &mut threads)?;
+    }
+
+    if args.unresolved {
+        threads.retain(|t| !t.resolved);
+    }
+    if args.resolved {
+        threads.retain(|t| t.resolved);
+    }
+
+    let messages = change.messages.as_deref().unwrap_or(&[]);
+    let gerrit_url = app.config.gerrit_base_url()?.to_string();
+
+    match format {
+        CommentsFormat::Text => {
+            let text = comments::format_text(&change, messages, &threads, &gerrit_url, style);
+            print!("{text}");
+        }
+        CommentsFormat::Json => {
+            let json = comments::format_json(&change, messages, &threads, &gerrit_url);
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        CommentsFormat::Prompt => {
+            let prompt = comments_prompt(&app, &change_id, &change, threads, &gerrit_url).await?;
+            print!("{prompt}");
+        }
+        _ => {
+            let output = comments::comment_output(&change, messages, &threads, &gerrit_url);
+            print_comment_outputs(format, &[output], &gerrit_url)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the `--format prompt` bundle: the unresolved threads with the code
+/// around each, plus the commit message and patch of the current revision.
+///
+/// Each file is fetched once per patchset it was commented on; a file that
+/// cannot be read (e.g. deleted) just loses its code context.
+async fn comments_prompt(
+    app: &App,
+    change_id: &str,
+    change: &grt::gerrit::ChangeInfo,
+    threads: Vec<comments::CommentThread>,
+    gerrit_url: &str,
+) -> Result<String> {
+    let threads: Vec<_> = threads.into_iter().filter(|t| !t.resolved).collect();
+    let commit = app.gerrit.get_revision_commit(change_id, "current").await?;
+    let patch = app.gerrit.get_revision_patch(change_id, "current").await?;
+
+    let mut files: HashMap<(String, String), Option<String>> = HashMap::new();
+    let mut contexts = Vec::with_capacity(threads.len());
+    for thread in &threads {
+        let Some(line) = thread.line.filter(|_| !thread.file.starts_with('/')) else {
+            contexts.push(None);
+            continue;
+        };
+        let key = (thread.file.clone(), comments::thread_revision(thread));
+        if !files.contains_key(&key) {
+            let content = match app.gerrit.get_file_content(change_id, &key.1, &key.0).await {
+                Ok(content) => Some(content),
+                Err(e) => {
+                    debug!("could not read {} at {}: {e:#}", key.0, key.1);
+                    None
+                }
+            };
+            files.insert(key.clone(), content);
+        }
+        contexts.push(
+            files[&key]
+                .as_deref()
+                .and_then(|c| comments::code_context(c, line, comments::PROMPT_CONTEXT_LINES)),
+        );
+    }
+
+    Ok(comments::format_prompt(
+        change,
+        commit.message.as_deref().unwrap_or(""),
+        &threads,
+        &contexts,
+        &patch,
+        gerrit_url,
+    ))
+}
+
+/// Print comments of one or more changes in a structured `format`. Text and
+/// prompt bundles are printed per change by the callers instead.
+fn print_comment_outputs(
+    format: CommentsFormat,
+    outputs: &[comments::CommentOutput],
+    gerrit_url: &str,
+) -> Result<()> {
+    match format {
+        CommentsFormat::Text | CommentsFormat::Prompt => {}
+        CommentsFormat::Json => {
+            let multi = comments::format_json_multi(outputs);
+            println!("{}", serde_json::to_string_pretty(&multi)?);
+        }
+        CommentsFormat::Markdown => {
+            let docs: Vec<String> = outputs
+                .iter()
+                .map(|o| comments::format_markdown(o, gerrit_url))
+                .collect();
+            print!("{}", docs.join("\n---\n\n"));
+        }
+        CommentsFormat::Sarif => {
+            let sarif = comments::format_sarif(outputs, gerrit_url);
+            println!("{}", serde_json::to_string_pretty(&sarif)?);
+        }
+        CommentsFormat::Quickfix => print!("{}", comments::format_quickfix(outputs)),
+        CommentsFormat::Rdjson => {
+            let rdjson = comments::format_rdjson(outputs, gerrit_url);
+            println!("{}", serde_json::to_string_pretty(&rdjson)?);
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_comments_reply(work_dir: &Path, args: ReplyArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change = change_or_head(&app, args.change.as_deref())?;
+    app.authenticate_and_verify().await?;
+
+    // A reply lands on the parent's file and, unless overridden, its line
+    let (file, line) = match (args.file, &args.in_reply_to) {
+        (Some(file), _) => (file, args.line),
+        (None, Some(parent)) => {
+            let all = app.gerrit.get_change_comments(&change).await?;
+            let (file, parent_line) = comments::find_comment(&all, parent)
+                .with_context(|| format!("no comment with id {parent} on change {change}"))?;
+            let parent_line = parent_line.and_then(|l| u32::try_from(l).ok());
+            (file, args.line.or(parent_line))
+        }
+        (None, None) => anyhow::bail!("missing argument: give --file, or --in-reply-to a comment"),
+    };
+    let resolved = match (args.resolved, args.unresolved) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    let input = comments::draft_comment(
+        &file,
+        line,
+        &args.message,
+        args.in_reply_to.as_deref(),
+        resolved,
+    )?;
+    let draft = app
+        .gerrit
+        .create_draft(&change, &args.revision, &input)
+        .await?;
+
+    let location = match input.line {
+        Some(line) => format!("{file}:{line}"),
+        None => file,
+    };
+    println!(
+        "Saved draft {} on {location} (change {change}); publish with `grt comments publish`",
+        draft.id.as_deref().unwrap_or("-")
+    );
+    Ok(())
+}
+
+async fn cmd_comments_publish(
+    work_dir: &Path,
+    args: PublishArgs,
+    globals: &GlobalOpts,
+) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change = change_or_head(&app, args.change.as_deref())?;
+    app.authenticate_and_verify().await?;
+
+    let drafts = comments::count_drafts(&app.gerrit.get_drafts(&change).await?);
+    if drafts == 0 && args.message.is_none() {
+        anyhow::bail!("no draft comments on change {change}; nothing to publish");
+    }
+    let input = grt::gerrit::ReviewInput {
+        message: args.message,
+        drafts: Some("PUBLISH_ALL_REVISIONS".to_string()),
+        ..Default::default()
+    };
+    app.gerrit.set_review(&change, "current", &input).await?;
+    println!("Published {drafts} draft comment(s) on change {change}");
+    Ok(())
+}
+
+/// Fetch a change and its comments for single-change `grt comments`.
+async fn fetch_comments(
+    app: &mut App,
+    change_id: &str,
+    args: &CommentsArgs,
+) -> Result<CachedComments> {
+    app.authenticate_and_verify().await?;
+    debug!("fetching comments for change: {}", change_id);
+
+    let change = app.gerrit.get_change_detail(change_id).await?;
+    let comments = if args.all_revisions {
+        app.gerrit.get_change_comments(change_id).await?
+    } else if let Some(ref rev) = args.revision {
+        app.gerrit.get_revision_comments(change_id, rev).await?
+    } else if let Some(ref current_rev) = change.current_revision {
+        app.gerrit
+            .get_revision_comments(change_id, current_rev)
+            .await?
+    } else {
+        app.gerrit.get_change_comments(change_id).await?
+    };
+
+    let robot_comments = if args.exclude_robot_comments
+        || !server_capabilities(app)
+            .await
+            .is_none_or(|caps| caps.supports(Feature::RobotComments))
+    {
+        HashMap::new()
+    } else {
+        app.gerrit
+            .get_robot_comments(change_id)
+            .await
+            .unwrap_or_default()
+    };
+
+    Ok(CachedComments {
+        fetched_at: cache::now_secs(),
+        change,
+        comments,
+        robot_comments,
+    })
+}
+
+/// Read cached comments for `key`, noting their age on stderr.
+fn cached_comments(cache: Option<&cache::Cache>, key: &str) -> Result<CachedComments> {
+    let cache = cache.context("the comment cache lives in a git repository")?;
+    let entry = cache.load_comments(key)?.with_context(|| {
+        format!("no cached comments for {key}; run `grt comments` while Gerrit is reachable")
+    })?;
+    eprintln!(
+        "Showing cached comments from {}.",
+        cache::describe_age(entry.fetched_at, cache::now_secs())
+    );
+    Ok(entry)
+}
+
+/// Resolve the transport scheme for `grt setup`.
+///
+/// SSH is the default and is used whenever `--http` is not passed.
+/// `--ssh` is accepted for explicitness but has the same effect as omitting both flags.
+fn setup_scheme(_ssh: bool, http: bool) -> &'static str {
+    if http {
+        "https"
+    } else {
+        "ssh"
+    }
+}
+
+/// Return true when setup should run HTTP connectivity and auth checks.
+/// SSH-only setup skips REST API calls to avoid prompting for HTTPS credentials.
+fn setup_needs_http_check(scheme: &str) -> bool {
+    scheme != "ssh"
+}
+
+/// Apply `--author`, `--file` and `--since`.
+fn filter_threads_by_scope(
+    threads: &mut Vec<comments::CommentThread>,
+    args: &CommentsArgs,
+    since: Option<&comments::Since>,
+) {
+    if let Some(ref pat) = args.author {
+        comments::filter_threads_by_starter(threads, pat);
+    }
+    if let Some(ref glob) = args.file {
+        comments::filter_threads_by_file(threads, glob);
+    }
+    if let Some(since) = since {
+        comments::filter_threads_since(threads, since);
+    }
+}
+
+/// Parse an age string like "1d", "2w", "3m", "1y" into a YYYY-MM-DD date
+/// representing that many days/weeks/months/years before today.
+///
+/// Returned date can be used as an `after` or `before` bound with
+/// `filter_threads_by_date`.
+fn parse_age_to_date(age: &str) -> Result<String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let (count_str, unit) = age
+        .find(|c: char| c.is_alphabetic())
+        .map(|i| (&age[..i], &age[i..]))
+        .context("age must be a number followed by a unit (d, w, m, y)")?;
+    let count: u64 = count_str
+        .parse()
+        .context("age count must be a positive integer")?;
+    if count == 0 {
+        anyhow::bail!("age count must be greater than zero");
+    }
+
+    let days: u64 = match unit {
+        "d" => count,
+        "w" => count * 7,
+        "m" => count * 30,
+        "y" => count * 365,
+        other => anyhow::bail!("unknown age unit '{}'; use d, w, m, or y", other),
+    };
+
+    let now_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("system time before Unix epoch")?
+        .as_secs();
+    let cutoff_secs = now_secs.saturating_sub(days * 86_400);
+
+    // Convert epoch seconds to YYYY-MM-DD without external deps
+    let date = epoch_secs_to_date(cutoff_secs);
+    Ok(date)
+}
+
+/// Convert Unix epoch seconds to a "YYYY-MM-DD" string using the proleptic
+/// Gregorian calendar.  Accurate for dates in the range 1970–9999.
+fn epoch_secs_to_date(secs: u64) -> String {
+    let days_since_epoch = secs / 86_400;
+    // Algorithm from http://howardhinnant.github.io/date_algorithms.html
+    // civil_from_days (public domain)
+    let z = days_since_epoch as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097) as u64; // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let d = doy - (153 * mp + 2) / 5 + 1; // [1, 31]
+    let m = if mp < 10 { mp + 3 } else { mp - 9 }; // [1, 12]
+    let y = if m <= 2 { y + 1 } else { y };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Parse a label filter like "Code-Review=-1" and retain only threads
+/// where at least one commenter's account_id voted that label value.
+fn apply_label_filter(
+    change: &grt::gerrit::ChangeInfo,
+    label_arg: &str,
+    threads: &mut Vec<grt::comments::CommentThread>,
+) -> Result<()> {
+    let (label_name, value_str) = label_arg
+        .split_once('=')
+        .context("--label must be in format 'LabelName=value' (e.g., Code-Review=-1)")?;
+    let target_value: i32 = value_str
+        .parse()
+        .context("label value must be an integer")?;
+
+    let voter_ids: std::collections::HashSet<i64> = change
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(label_name))
+        .and_then(|info| info.all.as_ref())
+        .map(|approvals| {
+            approvals
+                .iter()
+                .filter(|a| a.value == Some(target_value))
+                .filter_map(|a| a.account_id)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    threads.retain(|t| {
+        !voter_ids.is_empty()
+            && t.comments.iter().any(|c| {
+                c.account_id
+                    .map(|id| voter_ids.contains(&id))
+                    .unwrap_or(false)
+            })
+    });
+
+    Ok(())
+}
+
+async fn cmd_setup(work_dir: &Path, args: SetupArgs, globals: &GlobalOpts) -> Result<()> {
+    let scheme = Some(setup_scheme(args.ssh, args.http).to_string());
+
+    let cli_overrides = CliOverrides {
+        remote: args.remote.clone(),
+        scheme,
+        ..globals.cli_overrides()
+    };
+    let mut app = App::new(work_dir, &cli_overrides)?;
+    let root = app.require_git()?.root()?;
+    let style = Style::stderr(globals.color(&app));
+    let ok = style.green("OK");
+    let failed = style.red("FAILED");
+
+    eprintln!("Setting up grt for Gerrit...");
+
+    // 1. Install commit-msg hook
+    let hooks_dir = app.require_git()?.hooks_dir()?;
+    let hook_path = hooks_dir.join("commit-msg");
+    let will_write = args.force_hook
+        || args.remote_hook
+        || !hook_path.exists()
+        || (args.update_hook && hook::hook_is_outdated(&hooks_dir));
+    if will_write
+        && app.require_git()?.hooks_dir_outside_repo()?
+        && !confirm_shared_hooks_dir(&hooks_dir, args.force_hook)?
+    {
+        eprintln!(
+            "  commit-msg hook: skipped (shared hooks directory {})",
+            hooks_dir.display()
+        );
+    } else if args.remote_hook {
+        if args.force_hook && hook_path.exists() {
+            std::fs::remove_file(&hook_path).context("removing existing commit-msg hook")?;
+        }
+        // Download hook from remote Gerrit server (HTTP or SCP based on remote URL)
+        let remote_name = args.remote.as_deref().unwrap_or(&app.config.remote);
+        let remote_url = review_query::resolve_gerrit_url(remote_name, &root, &app.config)?
+            .or_else(|| app.config.gerrit_base_url().ok().map(|u| u.to_string()))
+            .context("no remote URL for hook download")?;
+        hook::fetch_remote_hook(&remote_url, &hooks_dir).await?;
+    } else if args.update_hook {
+        match hook::update_hook(&hooks_dir)? {
+            hook::HookUpdate::Updated { from } => eprintln!(
+                "  commit-msg hook: updated from version {from} to {} at {}",
+                hook::HOOK_VERSION,
+                hook_path.display()
+            ),
+            hook::HookUpdate::Current => eprintln!(
+                "  commit-msg hook: up to date (version {}) at {}",
+                hook::HOOK_VERSION,
+                hook_path.display()
+            ),
+            hook::HookUpdate::Installed => {
+                eprintln!("  commit-msg hook: installed at {}", hook_path.display())
+            }
+        }
+    } else {
+        if args.force_hook && hook_path.exists() {
+            std::fs::remove_file(&hook_path).context("removing existing commit-msg hook")?;
+        }
+        hook::ensure_hook_installed(&hooks_dir)?;
+        eprintln!("  commit-msg hook: installed at {}", hook_path.display());
+    }
+
+    // Propagate hook to submodules
+    if let Err(e) = hook::propagate_hook_to_submodules(&root) {
+        tracing::warn!("failed to propagate hook to submodules: {e}");
+    }
+
+    // 2. Ensure remote exists (create if missing, prompt for SSH username if needed)
+    check_and_create_remote(&mut app)?;
+    let remote = args.remote.unwrap_or_else(|| app.config.remote.clone());
+    match subprocess::git_output(&["remote", "get-url", &remote], &root) {
+        Ok(url) => eprintln!("  remote '{remote}': {}", url.trim()),
+        Err(_) => eprintln!("  remote '{remote}': {}", style.red("NOT FOUND")),
+    }
+
+    // 3. Test connectivity and auth (SSH setups run `gerrit version` instead)
+    let use_http = setup_needs_http_check(&app.config.scheme);
+    if !use_http {
+        let url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+            .unwrap_or_else(|| app.config.make_remote_url());
+        match review_query::get_version(&url, &app.gerrit, &root).await {
+            Ok(version) => eprintln!("  SSH connectivity: {ok} (Gerrit {version})"),
+            Err(e) => eprintln!("  SSH connectivity: {failed} ({e:#})"),
+        }
+    }
+    if use_http {
+        eprintln!("  Gerrit host: {}", app.config.host);
+        match app.gerrit.get_version().await {
+            Ok(version) => {
+                eprintln!("  connectivity: {ok} (Gerrit {version})");
+            }
+            Err(e) => {
+                eprintln!("  connectivity: {failed} ({e})");
+                eprintln!("  Trying with authentication...");
+                if app.authenticate().is_ok() {
+                    match app.gerrit.get_version().await {
+                        Ok(version) => {
+                            eprintln!("  connectivity: {ok} (Gerrit {version}, authenticated)")
+                        }
+                        Err(e) => eprintln!("  connectivity: {failed} with auth ({e})"),
+                    }
+                } else {
+                    eprintln!("  authentication: {failed} (check git credentials)");
+                }
+            }
+        }
+
+        // 4. Verify auth
+        match app.gerrit.get_self_account().await {
+            Ok(account) => {
+                let name = account.name.as_deref().unwrap_or("unknown");
+                let email = account.email.as_deref().unwrap_or("unknown");
+                eprintln!("  authenticated as: {name} <{email}>");
+            }
+            Err(_) => {
+                if app.authenticate().is_ok() {
+                    match app.gerrit.get_self_account().await {
+                        Ok(account) => {
+                            let name = account.name.as_deref().unwrap_or("unknown");
+                            let email = account.email.as_deref().unwrap_or("unknown");
+                            eprintln!("  authenticated as: {name} <{email}>");
+                        }
+                        Err(e) => eprintln!("  auth check: {failed} ({e})"),
+                    }
+                }
+            }
+        }
+    }
+
+    eprintln!("\n{}", style.green("Setup complete."));
+    Ok(())
+}
+
+async fn cmd_submit(work_dir: &Path, args: SubmitArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    if let Some(topic) = &args.topic {
+        app.authenticate_and_verify().await?;
+        let merged = submit::submit_topic(&app.gerrit, topic, args.project.as_deref()).await?;
+        print!("{}", submit::format_submitted(topic, &merged));
+        return Ok(());
+    }
+
+    let change = change_or_head(&app, args.change.as_deref())?;
+    app.authenticate_and_verify().await?;
+    let info = app.gerrit.get_change_status(&change).await?;
+    let report = status::build_report(&info, None);
+
+    if args.when_ready {
+        submit::check_open(&report)?;
+        if !submit::supports_auto_submit(&info) {
+            anyhow::bail!(
+                "change {change} has no {} label, so this server cannot submit it when ready; \
+                 run `grt submit` once `grt status` shows it is ready",
+                submit::AUTO_SUBMIT_LABEL
+            );
+        }
+        let mut input = grt::gerrit::ReviewInput::default();
+        input
+            .labels
+            .insert(submit::AUTO_SUBMIT_LABEL.to_string(), 1);
+        app.gerrit.set_review(&change, "current", &input).await?;
+        println!(
+            "Voted {}+1 on change {change}; Gerrit submits it once it is approved",
+            submit::AUTO_SUBMIT_LABEL
+        );
+        return Ok(());
+    }
+
+    submit::preflight(&report)?;
+    let merged = app
+        .gerrit
+        .submit_change(&change)
+        .await
+        .with_context(|| format!("submitting change {change}"))?;
+    println!(
+        "Submitted change {}: {}",
+        merged.number.map_or(change, |n| n.to_string()),
+        merged.subject.as_deref().unwrap_or("-")
+    );
+    Ok(())
+}
+
+/// Which way `grt abandon` / `grt restore` moves a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeState {
+    Abandon,
+    Restore,
+}
+
+async fn cmd_change_state(
+    work_dir: &Path,
+    args: ChangeStateArgs,
+    globals: &GlobalOpts,
+    state: ChangeState,
+) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change = change_or_head(&app, args.change.as_deref())?;
+    let message = match args.message {
+        Some(message) => Some(message),
+        None => prompt_optional_message()?,
+    };
+
+    app.authenticate_and_verify().await?;
+    let (updated, verb) = match state {
+        ChangeState::Abandon => (
+            app.gerrit
+                .abandon_change(&change, message.as_deref())
+                .await?,
+            "Abandoned",
+        ),
+        ChangeState::Restore => (
+            app.gerrit
+                .restore_change(&change, message.as_deref())
+                .await?,
+            "Restored",
+        ),
+    };
+    println!(
+        "{verb} change {}: {}",
+        updated
+            .number
+            .map_or_else(|| change.clone(), |n| n.to_string()),
+        updated.subject.as_deref().unwrap_or("-")
+    );
+    Ok(())
+}
+
+/// `grt wip` (`wip` true) / `grt ready`: flip the work-in-progress state of
+/// an existing change without re-pushing it.
+async fn cmd_wip(work_dir: &Path, args: WipArgs, globals: &GlobalOpts, wip: bool) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change = change_or_head(&app, args.change.as_deref())?;
+    app.authenticate_and_verify().await?;
+    require_feature(&app, Feature::WorkInProgress).await?;
+
+    let message = args.message.as_deref();
+    if wip {
+        app.gerrit.set_work_in_progress(&change, message).await?;
+        println!("Marked change {change} work in progress");
+    } else {
+        app.gerrit.set_ready_for_review(&change, message).await?;
+        println!("Marked change {change} ready for review");
+    }
+    Ok(())
+}
+
+/// Resolve a change argument, defaulting to the Change-Id of HEAD.
+fn change_or_head(app: &App, change: Option<&str>) -> Result<String> {
+    match change {
+        // A URL may carry a patchset (`123,2`); only the change matters here
+        Some(arg) => Ok(review::parse_change_patchset(&review::normalize_change_arg(arg)).0),
+        None => {
+            let commit_msg = match app.require_git()?.head_commit_message() {
+                Err(e) if e.downcast_ref::<NoCommitsError>().is_some() => {
+                    anyhow::bail!("no change given and the repository has no commits yet")
+                }
+                other => other?,
+            };
+            push::extract_change_id(&commit_msg)
+                .context("no change given and HEAD has no Change-Id")
+        }
+    }
+}
+
+async fn cmd_status(work_dir: &Path, args: StatusArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change = change_or_head(&app, args.change.as_deref())?;
+    // Only a change taken from HEAD is compared with HEAD
+    let head = match args.change {
+        Some(_) => None,
+        None => subprocess::git_rev_parse_head(work_dir).ok(),
+    };
+
+    app.authenticate_and_verify().await?;
+    let info = app.gerrit.get_change_status(&change).await?;
+    let report = status::build_report(&info, head.as_deref());
+    match app.config.output_format(args.format) {
+        OutputFormat::Text => print!("{}", status::format_text(&report)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+    Ok(())
+}
+
+async fn cmd_open(work_dir: &Path, args: OpenArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let (change, patchset) = match args.change.as_deref() {
+        Some(arg) => review::parse_change_patchset(&review::normalize_change_arg(arg)),
+        None => (change_or_head(&app, None)?, None),
+    };
+
+    app.authenticate_and_verify().await?;
+    let info = app.gerrit.get_change(&change).await?;
+    let number = info
+        .number
+        .with_context(|| format!("Gerrit returned no number for change {change}"))?;
+    let project = info
+        .project
+        .with_context(|| format!("Gerrit returned no project for change {change}"))?;
+    let base_url = app.config.gerrit_base_url()?.to_string();
+    let url = open::change_web_url(&base_url, &project, number, patchset);
+
+    if args.print {
+        println!("{url}");
+    } else {
+        open::open_in_browser(&url)?;
+        eprintln!("Opened {url}");
+    }
+    Ok(())
+}
+
+async fn cmd_deps(work_dir: &Path, args: DepsArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change = change_or_head(&app, args.change.as_deref())?;
+
+    app.authenticate_and_verify().await?;
+    let info = app.gerrit.get_change(&change).await?;
+    let related = app.gerrit.get_related_changes(&change).await?;
+    let chain = deps::build_chain(&related, info.number);
+    match app.config.output_format(args.format) {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&chain)?),
+        OutputFormat::Text if chain.is_empty() => {
+            let number = info.number.map_or(change, |n| n.to_string());
+            println!("Change {number} has no related changes");
+        }
+        OutputFormat::Text => print!("{}", deps::format_graph(&chain)),
+    }
+    Ok(())
+}
+
+/// Timeline of each unpushed and recently merged commit's change, oldest first.
+///
+/// Commits are matched to changes by Change-Id on the target branch; commits
+/// without one are skipped and ones Gerrit does not know are listed as such.
+async fn cmd_log(work_dir: &Path, args: LogArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let root = app.require_git()?.root()?;
+    let remote = app.config.remote.clone();
+    let branch = target_branch(&app, args.branch, None, &remote)?;
+
+    let remote_ref = format!("refs/remotes/{remote}/{branch}");
+    if subprocess::git_output(&["show-ref", "--verify", "--quiet", &remote_ref], &root).is_err() {
+        anyhow::bail!("{remote}/{branch} is not known locally; run `git remote update {remote}`");
+    }
+    let unpushed = subprocess::list_unpushed_messages(&remote, &branch, &root)?;
+    let merged = if args.merged == 0 {
+        Vec::new()
+    } else {
+        subprocess::list_merged_messages(&remote, &branch, args.merged, &root).unwrap_or_else(|e| {
+            debug!("no merged commits shared with {remote}/{branch}: {e:#}");
+            Vec::new()
+        })
+    };
+    let commits: Vec<(String, history::CommitState)> = unpushed
+        .into_iter()
+        .map(|msg| (msg, history::CommitState::Unpushed))
+        .chain(
+            merged
+                .into_iter()
+                .map(|msg| (msg, history::CommitState::Merged)),
+        )
+        .rev()
+        .collect();
+
+    app.authenticate_and_verify().await?;
+    let mut histories = Vec::new();
+    let mut without_id = 0;
+    for (msg, state) in &commits {
+        let Some(change_id) = push::extract_change_id(msg) else {
+            without_id += 1;
+            continue;
+        };
+        let subject = msg.lines().next().unwrap_or("");
+        // The same Change-Id may exist on other branches
+        let id = if app.config.project.is_empty() {
+            change_id.clone()
+        } else {
+            format!("{}~{branch}~{change_id}", app.config.project)
+        };
+        let info = match app.gerrit.get_change_detail(&id).await {
+            Ok(info) => Some(info),
+            Err(e) if matches!(e.downcast_ref::<GerritError>(), Some(GerritError::NotFound)) => {
+                None
+            }
+            Err(e) => return Err(e.context(format!("looking up change {change_id}"))),
+        };
+        histories.push(history::build_history(
+            &change_id,
+            subject,
+            *state,
+            info.as_ref(),
+        ));
+    }
+    if without_id > 0 {
+        eprintln!("Skipped {without_id} commit(s) without a Change-Id");
+    }
+
+    match app.config.output_format(args.format) {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&histories)?),
+        OutputFormat::Text if histories.is_empty() => {
+            println!("No commits with a Change-Id on this branch relative to {remote}/{branch}");
+        }
+        OutputFormat::Text => print!("{}", history::format_text(&histories)),
+    }
+    Ok(())
+}
+
+async fn cmd_diff(work_dir: &Path, args: DiffArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let (change_id, patchset) =
+        review::parse_change_patchset(&review::normalize_change_arg(&args.change));
+
+    let root = app.require_git()?.root()?;
+    let remote = app.config.remote.clone();
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
+    if review_query::is_http_remote(&remote_url) {
+        app.authenticate_and_verify().await?;
+    }
+
+    let change =
+        review_query::get_change_all_revisions(&remote_url, &change_id, &app.gerrit, &root).await?;
+    let (_, revision) = review::find_target_revision(&change, patchset)?;
+    let ps_num = revision.number.context("revision has no patchset number")?;
+    let git_ref = revision.git_ref.as_deref().context("revision has no ref")?;
+    let sha = subprocess::git_fetch_ref_sha(&remote, git_ref, &root)?;
+
+    let base = if args.against_target {
+        let target = change.branch.as_deref().unwrap_or("master");
+        let target_sha =
+            subprocess::git_fetch_ref_sha(&remote, &format!("refs/heads/{target}"), &root)?;
+        eprintln!("Diffing change {change_id} patchset {ps_num} against {remote}/{target}...");
+        subprocess::git_merge_base(&target_sha, &sha, &root)?
+    } else {
+        eprintln!("Diffing change {change_id} patchset {ps_num} against its parent...");
+        format!("{sha}^")
+    };
+
+    let pager = diff::diff_pager(
+        std::env::var("GIT_PAGER").ok().as_deref(),
+        subprocess::git_config_get("core.pager", &root)?.as_deref(),
+        diff::on_path("delta"),
+    );
+    let diff_args = diff::diff_args(pager.as_deref(), globals.color(&app), &base, &sha);
+    let diff_args: Vec<&str> = diff_args.iter().map(String::as_str).collect();
+    subprocess::git_exec(&diff_args, &root)
+}
+
+async fn cmd_fix(work_dir: &Path, args: FixArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change_id = change_or_head(&app, args.change.as_deref())?;
+    let root = app.require_git()?.root()?;
+
+    app.authenticate_and_verify().await?;
+    let change = app.gerrit.get_change_detail(&change_id).await?;
+    let current = change
+        .current_revision
+        .clone()
+        .context("change has no current revision")?;
+    let patchset = change
+        .revisions
+        .as_ref()
+        .and_then(|revs| revs.get(&current))
+        .and_then(|rev| rev.number);
+
+    let mut comments = app
+        .gerrit
+        .get_revision_comments(&change_id, &current)
+        .await?;
+    if server_capabilities(&app)
+        .await
+        .is_none_or(|caps| caps.supports(Feature::RobotComments))
+    {
+        let robot = app
+            .gerrit
+            .get_robot_comments(&change_id)
+            .await
+            .unwrap_or_default();
+        for (file, items) in robot {
+            comments.entry(file).or_default().extend(items);
+        }
+    }
+
+    let suggestions = fix::collect_suggestions(&comments, patchset);
+    if suggestions.is_empty() {
+        eprintln!("No suggested fixes on the current patchset.");
+        return Ok(());
+    }
+    print!("{}", fix::format_suggestions(&suggestions));
+    if args.list {
+        return Ok(());
+    }
+
+    if !args.force {
+        let head = subprocess::git_rev_parse_head(&root)?;
+        if head != current {
+            anyhow::bail!(
+                "HEAD ({}) is not the current patchset of {change_id} ({}); run `grt checkout {change_id}` first or pass --force",
+                &head[..head.len().min(12)],
+                &current[..current.len().min(12)]
+            );
+        }
+        if !subprocess::check_worktree_clean(&root)? {
+            anyhow::bail!(
+                "working tree has uncommitted changes; commit or stash them, or pass --force"
+            );
+        }
+    }
+
+    let selected = if args.all {
+        (0..suggestions.len()).collect()
+    } else {
+        prompt_fix_selection(suggestions.len())?
+    };
+    if selected.is_empty() {
+        eprintln!("No fixes selected.");
+        return Ok(());
+    }
+    let chosen: Vec<&fix::Suggestion> = selected.iter().map(|&i| &suggestions[i]).collect();
+    let files = fix::apply_suggestions(&root, &chosen)?;
+    eprintln!("Applied {} fix(es) to {}.", chosen.len(), files.join(", "));
+
+    if args.amend {
+        let mut add = vec!["add", "--"];
+        add.extend(files.iter().map(String::as_str));
+        subprocess::git_exec(&add, &root)?;
+        subprocess::git_exec(&["commit", "--amend", "--no-edit"], &root)?;
+    }
+    Ok(())
+}
+
+/// Ask which suggested fixes to apply; needs a terminal (use `--all` otherwise).
+fn prompt_fix_selection(count: usize) -> Result<Vec<usize>> {
+    use std::io::IsTerminal as _;
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "not a terminal; pass --all to apply every suggestion or --list to review them"
+        );
+    }
+    eprint!("Apply which fixes? [all, none, or e.g. 1,3-4]: ");
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .context("reading selection from stdin")?;
+    fix::parse_selection(&line, count)
+}
+
+/// `grt backport`: for each `--to` branch, cherry-pick the change with `-x`
+/// onto a fresh `backport/<change>/<target>` branch and push it to
+/// `refs/for/<target>`.
+async fn cmd_backport(work_dir: &Path, args: BackportArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let status_style = Style::stderr(globals.color(&app));
+    let root = app.require_git()?.root()?;
+    let remote = app.config.remote.clone();
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
+    if review_query::is_http_remote(&remote_url) {
+        app.authenticate_and_verify().await?;
+    }
+
+    let (change_arg, patchset) =
+        review::parse_change_patchset(&review::normalize_change_arg(&args.change));
+    let change_arg = review::resolve_change_id(&app, &remote_url, &root, change_arg).await?;
+    let change =
+        review_query::get_change_all_revisions(&remote_url, &change_arg, &app.gerrit, &root)
+            .await?;
+    let number = change.number.context("change has no number")?;
+    let targets = backport::backport_targets(&args.to, change.branch.as_deref())?;
+    if change.status.as_deref() != Some("MERGED") {
+        eprintln!(
+            "{} change {number} is not merged yet",
+            status_style.warning()
+        );
+    }
+    let (_, revision) = review::find_target_revision(&change, patchset)?;
+    let git_ref = revision.git_ref.as_deref().context("revision has no ref")?;
+
+    if !subprocess::check_worktree_clean(&root)? {
+        anyhow::bail!("Cannot switch branches: working tree has uncommitted changes.");
+    }
+    let commit = subprocess::git_fetch_ref_sha(&remote, git_ref, &root)?;
+    let orig_ref = subprocess::git_head_restore_ref(&root)?;
+
+    // Keep going after a failure so one target does not strand the rest
+    let mut backports = Vec::with_capacity(targets.len());
+    let mut failed = Vec::new();
+    for target in targets {
+        let branch = backport::backport_branch_name(number, target);
+        eprintln!("Backporting change {number} to {target} on {branch}...");
+        let result = match backport::cherry_pick_onto(
+            &remote,
+            target,
+            &branch,
+            &commit,
+            args.new_change_id,
+            &root,
+        ) {
+            Ok(backport) if args.no_push => Ok(backport),
+            Ok(backport) => cmd_push(&root, backport_push_args(&args, target), globals)
+                .await
+                .map(|_| backport),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(backport) => backports.push(backport),
+            Err(e) => {
+                eprintln!("{} {target}: {e:#}", status_style.warning());
+                failed.push(target);
+            }
+        }
+    }
+    subprocess::git_checkout(&orig_ref, &root)?;
+
+    if !backports.is_empty() {
+        eprintln!("\nBackports:\n\n{}", backport::format_backports(&backports));
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("backport failed for: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Push options for one backport branch, checked out on its target's tracking branch.
+fn backport_push_args(args: &BackportArgs, target: &str) -> PushArgs {
+    PushArgs {
+        branch: Some(target.to_string()),
+        topic: args.topic.clone(),
+        reviewers: args.reviewers.clone(),
+        dry_run: args.dry_run,
+        yes: true,
+        ..Default::default()
+    }
+}
+
+/// `grt cherrypick-remote`: have Gerrit cherry-pick a change onto another
+/// branch, leaving the local repository untouched, and print the new change's URL.
+async fn cmd_cherrypick_remote(
+    work_dir: &Path,
+    args: CherrypickRemoteArgs,
+    globals: &GlobalOpts,
+) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let (change, patchset) =
+        review::parse_change_patchset(&review::normalize_change_arg(&args.change));
+    // Gerrit accepts a patchset number as a revision id
+    let revision = patchset.map_or_else(|| "current".to_string(), |ps| ps.to_string());
+
+    app.authenticate_and_verify().await?;
+    let input = CherryPickInput {
+        destination: args.branch.clone(),
+        message: args.message,
+        topic: args.topic,
+        keep_reviewers: args.keep_reviewers,
+        allow_conflicts: args.allow_conflicts,
+    };
+    let picked = app
+        .gerrit
+        .cherry_pick_revision(&change, &revision, &input)
+        .await?;
+    let number = picked
+        .number
+        .context("Gerrit returned no number for the cherry-picked change")?;
+    let project = picked
+        .project
+        .clone()
+        .unwrap_or_else(|| app.config.project.clone());
+    let base_url = app.config.gerrit_base_url()?.to_string();
+    let url = open::change_web_url(&base_url, &project, number, None);
+
+    match app.config.output_format(args.format) {
+        OutputFormat::Text => {
+            eprintln!("Cherry-picked change {change} onto {}", args.branch);
+            println!("{url}");
+        }
+        OutputFormat::Json => {
+            let value = serde_json::json!({
+                "branch": args.branch,
+                "change_number": number,
+                "change_id": picked.change_id,
+                "change_url": url,
+            });
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+    }
+    Ok(())
+}
+
+fn cmd_rebase_chain(work_dir: &Path, args: RebaseChainArgs, globals: &GlobalOpts) -> Result<()> {
+    let app = App::new(work_dir, &globals.cli_overrides())?;
+    let root = app.require_git()?.root()?;
+    let remote = args.remote.unwrap_or_else(|| app.config.remote.clone());
+    let branch = target_branch(&app, args.branch, None, &remote)?;
+
+    if args.interactive {
+        subprocess::git_remote_update(&remote, &root)?;
+        let onto = format!("{remote}/{branch}");
+        let series = rebase::list_series(&onto, &root)?;
+        if series.is_empty() {
+            eprintln!("No local commits on top of {onto}.");
+            return Ok(());
+        }
+        eprintln!("Rebasing {} commit(s) onto {onto}...", series.len());
+        return subprocess::git_exec(&["rebase", "-i", &onto], &root);
+    }
+
+    let report = rebase::rebase_series(&remote, &branch, args.keep_rebase, &root)?;
+    match app.config.output_format(args.format) {
+        OutputFormat::Text => print!("{}", rebase::format_chain_report(&report)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+    if let Some(conflict) = &report.conflict {
+        let Some(commit) = conflict.index.map(|i| &report.commits[i]) else {
+            anyhow::bail!("rebase onto {} stopped at an unknown commit", report.onto);
+        };
+        anyhow::bail!(
+            "{} conflicts with {}",
+            &commit.sha[..commit.sha.len().min(7)],
+            report.onto
+        );
+    }
+    Ok(())
+}
+
+async fn cmd_query(work_dir: &Path, args: QueryArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let format = args
+        .format
+        .unwrap_or_else(|| app.config.output_format(None).into());
+    let limit = list::resolve_list_limit(args.limit, args.no_limit);
+
+    app.authenticate_and_verify().await?;
+    let changes = app
+        .gerrit
+        .query_changes_paginated(&args.query, limit, |_| {})
+        .await?;
+    let columns = if args.columns.is_empty() {
+        query::DEFAULT_COLUMNS
+    } else {
+        &args.columns
+    };
+    match format {
+        QueryFormat::Json => println!("{}", serde_json::to_string_pretty(&changes)?),
+        QueryFormat::Tsv => print!("{}", query::format_tsv(&changes, columns)),
+        QueryFormat::Text => {
+            let style = Style::stdout(globals.color(&app));
+            print!("{}", query::format_text(&changes, columns, style));
+        }
+    }
+    Ok(())
+}
+
+/// Split `grt topic` positionals into the change (if given) and the topic
+/// (`None` with `--clear`).
+fn split_topic_args(args: &[String], clear: bool) -> Result<(Option<String>, Option<String>)> {
+    match (args, clear) {
+        ([], true) => Ok((None, None)),
+        ([change], true) => Ok((Some(change.clone()), None)),
+        ([topic], false) => Ok((None, Some(topic.clone()))),
+        ([change, topic], false) => Ok((Some(change.clone()), Some(topic.clone()))),
+        ([], false) => anyhow::bail!("missing argument: give a TOPIC, or --clear to remove it"),
+        (_, true) => anyhow::bail!("unexpected argument: --clear takes only an optional CHANGE"),
+        (_, false) => anyhow::bail!("too many arguments: expected [CHANGE] TOPIC"),
+    }
+}
+
+async fn cmd_topic(work_dir: &Path, args: TopicArgs, globals: &GlobalOpts) -> Result<()> {
+    let (change, topic) = split_topic_args(&args.args, args.clear)?;
+    if topic.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        anyhow::bail!("topic must not be empty; use --clear to remove it");
+    }
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change = change_or_head(&app, change.as_deref())?;
+
+    app.authenticate_and_verify().await?;
+    app.gerrit.set_topic(&change, topic.as_deref()).await?;
+    match topic {
+        Some(topic) => println!("Set topic of change {change} to '{topic}'"),
+        None => println!("Cleared topic of change {change}"),
+    }
+    Ok(())
+}
+
+/// Hashtags from `TAG...` arguments: comma-separated or repeated, with an
+/// optional leading `#`.
+fn parse_hashtags(args: &[String]) -> Result<Vec<String>> {
+    let tags: Vec<String> = args
+        .iter()
+        .flat_map(|arg| arg.split(','))
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if tags.is_empty() {
+        anyhow::bail!("missing argument: give at least one TAG");
+    }
+    if let Some(tag) = tags.iter().find(|t| t.chars().any(char::is_whitespace)) {
+        anyhow::bail!("hashtag '{tag}' must not contain whitespace");
+    }
+    Ok(tags)
+}
+
+async fn cmd_hashtags(work_dir: &Path, args: HashtagsArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let (args, adding) = match args.action {
+        HashtagsAction::List { change } => {
+            let change = change_or_head(&app, change.as_deref())?;
+            app.authenticate_and_verify().await?;
+            require_feature(&app, Feature::Hashtags).await?;
+            let hashtags = app.gerrit.get_hashtags(&change).await?;
+            print_hashtags(&change, &hashtags);
+            return Ok(());
+        }
+        HashtagsAction::Add { args } => (args, true),
+        HashtagsAction::Remove { args } => (args, false),
+    };
+
+    // Hashtags are rarely numeric, so the reviewer rules for telling a
+    // leading change apart from the values apply here too
+    let (change, tags) = reviewers::split_change_and_users(&args);
+    let tags = parse_hashtags(&tags)?;
+    let change = change_or_head(&app, change.as_deref())?;
+    app.authenticate_and_verify().await?;
+    require_feature(&app, Feature::Hashtags).await?;
+    let (add, remove) = if adding {
+        (tags, Vec::new())
+    } else {
+        (Vec::new(), tags)
+    };
+    let hashtags = app.gerrit.set_hashtags(&change, &add, &remove).await?;
+    print_hashtags(&change, &hashtags);
+    Ok(())
+}
+
+fn print_hashtags(change: &str, hashtags: &[String]) {
+    if hashtags.is_empty() {
+        println!("Change {change} has no hashtags");
+    } else {
+        println!("Hashtags of change {change}: {}", hashtags.join(", "));
+    }
+}
+
+async fn cmd_attention(work_dir: &Path, args: AttentionArgs, globals: &GlobalOpts) -> Result<()> {
+    if args.reason.is_some() && args.add.is_empty() && args.remove.is_empty() {
+        anyhow::bail!("--reason needs --add or --remove");
+    }
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    let change = change_or_head(&app, args.change.as_deref())?;
+    app.authenticate_and_verify().await?;
+    require_feature(&app, Feature::AttentionSet).await?;
+
+    let reason = args.reason.as_deref().unwrap_or(attention::DEFAULT_REASON);
+    for user in &args.add {
+        app.gerrit
+            .add_to_attention_set(&change, user, reason)
+            .await?;
+    }
+    for user in &args.remove {
+        app.gerrit
+            .remove_from_attention_set(&change, user, reason)
+            .await?;
+    }
+
+    let info = app.gerrit.get_change(&change).await?;
+    let entries = attention::attention_entries(&info);
+    match app.config.output_format(args.format) {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&entries)?),
+        OutputFormat::Text => {
+            let number = info.number.map_or(change, |n| n.to_string());
+            if entries.is_empty() {
+                println!("Change {number} has an empty attention set");
+            } else {
+                println!("Attention set of change {number}:");
+                print!("{}", attention::format_entries(&entries));
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_reviewers(work_dir: &Path, args: ReviewersArgs, globals: &GlobalOpts) -> Result<()> {
+    let mut app = App::new(work_dir, &globals.cli_overrides())?;
+    match args.action {
+        ReviewersAction::List { change } => {
+            let change = change_or_head(&app, change.as_deref())?;
+            app.authenticate_and_verify().await?;
+            let by_state = app.gerrit.get_reviewers_by_state(&change).await?;
+            print!("{}", reviewers::format_reviewers(&by_state));
+        }
+        ReviewersAction::Suggest { args, limit } => {
+            let (change, query) = reviewers::split_change_and_users(&args);
+            let query = query
+                .first()
+                .context("missing argument: give the name or email to look up")?;
+            let change = change_or_head(&app, change.as_deref())?;
+            app.authenticate_and_verify().await?;
+            let suggestions = app.gerrit.suggest_reviewers(&change, query, limit).await?;
+            if suggestions.is_empty() {
+                eprintln!("No reviewers match '{query}'.");
+            }
+            print!("{}", reviewers::format_suggestions(&suggestions));
+        }
+        ReviewersAction::Add { args, cc } => {
+            let (change, users) = reviewers::split_change_and_users(&args);
+            if users.is_empty() {
+                anyhow::bail!("missing argument: give at least one USER to add");
+            }
+            let change = change_or_head(&app, change.as_deref())?;
+            if let Some((remote_url, root)) = ssh_remote(&app)? {
+                if cc {
+                    anyhow::bail!(
+                        "--cc needs the REST API; `gerrit set-reviewers` over SSH only adds reviewers"
+                    );
+                }
+                review_query::add_reviewers(&remote_url, &change, &users, &app.gerrit, &root)
+                    .await?;
+                for user in &users {
+                    println!("Added {user} as reviewer on change {change}");
+                }
+                return Ok(());
+            }
+            app.authenticate_and_verify().await?;
+            let (state, role) = if cc {
+                (ReviewerState::Cc, "CC")
+            } else {
+                (ReviewerState::Reviewer, "reviewer")
+            };
+            let mut failed = 0;
+            for user in &users {
+                // Older Gerrit versions answer 400 instead of setting `error`
+                let error = match app.gerrit.post_reviewer(&change, user, state).await {
+                    Ok(result) => result.error,
+                    Err(e) => match e.downcast_ref::<GerritError>() {
+                        Some(GerritError::ServerError { status: 400, body }) => Some(body.clone()),
+                        _ => return Err(e),
+                    },
+                };
+                let Some(error) = error else {
+                    println!("Added {user} as {role} on change {change}");
+                    continue;
+                };
+                failed += 1;
+                eprintln!("error: could not add {user}: {}", error.trim());
+                if let Ok(suggestions) = app.gerrit.suggest_reviewers(&change, user, 5).await {
+                    if !suggestions.is_empty() {
+                        eprint!(
+                            "Did you mean:\n{}",
+                            reviewers::format_suggestions(&suggestions)
+                        );
+                    }
+                }
+            }
+            if failed > 0 {
+                anyhow::bail!("{failed} of {} reviewer(s) could not be added", users.len());
+            }
+        }
+        ReviewersAction::Remove { args } => {
+            let (change, users) = reviewers::split_change_and_users(&args);
+            if users.is_empty() {
+                anyhow::bail!("missing argument: give at least one USER to remove");
+            }
+            let change = change_or_head(&app, change.as_deref())?;
+            if let Some((remote_url, root)) = ssh_remote(&app)? {
+                review_query::remove_reviewers(&remote_url, &change, &users, &app.gerrit, &root)
+                    .await?;
+                for user in &users {
+                    println!("Removed {user} from change {change}");
+                }
+                return Ok(());
+            }
+            app.authenticate_and_verify().await?;
+            for user in &users {
+                match app.gerrit.remove_reviewer(&change, user).await {
+                    Ok(()) => println!("Removed {user} from change {change}"),
+                    Err(e)
+                        if matches!(
+                            e.downcast_ref::<GerritError>(),
+                            Some(GerritError::NotFound)
+                        ) =>
+                    {
+                        anyhow::bail!("{user} is not a reviewer or CC on change {change}")
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ask for an optional message on a terminal; empty input or no tty means none.
+fn prompt_optional_message() -> Result<Option<String>> {
+    use std::io::IsTerminal as _;
+
+    if !std::io::stdin().is_terminal() {
+        return Ok(None);
+    }
+    eprint!("Message (optional, press enter to skip): ");
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .context("reading message from stdin")?;
+    let message = line.trim();
+    Ok((!message.is_empty()).then(|| message.to_string()))
+}
+
+async fn cmd_checkout(work_dir: &Path, args: CheckoutArgs, globals: &GlobalOpts) -> Result<()> {
+    let cli_overrides = CliOverrides {
+        remote: args.remote.clone(),
+        ..globals.cli_overrides()
+    };
+    let mut app = App::new(work_dir, &cli_overrides)?;
+    let format = app.config.output_format(args.format);
+    review::cmd_checkout(&mut app, &args.change, &format, args.verify).await
+}
+
+async fn cmd_tui(work_dir: &Path, args: TuiArgs, globals: &GlobalOpts) -> Result<()> {
+    use std::io::IsTerminal;
+    if !std::io::stdout().is_terminal() || !std::io::stdin().is_terminal() {
+        anyhow::bail!("grt tui needs an interactive terminal; use `grt review -l` in scripts");
+    }
+
+    let cli_overrides = CliOverrides {
+        remote: args.remote.clone(),
+        ..globals.cli_overrides()
+    };
+    let mut app = App::new(work_dir, &cli_overrides)?;
+    app.authenticate_and_verify().await?;
+
+    let limit = args.limit.unwrap_or(list::DEFAULT_LIST_LIMIT);
+    let outcome = tui::run(&app, args.branch.as_deref(), limit).await?;
+    match outcome {
+        Some(tui::Outcome::Download(number)) => {
+            review::cmd_review_download(
+                &mut app,
+                &number.to_string(),
+                &OutputFormat::Text,
+                false,
+                None,
+                false,
+            )
+            .await
+        }
+        Some(tui::Outcome::CherryPick(number)) => {
+            review::cmd_review_cherrypick(&mut app, &number.to_string(), false, false, false).await
+        }
+        None => Ok(()),
+    }
+}
+
+/// Version and build metadata reported by `grt version --json`.
+///
+/// `commit` and `build_date` come from the build script and are `null` when
+/// the build had no git checkout; `gerrit` and `git` are `null` when the
+/// server or the git binary could not be queried.
+#[derive(Debug, serde::Serialize)]
+struct VersionInfo {
+    grt: &'static str,
+    commit: Option<&'static str>,
+    build_date: Option<&'static str>,
+    gerrit: Option<String>,
+    git: Option<String>,
+}
+
+impl VersionInfo {
+    fn new(gerrit: Option<String>, git: Option<String>) -> Self {
+        Self {
+            grt: env!("CARGO_PKG_VERSION"),
+            commit: option_env!("GRT_GIT_COMMIT"),
+            build_date: option_env!("GRT_BUILD_DATE"),
+            gerrit,
+            git,
+        }
+    }
+}
+
+/// Gerrit server version over whichever protocol the configured remote uses.
+async fn server_version(app: &App) -> Result<String> {
+    let root = app.require_git()?.root()?;
+    let url = configured_remote_url(app, &root)?;
+    review_query::get_version(&url, &app.gerrit, &root).await
+}
+
+fn configured_remote_url(app: &App, root: &Path) -> Result<String> {
+    Ok(
+        review_query::resolve_gerrit_url(&app.config.remote, root, &app.config)?
+            .unwrap_or_else(|| app.config.make_remote_url()),
+    )
+}
+
+/// The configured remote's URL and the repository root when that remote is
+/// SSH; `None` outside a repository or for an HTTP remote, meaning REST.
+fn ssh_remote(app: &App) -> Result<Option<(String, PathBuf)>> {
+    let Some(git) = &app.git else {
+        return Ok(None);
+    };
+    let root = git.root()?;
+    let url = configured_remote_url(app, &root)?;
+    Ok((!review_query::is_http_remote(&url)).then_some((url, root)))
+}
+
+/// Probe the server version and, over HTTP, `/config/server/info`.
+async fn probe_capabilities(app: &App) -> Result<Capabilities> {
+    let http = match &app.git {
+        Some(git) => review_query::is_http_remote(&configured_remote_url(app, &git.root()?)?),
+        None => true,
+    };
+    let (version, note_db) = if http {
+        let version = app.gerrit.get_version().await?;
+        let note_db = match app.gerrit.get_server_info().await {
+            Ok(info) => info.note_db_enabled,
+            Err(e) => {
+                debug!("could not read server info: {e:#}");
+                None
+            }
+        };
+        (version, note_db)
+    } else {
+        (server_version(app).await?, None)
+    };
+    Ok(Capabilities {
+        fetched_at: cache::now_secs(),
+        version: version.trim().to_string(),
+        note_db,
+    })
+}
+
+/// Capabilities of the configured host, from the per-host cache while fresh.
+///
+/// `None` when the server cannot be probed; nothing is gated then and Gerrit
+/// has the last word.
+async fn server_capabilities(app: &App) -> Option<Capabilities> {
+    let cache = cache::Cache::for_user();
+    let host = &app.config.host;
+    let cached = cache
+        .as_ref()
+        .and_then(|c| c.load_capabilities(host).ok().flatten())
+        .filter(|caps| caps.is_fresh(cache::now_secs()));
+    if cached.is_some() {
+        return cached;
+    }
+    match probe_capabilities(app).await {
+        Ok(caps) => {
+            if let Some(cache) = &cache {
+                if let Err(e) = cache.store_capabilities(host, &caps) {
+                    debug!("could not cache server capabilities: {e:#}");
+                }
+            }
+            Some(caps)
+        }
+        Err(e) => {
+            debug!("could not probe server capabilities: {e:#}");
+            None
+        }
+    }
+}
+
+/// Fail early when the server is known to lack `feature`.
+async fn require_feature(app: &App, feature: Feature) -> Result<()> {
+    match server_capabilities(app).await {
+        Some(caps) => caps.require(feature),
+        None => Ok(()),
+    }
+}
+
+async fn cmd_version(work_dir: &Path, args: &VersionArgs, globals: &GlobalOpts) -> Result<()> {
+    if args.json {
+        let gerrit = match App::new(work_dir, &globals.cli_overrides()) {
+            Ok(app) => server_version(&app).await.ok(),
+            Err(_) => None,
+        };
+        let git = subprocess::git_output(&["--version"], work_dir)
+            .ok()
+            .map(|v| v.trim_start_matches("git version ").to_string());
+        let info = VersionInfo::new(gerrit, git);
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    println!("grt {}", env!("CARGO_PKG_VERSION"));
+
+    // Try to get Gerrit version
+    match App::new(work_dir, &globals.cli_overrides()) {
+        Ok(app) => match server_version(&app).await {
+            Ok(version) => println!("Gerrit {version}"),
+            Err(_) => println!("Gerrit version: unavailable"),
+        },
+        Err(_) => println!("Gerrit version: unavailable (not in a configured repository)"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_cli() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn verify_git_review_cli() {
+        use clap::CommandFactory;
+        GitReviewCli::command().debug_assert();
+    }
+
+    // === Existing push/comments/setup/version tests ===
+
+    #[test]
+    fn parse_push_defaults() {
+        let cli = Cli::parse_from(["grt", "push"]);
+        assert!(matches!(cli.command, Commands::Push(_)));
+    }
+
+    #[test]
+    fn parse_push_all_flags() {
+        let cli = Cli::parse_from([
+            "grt", "push", "--topic", "foo", "--wip", "-r", "alice", "main",
+        ]);
+        if let Commands::Push(args) = cli.command {
+            assert_eq!(args.topic.as_deref(), Some("foo"));
+            assert!(args.wip);
+            assert_eq!(args.reviewers, vec!["alice"]);
+            assert_eq!(args.branch.as_deref(), Some("main"));
+        } else {
+            panic!("expected Push command");
+        }
+    }
+
+    #[test]
+    fn parse_comments_defaults() {
+        let cli = Cli::parse_from(["grt", "comments"]);
+        assert!(matches!(cli.command, Commands::Comments(_)));
+    }
+
+    #[test]
+    fn parse_comments_with_change() {
+        let cli = Cli::parse_from([
+            "grt",
+            "comments",
+            "12345",
+            "--unresolved",
+            "--format",
+            "json",
+        ]);
+        if let Commands::Comments(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345"));
+            assert!(args.unresolved);
+            assert!(matches!(args.format, Some(CommentsFormat::Json)));
+        } else {
+            panic!("expected Comments command");
+        }
+    }
+
+    #[test]
+    fn parse_comments_editor_formats() {
+        for (value, expected) in [
+            ("quickfix", CommentsFormat::Quickfix),
+            ("rdjson", CommentsFormat::Rdjson),
+            ("prompt", CommentsFormat::Prompt),
+        ] {
+            let cli = Cli::parse_from(["grt", "comments", "--format", value]);
+            if let Commands::Comments(args) = cli.command {
+                assert_eq!(args.format, Some(expected));
+            } else {
+                panic!("expected Comments command");
+            }
+        }
+    }
+
+    #[test]
+    fn parse_comments_scope_filters() {
+        let cli = Cli::parse_from([
+            "grt", "comments", "12345", "--file", "nova/**", "--since", "3", "--author", "alice",
+        ]);
+        if let Commands::Comments(args) = cli.command {
+            assert_eq!(args.file.as_deref(), Some("nova/**"));
+            assert_eq!(args.since.as_deref(), Some("3"));
+            assert_eq!(args.author.as_deref(), Some("alice"));
+        } else {
+            panic!("expected Comments command");
+        }
+    }
+
+    #[test]
+    fn parse_comments_cached_conflicts_with_refresh() {
+        let cli = Cli::parse_from(["grt", "comments", "12345", "--cached"]);
+        if let Commands::Comments(args) = cli.command {
+            assert!(args.cached);
+            assert!(!args.refresh);
+        } else {
+            panic!("expected Comments command");
+        }
+        assert!(Cli::try_parse_from(["grt", "comments", "--cached", "--refresh"]).is_err());
+    }
+
+    #[test]
+    fn parse_comments_reply() {
+        let cli = Cli::parse_from([
+            "grt",
+            "comments",
+            "reply",
+            "--file",
+            "src/foo.rs",
+            "--line",
+            "42",
+            "-m",
+            "fixed",
+            "--resolved",
+        ]);
+        if let Commands::Comments(CommentsArgs {
+            action: Some(CommentsAction::Reply(reply)),
+            ..
+        }) = cli.command
+        {
+            assert!(reply.change.is_none());
+            assert_eq!(reply.file.as_deref(), Some("src/foo.rs"));
+            assert_eq!(reply.line, Some(42));
+            assert_eq!(reply.message, "fixed");
+            assert!(reply.resolved);
+            assert_eq!(reply.revision, "current");
+        } else {
+            panic!("expected comments reply command");
+        }
+    }
+
+    #[test]
+    fn parse_comments_publish_and_plain_change() {
+        let cli = Cli::parse_from(["grt", "comments", "publish", "12345", "-m", "Done"]);
+        if let Commands::Comments(CommentsArgs {
+            action: Some(CommentsAction::Publish(publish)),
+            ..
+        }) = cli.command
+        {
+            assert_eq!(publish.change.as_deref(), Some("12345"));
+            assert_eq!(publish.message.as_deref(), Some("Done"));
+        } else {
+            panic!("expected comments publish command");
+        }
+
+        // A change number is still read as the change to show
+        let cli = Cli::parse_from(["grt", "comments", "12345"]);
+        if let Commands::Comments(args) = cli.command {
+            assert!(args.action.is_none());
+            assert_eq!(args.change.as_deref(), Some("12345"));
+        } else {
+            panic!("expected Comments command");
+        }
+    }
+
+    #[test]
+    fn parse_comments_remote_and_branch() {
+        let cli = Cli::parse_from([
+            "grt", "comments", "123", "--remote", "gerrit", "--branch", "main",
+        ]);
+        if let Commands::Comments(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("123"));
+            assert_eq!(args.remote.as_deref(), Some("gerrit"));
+            assert_eq!(args.branch.as_deref(), Some("main"));
+        } else {
+            panic!("expected Comments command");
+        }
+    }
+
+    #[test]
+    fn comments_remote_reaches_cli_overrides() {
+        let cli = Cli::parse_from(["grt", "comments", "123", "--remote", "upstream"]);
+        if let Commands::Comments(args) = cli.command {
+            let overrides = comments_cli_overrides(&args, &GlobalOpts::default());
+            assert_eq!(overrides.remote.as_deref(), Some("upstream"));
+            assert!(overrides.branch.is_none());
+        } else {
+            panic!("expected Comments command");
+        }
+    }
+
+    #[test]
+    fn parse_comments_resolved_flag() {
+        let cli = Cli::parse_from(["grt", "comments", "12345", "--resolved"]);
+        if let Commands::Comments(args) = cli.command {
+            assert!(args.resolved);
+            assert!(!args.unresolved);
+        } else {
+            panic!("expected Comments command");
+        }
+    }
+
+    #[test]
+    fn resolved_and_unresolved_are_mutually_exclusive() {
+        let result =
+            Cli::try_parse_from(["grt", "comments", "12345", "--resolved", "--unresolved"]);
+        assert!(
+            result.is_err(),
+            "--resolved and --unresolved should conflict"
+        );
+    }
+
+    #[test]
+    fn parse_setup_defaults() {
+        let cli = Cli::parse_from(["grt", "setup"]);
+        assert!(matches!(cli.command, Commands::Setup(_)));
+    }
+
+    #[test]
+    fn parse_version() {
+        let cli = Cli::parse_from(["grt", "version"]);
+        assert!(matches!(cli.command, Commands::Version(_)));
+    }
+
+    #[test]
+    fn parse_version_json() {
+        let cli = Cli::parse_from(["grt", "version", "--json"]);
+        if let Commands::Version(args) = cli.command {
+            assert!(args.json);
+        } else {
+            panic!("expected Version command");
+        }
+    }
+
+    #[test]
+    fn version_json_has_expected_keys() {
+        let info = VersionInfo::new(None, Some("2.43.0".to_string()));
+        let value = serde_json::to_value(&info).unwrap();
+        let obj = value.as_object().unwrap();
+        for key in ["grt", "commit", "build_date", "gerrit", "git"] {
+            assert!(obj.contains_key(key), "missing key {key}: {value}");
+        }
+        assert_eq!(obj["grt"], env!("CARGO_PKG_VERSION"));
+        assert!(obj["gerrit"].is_null());
+        assert_eq!(obj["git"], "2.43.0");
+    }
+
+    #[test]
+    fn parse_global_verbose() {
+        let cli = Cli::parse_from(["grt", "-vvv", "version"]);
+        assert_eq!(cli.verbose, 3);
+    }
+
+    #[test]
+    fn parse_global_directory() {
+        let cli = Cli::parse_from(["grt", "-C", "/tmp", "version"]);
+        assert_eq!(cli.directory, vec![PathBuf::from("/tmp")]);
+    }
+
+    #[test]
+    fn parse_global_directory_repeated() {
+        let cli = Cli::parse_from(["grt", "-C", "a", "-C", "b", "version"]);
+        assert_eq!(cli.directory, vec![PathBuf::from("a"), PathBuf::from("b")]);
+    }
+
+    #[test]
+    fn resolve_work_dir_single() {
+        let dir = resolve_work_dir(PathBuf::from("/home/u"), &[PathBuf::from("repo")]);
+        assert_eq!(dir, PathBuf::from("/home/u/repo"));
+    }
+
+    #[test]
+    fn resolve_work_dir_cumulative() {
+        let dirs = [PathBuf::from("src"), PathBuf::from("repo")];
+        assert_eq!(
+            resolve_work_dir(PathBuf::from("/home/u"), &dirs),
+            PathBuf::from("/home/u/src/repo")
+        );
+    }
+
+    #[test]
+    fn resolve_work_dir_absolute_resets_base() {
+        let dirs = [
+            PathBuf::from("src"),
+            PathBuf::from("/srv/git"),
+            PathBuf::from("repo"),
+        ];
+        assert_eq!(
+            resolve_work_dir(PathBuf::from("/home/u"), &dirs),
+            PathBuf::from("/srv/git/repo")
+        );
+    }
+
+    #[test]
+    fn resolve_work_dir_ignores_empty() {
+        let dirs = [PathBuf::from(""), PathBuf::from("repo")];
+        assert_eq!(
+            resolve_work_dir(PathBuf::from("/home/u"), &dirs),
+            PathBuf::from("/home/u/repo")
+        );
+    }
+
+    // === New: review subcommand tests ===
+
+    #[test]
+    fn parse_review_subcommand() {
+        let cli = Cli::parse_from(["grt", "review", "main"]);
+        if let Commands::Review(args) = cli.command {
+            assert_eq!(args.branch.as_deref(), Some("main"));
+        } else {
+            panic!("expected Review command");
+        }
+    }
+
+    #[test]
+    fn parse_review_with_download() {
+        let cli = Cli::parse_from(["grt", "review", "-d", "12345"]);
+        if let Commands::Review(args) = cli.command {
+            assert_eq!(args.download.as_deref(), Some("12345"));
+        } else {
+            panic!("expected Review command");
+        }
+    }
+
+    #[test]
+    fn parse_review_with_setup() {
+        let cli = Cli::parse_from(["grt", "review", "-s"]);
+        if let Commands::Review(args) = cli.command {
+            assert!(args.setup);
+        } else {
+            panic!("expected Review command");
+        }
+    }
+
+    #[test]
+    fn parse_review_push_mode_flags() {
+        let cli = Cli::parse_from([
+            "grt", "review", "-w", "-t", "my-topic", "-r", "origin", "main",
+        ]);
+        if let Commands::Review(args) = cli.command {
+            assert!(args.wip);
+            assert_eq!(args.topic.as_deref(), Some("my-topic"));
+            assert_eq!(args.remote.as_deref(), Some("origin"));
+            assert_eq!(args.branch.as_deref(), Some("main"));
+        } else {
+            panic!("expected Review command");
+        }
+    }
+
+    #[test]
+    fn parse_review_no_args() {
+        let cli = Cli::parse_from(["grt", "review"]);
+        assert!(matches!(cli.command, Commands::Review(_)));
+    }
+
+    // === New: export subcommand tests ===
+
+    #[test]
+    fn parse_export_git_review() {
+        let cli = Cli::parse_from(["grt", "export", "git-review"]);
+        if let Commands::Export(args) = cli.command {
+            assert!(matches!(
+                args.target,
+                export::ExportTarget::GitReview { clean: false }
+            ));
+        } else {
+            panic!("expected Export command");
+        }
+    }
+
+    #[test]
+    fn parse_export_git_review_clean() {
+        let cli = Cli::parse_from(["grt", "export", "git-review", "--clean"]);
+        if let Commands::Export(args) = cli.command {
+            assert!(matches!(
+                args.target,
+                export::ExportTarget::GitReview { clean: true }
+            ));
+        } else {
+            panic!("expected Export command");
+        }
+    }
+
+    // === New: argv[0] personality detection tests ===
+
+    #[test]
+    fn detect_personality_grt_bare() {
+        assert_eq!(detect_personality("grt"), Personality::Grt);
+    }
+
+    #[test]
+    fn detect_personality_grt_absolute() {
+        assert_eq!(detect_personality("/usr/bin/grt"), Personality::Grt);
+    }
+
+    #[test]
+    fn detect_personality_git_review_bare() {
+        assert_eq!(detect_personality("git-review"), Personality::GitReview);
+    }
+
+    #[test]
+    fn detect_personality_git_review_absolute() {
+        assert_eq!(
+            detect_personality("/usr/local/bin/git-review"),
+            Personality::GitReview
+        );
+    }
+
+    #[test]
+    fn detect_personality_git_review_home_local() {
+        assert_eq!(
+            detect_personality("/home/user/.local/bin/git-review"),
+            Personality::GitReview
+        );
+    }
+
+    #[test]
+    fn detect_personality_unknown_defaults_to_grt() {
+        assert_eq!(detect_personality("something-else"), Personality::Grt);
+    }
+
+    // === New: git-review mode parsing tests ===
+
+    #[test]
+    fn git_review_parse_branch() {
+        let cli = GitReviewCli::parse_from(["git-review", "main"]);
+        assert_eq!(cli.review.branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn git_review_parse_download() {
+        let cli = GitReviewCli::parse_from(["git-review", "-d", "12345"]);
+        assert_eq!(cli.review.download.as_deref(), Some("12345"));
+    }
+
+    #[test]
+    fn git_review_parse_verbose() {
+        let cli = GitReviewCli::parse_from(["git-review", "-v", "-d", "12345"]);
+        assert_eq!(cli.verbose, 1);
+        assert_eq!(cli.review.download.as_deref(), Some("12345"));
+    }
+
+    #[test]
+    fn git_review_parse_no_color() {
+        let cli = GitReviewCli::parse_from(["git-review", "--no-color", "main"]);
+        assert!(cli.no_color);
+        assert_eq!(cli.review.branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn git_review_parse_list() {
+        let cli = GitReviewCli::parse_from(["git-review", "-l"]);
+        assert_eq!(cli.review.list, 1);
+    }
+
+    #[test]
+    fn git_review_parse_setup() {
+        let cli = GitReviewCli::parse_from(["git-review", "-s"]);
+        assert!(cli.review.setup);
+    }
+
+    // === Completions subcommand ===
+
+    #[test]
+    fn parse_completions_bash() {
+        let cli = Cli::parse_from(["grt", "completions", "bash"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Completions {
+                shell: clap_complete::Shell::Bash
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_completions_zsh() {
+        let cli = Cli::parse_from(["grt", "completions", "zsh"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Completions {
+                shell: clap_complete::Shell::Zsh
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_completions_fish() {
+        let cli = Cli::parse_from(["grt", "completions", "fish"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Completions {
+                shell: clap_complete::Shell::Fish
+            }
+        ));
+    }
+
+    // === Exit code mapping ===
+
+    #[test]
+    fn exit_code_network_error() {
+        let err: anyhow::Error = GerritError::Network("connection refused".into()).into();
+        assert_eq!(exit_code_for_error(&err), ExitReason::Network);
+    }
+
+    #[test]
+    fn exit_code_auth_error() {
+        let err: anyhow::Error = GerritError::AuthFailed { status: 401 }.into();
+        assert_eq!(exit_code_for_error(&err), ExitReason::Generic);
+    }
+
+    #[test]
+    fn exit_code_generic() {
+        let err = anyhow::anyhow!("something went wrong");
+        assert_eq!(exit_code_for_error(&err), ExitReason::Generic);
+    }
+
+    #[test]
+    fn exit_code_config_error() {
+        let err = anyhow::anyhow!("no Gerrit host configured");
+        assert_eq!(exit_code_for_error(&err), ExitReason::GitConfig);
+    }
+
+    #[test]
+    fn exit_code_malformed_input() {
+        let err = anyhow::anyhow!("compare argument must be CHANGE,PS[-PS]");
+        assert_eq!(exit_code_for_error(&err), ExitReason::MalformedInput);
+    }
+
+    #[test]
+    fn exit_code_hook_error() {
+        let err = anyhow::anyhow!("commit-msg hook not found");
+        assert_eq!(exit_code_for_error(&err), ExitReason::Hook);
+    }
+
+    #[test]
+    fn exit_code_variants_map_to_documented_integers() {
+        let codes: Vec<i32> = ExitReason::ALL.iter().map(|c| c.code()).collect();
+        assert_eq!(codes, vec![0, 1, 2, 3, 40, 128]);
+    }
+
+    #[test]
+    fn exit_code_descriptions() {
+        assert_eq!(ExitReason::Success.describe(), "Success");
+        assert_eq!(
+            ExitReason::Generic.describe(),
+            "Generic error (auth failed, not found, server error)"
+        );
+        assert_eq!(ExitReason::Hook.describe(), "Hook-related error");
+        assert_eq!(
+            ExitReason::MalformedInput.describe(),
+            "Malformed input (bad argument format, e.g. invalid CHANGE,PS)"
+        );
+        assert_eq!(ExitReason::Network.describe(), "Network/connectivity error");
+        assert_eq!(
+            ExitReason::GitConfig.describe(),
+            "Git config error (no Gerrit host configured)"
+        );
+    }
+
+    #[test]
+    fn exit_code_from_code_roundtrip() {
+        for exit in ExitReason::ALL {
+            assert_eq!(ExitReason::from_code(exit.code()), Some(exit));
+        }
+        assert_eq!(ExitReason::from_code(42), None);
+    }
+
+    #[test]
+    fn parse_explain_exit_without_subcommand() {
+        match parse_invocation(["grt", "--explain-exit", "40"]).unwrap() {
+            Invocation::ExplainExit(code) => assert_eq!(code, "40"),
+            other => panic!("expected ExplainExit, got {other:?}"),
+        }
+        match parse_invocation(["grt", "-v", "push"]).unwrap() {
+            Invocation::Run(cli) => {
+                assert!(matches!(cli.command, Commands::Push(_)));
+                assert_eq!(cli.verbose, 1);
+            }
+            other => panic!("expected Run, got {other:?}"),
+        }
+        assert_eq!(
+            parse_invocation(["grt", "-v"]).unwrap_err().kind(),
+            clap::error::ErrorKind::MissingSubcommand
+        );
+        assert_eq!(
+            parse_invocation(["grt"]).unwrap_err().kind(),
+            clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+        );
+    }
+
+    #[test]
+    fn explain_exit_refused_with_subcommand() {
+        // Must not silently skip the push
+        assert!(parse_invocation(["grt", "push", "--explain-exit=128"]).is_err());
+        assert!(parse_invocation(["grt", "--explain-exit", "2", "push"]).is_err());
+    }
+
+    #[test]
+    fn explain_exit_known_and_unknown_codes() {
+        assert_eq!(
+            explain_exit("40").unwrap(),
+            "40: Network/connectivity error"
+        );
+        assert_eq!(
+            explain_exit("7").unwrap(),
+            "7: not an exit code used by grt"
+        );
+        assert!(explain_exit("abc").is_err());
+    }
+
+    // === Task B2: --finish guard on dry_run ===
+
+    #[test]
+    fn parse_review_finish_flag() {
+        let cli = Cli::parse_from(["grt", "review", "-f"]);
+        if let Commands::Review(args) = cli.command {
+            assert!(args.finish);
+            assert!(!args.dry_run);
+        } else {
+            panic!("expected Review command");
+        }
+    }
+
+    #[test]
+    fn parse_review_finish_with_dry_run() {
+        let cli = Cli::parse_from(["grt", "review", "-f", "-n"]);
+        if let Commands::Review(args) = cli.command {
+            assert!(args.finish);
+            assert!(args.dry_run);
+        } else {
+            panic!("expected Review command");
+        }
+    }
+
+    // === Task H2: force_rebase flag parsing ===
+
+    #[test]
+    fn parse_review_force_rebase() {
+        let cli = Cli::parse_from(["grt", "review", "-F"]);
+        if let Commands::Review(args) = cli.command {
+            assert!(args.force_rebase);
+        } else {
+            panic!("expected Review command");
+        }
+    }
+
+    // === Task H3: default topic to branch name ===
+
+    #[test]
+    fn parse_review_no_topic_flag() {
+        let cli = Cli::parse_from(["grt", "review", "-T"]);
+        if let Commands::Review(args) = cli.command {
+            assert!(args.no_topic);
+        } else {
+            panic!("expected Review command");
+        }
+    }
+
+    // === Task M7: no_thin flag in PushArgs ===
+
+    #[test]
+    fn parse_push_no_thin() {
+        let cli = Cli::parse_from(["grt", "push", "--no-thin"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.no_thin);
+        } else {
+            panic!("expected Push command");
+        }
+    }
+
+    #[test]
+    fn parse_push_strict_message() {
+        let cli = Cli::parse_from(["grt", "push", "--strict-message"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.strict_message);
+        } else {
+            panic!("expected Push command");
+        }
+        assert!(Cli::try_parse_from(["grt", "push", "--strict"]).is_err());
+    }
+
+    #[test]
+    fn parse_push_no_thin_default_false() {
+        let cli = Cli::parse_from(["grt", "push"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(!args.no_thin);
+        } else {
+            panic!("expected Push command");
+        }
+    }
+
+    // === Task M14: --color/--no-color warnings ===
+
+    #[test]
+    fn git_review_parse_color_flag() {
+        let cli = GitReviewCli::parse_from(["git-review", "--color", "always", "main"]);
+        assert_eq!(cli.color.as_deref(), Some("always"));
+    }
+
+    // === Task M16: --setup --finish together ===
+
+    #[test]
+    fn parse_review_setup_and_finish() {
+        // setup is in the "mode" group, but finish is not, so they can coexist
+        let cli = Cli::parse_from(["grt", "review", "-s", "-f"]);
+        if let Commands::Review(args) = cli.command {
+            assert!(args.setup);
+            assert!(args.finish);
+        } else {
+            panic!("expected Review command");
         }
     }
 
-    eprintln!("\nSetup complete.");
-    Ok(())
-}
-
-async fn cmd_version(work_dir: &Path) -> Result<()> {
-    println!("grt {}", env!("CARGO_PKG_VERSION"));
+    // === Task L2: --license flag ===
 
-    // Try to get Gerrit version
-    let cli_overrides = CliOverrides::default();
-    match App::new(work_dir, &cli_overrides) {
-        Ok(app) => match app.gerrit.get_version().await {
-            Ok(version) => println!("Gerrit {version}"),
-            Err(_) => println!("Gerrit version: unavailable"),
-        },
-        Err(_) => println!("Gerrit version: unavailable (not in a configured repository)"),
+    #[test]
+    fn git_review_parse_license() {
+        let cli = GitReviewCli::parse_from(["git-review", "--license"]);
+        assert!(cli.license);
     }
 
-    Ok(())
-}
+    #[test]
+    fn git_review_license_default_false() {
+        let cli = GitReviewCli::parse_from(["git-review", "main"]);
+        assert!(!cli.license);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // === Task L13: dry-run shows full push command ===
 
     #[test]
-    fn verify_cli() {
-        use clap::CommandFactory;
-        Cli::command().debug_assert();
+    fn parse_push_dry_run_flag() {
+        let cli = Cli::parse_from(["grt", "push", "--dry-run"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.dry_run);
+        } else {
+            panic!("expected Push command");
+        }
     }
 
+    // === default reviewers opt-out ===
+
     #[test]
-    fn verify_git_review_cli() {
-        use clap::CommandFactory;
-        GitReviewCli::command().debug_assert();
+    fn parse_push_no_default_reviewers() {
+        let cli = Cli::parse_from(["grt", "push", "--no-default-reviewers"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.no_default_reviewers);
+        } else {
+            panic!("expected Push command");
+        }
     }
 
-    // === Existing push/comments/setup/version tests ===
-
     #[test]
-    fn parse_push_defaults() {
-        let cli = Cli::parse_from(["grt", "push"]);
-        assert!(matches!(cli.command, Commands::Push(_)));
+    fn parse_push_print_refspec() {
+        for flag in ["--print-refspec", "--debug-refspec"] {
+            let cli = Cli::parse_from(["grt", "push", flag]);
+            if let Commands::Push(args) = cli.command {
+                assert!(args.print_refspec);
+            } else {
+                panic!("expected Push command");
+            }
+        }
     }
 
     #[test]
-    fn parse_push_all_flags() {
-        let cli = Cli::parse_from([
-            "grt", "push", "--topic", "foo", "--wip", "-r", "alice", "main",
-        ]);
+    fn parse_push_codeowners() {
+        let cli = Cli::parse_from(["grt", "push", "--codeowners"]);
         if let Commands::Push(args) = cli.command {
-            assert_eq!(args.topic.as_deref(), Some("foo"));
-            assert!(args.wip);
-            assert_eq!(args.reviewers, vec!["alice"]);
-            assert_eq!(args.branch.as_deref(), Some("main"));
+            assert!(args.codeowners);
         } else {
             panic!("expected Push command");
         }
     }
 
     #[test]
-    fn parse_comments_defaults() {
-        let cli = Cli::parse_from(["grt", "comments"]);
-        assert!(matches!(cli.command, Commands::Comments(_)));
+    fn parse_push_default_reviewers_enabled_by_default() {
+        let cli = Cli::parse_from(["grt", "push"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(!args.no_default_reviewers);
+        } else {
+            panic!("expected Push command");
+        }
     }
 
+    // === per-recipient notify ===
+
     #[test]
-    fn parse_comments_with_change() {
+    fn parse_push_notify_to_cc() {
         let cli = Cli::parse_from([
             "grt",
-            "comments",
-            "12345",
-            "--unresolved",
-            "--format",
-            "json",
+            "push",
+            "--notify-to",
+            "alice,bob",
+            "--notify-cc",
+            "carol",
         ]);
-        if let Commands::Comments(args) = cli.command {
-            assert_eq!(args.change.as_deref(), Some("12345"));
-            assert!(args.unresolved);
-            assert!(matches!(args.format, OutputFormat::Json));
+        if let Commands::Push(args) = cli.command {
+            assert_eq!(args.notify_to, vec!["alice", "bob"]);
+            assert_eq!(args.notify_cc, vec!["carol"]);
         } else {
-            panic!("expected Comments command");
+            panic!("expected Push command");
         }
     }
 
+    // === self-review filtering ===
+
     #[test]
-    fn parse_comments_resolved_flag() {
-        let cli = Cli::parse_from(["grt", "comments", "12345", "--resolved"]);
-        if let Commands::Comments(args) = cli.command {
-            assert!(args.resolved);
-            assert!(!args.unresolved);
+    fn parse_push_cc_self() {
+        let cli = Cli::parse_from(["grt", "push", "--cc-self"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.cc_self);
+            assert!(!args.allow_self_review);
         } else {
-            panic!("expected Comments command");
+            panic!("expected Push command");
         }
     }
 
     #[test]
-    fn resolved_and_unresolved_are_mutually_exclusive() {
-        let result =
-            Cli::try_parse_from(["grt", "comments", "12345", "--resolved", "--unresolved"]);
-        assert!(
-            result.is_err(),
-            "--resolved and --unresolved should conflict"
-        );
+    fn parse_push_cc_self_conflicts_with_allow_self_review() {
+        let result = Cli::try_parse_from(["grt", "push", "--cc-self", "--allow-self-review"]);
+        assert!(result.is_err());
     }
 
-    #[test]
-    fn parse_setup_defaults() {
-        let cli = Cli::parse_from(["grt", "setup"]);
-        assert!(matches!(cli.command, Commands::Setup(_)));
-    }
+    // === raw push options ===
 
     #[test]
-    fn parse_version() {
-        let cli = Cli::parse_from(["grt", "version"]);
-        assert!(matches!(cli.command, Commands::Version));
+    fn parse_push_option_repeated() {
+        let cli = Cli::parse_from([
+            "grt",
+            "push",
+            "--push-option",
+            "trace=1234",
+            "--push-option",
+            "skip-validation",
+        ]);
+        if let Commands::Push(args) = cli.command {
+            assert_eq!(args.push_options, vec!["trace=1234", "skip-validation"]);
+        } else {
+            panic!("expected Push command");
+        }
     }
 
     #[test]
-    fn parse_global_verbose() {
-        let cli = Cli::parse_from(["grt", "-vvv", "version"]);
-        assert_eq!(cli.verbose, 3);
+    fn parse_push_change_id_file() {
+        let cli = Cli::parse_from(["grt", "push", "--change-id-file", "out/change.env"]);
+        if let Commands::Push(args) = cli.command {
+            assert_eq!(args.change_id_file, Some(PathBuf::from("out/change.env")));
+        } else {
+            panic!("expected Push command");
+        }
     }
 
     #[test]
-    fn parse_global_directory() {
-        let cli = Cli::parse_from(["grt", "-C", "/tmp", "version"]);
-        assert_eq!(cli.directory, Some(PathBuf::from("/tmp")));
+    fn parse_push_label_base_merged() {
+        let cli = Cli::parse_from([
+            "grt",
+            "push",
+            "--label",
+            "Code-Review+2,Verified",
+            "--label",
+            "Workflow+1",
+            "--base",
+            "HEAD~2",
+            "--merged",
+            "--publish-comments",
+        ]);
+        if let Commands::Push(args) = cli.command {
+            assert_eq!(args.labels, vec!["Code-Review+2", "Verified", "Workflow+1"]);
+            assert_eq!(args.base.as_deref(), Some("HEAD~2"));
+            assert!(args.merged);
+            assert!(args.publish_comments);
+        } else {
+            panic!("expected Push command");
+        }
     }
 
-    // === New: review subcommand tests ===
+    // === signed push ===
 
     #[test]
-    fn parse_review_subcommand() {
-        let cli = Cli::parse_from(["grt", "review", "main"]);
-        if let Commands::Review(args) = cli.command {
-            assert_eq!(args.branch.as_deref(), Some("main"));
+    fn parse_push_sign_short() {
+        let cli = Cli::parse_from(["grt", "push", "-S"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.sign);
         } else {
-            panic!("expected Review command");
+            panic!("expected Push command");
         }
     }
 
     #[test]
-    fn parse_review_with_download() {
-        let cli = Cli::parse_from(["grt", "review", "-d", "12345"]);
-        if let Commands::Review(args) = cli.command {
-            assert_eq!(args.download.as_deref(), Some("12345"));
+    fn parse_push_sign_long() {
+        let cli = Cli::parse_from(["grt", "push", "--sign"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.sign);
         } else {
-            panic!("expected Review command");
+            panic!("expected Push command");
         }
     }
 
     #[test]
-    fn parse_review_with_setup() {
-        let cli = Cli::parse_from(["grt", "review", "-s"]);
-        if let Commands::Review(args) = cli.command {
-            assert!(args.setup);
+    fn parse_push_signed_push_alias_and_no_sign() {
+        let cli = Cli::parse_from(["grt", "push", "--signed-push"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.sign);
         } else {
-            panic!("expected Review command");
+            panic!("expected Push command");
         }
-    }
 
-    #[test]
-    fn parse_review_push_mode_flags() {
-        let cli = Cli::parse_from([
-            "grt", "review", "-w", "-t", "my-topic", "-r", "origin", "main",
-        ]);
-        if let Commands::Review(args) = cli.command {
-            assert!(args.wip);
-            assert_eq!(args.topic.as_deref(), Some("my-topic"));
-            assert_eq!(args.remote.as_deref(), Some("origin"));
-            assert_eq!(args.branch.as_deref(), Some("main"));
+        let cli = Cli::parse_from(["grt", "push", "--no-sign"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.no_sign);
         } else {
-            panic!("expected Review command");
+            panic!("expected Push command");
         }
+
+        assert!(Cli::try_parse_from(["grt", "push", "--sign", "--no-sign"]).is_err());
     }
 
     #[test]
-    fn parse_review_no_args() {
-        let cli = Cli::parse_from(["grt", "review"]);
-        assert!(matches!(cli.command, Commands::Review(_)));
-    }
+    fn parse_push_no_message() {
+        let cli = Cli::parse_from(["grt", "push", "--no-message"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.no_message);
+            assert!(args.message.is_none());
+        } else {
+            panic!("expected Push command");
+        }
 
-    // === New: export subcommand tests ===
+        assert!(Cli::try_parse_from(["grt", "push", "--no-message", "-m", "hi"]).is_err());
+    }
 
     #[test]
-    fn parse_export_git_review() {
-        let cli = Cli::parse_from(["grt", "export", "git-review"]);
-        if let Commands::Export(args) = cli.command {
-            assert!(matches!(
-                args.target,
-                export::ExportTarget::GitReview { clean: false }
-            ));
+    fn parse_push_recurse() {
+        let cli = Cli::parse_from(["grt", "push", "--recurse", "--topic", "bump"]);
+        if let Commands::Push(args) = cli.command {
+            assert!(args.recurse);
+            assert_eq!(args.topic.as_deref(), Some("bump"));
         } else {
-            panic!("expected Export command");
+            panic!("expected Push command");
         }
+
+        assert!(Cli::try_parse_from(["grt", "push", "--recurse", "main"]).is_err());
+        assert!(Cli::try_parse_from(["grt", "push", "--recurse", "--remote", "origin"]).is_err());
     }
 
     #[test]
-    fn parse_export_git_review_clean() {
-        let cli = Cli::parse_from(["grt", "export", "git-review", "--clean"]);
-        if let Commands::Export(args) = cli.command {
-            assert!(matches!(
-                args.target,
-                export::ExportTarget::GitReview { clean: true }
-            ));
+    fn push_args_default_matches_empty_command_line() {
+        // backport_push_args relies on Default giving the same values as
+        // `grt push` with no flags
+        assert_eq!(
+            format!("{:?}", PushArgs::default()),
+            format!("{:?}", PushArgs::parse_from(["push"]))
+        );
+    }
+
+    #[test]
+    fn parse_push_all_branches() {
+        let cli = Cli::parse_from(["grt", "push", "--all-branches", "fix/*", "--yes"]);
+        if let Commands::Push(args) = cli.command {
+            assert_eq!(args.all_branches.as_deref(), Some("fix/*"));
+            assert!(args.yes);
         } else {
-            panic!("expected Export command");
+            panic!("expected Push command");
         }
+
+        assert!(Cli::try_parse_from(["grt", "push", "--all-branches", "fix/*", "main"]).is_err());
+        assert!(
+            Cli::try_parse_from(["grt", "push", "--all-branches", "fix/*", "--recurse"]).is_err()
+        );
+        // One file cannot hold the result of every branch
+        assert!(Cli::try_parse_from([
+            "grt",
+            "push",
+            "--all-branches",
+            "fix/*",
+            "--change-id-file",
+            "out.txt"
+        ])
+        .is_err());
     }
 
-    // === New: argv[0] personality detection tests ===
+    // === stacked push confirmation ===
 
     #[test]
-    fn detect_personality_grt_bare() {
-        assert_eq!(detect_personality("grt"), Personality::Grt);
+    fn stack_confirmation_lists_each_commit_once() {
+        let table = "  COMMIT   STATUS  CHANGE-ID  SUBJECT\n\
+                     \x20 aaa1111  -       -          Implement feature\n\
+                     \x20 bbb2222  -       -          Add tests (HEAD -> topic)\n";
+        let prompt = stack_confirmation("gerrit", "main", table);
+        assert_eq!(
+            prompt,
+            format!("You are about to submit multiple commits to gerrit/main:\n\n{table}\nContinue? [y/N] ")
+        );
+        assert_eq!(prompt.matches("Add tests").count(), 1);
     }
 
+    // === --profile ===
+
     #[test]
-    fn detect_personality_grt_absolute() {
-        assert_eq!(detect_personality("/usr/bin/grt"), Personality::Grt);
+    fn parse_global_profile() {
+        let cli = Cli::parse_from(["grt", "push", "--profile", "work"]);
+        assert_eq!(cli.profile.as_deref(), Some("work"));
     }
 
     #[test]
-    fn detect_personality_git_review_bare() {
-        assert_eq!(detect_personality("git-review"), Personality::GitReview);
+    fn parse_global_color() {
+        let cli = Cli::parse_from(["grt", "comments", "--color", "always"]);
+        assert_eq!(cli.color, Some(ColorWhen::Always));
+        assert!(!cli.no_color);
+
+        let cli = Cli::parse_from(["grt", "--no-color", "status"]);
+        assert!(cli.no_color);
+        assert_eq!(cli.color, None);
+
+        assert!(Cli::try_parse_from(["grt", "--color", "sometimes", "status"]).is_err());
     }
 
     #[test]
-    fn detect_personality_git_review_absolute() {
-        assert_eq!(
-            detect_personality("/usr/local/bin/git-review"),
-            Personality::GitReview
-        );
+    fn parse_global_http1() {
+        let cli = Cli::parse_from(["grt", "comments", "--http1"]);
+        assert!(cli.http1);
     }
 
     #[test]
-    fn detect_personality_git_review_home_local() {
-        assert_eq!(
-            detect_personality("/home/user/.local/bin/git-review"),
-            Personality::GitReview
-        );
+    fn global_opts_cli_overrides() {
+        let globals = GlobalOpts {
+            insecure: true,
+            server: Some("review.example.com".into()),
+            profile: Some("work".into()),
+            http1: true,
+            no_color: false,
+            color: None,
+            prefer_gitreview: true,
+        };
+        let overrides = globals.cli_overrides();
+        assert_eq!(overrides.host.as_deref(), Some("review.example.com"));
+        assert_eq!(overrides.profile.as_deref(), Some("work"));
+        assert!(overrides.insecure);
+        assert!(overrides.http1);
+        assert!(overrides.prefer_gitreview);
     }
 
     #[test]
-    fn detect_personality_unknown_defaults_to_grt() {
-        assert_eq!(detect_personality("something-else"), Personality::Grt);
+    fn parse_no_profile_by_default() {
+        let cli = Cli::parse_from(["grt", "push"]);
+        assert!(cli.profile.is_none());
     }
 
-    // === New: git-review mode parsing tests ===
+    // === submit --topic ===
 
     #[test]
-    fn git_review_parse_branch() {
-        let cli = GitReviewCli::parse_from(["git-review", "main"]);
-        assert_eq!(cli.review.branch.as_deref(), Some("main"));
+    fn parse_submit_topic() {
+        let cli = Cli::parse_from(["grt", "submit", "--topic", "stack", "--project", "p"]);
+        if let Commands::Submit(args) = cli.command {
+            assert_eq!(args.topic.as_deref(), Some("stack"));
+            assert_eq!(args.project.as_deref(), Some("p"));
+        } else {
+            panic!("expected Submit command");
+        }
     }
 
     #[test]
-    fn git_review_parse_download() {
-        let cli = GitReviewCli::parse_from(["git-review", "-d", "12345"]);
-        assert_eq!(cli.review.download.as_deref(), Some("12345"));
+    fn parse_submit_defaults_to_head() {
+        let cli = Cli::parse_from(["grt", "submit"]);
+        if let Commands::Submit(args) = cli.command {
+            assert!(args.change.is_none());
+            assert!(args.topic.is_none());
+            assert!(!args.when_ready);
+        } else {
+            panic!("expected Submit command");
+        }
+    }
+
+    #[test]
+    fn parse_submit_change_when_ready() {
+        let cli = Cli::parse_from(["grt", "submit", "12345", "--when-ready"]);
+        if let Commands::Submit(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345"));
+            assert!(args.when_ready);
+        } else {
+            panic!("expected Submit command");
+        }
+    }
+
+    #[test]
+    fn parse_submit_topic_conflicts() {
+        assert!(Cli::try_parse_from(["grt", "submit", "12345", "--topic", "t"]).is_err());
+        assert!(Cli::try_parse_from(["grt", "submit", "--topic", "t", "--when-ready"]).is_err());
+        assert!(Cli::try_parse_from(["grt", "submit", "--project", "p"]).is_err());
     }
 
+    // === abandon / restore ===
+
     #[test]
-    fn git_review_parse_verbose() {
-        let cli = GitReviewCli::parse_from(["git-review", "-v", "-d", "12345"]);
-        assert_eq!(cli.verbose, 1);
-        assert_eq!(cli.review.download.as_deref(), Some("12345"));
+    fn parse_abandon_defaults_to_head() {
+        let cli = Cli::parse_from(["grt", "abandon"]);
+        if let Commands::Abandon(args) = cli.command {
+            assert!(args.change.is_none());
+            assert!(args.message.is_none());
+        } else {
+            panic!("expected Abandon command");
+        }
     }
 
     #[test]
-    fn git_review_parse_no_color() {
-        let cli = GitReviewCli::parse_from(["git-review", "--no-color", "main"]);
-        assert!(cli.no_color);
-        assert_eq!(cli.review.branch.as_deref(), Some("main"));
+    fn parse_restore_with_change_and_message() {
+        let cli = Cli::parse_from(["grt", "restore", "12345", "-m", "still needed"]);
+        if let Commands::Restore(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345"));
+            assert_eq!(args.message.as_deref(), Some("still needed"));
+        } else {
+            panic!("expected Restore command");
+        }
     }
 
+    // === wip / ready ===
+
     #[test]
-    fn git_review_parse_list() {
-        let cli = GitReviewCli::parse_from(["git-review", "-l"]);
-        assert_eq!(cli.review.list, 1);
+    fn parse_wip_defaults_to_head() {
+        let cli = Cli::parse_from(["grt", "wip"]);
+        if let Commands::Wip(args) = cli.command {
+            assert!(args.change.is_none());
+            assert!(args.message.is_none());
+        } else {
+            panic!("expected Wip command");
+        }
     }
 
     #[test]
-    fn git_review_parse_setup() {
-        let cli = GitReviewCli::parse_from(["git-review", "-s"]);
-        assert!(cli.review.setup);
+    fn parse_ready_with_change_and_message() {
+        let cli = Cli::parse_from(["grt", "ready", "12345", "-m", "addressed comments"]);
+        if let Commands::Ready(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345"));
+            assert_eq!(args.message.as_deref(), Some("addressed comments"));
+        } else {
+            panic!("expected Ready command");
+        }
     }
 
-    // === Completions subcommand ===
+    // === topic ===
+
+    // === auth ===
 
     #[test]
-    fn parse_completions_bash() {
-        let cli = Cli::parse_from(["grt", "completions", "bash"]);
-        assert!(matches!(
-            cli.command,
-            Commands::Completions {
-                shell: clap_complete::Shell::Bash
-            }
-        ));
+    fn parse_auth_login() {
+        let cli = Cli::parse_from([
+            "grt",
+            "auth",
+            "login",
+            "review.example.com",
+            "--username",
+            "alice",
+            "--password-stdin",
+        ]);
+        if let Commands::Auth(AuthArgs {
+            action:
+                AuthAction::Login {
+                    host,
+                    username,
+                    bearer,
+                    password_stdin,
+                },
+        }) = cli.command
+        {
+            assert_eq!(host.as_deref(), Some("review.example.com"));
+            assert_eq!(username.as_deref(), Some("alice"));
+            assert!(!bearer);
+            assert!(password_stdin);
+        } else {
+            panic!("expected auth login command");
+        }
     }
 
     #[test]
-    fn parse_completions_zsh() {
-        let cli = Cli::parse_from(["grt", "completions", "zsh"]);
+    fn parse_auth_logout_defaults_host() {
+        let cli = Cli::parse_from(["grt", "auth", "logout"]);
         assert!(matches!(
             cli.command,
-            Commands::Completions {
-                shell: clap_complete::Shell::Zsh
-            }
+            Commands::Auth(AuthArgs {
+                action: AuthAction::Logout { host: None }
+            })
         ));
     }
 
     #[test]
-    fn parse_completions_fish() {
-        let cli = Cli::parse_from(["grt", "completions", "fish"]);
-        assert!(matches!(
-            cli.command,
-            Commands::Completions {
-                shell: clap_complete::Shell::Fish
-            }
-        ));
-    }
+    fn parse_status() {
+        let cli = Cli::parse_from(["grt", "status"]);
+        if let Commands::Status(args) = cli.command {
+            assert!(args.change.is_none());
+            assert!(args.format.is_none());
+        } else {
+            panic!("expected Status command");
+        }
 
-    // === Exit code mapping ===
+        let cli = Cli::parse_from(["grt", "status", "12345", "--format", "json"]);
+        if let Commands::Status(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345"));
+            assert!(matches!(args.format, Some(OutputFormat::Json)));
+        } else {
+            panic!("expected Status command");
+        }
+    }
 
     #[test]
-    fn exit_code_network_error() {
-        let err: anyhow::Error = GerritError::Network("connection refused".into()).into();
-        assert_eq!(exit_code_for_error(&err), 40);
+    fn parse_open() {
+        let cli = Cli::parse_from(["grt", "open"]);
+        if let Commands::Open(args) = cli.command {
+            assert!(args.change.is_none());
+            assert!(!args.print);
+        } else {
+            panic!("expected Open command");
+        }
+
+        let cli = Cli::parse_from(["grt", "open", "12345,2", "--print"]);
+        if let Commands::Open(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345,2"));
+            assert!(args.print);
+        } else {
+            panic!("expected Open command");
+        }
     }
 
     #[test]
-    fn exit_code_auth_error() {
-        let err: anyhow::Error = GerritError::AuthFailed { status: 401 }.into();
-        assert_eq!(exit_code_for_error(&err), 1);
+    fn parse_deps() {
+        let cli = Cli::parse_from(["grt", "deps", "12345", "--format", "json"]);
+        if let Commands::Deps(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345"));
+            assert!(matches!(args.format, Some(OutputFormat::Json)));
+        } else {
+            panic!("expected Deps command");
+        }
     }
 
     #[test]
-    fn exit_code_generic() {
-        let err = anyhow::anyhow!("something went wrong");
-        assert_eq!(exit_code_for_error(&err), 1);
+    fn parse_log() {
+        let cli = Cli::parse_from(["grt", "log"]);
+        if let Commands::Log(args) = cli.command {
+            assert_eq!(args.branch, None);
+            assert_eq!(args.merged, 5);
+        } else {
+            panic!("expected Log command");
+        }
+
+        let cli = Cli::parse_from(["grt", "log", "--branch", "stable/2024.1", "--merged", "0"]);
+        if let Commands::Log(args) = cli.command {
+            assert_eq!(args.branch.as_deref(), Some("stable/2024.1"));
+            assert_eq!(args.merged, 0);
+        } else {
+            panic!("expected Log command");
+        }
     }
 
     #[test]
-    fn exit_code_config_error() {
-        let err = anyhow::anyhow!("no Gerrit host configured");
-        assert_eq!(exit_code_for_error(&err), 128);
+    fn parse_query() {
+        let cli = Cli::parse_from([
+            "grt",
+            "query",
+            "owner:self status:open",
+            "--columns",
+            "number,subject",
+            "--format",
+            "tsv",
+        ]);
+        if let Commands::Query(args) = cli.command {
+            assert_eq!(args.query, "owner:self status:open");
+            assert_eq!(
+                args.columns,
+                vec![query::Column::Number, query::Column::Subject]
+            );
+            assert_eq!(args.format, Some(QueryFormat::Tsv));
+            assert!(args.limit.is_none());
+        } else {
+            panic!("expected Query command");
+        }
+
+        assert!(Cli::try_parse_from(["grt", "query", "q", "--limit", "5", "--no-limit"]).is_err());
     }
 
     #[test]
-    fn exit_code_malformed_input() {
-        let err = anyhow::anyhow!("compare argument must be CHANGE,PS[-PS]");
-        assert_eq!(exit_code_for_error(&err), 3);
-    }
+    fn parse_diff() {
+        let cli = Cli::parse_from(["grt", "diff", "12345,2"]);
+        if let Commands::Diff(args) = cli.command {
+            assert_eq!(args.change, "12345,2");
+            assert!(!args.against_target);
+        } else {
+            panic!("expected Diff command");
+        }
 
-    // === Task B2: --finish guard on dry_run ===
+        let cli = Cli::parse_from(["grt", "diff", "12345", "--against-target"]);
+        if let Commands::Diff(args) = cli.command {
+            assert!(args.against_target);
+        } else {
+            panic!("expected Diff command");
+        }
+    }
 
     #[test]
-    fn parse_review_finish_flag() {
-        let cli = Cli::parse_from(["grt", "review", "-f"]);
-        if let Commands::Review(args) = cli.command {
-            assert!(args.finish);
-            assert!(!args.dry_run);
+    fn parse_cherrypick_remote() {
+        let cli = Cli::parse_from([
+            "grt",
+            "cherrypick-remote",
+            "12345,3",
+            "--branch",
+            "stable/2024.1",
+            "--keep-reviewers",
+        ]);
+        if let Commands::CherrypickRemote(args) = cli.command {
+            assert_eq!(args.change, "12345,3");
+            assert_eq!(args.branch, "stable/2024.1");
+            assert!(args.keep_reviewers);
+            assert!(!args.allow_conflicts);
+            assert!(args.message.is_none() && args.topic.is_none());
         } else {
-            panic!("expected Review command");
+            panic!("expected CherrypickRemote command");
         }
+
+        assert!(Cli::try_parse_from(["grt", "cherrypick-remote", "12345"]).is_err());
     }
 
     #[test]
-    fn parse_review_finish_with_dry_run() {
-        let cli = Cli::parse_from(["grt", "review", "-f", "-n"]);
-        if let Commands::Review(args) = cli.command {
-            assert!(args.finish);
-            assert!(args.dry_run);
+    fn parse_rebase_chain() {
+        let cli = Cli::parse_from(["grt", "rebase-chain"]);
+        if let Commands::RebaseChain(args) = cli.command {
+            assert!(args.branch.is_none());
+            assert!(!args.interactive && !args.keep_rebase);
         } else {
-            panic!("expected Review command");
+            panic!("expected RebaseChain command");
         }
-    }
 
-    // === Task H2: force_rebase flag parsing ===
+        let cli = Cli::parse_from([
+            "grt",
+            "rebase-chain",
+            "stable",
+            "--remote",
+            "up",
+            "--keep-rebase",
+        ]);
+        if let Commands::RebaseChain(args) = cli.command {
+            assert_eq!(args.branch.as_deref(), Some("stable"));
+            assert_eq!(args.remote.as_deref(), Some("up"));
+            assert!(args.keep_rebase);
+        } else {
+            panic!("expected RebaseChain command");
+        }
+
+        assert!(Cli::try_parse_from(["grt", "rebase-chain", "-i", "--keep-rebase"]).is_err());
+    }
 
     #[test]
-    fn parse_review_force_rebase() {
-        let cli = Cli::parse_from(["grt", "review", "-F"]);
-        if let Commands::Review(args) = cli.command {
-            assert!(args.force_rebase);
+    fn parse_backport() {
+        let cli = Cli::parse_from([
+            "grt",
+            "backport",
+            "12345",
+            "--to",
+            "stable/2024.1",
+            "--to",
+            "stable/2023.2",
+            "--new-change-id",
+        ]);
+        if let Commands::Backport(args) = cli.command {
+            assert_eq!(args.change, "12345");
+            assert_eq!(args.to, vec!["stable/2024.1", "stable/2023.2"]);
+            assert!(args.new_change_id);
+            assert!(!args.no_push);
         } else {
-            panic!("expected Review command");
+            panic!("expected Backport command");
         }
+
+        assert!(Cli::try_parse_from(["grt", "backport", "12345"]).is_err());
+        assert!(Cli::try_parse_from([
+            "grt",
+            "backport",
+            "12345",
+            "--to",
+            "stable/1",
+            "--no-push",
+            "--dry-run"
+        ])
+        .is_err());
     }
 
-    // === Task H3: default topic to branch name ===
+    #[test]
+    fn parse_fix() {
+        let cli = Cli::parse_from(["grt", "fix"]);
+        if let Commands::Fix(args) = cli.command {
+            assert!(args.change.is_none());
+            assert!(!args.all && !args.list && !args.amend && !args.force);
+        } else {
+            panic!("expected Fix command");
+        }
+
+        let cli = Cli::parse_from(["grt", "fix", "12345", "--all", "--amend"]);
+        if let Commands::Fix(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345"));
+            assert!(args.all && args.amend);
+        } else {
+            panic!("expected Fix command");
+        }
+
+        assert!(Cli::try_parse_from(["grt", "fix", "--list", "--all"]).is_err());
+    }
 
     #[test]
-    fn parse_review_no_topic_flag() {
-        let cli = Cli::parse_from(["grt", "review", "-T"]);
-        if let Commands::Review(args) = cli.command {
-            assert!(args.no_topic);
+    fn parse_topic_args() {
+        let cli = Cli::parse_from(["grt", "topic", "12345", "feature-x"]);
+        if let Commands::Topic(args) = cli.command {
+            assert_eq!(args.args, vec!["12345", "feature-x"]);
+            assert!(!args.clear);
         } else {
-            panic!("expected Review command");
+            panic!("expected Topic command");
         }
+        assert!(Cli::try_parse_from(["grt", "topic", "a", "b", "c"]).is_err());
     }
 
-    // === Task M7: no_thin flag in PushArgs ===
+    #[test]
+    fn split_topic_args_variants() {
+        let s = |v: &[&str]| v.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            split_topic_args(&s(&["feature-x"]), false).unwrap(),
+            (None, Some("feature-x".into()))
+        );
+        assert_eq!(
+            split_topic_args(&s(&["123", "feature-x"]), false).unwrap(),
+            (Some("123".into()), Some("feature-x".into()))
+        );
+        assert_eq!(split_topic_args(&[], true).unwrap(), (None, None));
+        assert_eq!(
+            split_topic_args(&s(&["123"]), true).unwrap(),
+            (Some("123".into()), None)
+        );
+        assert!(split_topic_args(&[], false).is_err());
+        assert!(split_topic_args(&s(&["123", "x"]), true).is_err());
+    }
+
+    // === hashtags ===
 
     #[test]
-    fn parse_push_no_thin() {
-        let cli = Cli::parse_from(["grt", "push", "--no-thin"]);
-        if let Commands::Push(args) = cli.command {
-            assert!(args.no_thin);
+    fn parse_hashtags_add() {
+        let cli = Cli::parse_from(["grt", "hashtags", "add", "12345", "perf", "#ci"]);
+        if let Commands::Hashtags(HashtagsArgs {
+            action: HashtagsAction::Add { args },
+        }) = cli.command
+        {
+            assert_eq!(args, vec!["12345", "perf", "#ci"]);
         } else {
-            panic!("expected Push command");
+            panic!("expected Hashtags Add command");
         }
+        assert!(Cli::try_parse_from(["grt", "hashtags", "remove"]).is_err());
     }
 
     #[test]
-    fn parse_push_no_thin_default_false() {
-        let cli = Cli::parse_from(["grt", "push"]);
-        if let Commands::Push(args) = cli.command {
-            assert!(!args.no_thin);
+    fn parse_hashtags_list_defaults_to_head() {
+        let cli = Cli::parse_from(["grt", "hashtags", "list"]);
+        if let Commands::Hashtags(HashtagsArgs {
+            action: HashtagsAction::List { change },
+        }) = cli.command
+        {
+            assert!(change.is_none());
         } else {
-            panic!("expected Push command");
+            panic!("expected Hashtags List command");
         }
     }
 
-    // === Task M14: --color/--no-color warnings ===
-
     #[test]
-    fn git_review_parse_color_flag() {
-        let cli = GitReviewCli::parse_from(["git-review", "--color", "always", "main"]);
-        assert_eq!(cli.color.as_deref(), Some("always"));
+    fn parse_hashtags_values() {
+        let s = |v: &[&str]| v.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_hashtags(&s(&["#perf", "ci,  docs"])).unwrap(),
+            vec!["perf", "ci", "docs"]
+        );
+        assert!(parse_hashtags(&s(&["#", ","])).is_err());
+        assert!(parse_hashtags(&s(&["two words"])).is_err());
     }
 
-    // === Task M16: --setup --finish together ===
+    // === attention ===
 
     #[test]
-    fn parse_review_setup_and_finish() {
-        // setup is in the "mode" group, but finish is not, so they can coexist
-        let cli = Cli::parse_from(["grt", "review", "-s", "-f"]);
-        if let Commands::Review(args) = cli.command {
-            assert!(args.setup);
-            assert!(args.finish);
+    fn parse_attention_add_remove() {
+        let cli = Cli::parse_from([
+            "grt",
+            "attention",
+            "12345",
+            "--add",
+            "alice",
+            "--add",
+            "bob@example.com",
+            "--remove",
+            "carol",
+            "--reason",
+            "Please take a look",
+        ]);
+        if let Commands::Attention(args) = cli.command {
+            assert_eq!(args.change.as_deref(), Some("12345"));
+            assert_eq!(args.add, vec!["alice", "bob@example.com"]);
+            assert_eq!(args.remove, vec!["carol"]);
+            assert_eq!(args.reason.as_deref(), Some("Please take a look"));
         } else {
-            panic!("expected Review command");
+            panic!("expected Attention command");
         }
     }
 
-    // === Task L2: --license flag ===
+    #[test]
+    fn parse_attention_defaults_to_head() {
+        let cli = Cli::parse_from(["grt", "attention"]);
+        if let Commands::Attention(args) = cli.command {
+            assert!(args.change.is_none());
+            assert!(args.add.is_empty() && args.remove.is_empty());
+        } else {
+            panic!("expected Attention command");
+        }
+    }
+
+    // === reviewers ===
 
     #[test]
-    fn git_review_parse_license() {
-        let cli = GitReviewCli::parse_from(["git-review", "--license"]);
-        assert!(cli.license);
+    fn parse_reviewers_add_cc() {
+        let cli = Cli::parse_from(["grt", "reviewers", "add", "12345", "alice", "--cc"]);
+        if let Commands::Reviewers(ReviewersArgs {
+            action: ReviewersAction::Add { args, cc },
+        }) = cli.command
+        {
+            assert_eq!(args, vec!["12345", "alice"]);
+            assert!(cc);
+        } else {
+            panic!("expected Reviewers add command");
+        }
     }
 
     #[test]
-    fn git_review_license_default_false() {
-        let cli = GitReviewCli::parse_from(["git-review", "main"]);
-        assert!(!cli.license);
+    fn parse_reviewers_requires_users() {
+        assert!(Cli::try_parse_from(["grt", "reviewers", "add"]).is_err());
+        assert!(Cli::try_parse_from(["grt", "reviewers", "remove"]).is_err());
+        let cli = Cli::parse_from(["grt", "reviewers", "list"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Reviewers(ReviewersArgs {
+                action: ReviewersAction::List { change: None }
+            })
+        ));
     }
 
-    // === Task L13: dry-run shows full push command ===
+    // === checkout ===
 
     #[test]
-    fn parse_push_dry_run_flag() {
-        let cli = Cli::parse_from(["grt", "push", "--dry-run"]);
-        if let Commands::Push(args) = cli.command {
-            assert!(args.dry_run);
+    fn parse_checkout_args() {
+        let cli = Cli::parse_from(["grt", "checkout", "12345,3", "--verify"]);
+        if let Commands::Checkout(args) = cli.command {
+            assert_eq!(args.change, "12345,3");
+            assert!(args.verify);
+            assert!(args.remote.is_none());
+            assert!(args.format.is_none());
         } else {
-            panic!("expected Push command");
+            panic!("expected Checkout command");
+        }
+        assert!(Cli::try_parse_from(["grt", "checkout"]).is_err());
+    }
+
+    // === tui ===
+
+    #[test]
+    fn parse_tui_args() {
+        let cli = Cli::parse_from(["grt", "tui", "--branch", "main", "--limit", "50"]);
+        if let Commands::Tui(args) = cli.command {
+            assert_eq!(args.branch.as_deref(), Some("main"));
+            assert_eq!(args.limit, Some(50));
+            assert!(args.remote.is_none());
+        } else {
+            panic!("expected Tui command");
         }
     }
 
@@ -1752,6 +6289,63 @@ mod tests {
         assert_eq!(setup_scheme(false, true), "https");
     }
 
+    #[test]
+    fn parse_config_show_and_set() {
+        let cli = Cli::parse_from(["grt", "config", "show", "--format", "json"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Config(ConfigArgs {
+                action: ConfigAction::Show {
+                    format: Some(OutputFormat::Json)
+                }
+            })
+        ));
+
+        let cli = Cli::parse_from(["grt", "config", "set", "host", "review.example.org"]);
+        if let Commands::Config(ConfigArgs {
+            action: ConfigAction::Set { key, value },
+        }) = cli.command
+        {
+            assert_eq!(key, "host");
+            assert_eq!(value, "review.example.org");
+        } else {
+            panic!("expected Config set command");
+        }
+    }
+
+    #[test]
+    fn parse_hooks() {
+        let cli = Cli::parse_from(["grt", "hooks", "install", "--global", "--set-hooks-path"]);
+        if let Commands::Hooks(HooksArgs {
+            action:
+                HooksAction::Install {
+                    global,
+                    dir,
+                    set_hooks_path,
+                    force,
+                },
+        }) = cli.command
+        {
+            assert!(global && set_hooks_path && !force);
+            assert!(dir.is_none());
+        } else {
+            panic!("expected Hooks install command");
+        }
+
+        let cli = Cli::parse_from(["grt", "hooks", "status", "--format", "json"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Hooks(HooksArgs {
+                action: HooksAction::Status {
+                    format: Some(OutputFormat::Json)
+                }
+            })
+        ));
+
+        assert!(Cli::try_parse_from(["grt", "hooks", "install", "--dir", "/tmp/h"]).is_err());
+        assert!(Cli::try_parse_from(["grt", "hooks", "install", "--set-hooks-path"]).is_err());
+    }
+
     #[test]
     fn parse_setup_defaults_to_no_flags() {
         let cli = Cli::parse_from(["grt", "setup"]);
@@ -1764,6 +6358,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_setup_update_hook() {
+        let cli = Cli::parse_from(["grt", "setup", "--update-hook"]);
+        if let Commands::Setup(args) = cli.command {
+            assert!(args.update_hook);
+            assert!(!args.force_hook);
+        } else {
+            panic!("expected Setup command");
+        }
+        assert!(Cli::try_parse_from(["grt", "setup", "--update-hook", "--force-hook"]).is_err());
+    }
+
     #[test]
     fn parse_setup_ssh_flag() {
         let cli = Cli::parse_from(["grt", "setup", "--ssh"]);
@@ -1851,6 +6457,7 @@ mod tests {
                 patch_set: None,
                 date: format!("{} 00:00:00.000000000", cutoff),
                 message: "msg".into(),
+                robot_id: None,
             }],
         }];
         comments::filter_threads_by_date(&mut threads, Some(&cutoff), None);
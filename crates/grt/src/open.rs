@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! `grt open`: show a change in the Gerrit web UI.
+
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+/// Web UI URL of a change, optionally at a specific patchset.
+pub fn change_web_url(base_url: &str, project: &str, number: i64, patchset: Option<i32>) -> String {
+    let mut url = format!(
+        "{}/c/{}/+/{}",
+        base_url.trim_end_matches('/'),
+        project,
+        number
+    );
+    if let Some(ps) = patchset {
+        url.push_str(&format!("/{ps}"));
+    }
+    url
+}
+
+/// Command line that opens `url`: `$BROWSER` when set, else the platform's
+/// default opener.
+pub fn browser_command(browser: Option<&str>, url: &str) -> Vec<String> {
+    if let Some(browser) = browser.map(str::trim).filter(|b| !b.is_empty()) {
+        let mut cmd: Vec<String> = browser.split_whitespace().map(String::from).collect();
+        cmd.push(url.to_string());
+        return cmd;
+    }
+    let opener: &[&str] = if cfg!(target_os = "macos") {
+        &["open"]
+    } else if cfg!(windows) {
+        &["cmd", "/C", "start", ""]
+    } else {
+        &["xdg-open"]
+    };
+    opener
+        .iter()
+        .map(|s| s.to_string())
+        .chain(std::iter::once(url.to_string()))
+        .collect()
+}
+
+/// Launch the user's browser on `url` without waiting for it to exit.
+pub fn open_in_browser(url: &str) -> Result<()> {
+    let browser = std::env::var("BROWSER").ok();
+    let cmd = browser_command(browser.as_deref(), url);
+    Command::new(&cmd[0])
+        .args(&cmd[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("launching {} (use --print to only show the URL)", cmd[0]))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_url_for_change() {
+        assert_eq!(
+            change_web_url("https://review.example.com/", "openstack/nova", 12345, None),
+            "https://review.example.com/c/openstack/nova/+/12345"
+        );
+    }
+
+    #[test]
+    fn web_url_with_patchset() {
+        assert_eq!(
+            change_web_url("https://review.example.com/gerrit", "nova", 7, Some(3)),
+            "https://review.example.com/gerrit/c/nova/+/7/3"
+        );
+    }
+
+    #[test]
+    fn browser_env_takes_precedence() {
+        assert_eq!(
+            browser_command(Some("firefox --new-tab"), "https://r.example.com/c/p/+/1"),
+            vec!["firefox", "--new-tab", "https://r.example.com/c/p/+/1"]
+        );
+    }
+
+    #[test]
+    fn empty_browser_env_uses_platform_opener() {
+        let cmd = browser_command(Some("  "), "https://r.example.com/c/p/+/1");
+        assert_eq!(cmd.last().unwrap(), "https://r.example.com/c/p/+/1");
+        assert!(cmd.len() >= 2);
+    }
+}
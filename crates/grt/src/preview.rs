@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Per-commit preview of a stacked push: what each commit will become on Gerrit.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use crate::push;
+use crate::subprocess::UnpushedCommit;
+
+/// What pushing a commit will do on Gerrit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitFate {
+    /// No open change has this Change-Id on the target branch.
+    NewChange,
+    /// Uploads a new patchset to the open change with this number.
+    NewPatchset(i64),
+    /// Gerrit was not asked (SSH remote, or the lookup failed).
+    Unknown,
+}
+
+/// One commit in the push preview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewRow {
+    /// Abbreviated commit hash.
+    pub commit: String,
+    /// Ref names pointing at the commit, e.g. `HEAD -> topic`; empty if none.
+    pub refs: String,
+    pub subject: String,
+    pub change_id: Option<String>,
+    pub fate: CommitFate,
+}
+
+/// Build preview rows, oldest commit first.
+///
+/// `commits` are listed newest first (as `git log` lists them). `open` maps
+/// Change-Ids of open changes on the target branch to their numbers; `None`
+/// means Gerrit could not be queried.
+pub fn build_preview(
+    commits: &[UnpushedCommit],
+    open: Option<&HashMap<String, i64>>,
+) -> Vec<PreviewRow> {
+    commits
+        .iter()
+        .rev()
+        .map(|c| {
+            let change_id = push::extract_change_id(&c.message);
+            let fate = match (open, &change_id) {
+                (None, _) => CommitFate::Unknown,
+                (Some(open), Some(id)) => open
+                    .get(id)
+                    .map_or(CommitFate::NewChange, |&n| CommitFate::NewPatchset(n)),
+                (Some(_), None) => CommitFate::NewChange,
+            };
+            PreviewRow {
+                commit: c.commit.clone(),
+                refs: c.refs.clone(),
+                subject: c.message.lines().next().unwrap_or("").trim().to_string(),
+                change_id,
+                fate,
+            }
+        })
+        .collect()
+}
+
+/// Render the preview as an aligned table, with each commit's ref names
+/// after its subject as `git log --oneline --decorate` shows them.
+pub fn format_preview(rows: &[PreviewRow]) -> String {
+    let status = |fate: CommitFate| match fate {
+        CommitFate::NewChange => "new change".to_string(),
+        CommitFate::NewPatchset(n) => format!("new patchset ({n})"),
+        CommitFate::Unknown => "-".to_string(),
+    };
+    let statuses: Vec<String> = rows.iter().map(|r| status(r.fate)).collect();
+    let width = statuses.iter().map(String::len).max().unwrap_or(0).max(6);
+    let commit_width = rows
+        .iter()
+        .map(|r| r.commit.len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    let mut out = format!(
+        "  {:<commit_width$}  {:<width$}  {:<41}  SUBJECT\n",
+        "COMMIT", "STATUS", "CHANGE-ID"
+    );
+    for (row, status) in rows.iter().zip(&statuses) {
+        let _ = write!(
+            out,
+            "  {:<commit_width$}  {status:<width$}  {:<41}  {}",
+            row.commit,
+            row.change_id.as_deref().unwrap_or("-"),
+            row.subject
+        );
+        if !row.refs.is_empty() {
+            let _ = write!(out, " ({})", row.refs);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID_A: &str = "I1111111111111111111111111111111111111111";
+    const ID_B: &str = "I2222222222222222222222222222222222222222";
+
+    fn commit(commit: &str, refs: &str, message: &str) -> UnpushedCommit {
+        UnpushedCommit {
+            commit: commit.into(),
+            refs: refs.into(),
+            message: message.into(),
+        }
+    }
+
+    fn commits() -> Vec<UnpushedCommit> {
+        vec![
+            commit(
+                "bbb2222",
+                "HEAD -> topic",
+                &format!("Second change\n\nChange-Id: {ID_B}\n"),
+            ),
+            commit(
+                "aaa1111",
+                "",
+                &format!("First change\n\nBody\n\nChange-Id: {ID_A}\n"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn build_preview_oldest_first_with_fates() {
+        let open = HashMap::from([(ID_A.to_string(), 101)]);
+        let rows = build_preview(&commits(), Some(&open));
+        assert_eq!(
+            rows,
+            vec![
+                PreviewRow {
+                    commit: "aaa1111".into(),
+                    refs: String::new(),
+                    subject: "First change".into(),
+                    change_id: Some(ID_A.into()),
+                    fate: CommitFate::NewPatchset(101),
+                },
+                PreviewRow {
+                    commit: "bbb2222".into(),
+                    refs: "HEAD -> topic".into(),
+                    subject: "Second change".into(),
+                    change_id: Some(ID_B.into()),
+                    fate: CommitFate::NewChange,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn build_preview_without_lookup_is_unknown() {
+        let rows = build_preview(&commits(), None);
+        assert!(rows.iter().all(|r| r.fate == CommitFate::Unknown));
+    }
+
+    #[test]
+    fn build_preview_missing_change_id() {
+        let rows = build_preview(
+            &[commit("ccc3333", "", "No trailer")],
+            Some(&HashMap::new()),
+        );
+        assert_eq!(rows[0].change_id, None);
+        assert_eq!(rows[0].fate, CommitFate::NewChange);
+    }
+
+    #[test]
+    fn format_preview_aligns_columns() {
+        let open = HashMap::from([(ID_A.to_string(), 101)]);
+        let out = format_preview(&build_preview(&commits(), Some(&open)));
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("  COMMIT   STATUS"));
+        assert_eq!(
+            lines[1],
+            format!("  aaa1111  new patchset (101)  {ID_A}  First change")
+        );
+        assert_eq!(
+            lines[2],
+            format!("  bbb2222  new change          {ID_B}  Second change (HEAD -> topic)")
+        );
+    }
+}
@@ -1,17 +1,31 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
+use std::collections::HashSet;
+
 use anyhow::{Context, Result};
 use serde::Serialize;
 
+use crate::config::GerritConfig;
+
 /// Structured result from a push operation.
 #[derive(Debug, Serialize)]
 pub struct PushResult {
+    /// Commits pushed, or that would be with `--dry-run`; 0 when there was nothing to push.
     pub commits: usize,
     pub remote: String,
     pub branch: String,
     pub change_id: Option<String>,
+    /// Empty when there was nothing to push.
     pub refspec: String,
+    /// True when nothing was pushed because of `--dry-run`.
+    pub dry_run: bool,
+    /// Number of HEAD's change, as reported by Gerrit after the push.
+    pub change_number: Option<u64>,
+    /// URL of HEAD's change, as reported by Gerrit after the push.
+    pub change_url: Option<String>,
+    /// Every change Gerrit listed, in the order it listed them; empty for dry runs.
+    pub changes: Vec<PushedChange>,
 }
 
 /// Options for building a Gerrit push refspec.
@@ -28,6 +42,117 @@ pub struct PushOptions {
     pub hashtags: Vec<String>,
     pub message: Option<String>,
     pub notify: Option<String>,
+    /// Votes to apply at push time, as `Label` (+1) or `Label+N`/`Label-N`.
+    pub labels: Vec<String>,
+    /// Commit the pushed changes are based on (`base=<sha>`).
+    pub base: Option<String>,
+    /// Create changes for already-merged commits (`merged`).
+    pub merged: bool,
+    /// Publish the uploader's draft comments (`publish-comments`).
+    pub publish_comments: bool,
+    /// Raw `KEY=VALUE` or `KEY` options appended after the structured ones.
+    pub raw_options: Vec<String>,
+}
+
+/// Flags that shape the `git push` command line, as opposed to the refspec.
+#[derive(Debug, Default)]
+pub struct GitPushFlags {
+    /// Value for `color.remote` (e.g. `always`, `never`, `auto`).
+    pub color: String,
+    /// Pass `--no-thin` to disable thin packs.
+    pub no_thin: bool,
+    /// Pass `--signed` to send a GPG push certificate.
+    pub signed: bool,
+    /// Pass `--progress` to keep progress output when stderr is captured.
+    pub progress: bool,
+}
+
+/// Build the arguments for `git push` (everything after `git`).
+pub fn build_push_args(flags: &GitPushFlags, remote: &str, refspec: &str) -> Vec<String> {
+    let mut args = vec![
+        "-c".to_string(),
+        format!("color.remote={}", flags.color),
+        "push".to_string(),
+        "--no-follow-tags".to_string(),
+    ];
+    if flags.no_thin {
+        args.push("--no-thin".to_string());
+    }
+    if flags.signed {
+        args.push("--signed".to_string());
+    }
+    if flags.progress {
+        args.push("--progress".to_string());
+    }
+    args.push(remote.to_string());
+    args.push(refspec.to_string());
+    args
+}
+
+/// Return a hint for a failed push, if the failure looks like a known case.
+///
+/// `error_message` should include git's stderr: push-certificate hints are
+/// only given when it shows a signing or certificate error, so unrelated
+/// failures of a signed push get none.
+pub fn push_failure_hint(error_message: &str, signed: bool) -> Option<&'static str> {
+    if error_message.contains("Missing tree") || error_message.contains("missing tree") {
+        return Some("Consider trying again with --no-thin");
+    }
+    if !signed {
+        return None;
+    }
+    if error_message.contains("does not support --signed push") {
+        return Some(
+            "The server does not accept push certificates (Gerrit needs receive.enableSignedPush); \
+             push with --no-sign or turn off gitreview.signedpush",
+        );
+    }
+    if error_message.contains("gpg failed to sign") {
+        return Some(
+            "git could not sign the push certificate; check user.signingKey and that gpg can \
+             prompt for the passphrase (export GPG_TTY=$(tty))",
+        );
+    }
+    if error_message.contains("push cert error") || error_message.contains("No public keys") {
+        return Some(
+            "Gerrit rejected the push certificate; add your GPG public key to your Gerrit \
+             account (Settings > GPG Keys) and check that it has not expired",
+        );
+    }
+    None
+}
+
+/// Hosts of well-known forges that never accept `refs/for/` pushes.
+const NON_GERRIT_FORGES: &[&str] = &["github.com", "gitlab.com", "bitbucket.org"];
+
+/// Heuristic: does `url` look like a Gerrit remote that accepts `refs/for/` pushes?
+///
+/// Returns false for known non-Gerrit forges (including their subdomains,
+/// e.g. `ssh.github.com`) and for URLs with no project path at all. Anything
+/// else is given the benefit of the doubt, since self-hosted Gerrit servers
+/// can live on any host.
+pub fn looks_like_gerrit_remote(url: &str) -> bool {
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let host_port = authority.rsplit('@').next().unwrap_or(authority);
+            (host_port.split(':').next().unwrap_or(host_port), path)
+        }
+        // scp-like syntax: [user@]host:path
+        None => match url.split_once(':') {
+            Some((user_host, path)) => (user_host.rsplit('@').next().unwrap_or(user_host), path),
+            // A local path; nothing to judge by.
+            None => return true,
+        },
+    };
+    let host = host.to_ascii_lowercase();
+    if NON_GERRIT_FORGES
+        .iter()
+        .any(|forge| host == *forge || host.ends_with(&format!(".{forge}")))
+    {
+        return false;
+    }
+    !path.trim_matches('/').is_empty()
 }
 
 /// Build the refspec for `git push`, e.g. `HEAD:refs/for/main%topic=foo,r=alice`.
@@ -89,6 +214,39 @@ pub fn build_refspec(opts: &PushOptions) -> Result<String> {
         options.push(format!("notify={notify}"));
     }
 
+    for label in &opts.labels {
+        let trimmed = label.trim();
+        if !is_label_vote(trimmed) {
+            anyhow::bail!("label must be LABEL, LABEL+N or LABEL-N: {label:?}");
+        }
+        options.push(format!("l={trimmed}"));
+    }
+
+    if let Some(ref base) = opts.base {
+        if !(4..=40).contains(&base.len()) || !base.chars().all(|c| c.is_ascii_hexdigit()) {
+            anyhow::bail!("base must be a commit SHA-1: {base:?}");
+        }
+        options.push(format!("base={base}"));
+    }
+
+    if opts.merged {
+        options.push("merged".to_string());
+    }
+
+    if opts.publish_comments {
+        options.push("publish-comments".to_string());
+    }
+
+    for raw in &opts.raw_options {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.contains(|c: char| c.is_whitespace() || c == ',') {
+            anyhow::bail!(
+                "push option must be KEY or KEY=VALUE without whitespace or commas: {raw:?}"
+            );
+        }
+        options.push(trimmed.to_string());
+    }
+
     let refspec = if options.is_empty() {
         format!("HEAD:refs/for/{}", opts.branch)
     } else {
@@ -98,10 +256,376 @@ pub fn build_refspec(opts: &PushOptions) -> Result<String> {
     Ok(refspec)
 }
 
+/// Whether `vote` is a label vote Gerrit accepts in `l=`: a label name
+/// optionally followed by a signed score, e.g. `Verified`, `Code-Review+2`.
+fn is_label_vote(vote: &str) -> bool {
+    let name = match vote.rfind(['+', '-']) {
+        Some(i) if i + 1 < vote.len() && vote[i + 1..].chars().all(|c| c.is_ascii_digit()) => {
+            &vote[..i]
+        }
+        _ => vote,
+    };
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Decode the push options of a `HEAD:refs/for/<branch>%...` refspec into
+/// readable key/value pairs, in refspec order.
+///
+/// Short keys are expanded (`r` → `reviewer`, `m` → `message`) and values are
+/// percent-decoded. Flag options such as `wip` have an empty value.
+pub fn describe_refspec(refspec: &str) -> Vec<(String, String)> {
+    let Some((_, options)) = refspec.split_once('%') else {
+        return Vec::new();
+    };
+    options
+        .split(',')
+        .filter(|opt| !opt.is_empty())
+        .map(|opt| {
+            let (key, value) = opt.split_once('=').unwrap_or((opt, ""));
+            let key = match key {
+                "r" => "reviewer",
+                "m" => "message",
+                "l" => "label",
+                other => other,
+            };
+            let value = urlencoding::decode(value)
+                .map(|v| v.into_owned())
+                .unwrap_or_else(|_| value.to_string());
+            (key.to_string(), value)
+        })
+        .collect()
+}
+
+/// Everything known about where a push could be aimed, from most to least specific.
+#[derive(Debug, Default)]
+pub struct TargetBranchFacts {
+    /// Branch passed on the command line.
+    pub positional: Option<String>,
+    /// Upstream resolved by `--track`.
+    pub tracked: Option<String>,
+    /// Branch set explicitly in `.gitreview`, config, or the environment.
+    pub configured: Option<String>,
+    /// The remote's default branch (`refs/remotes/<remote>/HEAD`).
+    pub remote_default: Option<String>,
+    /// Built-in default used when nothing else is known.
+    pub fallback: String,
+}
+
+/// Pick the push target branch.
+///
+/// Order: positional argument, `--track` upstream, explicitly configured
+/// branch, the remote's default branch, then the built-in default. The
+/// remote default keeps a `main`-only repo without `defaultbranch` from
+/// pushing to `master`.
+pub fn resolve_target_branch(facts: TargetBranchFacts) -> String {
+    facts
+        .positional
+        .or(facts.tracked)
+        .or(facts.configured)
+        .or(facts.remote_default)
+        .unwrap_or(facts.fallback)
+}
+
+/// Values available to `{placeholder}`s in `--message` and the
+/// `push.message_template` setting.
+#[derive(Debug, Default)]
+pub struct MessageContext {
+    /// Change-Id of the commit being pushed (`{change}`).
+    pub change: Option<String>,
+    /// Target branch (`{branch}`).
+    pub branch: Option<String>,
+    /// Patchset number the push will create (`{patchset}`).
+    pub patchset: Option<i32>,
+    /// Topic given with `--topic` (`{topic}`).
+    pub topic: Option<String>,
+    /// Ticket the commit refers to, see [`find_ticket`] (`{ticket}`).
+    pub ticket: Option<String>,
+}
+
+/// Substitute `{change}`, `{branch}`, `{patchset}`, `{topic}` and `{ticket}`
+/// in a review message.
+///
+/// Unknown placeholders, and known ones whose value is unavailable, are kept
+/// literally unless `strict` is set, in which case they are an error. Braces
+/// around anything that is not a plain lowercase name (e.g. `{ }` or JSON)
+/// are never treated as placeholders.
+pub fn expand_message_template(msg: &str, ctx: &MessageContext, strict: bool) -> Result<String> {
+    let mut out = String::with_capacity(msg.len());
+    let mut rest = msg;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let name = after.find('}').map(|close| &after[..close]).filter(|name| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+        });
+        let Some(name) = name else {
+            out.push('{');
+            rest = after;
+            continue;
+        };
+        let value = match name {
+            "change" => ctx.change.clone(),
+            "branch" => ctx.branch.clone(),
+            "patchset" => ctx.patchset.map(|ps| ps.to_string()),
+            "topic" => ctx.topic.clone(),
+            "ticket" => ctx.ticket.clone(),
+            _ => None,
+        };
+        match value {
+            Some(value) => out.push_str(&value),
+            None if strict => anyhow::bail!("cannot expand {{{name}}} in the review message"),
+            None => {
+                out.push('{');
+                out.push_str(name);
+                out.push('}');
+            }
+        }
+        rest = &after[name.len() + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// The ticket a commit refers to, for `{ticket}`.
+///
+/// The first of the `topic_from_trailer` trailers in `commit_message` wins
+/// (see [`topic_from_trailers`]); otherwise the local branch name is searched
+/// for an issue key such as `ABC-123`, or a `bug/<number>` style suffix.
+pub fn find_ticket(
+    commit_message: &str,
+    trailers: &[String],
+    local_branch: Option<&str>,
+) -> Option<String> {
+    topic_from_trailers(commit_message, trailers).or_else(|| local_branch.and_then(branch_ticket))
+}
+
+fn branch_ticket(branch: &str) -> Option<String> {
+    let is_key = |word: &&str| {
+        word.split_once('-').is_some_and(|(project, number)| {
+            project.starts_with(|c: char| c.is_ascii_uppercase())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        })
+    };
+    let words = || branch.split(['/', '_', '.']);
+    if let Some(key) = words().find(is_key) {
+        return Some(key.to_string());
+    }
+    // `ABC-123-fix-login` keeps its key in the first two dash-separated parts
+    if let Some(key) = words().find_map(|word| {
+        let mut parts = word.splitn(3, '-');
+        let key = format!("{}-{}", parts.next()?, parts.next()?);
+        is_key(&key.as_str()).then_some(key)
+    }) {
+        return Some(key);
+    }
+    let (_, last) = branch.rsplit_once('/')?;
+    (!last.is_empty() && last.chars().all(|c| c.is_ascii_digit())).then(|| last.to_string())
+}
+
+/// Refspecs longer than this risk git or Gerrit rejecting the push.
+pub const MAX_REFSPEC_LEN: usize = 1024;
+
+/// Build the refspec, moving an over-long `--message` out of it.
+///
+/// If the encoded message would push the refspec past [`MAX_REFSPEC_LEN`],
+/// the message is taken out of `opts` and returned alongside the shortened
+/// refspec so it can be posted through the review API after the push.
+pub fn build_refspec_deferring_message(opts: &mut PushOptions) -> Result<(String, Option<String>)> {
+    let refspec = build_refspec(opts)?;
+    if refspec.len() <= MAX_REFSPEC_LEN || opts.message.is_none() {
+        return Ok((refspec, None));
+    }
+    let message = opts.message.take();
+    Ok((build_refspec(opts)?, message))
+}
+
+/// A change that Gerrit reported in the `remote:` lines of a push.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PushedChange {
+    pub number: u64,
+    pub url: String,
+    /// Subject as Gerrit printed it, without the `[NEW]`/`[WIP]` markers.
+    pub subject: String,
+}
+
+/// Parse change URLs such as `remote:   https://host/c/proj/+/123 Subject`
+/// from `git push` output, in the order Gerrit listed them.
+pub fn parse_pushed_changes(output: &str) -> Vec<PushedChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("remote:")?.trim();
+            let (url, subject) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                return None;
+            }
+            let tail = url.trim_end_matches('/').rsplit('/').next()?;
+            let number = tail.parse::<u64>().ok()?;
+            Some(PushedChange {
+                number,
+                url: url.to_string(),
+                subject: strip_change_markers(subject).to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Drop the trailing `[NEW]`, `[WIP]`, `[PRIVATE]`... markers from a pushed subject.
+fn strip_change_markers(subject: &str) -> &str {
+    let mut subject = subject.trim();
+    while let Some(rest) = subject.strip_suffix(']') {
+        match rest.rfind(" [") {
+            Some(i) if !rest[i + 2..].contains(char::is_whitespace) => {
+                subject = rest[..i].trim_end()
+            }
+            _ => break,
+        }
+    }
+    subject
+}
+
+/// Find HEAD's change among those Gerrit listed after a push.
+///
+/// Gerrit does not print Change-Ids, and the order of the list is not
+/// guaranteed, so match on HEAD's subject. Gerrit crops long subjects with
+/// `...`, which is matched as a prefix.
+pub fn head_pushed_change<'a>(
+    pushed: &'a [PushedChange],
+    head_message: &str,
+) -> Option<&'a PushedChange> {
+    let subject = head_message.lines().next().unwrap_or("").trim();
+    pushed.iter().find(|change| {
+        change.subject == subject
+            || change
+                .subject
+                .strip_suffix("...")
+                .is_some_and(|prefix| !prefix.is_empty() && subject.starts_with(prefix))
+    })
+}
+
+/// Render the `key=value` lines written by `--change-id-file`.
+pub fn change_id_file_contents(change_id: &str, pushed: Option<&PushedChange>) -> String {
+    let mut out = format!("change_id={change_id}\n");
+    if let Some(change) = pushed {
+        out.push_str(&format!("change_number={}\n", change.number));
+        out.push_str(&format!("change_url={}\n", change.url));
+    }
+    out
+}
+
+/// Write `refspec` on its own line, with nothing else, for `--print-refspec`.
+pub fn write_refspec(out: &mut impl std::io::Write, refspec: &str) -> std::io::Result<()> {
+    writeln!(out, "{refspec}")
+}
+
+/// Append configured default reviewers to an explicit reviewer list.
+///
+/// Reviewers already present (case-insensitive) are not added twice.
+pub fn merge_default_reviewers(reviewers: &mut Vec<String>, defaults: &[String]) {
+    for reviewer in defaults {
+        if !reviewers.iter().any(|r| r.eq_ignore_ascii_case(reviewer)) {
+            reviewers.push(reviewer.clone());
+        }
+    }
+}
+
+/// Drop CC entries that are also reviewers (case-insensitive); reviewer wins.
+pub fn reconcile_reviewers_cc(
+    reviewers: Vec<String>,
+    cc: Vec<String>,
+) -> (Vec<String>, Vec<String>) {
+    let cc = cc
+        .into_iter()
+        .filter(|c| {
+            !reviewers
+                .iter()
+                .any(|r| r.trim().eq_ignore_ascii_case(c.trim()))
+        })
+        .collect();
+    (reviewers, cc)
+}
+
+/// Remove the pushing user from `reviewers` and return the removed entries.
+///
+/// `self_ids` holds the account's username and email; matching is
+/// case-insensitive. Gerrit rejects a push that adds the owner as a reviewer.
+pub fn strip_self_reviewer(reviewers: &mut Vec<String>, self_ids: &[String]) -> Vec<String> {
+    let mut removed = Vec::new();
+    reviewers.retain(|r| {
+        let is_self = self_ids.iter().any(|id| id.eq_ignore_ascii_case(r.trim()));
+        if is_self {
+            removed.push(r.clone());
+        }
+        !is_self
+    });
+    removed
+}
+
+/// Strip the `@` marker from `@group` entries and return the group names.
+///
+/// Gerrit expands a group name passed as a reviewer or CC; the `@` only
+/// tells grt which entries to check against the groups API.
+pub fn take_group_markers(recipients: &mut [String]) -> Vec<String> {
+    let mut groups = Vec::new();
+    for entry in recipients.iter_mut() {
+        if let Some(name) = entry.strip_prefix('@') {
+            *entry = name.to_string();
+            groups.push(entry.clone());
+        }
+    }
+    groups
+}
+
+/// Merge configured default reviewers and CC recipients into the push lists.
+///
+/// Does nothing when `skip` is set (`--no-default-reviewers`).
+pub fn apply_default_recipients(
+    reviewers: &mut Vec<String>,
+    cc: &mut Vec<String>,
+    config: &GerritConfig,
+    skip: bool,
+) {
+    if skip {
+        return;
+    }
+    merge_default_reviewers(reviewers, &config.default_reviewers);
+    merge_default_reviewers(cc, &config.default_cc);
+}
+
+/// Gerrit query for open changes among `change_ids` on `project`/`branch`.
+pub fn open_changes_query(change_ids: &[String], project: &str, branch: &str) -> String {
+    let ids: Vec<String> = change_ids.iter().map(|id| format!("change:{id}")).collect();
+    format!(
+        "status:open project:{project} branch:{branch} ({})",
+        ids.join(" OR ")
+    )
+}
+
+/// Return the Change-Ids in `ids` that `is_open` reports as existing open changes.
+///
+/// The caller supplies the lookup (typically backed by one REST query) so the
+/// partitioning stays independent of the Gerrit client.
+pub fn already_pushed_change_ids<F>(ids: &[String], mut is_open: F) -> HashSet<String>
+where
+    F: FnMut(&str) -> bool,
+{
+    ids.iter().filter(|id| is_open(id)).cloned().collect()
+}
+
 /// Extract the Change-Id trailer value from a commit message.
 /// Returns `Some("I<hex>")` if found, `None` otherwise.
+///
+/// Lines are split on both `\n` and `\r` so messages authored with CRLF or
+/// lone-CR line endings still yield a clean 41-character id.
 pub fn extract_change_id(commit_message: &str) -> Option<String> {
-    for line in commit_message.lines().rev() {
+    for line in commit_message.split(['\n', '\r']).rev() {
         let trimmed = line.trim();
         if let Some(id) = trimmed.strip_prefix("Change-Id: ") {
             let id = id.trim();
@@ -116,6 +640,45 @@ pub fn extract_change_id(commit_message: &str) -> Option<String> {
     None
 }
 
+/// The lines of the trailer block: the final paragraph of `commit_message`,
+/// unless the message is a single paragraph (just the subject and body).
+fn trailer_paragraph(commit_message: &str) -> Vec<&str> {
+    let lines: Vec<&str> = commit_message
+        .lines()
+        .flat_map(|line| line.split('\r'))
+        .map(str::trim)
+        .collect();
+    let Some(end) = lines.iter().rposition(|line| !line.is_empty()) else {
+        return Vec::new();
+    };
+    match lines[..end].iter().rposition(|line| line.is_empty()) {
+        Some(blank) => lines[blank + 1..=end].to_vec(),
+        None => Vec::new(),
+    }
+}
+
+/// The topic named by the first of `trailers` present in `commit_message`.
+///
+/// Only the final paragraph is searched, so a `Closes-Bug:` line quoted in
+/// the body is ignored. Trailer keys match case-insensitively and the last
+/// occurrence of a key wins. The value's first word is used, without a
+/// leading `#`, so `Closes-Bug: #2045123` gives `2045123` and
+/// `JIRA: ABC-42, ABC-43` gives `ABC-42`.
+pub fn topic_from_trailers(commit_message: &str, trailers: &[String]) -> Option<String> {
+    let block = trailer_paragraph(commit_message);
+    trailers.iter().find_map(|trailer| {
+        block.iter().rev().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if !key.trim().eq_ignore_ascii_case(trailer) {
+                return None;
+            }
+            let word = value.split_whitespace().next()?;
+            let topic = word.trim_end_matches([',', ';']).trim_start_matches('#');
+            (!topic.is_empty()).then(|| topic.to_string())
+        })
+    })
+}
+
 /// Status of the Change-Id in a commit message.
 #[derive(Debug, PartialEq, Eq)]
 pub enum ChangeIdStatus {
@@ -147,10 +710,84 @@ pub fn validate_change_id(commit_message: &str) -> Result<String> {
         .context("HEAD commit is missing a Change-Id trailer. Run `grt setup` to install the commit-msg hook, then amend the commit")
 }
 
+/// Confirm that amending HEAD through the commit-msg hook added a Change-Id.
+///
+/// `before` and `after` are the HEAD commit messages around the amend. A hook
+/// that exists but is not executable (e.g. copied by another tool) is skipped
+/// silently by git, leaving the message untouched.
+pub fn verify_amend_added_change_id(before: &str, after: &str) -> Result<String> {
+    if let Some(id) = extract_change_id(after) {
+        return Ok(id);
+    }
+    if before.trim_end() == after.trim_end() {
+        anyhow::bail!(
+            "the commit-msg hook did not run: amending left the commit message unchanged. \
+             Check that .git/hooks/commit-msg is executable, or reinstall it with `grt setup --force-hook`"
+        );
+    }
+    anyhow::bail!(
+        "the commit-msg hook ran but did not add a Change-Id trailer. \
+         Reinstall it with `grt setup --force-hook`"
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn push_result_json_fields() {
+        let result = PushResult {
+            commits: 2,
+            remote: "gerrit".into(),
+            branch: "main".into(),
+            change_id: Some("I0123456789abcdef0123456789abcdef01234567".into()),
+            refspec: "HEAD:refs/for/main".into(),
+            dry_run: true,
+            change_number: None,
+            change_url: None,
+            changes: Vec::new(),
+        };
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["commits"], 2);
+        assert_eq!(value["refspec"], "HEAD:refs/for/main");
+        assert_eq!(value["dry_run"], true);
+        assert_eq!(
+            value["change_id"],
+            "I0123456789abcdef0123456789abcdef01234567"
+        );
+        assert!(value["change_number"].is_null());
+    }
+
+    #[test]
+    fn push_result_json_includes_pushed_changes() {
+        let changes = parse_pushed_changes(
+            "remote: \n\
+             remote:   https://review.example.org/c/proj/+/101 Base [NEW]\n\
+             remote:   https://review.example.org/c/proj/+/102 Top [NEW]\n",
+        );
+        let head = head_pushed_change(&changes, "Top\n").cloned().unwrap();
+        let result = PushResult {
+            commits: 2,
+            remote: "gerrit".into(),
+            branch: "main".into(),
+            change_id: None,
+            refspec: "HEAD:refs/for/main".into(),
+            dry_run: false,
+            change_number: Some(head.number),
+            change_url: Some(head.url),
+            changes,
+        };
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["change_number"], 102);
+        assert_eq!(
+            value["change_url"],
+            "https://review.example.org/c/proj/+/102"
+        );
+        assert_eq!(value["changes"][0]["number"], 101);
+        assert_eq!(value["changes"].as_array().unwrap().len(), 2);
+    }
+
     fn opts(branch: &str) -> PushOptions {
         PushOptions {
             branch: branch.to_string(),
@@ -172,6 +809,275 @@ mod tests {
         assert_eq!(refspec, "HEAD:refs/for/main%topic=my-feature");
     }
 
+    #[test]
+    fn already_pushed_partitions_existing_and_new() {
+        let ids: Vec<String> = ["Iaaa", "Ibbb", "Iccc"].map(String::from).to_vec();
+        let open: HashSet<&str> = ["Iaaa", "Iccc", "Izzz"].into_iter().collect();
+        let existing = already_pushed_change_ids(&ids, |id| open.contains(id));
+        assert_eq!(
+            existing,
+            HashSet::from(["Iaaa".to_string(), "Iccc".to_string()])
+        );
+        let new: Vec<&String> = ids.iter().filter(|id| !existing.contains(*id)).collect();
+        assert_eq!(new, [&"Ibbb".to_string()]);
+    }
+
+    #[test]
+    fn already_pushed_none_existing() {
+        let ids = vec!["Iaaa".to_string()];
+        assert!(already_pushed_change_ids(&ids, |_| false).is_empty());
+    }
+
+    #[test]
+    fn open_changes_query_ors_change_ids() {
+        let ids = vec!["Iaaa".to_string(), "Ibbb".to_string()];
+        assert_eq!(
+            open_changes_query(&ids, "nova", "main"),
+            "status:open project:nova branch:main (change:Iaaa OR change:Ibbb)"
+        );
+    }
+
+    #[test]
+    fn github_urls_are_not_gerrit() {
+        assert!(!looks_like_gerrit_remote(
+            "https://github.com/openstack/nova.git"
+        ));
+        assert!(!looks_like_gerrit_remote(
+            "git@github.com:openstack/nova.git"
+        ));
+        assert!(!looks_like_gerrit_remote(
+            "ssh://git@ssh.github.com:443/openstack/nova.git"
+        ));
+        assert!(!looks_like_gerrit_remote(
+            "https://GitLab.com/group/project"
+        ));
+    }
+
+    #[test]
+    fn gerrit_urls_look_like_gerrit() {
+        assert!(looks_like_gerrit_remote(
+            "ssh://alice@review.opendev.org:29418/openstack/nova.git"
+        ));
+        assert!(looks_like_gerrit_remote(
+            "https://review.opendev.org/openstack/nova"
+        ));
+        assert!(looks_like_gerrit_remote("/srv/git/nova.git"));
+    }
+
+    #[test]
+    fn url_without_project_path_is_not_gerrit() {
+        assert!(!looks_like_gerrit_remote("https://review.example.com/"));
+    }
+
+    fn all_branch_facts() -> TargetBranchFacts {
+        TargetBranchFacts {
+            positional: Some("positional".into()),
+            tracked: Some("tracked".into()),
+            configured: Some("configured".into()),
+            remote_default: Some("main".into()),
+            fallback: "master".into(),
+        }
+    }
+
+    #[test]
+    fn target_branch_prefers_positional() {
+        assert_eq!(resolve_target_branch(all_branch_facts()), "positional");
+    }
+
+    #[test]
+    fn target_branch_then_tracked() {
+        let facts = TargetBranchFacts {
+            positional: None,
+            ..all_branch_facts()
+        };
+        assert_eq!(resolve_target_branch(facts), "tracked");
+    }
+
+    #[test]
+    fn target_branch_then_configured() {
+        let facts = TargetBranchFacts {
+            positional: None,
+            tracked: None,
+            ..all_branch_facts()
+        };
+        assert_eq!(resolve_target_branch(facts), "configured");
+    }
+
+    #[test]
+    fn target_branch_then_remote_default() {
+        let facts = TargetBranchFacts {
+            positional: None,
+            tracked: None,
+            configured: None,
+            ..all_branch_facts()
+        };
+        assert_eq!(resolve_target_branch(facts), "main");
+    }
+
+    #[test]
+    fn target_branch_falls_back_to_default() {
+        let facts = TargetBranchFacts {
+            fallback: "master".into(),
+            ..Default::default()
+        };
+        assert_eq!(resolve_target_branch(facts), "master");
+    }
+
+    fn template_ctx() -> MessageContext {
+        MessageContext {
+            change: Some("I0123456789abcdef0123456789abcdef01234567".to_string()),
+            branch: Some("stable/2024.1".to_string()),
+            patchset: Some(4),
+            topic: Some("fix-login".to_string()),
+            ticket: Some("ABC-42".to_string()),
+        }
+    }
+
+    #[test]
+    fn template_substitutes_topic_and_ticket() {
+        let msg = expand_message_template("[{ticket}] {topic} on {branch}", &template_ctx(), true)
+            .unwrap();
+        assert_eq!(msg, "[ABC-42] fix-login on stable/2024.1");
+    }
+
+    #[test]
+    fn find_ticket_prefers_trailer() {
+        let msg = "Fix login\n\nCloses-Bug: #2045123\nChange-Id: I0123\n";
+        let trailers = vec!["Closes-Bug".to_string()];
+        assert_eq!(
+            find_ticket(msg, &trailers, Some("ABC-1-login")).as_deref(),
+            Some("2045123")
+        );
+    }
+
+    #[test]
+    fn find_ticket_from_branch_name() {
+        let ticket = |branch| find_ticket("Fix login", &[], Some(branch));
+        assert_eq!(ticket("ABC-123").as_deref(), Some("ABC-123"));
+        assert_eq!(
+            ticket("feature/ABC-123-fix-login").as_deref(),
+            Some("ABC-123")
+        );
+        assert_eq!(ticket("bug/2045123").as_deref(), Some("2045123"));
+        assert_eq!(ticket("fix-login"), None);
+        assert_eq!(ticket("release-2"), None);
+        assert_eq!(find_ticket("Fix login", &[], None), None);
+    }
+
+    #[test]
+    fn template_substitutes_placeholders() {
+        let msg = expand_message_template(
+            "PS{patchset} of {change} for {branch}",
+            &template_ctx(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            msg,
+            "PS4 of I0123456789abcdef0123456789abcdef01234567 for stable/2024.1"
+        );
+    }
+
+    #[test]
+    fn template_passes_unknown_placeholders_through() {
+        let msg =
+            expand_message_template("see {docs} and {branch}", &template_ctx(), false).unwrap();
+        assert_eq!(msg, "see {docs} and stable/2024.1");
+    }
+
+    #[test]
+    fn template_leaves_non_placeholder_braces_alone() {
+        let msg =
+            expand_message_template("{ } {\"a\": 1} {Branch} trailing {", &template_ctx(), true)
+                .unwrap();
+        assert_eq!(msg, "{ } {\"a\": 1} {Branch} trailing {");
+    }
+
+    #[test]
+    fn template_unavailable_value_is_literal() {
+        let ctx = MessageContext::default();
+        let msg = expand_message_template("PS{patchset}", &ctx, false).unwrap();
+        assert_eq!(msg, "PS{patchset}");
+    }
+
+    #[test]
+    fn template_strict_rejects_unknown_placeholder() {
+        let err = expand_message_template("see {docs}", &template_ctx(), true).unwrap_err();
+        assert!(err.to_string().contains("{docs}"), "{err}");
+    }
+
+    #[test]
+    fn write_refspec_is_exactly_refspec_and_newline() {
+        let mut o = opts("main");
+        o.topic = Some("foo".to_string());
+        let refspec = build_refspec(&o).unwrap();
+        let mut out = Vec::new();
+        write_refspec(&mut out, &refspec).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HEAD:refs/for/main%topic=foo\n"
+        );
+    }
+
+    #[test]
+    fn short_message_stays_in_refspec() {
+        let mut o = opts("main");
+        o.message = Some("Rebased on main".to_string());
+        let (refspec, deferred) = build_refspec_deferring_message(&mut o).unwrap();
+        assert!(refspec.contains("m=Rebased%20on%20main"), "{refspec}");
+        assert!(deferred.is_none());
+    }
+
+    #[test]
+    fn long_message_is_deferred_to_review_api() {
+        let long = "Addressed review comments. ".repeat(60);
+        let mut o = opts("main");
+        o.topic = Some("feature".to_string());
+        o.message = Some(long.clone());
+        let (refspec, deferred) = build_refspec_deferring_message(&mut o).unwrap();
+        assert_eq!(refspec, "HEAD:refs/for/main%topic=feature");
+        assert_eq!(deferred.as_deref(), Some(long.as_str()));
+    }
+
+    #[test]
+    fn long_refspec_without_message_is_unchanged() {
+        let mut o = opts("main");
+        o.reviewers = (0..200).map(|i| format!("reviewer{i}")).collect();
+        let (refspec, deferred) = build_refspec_deferring_message(&mut o).unwrap();
+        assert!(refspec.len() > MAX_REFSPEC_LEN);
+        assert!(deferred.is_none());
+    }
+
+    #[test]
+    fn describe_refspec_without_options() {
+        assert!(describe_refspec("HEAD:refs/for/main").is_empty());
+    }
+
+    #[test]
+    fn describe_refspec_decodes_options() {
+        let mut o = opts("main");
+        o.topic = Some("my-feature".to_string());
+        o.wip = true;
+        o.reviewers = vec!["alice".into()];
+        o.message = Some("Fix: handle a,b & 100%".to_string());
+        let refspec = build_refspec(&o).unwrap();
+
+        let described = describe_refspec(&refspec);
+        let pairs: Vec<(&str, &str)> = described
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("topic", "my-feature"),
+                ("wip", ""),
+                ("reviewer", "alice"),
+                ("message", "Fix: handle a,b & 100%"),
+            ]
+        );
+    }
+
     #[test]
     fn build_refspec_with_wip() {
         let mut o = opts("main");
@@ -235,6 +1141,176 @@ mod tests {
         assert_eq!(refspec, "HEAD:refs/for/main");
     }
 
+    #[test]
+    fn take_group_markers_strips_at_prefix() {
+        let mut reviewers = vec!["alice".to_string(), "@core-team".to_string()];
+        let groups = take_group_markers(&mut reviewers);
+        assert_eq!(groups, vec!["core-team"]);
+        assert_eq!(reviewers, vec!["alice", "core-team"]);
+    }
+
+    #[test]
+    fn merge_default_reviewers_appends() {
+        let mut reviewers = vec!["alice".to_string()];
+        merge_default_reviewers(&mut reviewers, &["bob".into(), "carol".into()]);
+        assert_eq!(reviewers, vec!["alice", "bob", "carol"]);
+    }
+
+    #[test]
+    fn merge_default_reviewers_skips_duplicates() {
+        let mut reviewers = vec!["Alice".to_string()];
+        merge_default_reviewers(&mut reviewers, &["alice".into(), "bob".into()]);
+        assert_eq!(reviewers, vec!["Alice", "bob"]);
+    }
+
+    fn config_with_defaults() -> GerritConfig {
+        GerritConfig {
+            default_reviewers: vec!["bob".into()],
+            default_cc: vec!["carol".into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn apply_default_recipients_merges_reviewers_and_cc() {
+        let mut reviewers = vec!["alice".to_string()];
+        let mut cc = Vec::new();
+        apply_default_recipients(&mut reviewers, &mut cc, &config_with_defaults(), false);
+        assert_eq!(reviewers, vec!["alice", "bob"]);
+        assert_eq!(cc, vec!["carol"]);
+    }
+
+    #[test]
+    fn apply_default_recipients_opt_out() {
+        let mut reviewers = vec!["alice".to_string()];
+        let mut cc = Vec::new();
+        apply_default_recipients(&mut reviewers, &mut cc, &config_with_defaults(), true);
+        assert_eq!(reviewers, vec!["alice"]);
+        assert!(cc.is_empty());
+    }
+
+    #[test]
+    fn build_refspec_includes_default_recipients() {
+        let mut o = opts("main");
+        o.reviewers = vec!["alice".into()];
+        apply_default_recipients(&mut o.reviewers, &mut o.cc, &config_with_defaults(), false);
+        let refspec = build_refspec(&o).unwrap();
+        assert_eq!(refspec, "HEAD:refs/for/main%r=alice,r=bob,cc=carol");
+    }
+
+    #[test]
+    fn build_refspec_no_default_reviewers_omits_defaults() {
+        let mut o = opts("main");
+        o.reviewers = vec!["alice".into()];
+        apply_default_recipients(&mut o.reviewers, &mut o.cc, &config_with_defaults(), true);
+        let refspec = build_refspec(&o).unwrap();
+        assert_eq!(refspec, "HEAD:refs/for/main%r=alice");
+        assert!(!refspec.contains("r=bob"), "refspec: {refspec}");
+        assert!(!refspec.contains("cc=carol"), "refspec: {refspec}");
+    }
+
+    fn flags() -> GitPushFlags {
+        GitPushFlags {
+            color: "always".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn build_push_args_basic() {
+        let args = build_push_args(&flags(), "gerrit", "HEAD:refs/for/main");
+        assert_eq!(
+            args,
+            vec![
+                "-c",
+                "color.remote=always",
+                "push",
+                "--no-follow-tags",
+                "gerrit",
+                "HEAD:refs/for/main"
+            ]
+        );
+    }
+
+    #[test]
+    fn build_push_args_signed() {
+        let mut f = flags();
+        f.signed = true;
+        let args = build_push_args(&f, "gerrit", "HEAD:refs/for/main");
+        assert!(args.contains(&"--signed".to_string()), "args: {args:?}");
+        assert_eq!(args.last().map(String::as_str), Some("HEAD:refs/for/main"));
+    }
+
+    #[test]
+    fn build_push_args_progress() {
+        let mut f = flags();
+        f.progress = true;
+        let args = build_push_args(&f, "gerrit", "HEAD:refs/for/main");
+        assert!(args.contains(&"--progress".to_string()), "args: {args:?}");
+        assert!(!build_push_args(&flags(), "gerrit", "HEAD:refs/for/main")
+            .contains(&"--progress".to_string()));
+    }
+
+    #[test]
+    fn build_push_args_unsigned_omits_signed() {
+        let args = build_push_args(&flags(), "gerrit", "HEAD:refs/for/main");
+        assert!(!args.contains(&"--signed".to_string()));
+    }
+
+    #[test]
+    fn build_push_args_no_thin() {
+        let mut f = flags();
+        f.no_thin = true;
+        let args = build_push_args(&f, "gerrit", "HEAD:refs/for/main");
+        assert!(args.contains(&"--no-thin".to_string()));
+    }
+
+    #[test]
+    fn push_failure_hint_missing_tree() {
+        let hint = push_failure_hint("error: Missing tree abc", false);
+        assert_eq!(hint, Some("Consider trying again with --no-thin"));
+    }
+
+    #[test]
+    fn push_failure_hint_signed_unrelated_failure() {
+        let hint = push_failure_hint(
+            "git push failed (exit 1)\n ! [remote rejected] HEAD -> refs/for/master (no new changes)",
+            true,
+        );
+        assert!(hint.is_none(), "hint: {hint:?}");
+    }
+
+    #[test]
+    fn push_failure_hint_signed_push_unsupported() {
+        let hint = push_failure_hint(
+            "git push failed (exit 128)\nfatal: the receiving end does not support --signed push",
+            true,
+        )
+        .unwrap();
+        assert!(hint.contains("receive.enableSignedPush"), "hint: {hint}");
+    }
+
+    #[test]
+    fn push_failure_hint_rejected_certificate() {
+        let hint = push_failure_hint(
+            "remote: error: push cert error: No public keys found for key ID 1234ABCD",
+            true,
+        )
+        .unwrap();
+        assert!(hint.contains("GPG Keys"), "hint: {hint}");
+    }
+
+    #[test]
+    fn push_failure_hint_gpg_failure() {
+        let hint = push_failure_hint("error: gpg failed to sign the data", true).unwrap();
+        assert!(hint.contains("user.signingKey"), "hint: {hint}");
+    }
+
+    #[test]
+    fn push_failure_hint_none() {
+        assert!(push_failure_hint("git push failed (exit 1)", false).is_none());
+    }
+
     #[test]
     fn detect_change_id_present() {
         let msg = "Fix bug\n\nSome description.\n\nChange-Id: I1234567890abcdef1234567890abcdef12345678\n";
@@ -251,6 +1327,47 @@ mod tests {
         assert!(extract_change_id(msg).is_none());
     }
 
+    #[test]
+    fn topic_from_trailers_prefers_configured_order() {
+        let trailers = vec!["Closes-Bug".to_string(), "JIRA".to_string()];
+        let msg = "Fix crash\n\nJIRA: ABC-42, ABC-43\ncloses-bug: #2045123\nChange-Id: I1234567890abcdef1234567890abcdef12345678\n";
+        assert_eq!(
+            topic_from_trailers(msg, &trailers).as_deref(),
+            Some("2045123")
+        );
+        let msg = "Fix crash\n\nJIRA: ABC-42, ABC-43\n";
+        assert_eq!(
+            topic_from_trailers(msg, &trailers).as_deref(),
+            Some("ABC-42")
+        );
+    }
+
+    #[test]
+    fn topic_from_trailers_none_without_match() {
+        let trailers = vec!["Closes-Bug".to_string()];
+        assert!(topic_from_trailers("Fix crash\n\nRelated-Bug: #1\n", &trailers).is_none());
+        assert!(topic_from_trailers("Fix crash\n\nCloses-Bug:\n", &trailers).is_none());
+        assert!(topic_from_trailers("Fix crash\n", &[]).is_none());
+    }
+
+    #[test]
+    fn topic_from_trailers_ignores_body_lines() {
+        let trailers = vec!["Closes-Bug".to_string()];
+        let msg = "Fix crash\n\nCloses-Bug: #1 was only half fixed.\n\nChange-Id: I1234567890abcdef1234567890abcdef12345678\n";
+        assert!(topic_from_trailers(msg, &trailers).is_none());
+        assert!(topic_from_trailers("Closes-Bug: #1\n", &trailers).is_none());
+    }
+
+    #[test]
+    fn topic_from_trailers_crlf() {
+        let trailers = vec!["Closes-Bug".to_string()];
+        let msg = "Fix crash\r\n\r\nCloses-Bug: #2045123\r\nChange-Id: I1234567890abcdef1234567890abcdef12345678\r\n";
+        assert_eq!(
+            topic_from_trailers(msg, &trailers).as_deref(),
+            Some("2045123")
+        );
+    }
+
     #[test]
     fn detect_change_id_multiple_trailers() {
         let msg = "Fix bug\n\nSigned-off-by: Alice <alice@example.com>\nChange-Id: Iabcdef1234567890abcdef1234567890abcdef12\nReviewed-by: Bob <bob@example.com>\n";
@@ -261,6 +1378,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn detect_change_id_crlf() {
+        let msg = "Fix bug\r\n\r\nChange-Id: I1234567890abcdef1234567890abcdef12345678\r\n";
+        assert_eq!(
+            extract_change_id(msg).as_deref(),
+            Some("I1234567890abcdef1234567890abcdef12345678")
+        );
+    }
+
+    #[test]
+    fn detect_change_id_lone_cr() {
+        let msg = "Fix bug\r\rChange-Id: I1234567890abcdef1234567890abcdef12345678\r";
+        assert_eq!(
+            extract_change_id(msg).as_deref(),
+            Some("I1234567890abcdef1234567890abcdef12345678")
+        );
+    }
+
     #[test]
     fn build_refspec_rejects_whitespace_in_cc() {
         let mut o = opts("main");
@@ -277,6 +1412,192 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn reconcile_reviewers_cc_removes_overlap_from_cc() {
+        let (reviewers, cc) = reconcile_reviewers_cc(
+            vec!["alice".into(), "Bob@Example.com".into()],
+            vec!["bob@example.com".into(), "carol".into(), "ALICE".into()],
+        );
+        assert_eq!(reviewers, vec!["alice", "Bob@Example.com"]);
+        assert_eq!(cc, vec!["carol"]);
+    }
+
+    #[test]
+    fn reconcile_reviewers_cc_disjoint_unchanged() {
+        let (reviewers, cc) =
+            reconcile_reviewers_cc(vec!["alice".into()], vec!["bob".into(), "carol".into()]);
+        assert_eq!(reviewers, vec!["alice"]);
+        assert_eq!(cc, vec!["bob", "carol"]);
+    }
+
+    #[test]
+    fn parse_pushed_changes_from_remote_lines() {
+        let output = "remote: Processing changes: new: 1, done\n\
+                      remote:\n\
+                      remote: SUCCESS\n\
+                      remote:\n\
+                      remote:   https://review.example.com/c/proj/+/1234 Fix a bug [NEW]\n\
+                      remote:\n\
+                      To ssh://review.example.com:29418/proj\n";
+        assert_eq!(
+            parse_pushed_changes(output),
+            vec![PushedChange {
+                number: 1234,
+                url: "https://review.example.com/c/proj/+/1234".to_string(),
+                subject: "Fix a bug".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn head_pushed_change_matches_subject_not_position() {
+        let pushed = parse_pushed_changes(
+            "remote:   https://review.example.org/c/proj/+/102 Top [WIP] [NEW]\n\
+             remote:   https://review.example.org/c/proj/+/101 Base\n",
+        );
+        let head = head_pushed_change(&pushed, "Top\n\nChange-Id: Iabc\n").unwrap();
+        assert_eq!(head.number, 102);
+        assert!(head_pushed_change(&pushed, "Unrelated\n").is_none());
+    }
+
+    #[test]
+    fn head_pushed_change_matches_cropped_subject() {
+        let pushed = parse_pushed_changes(
+            "remote:   https://review.example.org/c/proj/+/7 Refactor the pars... [NEW]\n",
+        );
+        let head = head_pushed_change(&pushed, "Refactor the parser for better errors\n");
+        assert_eq!(head.map(|c| c.number), Some(7));
+    }
+
+    #[test]
+    fn parse_pushed_changes_ignores_other_urls() {
+        let output = "remote: see https://review.example.com/Documentation/error.html\n";
+        assert!(parse_pushed_changes(output).is_empty());
+    }
+
+    #[test]
+    fn change_id_file_without_pushed_change_has_only_change_id() {
+        let msg = "Fix bug\n\nChange-Id: I1234567890abcdef1234567890abcdef12345678\n";
+        let change_id = extract_change_id(msg).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("change.env");
+        std::fs::write(&path, change_id_file_contents(&change_id, None)).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            written,
+            "change_id=I1234567890abcdef1234567890abcdef12345678\n"
+        );
+    }
+
+    #[test]
+    fn change_id_file_includes_pushed_change() {
+        let change = PushedChange {
+            number: 42,
+            url: "https://review.example.com/c/proj/+/42".to_string(),
+            subject: "Fix bug".to_string(),
+        };
+        assert_eq!(
+            change_id_file_contents("Iabc", Some(&change)),
+            "change_id=Iabc\nchange_number=42\nchange_url=https://review.example.com/c/proj/+/42\n"
+        );
+    }
+
+    #[test]
+    fn strip_self_reviewer_matches_username_and_email() {
+        let mut reviewers = vec![
+            "alice".to_string(),
+            "Me@Example.com".to_string(),
+            "bob".to_string(),
+            "me".to_string(),
+        ];
+        let ids = vec!["me".to_string(), "me@example.com".to_string()];
+        let removed = strip_self_reviewer(&mut reviewers, &ids);
+        assert_eq!(reviewers, vec!["alice", "bob"]);
+        assert_eq!(removed, vec!["Me@Example.com", "me"]);
+    }
+
+    #[test]
+    fn strip_self_reviewer_no_ids_keeps_all() {
+        let mut reviewers = vec!["alice".to_string()];
+        assert!(strip_self_reviewer(&mut reviewers, &[]).is_empty());
+        assert_eq!(reviewers, vec!["alice"]);
+    }
+
+    #[test]
+    fn build_refspec_appends_raw_options_last() {
+        let mut o = opts("main");
+        o.topic = Some("feat".into());
+        o.reviewers = vec!["alice".into()];
+        o.raw_options = vec!["trace=1234".into(), "skip-validation".into()];
+        let refspec = build_refspec(&o).unwrap();
+        assert_eq!(
+            refspec,
+            "HEAD:refs/for/main%topic=feat,r=alice,trace=1234,skip-validation"
+        );
+    }
+
+    #[test]
+    fn build_refspec_with_labels_base_and_flags() {
+        let mut o = opts("main");
+        o.labels = vec![
+            "Code-Review+2".into(),
+            "Verified".into(),
+            "Workflow-1".into(),
+        ];
+        o.base = Some("0123abcd".into());
+        o.merged = true;
+        o.publish_comments = true;
+        o.raw_options = vec!["trace=1".into()];
+        assert_eq!(
+            build_refspec(&o).unwrap(),
+            "HEAD:refs/for/main%l=Code-Review+2,l=Verified,l=Workflow-1,base=0123abcd,\
+             merged,publish-comments,trace=1"
+        );
+    }
+
+    #[test]
+    fn build_refspec_rejects_malformed_labels() {
+        for bad in [
+            "",
+            "Code Review+1",
+            "Code-Review+",
+            "Code-Review+x",
+            "+1",
+            "a,b",
+        ] {
+            let mut o = opts("main");
+            o.labels = vec![bad.into()];
+            assert!(build_refspec(&o).is_err(), "{bad:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn build_refspec_rejects_non_sha_base() {
+        let mut o = opts("main");
+        o.base = Some("origin/main".into());
+        assert!(build_refspec(&o).is_err());
+    }
+
+    #[test]
+    fn describe_refspec_expands_label_key() {
+        assert_eq!(
+            describe_refspec("HEAD:refs/for/main%l=Verified+1,merged"),
+            vec![
+                ("label".to_string(), "Verified+1".to_string()),
+                ("merged".to_string(), String::new())
+            ]
+        );
+    }
+
+    #[test]
+    fn build_refspec_rejects_malformed_raw_options() {
+        for bad in ["a b", "a=1,b=2", "", "  "] {
+            let mut o = opts("main");
+            o.raw_options = vec![bad.into()];
+            assert!(build_refspec(&o).is_err(), "{bad:?} should be rejected");
+        }
+    }
+
     #[test]
     fn check_change_id_status_present() {
         let msg = "Fix bug\n\nChange-Id: I1234567890abcdef1234567890abcdef12345678\n";
@@ -300,4 +1621,31 @@ mod tests {
         let status = check_change_id_status(msg, false);
         assert_eq!(status, ChangeIdStatus::MissingNeedHook);
     }
+
+    #[test]
+    fn verify_amend_change_id_added() {
+        let before = "Fix bug\n";
+        let after = "Fix bug\n\nChange-Id: I1234567890abcdef1234567890abcdef12345678\n";
+        assert_eq!(
+            verify_amend_added_change_id(before, after).unwrap(),
+            "I1234567890abcdef1234567890abcdef12345678"
+        );
+    }
+
+    #[test]
+    fn verify_amend_hook_did_not_run() {
+        let msg = "Fix bug\n";
+        let err = verify_amend_added_change_id(msg, msg).unwrap_err();
+        assert!(format!("{err}").contains("did not run"), "{err}");
+    }
+
+    #[test]
+    fn verify_amend_hook_ran_without_change_id() {
+        let err = verify_amend_added_change_id("Fix bug\n", "Fix bug\n\nSigned-off-by: A <a@b>\n")
+            .unwrap_err();
+        assert!(
+            format!("{err}").contains("did not add a Change-Id"),
+            "{err}"
+        );
+    }
 }
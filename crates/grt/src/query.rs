@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! `grt query`: run an arbitrary Gerrit change query and print chosen columns.
+
+use std::fmt::Write as _;
+
+use crate::gerrit::ChangeInfo;
+use crate::review::OutputFormat;
+use crate::ui::Style;
+
+/// Output format for `grt query`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum QueryFormat {
+    /// Aligned columns with a header
+    Text,
+    /// JSON array of ChangeInfo objects
+    Json,
+    /// Tab-separated columns without a header, for scripts
+    Tsv,
+}
+
+impl From<OutputFormat> for QueryFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Text => QueryFormat::Text,
+            OutputFormat::Json => QueryFormat::Json,
+        }
+    }
+}
+
+/// A column `grt query` can print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Column {
+    Number,
+    ChangeId,
+    Project,
+    Branch,
+    Topic,
+    Status,
+    Owner,
+    Subject,
+    Created,
+    Updated,
+    Insertions,
+    Deletions,
+}
+
+/// Columns shown when `--columns` is not given.
+pub const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Number,
+    Column::Project,
+    Column::Branch,
+    Column::Status,
+    Column::Owner,
+    Column::Subject,
+];
+
+impl Column {
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::Number => "NUMBER",
+            Column::ChangeId => "CHANGE-ID",
+            Column::Project => "PROJECT",
+            Column::Branch => "BRANCH",
+            Column::Topic => "TOPIC",
+            Column::Status => "STATUS",
+            Column::Owner => "OWNER",
+            Column::Subject => "SUBJECT",
+            Column::Created => "CREATED",
+            Column::Updated => "UPDATED",
+            Column::Insertions => "INSERTIONS",
+            Column::Deletions => "DELETIONS",
+        }
+    }
+
+    /// The column's value for `change`, `-` when Gerrit did not send it.
+    pub fn value(self, change: &ChangeInfo) -> String {
+        let text = |s: &Option<String>| s.clone().unwrap_or_else(|| "-".to_string());
+        let number = |n: Option<i64>| n.map_or_else(|| "-".to_string(), |n| n.to_string());
+        match self {
+            Column::Number => number(change.number),
+            Column::ChangeId => text(&change.change_id),
+            Column::Project => text(&change.project),
+            Column::Branch => text(&change.branch),
+            Column::Topic => text(&change.topic),
+            Column::Status => text(&change.status),
+            Column::Owner => change
+                .owner
+                .as_ref()
+                .and_then(|o| {
+                    o.name
+                        .clone()
+                        .or_else(|| o.username.clone())
+                        .or_else(|| o.email.clone())
+                        .or_else(|| o.account_id.map(|id| id.to_string()))
+                })
+                .unwrap_or_else(|| "-".to_string()),
+            Column::Subject => text(&change.subject),
+            // Gerrit timestamps carry nanoseconds; seconds are enough here
+            Column::Created | Column::Updated => {
+                let ts = if self == Column::Created {
+                    &change.created
+                } else {
+                    &change.updated
+                };
+                ts.as_deref()
+                    .map(|t| t.split('.').next().unwrap_or(t).to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            }
+            Column::Insertions => number(change.insertions),
+            Column::Deletions => number(change.deletions),
+        }
+    }
+}
+
+/// Aligned table with a header row. The last column is not padded.
+pub fn format_text(changes: &[ChangeInfo], columns: &[Column], style: Style) -> String {
+    if changes.is_empty() {
+        return "No changes found\n".to_string();
+    }
+    let rows: Vec<Vec<String>> = changes
+        .iter()
+        .map(|c| columns.iter().map(|col| col.value(c)).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, col)| {
+            rows.iter()
+                .map(|r| r[i].chars().count())
+                .chain(std::iter::once(col.header().len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    let headers: Vec<String> = columns.iter().map(|c| c.header().to_string()).collect();
+    write_row(&mut out, &headers, columns, &widths, |s| style.bold(s));
+    for row in &rows {
+        write_row(&mut out, row, columns, &widths, |s| s.to_string());
+    }
+    out
+}
+
+fn write_row(
+    out: &mut String,
+    cells: &[String],
+    columns: &[Column],
+    widths: &[usize],
+    paint: impl Fn(&str) -> String,
+) {
+    let last = cells.len().saturating_sub(1);
+    for (i, cell) in cells.iter().enumerate() {
+        if i > 0 {
+            out.push_str("  ");
+        }
+        let pad = widths[i].saturating_sub(cell.chars().count());
+        let numeric = matches!(
+            columns[i],
+            Column::Number | Column::Insertions | Column::Deletions
+        );
+        if numeric {
+            out.push_str(&" ".repeat(pad));
+            out.push_str(&paint(cell));
+        } else {
+            out.push_str(&paint(cell));
+            if i < last {
+                out.push_str(&" ".repeat(pad));
+            }
+        }
+    }
+    out.push('\n');
+}
+
+/// One tab-separated line per change. Tabs and newlines inside values are
+/// replaced by spaces so each change stays on one line.
+pub fn format_tsv(changes: &[ChangeInfo], columns: &[Column]) -> String {
+    let mut out = String::new();
+    for change in changes {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|col| col.value(change).replace(['\t', '\n', '\r'], " "))
+            .collect();
+        let _ = writeln!(out, "{}", cells.join("\t"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes() -> Vec<ChangeInfo> {
+        serde_json::from_str(
+            r#"[
+            {"_number": 12345, "project": "nova", "branch": "master", "status": "NEW",
+             "owner": {"_account_id": 1, "name": "Alice"}, "subject": "Fix bug",
+             "updated": "2026-01-02 03:04:05.000000000", "insertions": 10, "deletions": 2},
+            {"_number": 7, "project": "openstack/neutron", "branch": "stable/2025.1",
+             "status": "MERGED", "owner": {"_account_id": 2}, "subject": "Tab\there"}
+        ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn text_aligns_columns_with_header() {
+        let text = format_text(&changes(), DEFAULT_COLUMNS, Style::plain());
+        assert_eq!(
+            text,
+            "NUMBER  PROJECT            BRANCH         STATUS  OWNER  SUBJECT\n\
+             \x2012345  nova               master         NEW     Alice  Fix bug\n\
+             \x20    7  openstack/neutron  stable/2025.1  MERGED  2      Tab\there\n"
+        );
+    }
+
+    #[test]
+    fn tsv_has_no_header_and_escapes_tabs() {
+        let tsv = format_tsv(&changes(), &[Column::Number, Column::Subject]);
+        assert_eq!(tsv, "12345\tFix bug\n7\tTab here\n");
+    }
+
+    #[test]
+    fn missing_values_and_timestamps() {
+        let c = &changes();
+        assert_eq!(Column::Topic.value(&c[0]), "-");
+        assert_eq!(Column::Updated.value(&c[0]), "2026-01-02 03:04:05");
+        assert_eq!(Column::Insertions.value(&c[1]), "-");
+    }
+
+    #[test]
+    fn empty_result_message() {
+        assert_eq!(
+            format_text(&[], DEFAULT_COLUMNS, Style::plain()),
+            "No changes found\n"
+        );
+        assert_eq!(format_tsv(&[], DEFAULT_COLUMNS), "");
+    }
+}
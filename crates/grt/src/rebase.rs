@@ -1,12 +1,38 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
+use std::fmt::Write as _;
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use serde::Serialize;
 
 use crate::subprocess;
 
+/// What the pre-push rebase does, from `-R`, `-F` and `defaultrebase`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebaseMode {
+    /// No rebase (`-R`, or `defaultrebase` off without `-F`).
+    Skip,
+    /// Rebase to detect conflicts, then undo it and push the original commits.
+    Test,
+    /// Rebase and push the rebased commits (`-F`).
+    Force,
+}
+
+/// Pick the rebase mode; `-R` wins, then `-F`, then `defaultrebase`.
+pub fn rebase_mode(no_rebase: bool, force_rebase: bool, default_rebase: bool) -> RebaseMode {
+    if no_rebase {
+        RebaseMode::Skip
+    } else if force_rebase {
+        RebaseMode::Force
+    } else if default_rebase {
+        RebaseMode::Test
+    } else {
+        RebaseMode::Skip
+    }
+}
+
 /// Result of a pre-push rebase attempt.
 #[derive(Debug)]
 pub enum RebaseResult {
@@ -85,6 +111,185 @@ pub fn undo_rebase(orig_head: &str, work_dir: &Path) -> Result<()> {
     subprocess::git_reset_hard(orig_head, work_dir)
 }
 
+/// Undo a forced rebase whose commits were not pushed (push failed, dry run,
+/// or cancelled), unless `keep_rebase` (`-K`) asks to keep them.
+pub fn undo_unpushed_rebase(
+    orig_head: Option<&str>,
+    keep_rebase: bool,
+    work_dir: &Path,
+) -> Result<()> {
+    match orig_head {
+        Some(orig_head) if !keep_rebase => {
+            eprintln!("Nothing was pushed; undoing rebase.");
+            undo_rebase(orig_head, work_dir)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// One commit of a local series.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SeriesCommit {
+    pub sha: String,
+    pub subject: String,
+}
+
+/// The commit a series rebase stopped at and the files left conflicted.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainConflict {
+    /// Index into [`ChainReport::commits`]; `None` when git did not leave a
+    /// `REBASE_HEAD` naming the commit that stopped.
+    pub index: Option<usize>,
+    pub files: Vec<String>,
+}
+
+/// Outcome of rebasing a local series (`grt rebase-chain`).
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainReport {
+    /// `remote/branch` the series was rebased onto.
+    pub onto: String,
+    /// The series before the rebase, oldest first.
+    pub commits: Vec<SeriesCommit>,
+    pub conflict: Option<ChainConflict>,
+    /// Whether a conflicted rebase was left in progress (`--keep-rebase`).
+    pub kept: bool,
+}
+
+/// Parse `git log --format=%H%x00%s` output into commits.
+fn parse_series(log: &str) -> Vec<SeriesCommit> {
+    log.lines()
+        .filter_map(|line| {
+            let (sha, subject) = line.split_once('\0')?;
+            Some(SeriesCommit {
+                sha: sha.to_string(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Commits on HEAD that are not on `onto`, oldest first.
+pub fn list_series(onto: &str, work_dir: &Path) -> Result<Vec<SeriesCommit>> {
+    let log = subprocess::git_output(
+        &[
+            "log",
+            "--reverse",
+            "--format=%H%x00%s",
+            "HEAD",
+            "--not",
+            onto,
+        ],
+        work_dir,
+    )?;
+    Ok(parse_series(&log))
+}
+
+/// Rebase every unpushed commit onto the latest `remote/branch`.
+///
+/// Unlike [`rebase_changes`] the result is a report on the whole series:
+/// when a commit conflicts, it names that commit and its conflicted files.
+/// The rebase is aborted unless `keep_rebase` is set, so later commits are
+/// not tried.
+pub fn rebase_series(
+    remote: &str,
+    branch: &str,
+    keep_rebase: bool,
+    work_dir: &Path,
+) -> Result<ChainReport> {
+    subprocess::git_remote_update(remote, work_dir)?;
+    if !subprocess::check_worktree_clean(work_dir)? {
+        bail!("cannot rebase: working tree has uncommitted changes");
+    }
+    if !subprocess::check_remote_branch_exists(remote, branch, work_dir) {
+        bail!("remote branch {remote}/{branch} does not exist");
+    }
+
+    let onto = format!("{remote}/{branch}");
+    let commits = list_series(&onto, work_dir)?;
+    let mut report = ChainReport {
+        onto: onto.clone(),
+        commits,
+        conflict: None,
+        kept: false,
+    };
+    if report.commits.is_empty() {
+        return Ok(report);
+    }
+
+    if let Err(e) = subprocess::git_rebase(&onto, work_dir) {
+        tracing::debug!("series rebase stopped: {e:#}");
+        let stopped = subprocess::git_output(
+            &["rev-parse", "--verify", "--quiet", "REBASE_HEAD"],
+            work_dir,
+        )
+        .unwrap_or_default();
+        let files = subprocess::git_output(&["diff", "--name-only", "--diff-filter=U"], work_dir)
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect();
+        let index = report.commits.iter().position(|c| c.sha == stopped);
+        report.conflict = Some(ChainConflict { index, files });
+        if keep_rebase {
+            report.kept = true;
+        } else if let Err(abort_err) = subprocess::git_rebase_abort(work_dir) {
+            tracing::warn!("failed to abort rebase: {abort_err}");
+        }
+    }
+    Ok(report)
+}
+
+/// Text report: one line per commit, oldest first, with conflicted files
+/// under the commit that stopped the rebase.
+pub fn format_chain_report(report: &ChainReport) -> String {
+    let mut out = String::new();
+    if report.commits.is_empty() {
+        let _ = writeln!(out, "No local commits on top of {}.", report.onto);
+        return out;
+    }
+    let stop = report.conflict.as_ref().map(|c| c.index);
+    for (i, commit) in report.commits.iter().enumerate() {
+        let state = match stop {
+            Some(Some(s)) if i == s => "conflict",
+            Some(Some(s)) if i > s => "not tried",
+            Some(None) => "unknown",
+            _ => "rebased",
+        };
+        let short = &commit.sha[..commit.sha.len().min(7)];
+        let _ = writeln!(out, "{state:<9}  {short} {}", commit.subject);
+        if let Some(conflict) = report.conflict.as_ref().filter(|c| c.index == Some(i)) {
+            for file in &conflict.files {
+                let _ = writeln!(out, "             {file}");
+            }
+        }
+    }
+    if let Some(conflict) = report.conflict.as_ref().filter(|c| c.index.is_none()) {
+        out.push_str("Rebase stopped at an unknown commit.\n");
+        for file in &conflict.files {
+            let _ = writeln!(out, "             {file}");
+        }
+    }
+    match (&report.conflict, report.kept) {
+        (None, _) => {
+            let _ = writeln!(
+                out,
+                "Rebased {} commit(s) onto {}.",
+                report.commits.len(),
+                report.onto
+            );
+        }
+        (Some(_), true) => {
+            out.push_str(
+                "Rebase left in progress: resolve the conflicts, then `git rebase --continue`.\n",
+            );
+        }
+        (Some(_), false) => {
+            out.push_str("Rebase aborted; the series is unchanged.\n");
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,6 +350,109 @@ mod tests {
         remote_dir
     }
 
+    fn commit_file(dir: &Path, file: &str, content: &str, message: &str) {
+        std::fs::write(dir.join(file), content).unwrap();
+        git_cmd(&["add", file], dir).output().unwrap();
+        git_cmd(&["commit", "-m", message], dir).output().unwrap();
+    }
+
+    #[test]
+    fn parse_series_lines() {
+        let commits = parse_series("abc\0First\ndef\0Second: fix\n");
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].sha, "abc");
+        assert_eq!(commits[1].subject, "Second: fix");
+    }
+
+    #[test]
+    fn rebase_series_reports_conflicting_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let _remote = init_repo_with_remote(dir.path());
+        let base = subprocess::git_rev_parse_head(dir.path()).unwrap();
+
+        // Upstream moves on and edits a.txt
+        commit_file(dir.path(), "a.txt", "upstream\n", "upstream");
+        git_cmd(&["push", "gerrit", "HEAD:master"], dir.path())
+            .output()
+            .unwrap();
+        git_cmd(&["reset", "--hard", &base], dir.path())
+            .output()
+            .unwrap();
+
+        // Local series: an independent commit, then one touching a.txt
+        commit_file(dir.path(), "b.txt", "b\n", "independent");
+        commit_file(dir.path(), "a.txt", "local\n", "conflicting");
+        let head = subprocess::git_rev_parse_head(dir.path()).unwrap();
+
+        let report = rebase_series("gerrit", "master", false, dir.path()).unwrap();
+        assert_eq!(report.commits.len(), 2);
+        let conflict = report.conflict.as_ref().expect("expected a conflict");
+        assert_eq!(conflict.index, Some(1));
+        assert_eq!(conflict.files, ["a.txt"]);
+        assert!(!report.kept);
+        assert_eq!(subprocess::git_rev_parse_head(dir.path()).unwrap(), head);
+
+        let text = format_chain_report(&report);
+        assert!(text.contains("rebased    "));
+        assert!(text.contains("conflict   "));
+        assert!(text.contains("             a.txt\n"));
+        assert!(text.ends_with("Rebase aborted; the series is unchanged.\n"));
+    }
+
+    #[test]
+    fn format_chain_report_unknown_conflict_commit() {
+        let report = ChainReport {
+            onto: "gerrit/master".to_string(),
+            commits: parse_series("abc1234\0First\ndef5678\0Second\n"),
+            conflict: Some(ChainConflict {
+                index: None,
+                files: vec!["a.txt".to_string()],
+            }),
+            kept: false,
+        };
+        let text = format_chain_report(&report);
+        assert!(text.starts_with("unknown    abc1234 First\nunknown    def5678 Second\n"));
+        assert!(text.contains("Rebase stopped at an unknown commit.\n             a.txt\n"));
+        assert!(!text.contains("conflict "));
+    }
+
+    #[test]
+    fn rebase_series_moves_clean_series() {
+        let dir = tempfile::tempdir().unwrap();
+        let _remote = init_repo_with_remote(dir.path());
+        let base = subprocess::git_rev_parse_head(dir.path()).unwrap();
+        commit_file(dir.path(), "a.txt", "upstream\n", "upstream");
+        git_cmd(&["push", "gerrit", "HEAD:master"], dir.path())
+            .output()
+            .unwrap();
+        git_cmd(&["reset", "--hard", &base], dir.path())
+            .output()
+            .unwrap();
+        commit_file(dir.path(), "b.txt", "b\n", "one");
+        commit_file(dir.path(), "c.txt", "c\n", "two");
+
+        let report = rebase_series("gerrit", "master", false, dir.path()).unwrap();
+        assert!(report.conflict.is_none());
+        assert_eq!(
+            list_series("gerrit/master", dir.path())
+                .unwrap()
+                .iter()
+                .map(|c| c.subject.as_str())
+                .collect::<Vec<_>>(),
+            ["one", "two"]
+        );
+        assert!(format_chain_report(&report).ends_with("Rebased 2 commit(s) onto gerrit/master.\n"));
+    }
+
+    #[test]
+    fn rebase_mode_precedence() {
+        assert_eq!(rebase_mode(true, false, true), RebaseMode::Skip);
+        assert_eq!(rebase_mode(false, true, false), RebaseMode::Force);
+        assert_eq!(rebase_mode(false, true, true), RebaseMode::Force);
+        assert_eq!(rebase_mode(false, false, true), RebaseMode::Test);
+        assert_eq!(rebase_mode(false, false, false), RebaseMode::Skip);
+    }
+
     #[test]
     fn rebase_skipped_when_no_remote_branch() {
         let dir = tempfile::tempdir().unwrap();
@@ -208,4 +516,32 @@ mod tests {
         let restored = subprocess::git_rev_parse_head(dir.path()).unwrap();
         assert_eq!(restored, orig_head);
     }
+
+    #[test]
+    fn undo_unpushed_rebase_honours_keep() {
+        let dir = tempfile::tempdir().unwrap();
+        let _remote = init_repo_with_remote(dir.path());
+        let orig_head = subprocess::git_rev_parse_head(dir.path()).unwrap();
+        git_cmd(&["commit", "--allow-empty", "-m", "new"], dir.path())
+            .output()
+            .unwrap();
+        let new_head = subprocess::git_rev_parse_head(dir.path()).unwrap();
+
+        undo_unpushed_rebase(Some(&orig_head), true, dir.path()).unwrap();
+        assert_eq!(
+            subprocess::git_rev_parse_head(dir.path()).unwrap(),
+            new_head
+        );
+        undo_unpushed_rebase(None, false, dir.path()).unwrap();
+        assert_eq!(
+            subprocess::git_rev_parse_head(dir.path()).unwrap(),
+            new_head
+        );
+
+        undo_unpushed_rebase(Some(&orig_head), false, dir.path()).unwrap();
+        assert_eq!(
+            subprocess::git_rev_parse_head(dir.path()).unwrap(),
+            orig_head
+        );
+    }
 }
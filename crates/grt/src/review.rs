@@ -7,10 +7,12 @@ use serde::Serialize;
 use tracing::debug;
 
 use crate::app::App;
+use crate::cache::{self, CacheMode, CachedList};
 use crate::gerrit::{ChangeInfo, RevisionInfo};
 use crate::list;
 use crate::review_query;
 use crate::subprocess;
+use crate::ui::Style;
 
 /// Allowed notification levels for Gerrit push.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
@@ -46,7 +48,7 @@ pub struct ReviewArgs {
     pub branch: Option<String>,
 
     // === Mode flags (mutually exclusive group) ===
-    /// Download a change from Gerrit
+    /// Download a change from Gerrit (number, URL, Change-Id, or `topic:NAME`)
     #[arg(short = 'd', long = "download", value_name = "CHANGE", group = "mode")]
     pub download: Option<String>,
 
@@ -67,10 +69,48 @@ pub struct ReviewArgs {
     #[arg(short = 'm', long, value_name = "CHANGE[,PS[-PS]]", group = "mode")]
     pub compare: Option<String>,
 
+    /// With -m: show a rebase-aware interdiff (`git range-diff`) between the
+    /// two patchsets instead of a plain diff
+    #[arg(long, requires = "compare")]
+    pub interdiff: bool,
+
+    /// With -m: open the comparison in `git difftool --dir-diff`, with the
+    /// newer patchset checked out in a temporary worktree
+    #[arg(long, requires = "compare", conflicts_with = "interdiff")]
+    pub difftool: bool,
+
     /// List open changes (-l brief, -ll verbose)
     #[arg(short = 'l', long, action = clap::ArgAction::Count, group = "mode")]
     pub list: u8,
 
+    /// Maximum number of changes to list (default 500)
+    #[arg(long, value_name = "N", conflicts_with = "no_limit")]
+    pub limit: Option<usize>,
+
+    /// List every open change, fetching all pages
+    #[arg(long)]
+    pub no_limit: bool,
+
+    /// List changes from the local cache without contacting Gerrit
+    #[arg(long, conflicts_with = "refresh")]
+    pub cached: bool,
+
+    /// Always query Gerrit; never fall back to the local cache
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// With -l: only list your own changes
+    #[arg(long, requires = "list")]
+    pub mine: bool,
+
+    /// With -l: only list changes with USER as a reviewer
+    #[arg(long, value_name = "USER", requires = "list")]
+    pub reviewer: Option<String>,
+
+    /// With -l: add an owner column (implies -ll)
+    #[arg(long, requires = "list")]
+    pub owner: bool,
+
     /// Set up the current repository for Gerrit
     #[arg(short = 's', long, group = "mode")]
     pub setup: bool,
@@ -107,7 +147,7 @@ pub struct ReviewArgs {
     pub no_track: bool,
 
     // === WIP (mutually exclusive) ===
-    /// Mark as work-in-progress
+    /// Mark as work-in-progress (with -l: only list work-in-progress changes)
     #[arg(
         short = 'w',
         long = "wip",
@@ -121,7 +161,7 @@ pub struct ReviewArgs {
     pub ready: bool,
 
     // === Privacy (mutually exclusive) ===
-    /// Mark as private
+    /// Mark as private (with -l: only list private changes)
     #[arg(short = 'p', long, conflicts_with = "remove_private")]
     pub private: bool,
 
@@ -134,6 +174,22 @@ pub struct ReviewArgs {
     #[arg(long, value_name = "USER", num_args = 1..)]
     pub reviewers: Vec<String>,
 
+    /// Do not add the configured default reviewers and CC recipients
+    #[arg(long)]
+    pub no_default_reviewers: bool,
+
+    /// Add reviewers from CODEOWNERS for the files changed by the push
+    #[arg(long)]
+    pub codeowners: bool,
+
+    /// Keep yourself in the reviewer list instead of dropping it
+    #[arg(long)]
+    pub allow_self_review: bool,
+
+    /// Move yourself to CC when dropped from the reviewer list
+    #[arg(long, conflicts_with = "allow_self_review")]
+    pub cc_self: bool,
+
     /// Add CC recipients
     #[arg(long, value_name = "USER", num_args = 1..)]
     pub cc: Vec<String>,
@@ -146,10 +202,42 @@ pub struct ReviewArgs {
     #[arg(long, value_enum, value_name = "LEVEL")]
     pub notify: Option<NotifyLevel>,
 
-    /// Review message
+    /// Vote at push time: `LABEL`, `LABEL+N` or `LABEL-N`
+    #[arg(long = "label", value_name = "VOTE", num_args = 1..)]
+    pub labels: Vec<String>,
+
+    /// Base the pushed changes on this commit (any revision git can resolve)
+    #[arg(long, value_name = "COMMIT")]
+    pub base: Option<String>,
+
+    /// Create changes for commits that are already merged into the branch
+    #[arg(long)]
+    pub merged: bool,
+
+    /// Publish your draft comments on the updated changes
+    #[arg(long)]
+    pub publish_comments: bool,
+
+    /// Append a raw push option (`KEY=VALUE` or `KEY`) to the refspec
+    #[arg(long = "push-option", value_name = "OPTION")]
+    pub push_options: Vec<String>,
+
+    /// Notify these accounts as TO recipients (posted as a review after push)
+    #[arg(long, value_name = "USER", num_args = 1..)]
+    pub notify_to: Vec<String>,
+
+    /// Notify these accounts as CC recipients (posted as a review after push)
+    #[arg(long, value_name = "USER", num_args = 1..)]
+    pub notify_cc: Vec<String>,
+
+    /// Review message; `{change}`, `{branch}` and `{patchset}` are expanded
     #[arg(long, value_name = "TEXT")]
     pub message: Option<String>,
 
+    /// Fail if `--message` contains a placeholder that cannot be expanded
+    #[arg(long)]
+    pub strict_message: bool,
+
     // === Behavior flags ===
     /// Remote to push to
     #[arg(short = 'r', long, value_name = "REMOTE")]
@@ -159,6 +247,10 @@ pub struct ReviewArgs {
     #[arg(short = 'n', long)]
     pub dry_run: bool,
 
+    /// Print only the computed refspec and exit
+    #[arg(long, visible_alias = "debug-refspec", conflicts_with = "finish")]
+    pub print_refspec: bool,
+
     /// Generate a new Change-Id (amend HEAD)
     #[arg(short = 'i', long)]
     pub new_changeid: bool,
@@ -191,9 +283,57 @@ pub struct ReviewArgs {
     #[arg(long)]
     pub no_custom_script: bool,
 
-    /// Output format (applies to --list, --download, and push modes)
-    #[arg(long, value_enum, default_value = "text")]
-    pub format: OutputFormat,
+    /// Fail if the fetched commit does not match the revision Gerrit reported
+    #[arg(long)]
+    pub verify: bool,
+
+    /// With -x/-X/-N: check out the change's target branch before picking
+    #[arg(long, conflicts_with_all = ["download", "compare", "list", "setup"])]
+    pub onto: bool,
+
+    /// With -x/-X/-N: roll the pick back if it conflicts instead of leaving it to resolve
+    #[arg(long, conflicts_with_all = ["download", "compare", "list", "setup"])]
+    pub abort_on_conflict: bool,
+
+    /// With -d: check out the change on a detached HEAD instead of a branch
+    #[arg(long, requires = "download", conflicts_with = "download_branch")]
+    pub detach: bool,
+
+    /// With -d: local branch to use instead of the generated `review/...` name;
+    /// an existing branch is reset to the change
+    #[arg(long = "branch", value_name = "NAME", requires = "download")]
+    pub download_branch: Option<String>,
+
+    /// Output format for --list, --download, and push modes (default: the repo's
+    /// default_output_format, else text)
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+}
+
+impl ReviewArgs {
+    /// Whether to target the upstream tracking branch: `--track`/`--no-track`,
+    /// else the `track` setting from `.gitreview`.
+    pub fn use_track(&self, config_track: bool) -> bool {
+        !self.no_track && (self.track || config_track)
+    }
+
+    /// Topic for the push: `--topic`, nothing with `--no-topic`, else the
+    /// default topic (from a trailer or the branch name) unless `notopic` is
+    /// set in `.gitreview`.
+    pub fn push_topic(
+        &self,
+        config_notopic: bool,
+        default_topic: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        if self.no_topic {
+            return None;
+        }
+        match &self.topic {
+            Some(topic) => Some(topic.clone()),
+            None if config_notopic => None,
+            None => default_topic(),
+        }
+    }
 }
 
 /// Output format for structured data.
@@ -206,10 +346,12 @@ pub enum OutputFormat {
 /// Structured result from a download operation.
 #[derive(Debug, Serialize)]
 pub struct DownloadResult {
-    pub branch: String,
+    /// Local branch, `None` with `--detach`.
+    pub branch: Option<String>,
     pub change_number: Option<i64>,
     pub patchset: i32,
-    pub upstream: String,
+    /// Upstream set on the branch, `None` with `--detach`.
+    pub upstream: Option<String>,
 }
 
 /// Attempt to parse a Gerrit change URL into a `"CHANGE[,PS]"` string.
@@ -284,6 +426,77 @@ pub fn parse_change_patchset(input: &str) -> (String, Option<i32>) {
     (input.to_string(), None)
 }
 
+/// Return true if `s` looks like a Change-Id (`I` followed by 40 hex digits).
+pub fn is_change_id(s: &str) -> bool {
+    s.len() == 41 && s.starts_with('I') && s[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Extract the topic name from a `topic:NAME` download argument.
+pub fn parse_topic_arg(input: &str) -> Option<&str> {
+    input
+        .strip_prefix("topic:")
+        .map(|t| t.trim_matches('"'))
+        .filter(|t| !t.is_empty())
+}
+
+/// Gerrit query resolving a Change-Id within `project`.
+pub fn change_id_query(change_id: &str, project: &str) -> String {
+    format!("change:{change_id} project:{project}")
+}
+
+/// Gerrit query listing the open changes of `topic` within `project`.
+pub fn topic_query(topic: &str, project: &str) -> String {
+    format!("status:open topic:\"{topic}\" project:{project}")
+}
+
+/// Pick the change number a Change-Id refers to from the query results.
+///
+/// The same Change-Id may exist on several branches; that is reported as an
+/// error listing the candidates so the user can pass a change number instead.
+pub fn select_change_for_id(change_id: &str, found: &[ChangeInfo]) -> Result<i64> {
+    let mut numbers: Vec<(i64, &str)> = found
+        .iter()
+        .filter_map(|c| Some((c.number?, c.branch.as_deref().unwrap_or("?"))))
+        .collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    match numbers.as_slice() {
+        [] => anyhow::bail!("no change found with Change-Id {change_id}"),
+        [(number, _)] => Ok(*number),
+        many => {
+            let listed: Vec<String> = many
+                .iter()
+                .map(|(number, branch)| format!("{number} ({branch})"))
+                .collect();
+            anyhow::bail!(
+                "Change-Id {change_id} matches several changes: {}; download by change number",
+                listed.join(", ")
+            )
+        }
+    }
+}
+
+/// Order the changes of a topic so each one follows those it is built on.
+///
+/// `is_ancestor(a, b)` reports whether commit `a` is reachable from `b`.
+/// Changes are sorted by how many of the other commits they contain, so a
+/// stack comes out bottom first; unrelated changes keep their input order.
+pub fn stack_order(shas: &[String], is_ancestor: impl Fn(&str, &str) -> bool) -> Vec<usize> {
+    let depth: Vec<usize> = shas
+        .iter()
+        .enumerate()
+        .map(|(i, sha)| {
+            shas.iter()
+                .enumerate()
+                .filter(|(j, other)| *j != i && is_ancestor(other.as_str(), sha.as_str()))
+                .count()
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..shas.len()).collect();
+    order.sort_by_key(|&i| (depth[i], i));
+    order
+}
+
 /// Find the target revision from a change's revision map.
 ///
 /// If `patchset` is `Some(n)`, finds the revision with that patchset number.
@@ -344,39 +557,141 @@ pub fn download_branch_name(change: &ChangeInfo, patchset: i32) -> String {
     format!("review/{change_num}/{patchset}")
 }
 
+/// Return true if the fetched SHA matches the revision SHA Gerrit reported.
+pub fn revision_matches(expected: &str, fetched: &str) -> bool {
+    expected.trim().eq_ignore_ascii_case(fetched.trim())
+}
+
+/// Verify that `FETCH_HEAD` is the revision Gerrit reported for the change.
+///
+/// A mismatch means the ref was stale or tampered with. It is reported as a
+/// warning, or as an error when `strict` (`--verify`) is set.
+pub fn verify_fetched_revision(
+    expected: &str,
+    work_dir: &std::path::Path,
+    strict: bool,
+) -> Result<()> {
+    let fetched = subprocess::fetch_head_sha(work_dir)?;
+    check_fetched_revision(expected, &fetched, strict)
+}
+
+fn check_fetched_revision(expected: &str, fetched: &str, strict: bool) -> Result<()> {
+    if revision_matches(expected, fetched) {
+        return Ok(());
+    }
+    if strict {
+        anyhow::bail!(
+            "fetched commit {fetched} does not match revision {expected} reported by Gerrit"
+        );
+    }
+    eprintln!(
+        "warning: fetched commit {fetched} does not match revision {expected} reported by Gerrit"
+    );
+    Ok(())
+}
+
+/// Replace a Change-Id with the number of the change it names in this
+/// project; any other change argument is returned unchanged.
+pub async fn resolve_change_id(
+    app: &App,
+    remote_url: &str,
+    root: &std::path::Path,
+    change_id: String,
+) -> Result<String> {
+    if !is_change_id(&change_id) {
+        return Ok(change_id);
+    }
+    let query = change_id_query(&change_id, &app.config.project);
+    let found = review_query::search_changes(remote_url, &query, &app.gerrit, root).await?;
+    let number = select_change_for_id(&change_id, &found)?;
+    debug!("Change-Id {} resolved to change {}", change_id, number);
+    Ok(number.to_string())
+}
+
 /// Download a change from Gerrit: fetch the ref and create a local branch.
+///
+/// The change may be given as a number, URL, or Change-Id; `topic:NAME`
+/// downloads every open change of the topic instead (see [`download_topic`]).
+///
+/// `branch_name` replaces the generated branch name; an existing branch of
+/// that name is reset to the change with `reset --keep`. With `detach`, the
+/// change is checked out on a detached HEAD and no branch is created.
 pub async fn cmd_review_download(
     app: &mut App,
     change_arg: &str,
     format: &OutputFormat,
+    verify: bool,
+    branch_name: Option<&str>,
+    detach: bool,
 ) -> Result<()> {
     let normalized = normalize_change_arg(change_arg);
     let (change_id, patchset) = parse_change_patchset(&normalized);
 
     let root = app.require_git()?.root()?;
     let remote = app.config.remote.clone();
-    let remote_url =
-        review_query::resolve_remote_url(&remote, &root, Some(&app.config.make_remote_url()))?
-            .context("no remote URL configured")?;
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
 
     if review_query::is_http_remote(&remote_url) {
         app.authenticate_and_verify().await?;
     }
 
+    if let Some(topic) = parse_topic_arg(&normalized) {
+        return download_topic(
+            app,
+            topic,
+            &remote,
+            &remote_url,
+            &root,
+            format,
+            verify,
+            branch_name,
+            detach,
+        )
+        .await;
+    }
+
+    let change_id = resolve_change_id(app, &remote_url, &root, change_id).await?;
+
     debug!("fetching change {} (patchset: {:?})", change_id, patchset);
     let change =
         review_query::get_change_all_revisions(&remote_url, &change_id, &app.gerrit, &root).await?;
-    let (_, revision) = find_target_revision(&change, patchset)?;
+    let (sha, revision) = find_target_revision(&change, patchset)?;
     let ps_num = revision.number.context("revision has no patchset number")?;
     let git_ref = revision.git_ref.as_deref().context("revision has no ref")?;
 
-    let branch = download_branch_name(&change, ps_num);
+    if detach {
+        eprintln!("Downloading {} patchset {}...", change_id, ps_num);
+        subprocess::git_fetch_ref(&remote, git_ref, &root)?;
+        verify_fetched_revision(sha, &root, verify)?;
+        subprocess::git_checkout_detached("FETCH_HEAD", &root)?;
+        match format {
+            OutputFormat::Json => {
+                let result = DownloadResult {
+                    branch: None,
+                    change_number: change.number,
+                    patchset: ps_num,
+                    upstream: None,
+                };
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            OutputFormat::Text => {
+                eprintln!("HEAD is now detached at patchset {ps_num} of change {change_id}");
+            }
+        }
+        return Ok(());
+    }
+
+    let branch = branch_name
+        .map(str::to_string)
+        .unwrap_or_else(|| download_branch_name(&change, ps_num));
 
     eprintln!(
         "Downloading {} patchset {} into {branch}...",
         change_id, ps_num
     );
     subprocess::git_fetch_ref(&remote, git_ref, &root)?;
+    verify_fetched_revision(sha, &root, verify)?;
     subprocess::git_checkout_or_reset_branch(&branch, "FETCH_HEAD", &root)?;
     // Set upstream tracking for the new branch
     let upstream = format!("{remote}/{}", change.branch.as_deref().unwrap_or("master"));
@@ -385,10 +700,10 @@ pub async fn cmd_review_download(
     match format {
         OutputFormat::Json => {
             let result = DownloadResult {
-                branch: branch.clone(),
+                branch: Some(branch.clone()),
                 change_number: change.number,
                 patchset: ps_num,
-                upstream: upstream.clone(),
+                upstream: Some(upstream.clone()),
             };
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
@@ -400,16 +715,499 @@ pub async fn cmd_review_download(
     Ok(())
 }
 
+/// How one change of a topic ended up on the download branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopicStep {
+    /// The change the branch was started from.
+    Base,
+    /// Fast-forwarded: the change is built on the previous ones.
+    Stacked,
+    /// Already contained in the branch by an earlier change.
+    Included,
+    /// Cherry-picked on top of the previous changes.
+    CherryPicked,
+}
+
+/// One change of a topic download.
+#[derive(Debug, Serialize)]
+pub struct TopicChange {
+    pub change_number: Option<i64>,
+    pub patchset: i32,
+    pub subject: Option<String>,
+    pub step: TopicStep,
+}
+
+/// Structured result from downloading a topic.
+#[derive(Debug, Serialize)]
+pub struct TopicDownloadResult {
+    /// Local branch, `None` with `--detach`.
+    pub branch: Option<String>,
+    pub topic: String,
+    /// Changes in the order they were applied.
+    pub changes: Vec<TopicChange>,
+    /// Upstream set on the branch, `None` with `--detach`.
+    pub upstream: Option<String>,
+}
+
+/// Download every open change of `topic` onto one stacked local branch.
+///
+/// Changes are applied in dependency order: changes built on the previous
+/// ones are fast-forwarded, independent ones are cherry-picked on top. A
+/// cherry-pick conflict stops the download with the branch left mid-pick.
+#[allow(clippy::too_many_arguments)]
+async fn download_topic(
+    app: &App,
+    topic: &str,
+    remote: &str,
+    remote_url: &str,
+    root: &std::path::Path,
+    format: &OutputFormat,
+    verify: bool,
+    branch_name: Option<&str>,
+    detach: bool,
+) -> Result<()> {
+    let query = topic_query(topic, &app.config.project);
+    let found = review_query::search_changes(remote_url, &query, &app.gerrit, root).await?;
+    let mut numbers: Vec<i64> = found.iter().filter_map(|c| c.number).collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    if numbers.is_empty() {
+        anyhow::bail!("no open changes in topic {topic}");
+    }
+
+    let mut changes = Vec::with_capacity(numbers.len());
+    for number in &numbers {
+        changes.push(
+            review_query::get_change_all_revisions(
+                remote_url,
+                &number.to_string(),
+                &app.gerrit,
+                root,
+            )
+            .await?,
+        );
+    }
+    let mut branches: Vec<&str> = changes
+        .iter()
+        .map(|c| c.branch.as_deref().unwrap_or("master"))
+        .collect();
+    branches.sort_unstable();
+    branches.dedup();
+    if branches.len() > 1 {
+        anyhow::bail!(
+            "topic {topic} spans branches {}; download its changes individually",
+            branches.join(", ")
+        );
+    }
+
+    let mut targets = Vec::with_capacity(changes.len());
+    for change in &changes {
+        let (sha, revision) = find_target_revision(change, None)?;
+        let ps_num = revision.number.context("revision has no patchset number")?;
+        let git_ref = revision.git_ref.as_deref().context("revision has no ref")?;
+        targets.push((change, sha, ps_num, git_ref));
+    }
+
+    eprintln!("Downloading {} changes in topic {topic}...", targets.len());
+    let refs: Vec<&str> = targets.iter().map(|t| t.3).collect();
+    let fetched = subprocess::git_fetch_refs(remote, &refs, root)?;
+    for ((_, sha, _, _), got) in targets.iter().zip(&fetched) {
+        check_fetched_revision(sha, got, verify)?;
+    }
+
+    let order = stack_order(&fetched, |a, b| {
+        subprocess::git_is_ancestor(a, b, root).unwrap_or(false)
+    });
+    let (first_change, _, first_ps, _) = targets[order[0]];
+    let branch = (!detach).then(|| {
+        branch_name
+            .map(str::to_string)
+            .unwrap_or_else(|| download_branch_name(first_change, first_ps))
+    });
+    let move_to = |commit: &str| match branch {
+        Some(ref branch) => subprocess::git_checkout_or_reset_branch(branch, commit, root),
+        None => subprocess::git_checkout_detached(commit, root),
+    };
+
+    let mut applied = Vec::with_capacity(order.len());
+    for (pos, &i) in order.iter().enumerate() {
+        let (change, _, ps_num, _) = targets[i];
+        let commit = fetched[i].as_str();
+        let number = change.number.unwrap_or(0);
+        let step = if pos == 0 {
+            move_to(commit)?;
+            TopicStep::Base
+        } else {
+            let head = subprocess::git_rev_parse_head(root)?;
+            if subprocess::git_is_ancestor(&head, commit, root)? {
+                move_to(commit)?;
+                TopicStep::Stacked
+            } else if subprocess::git_is_ancestor(commit, &head, root)? {
+                TopicStep::Included
+            } else {
+                cherry_pick_change(commit, PickMode::Commit, false, root).with_context(|| {
+                    format!("change {number} does not apply on top of the rest of topic {topic}")
+                })?;
+                TopicStep::CherryPicked
+            }
+        };
+        applied.push(TopicChange {
+            change_number: change.number,
+            patchset: ps_num,
+            subject: change.subject.clone(),
+            step,
+        });
+    }
+
+    let upstream = match branch {
+        Some(ref branch) => {
+            let upstream = format!("{remote}/{}", branches[0]);
+            subprocess::git_set_upstream_tracking(branch, &upstream, root)?;
+            Some(upstream)
+        }
+        None => None,
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let result = TopicDownloadResult {
+                branch,
+                topic: topic.to_string(),
+                changes: applied,
+                upstream,
+            };
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        OutputFormat::Text => {
+            for change in &applied {
+                let how = match change.step {
+                    TopicStep::Base => "base",
+                    TopicStep::Stacked => "stacked",
+                    TopicStep::Included => "already included",
+                    TopicStep::CherryPicked => "cherry-picked",
+                };
+                eprintln!(
+                    "  {},{} {} ({how})",
+                    change.change_number.unwrap_or(0),
+                    change.patchset,
+                    change.subject.as_deref().unwrap_or("")
+                );
+            }
+            match branch {
+                Some(branch) => eprintln!("Switched to branch '{branch}'"),
+                None => eprintln!("HEAD is now detached at the top of topic {topic}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Structured result from `grt checkout`.
+#[derive(Debug, Serialize)]
+pub struct CheckoutResult {
+    pub branch: String,
+    pub change_number: Option<i64>,
+    pub patchset: i32,
+    /// Whether an existing tracking branch was moved rather than created.
+    pub reused: bool,
+}
+
+/// Branch config key holding the change number a `grt checkout` branch tracks.
+pub const CHECKOUT_CHANGE_KEY: &str = "grt-change";
+/// Branch config key holding the checked-out patchset number.
+pub const CHECKOUT_PATCHSET_KEY: &str = "grt-patchset";
+/// Branch config key holding the checked-out patchset commit.
+pub const CHECKOUT_REVISION_KEY: &str = "grt-revision";
+
+/// Name for a new `grt checkout` branch: `review/<owner>/<change_number>`.
+///
+/// Unlike [`download_branch_name`] this omits the patchset, so the same
+/// branch can follow the change as new patchsets are uploaded.
+pub fn checkout_branch_name(change: &ChangeInfo) -> String {
+    let change_num = change.number.unwrap_or(0);
+    let owner = change.owner.as_ref().and_then(|o| {
+        o.username
+            .clone()
+            .or_else(|| o.name.as_ref().map(|n| n.replace(' ', "_")))
+    });
+    match owner {
+        Some(owner) => format!("review/{owner}/{change_num}"),
+        None => format!("review/{change_num}"),
+    }
+}
+
+/// Branches whose `branch.<name>.grt-change` equals `change_number`.
+///
+/// `config` is the output of `git config --get-regexp '^branch\..*\.grt-change$'`.
+pub fn branches_tracking_change(config: &str, change_number: i64) -> Vec<String> {
+    let suffix = format!(".{CHECKOUT_CHANGE_KEY}");
+    config
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.rsplit_once(' ')?;
+            let name = key.strip_prefix("branch.")?.strip_suffix(&suffix)?;
+            (value.trim().parse::<i64>().ok()? == change_number).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Check out a change on a branch that follows it across patchsets.
+///
+/// An existing branch recorded for the change is reset (`--keep`) to the
+/// requested patchset; otherwise a new branch is created. Commits on the
+/// branch after the recorded patchset are reported before they are dropped.
+pub async fn cmd_checkout(
+    app: &mut App,
+    change_arg: &str,
+    format: &OutputFormat,
+    verify: bool,
+) -> Result<()> {
+    let normalized = normalize_change_arg(change_arg);
+    let (change_id, patchset) = parse_change_patchset(&normalized);
+
+    let root = app.require_git()?.root()?;
+    let remote = app.config.remote.clone();
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
+
+    if review_query::is_http_remote(&remote_url) {
+        app.authenticate_and_verify().await?;
+    }
+
+    debug!(
+        "checking out change {} (patchset: {:?})",
+        change_id, patchset
+    );
+    let change =
+        review_query::get_change_all_revisions(&remote_url, &change_id, &app.gerrit, &root).await?;
+    let (sha, revision) = find_target_revision(&change, patchset)?;
+    let ps_num = revision.number.context("revision has no patchset number")?;
+    let git_ref = revision.git_ref.as_deref().context("revision has no ref")?;
+    let change_num = change.number.context("change has no number")?;
+
+    let pattern = format!(r"^branch\..*\.{CHECKOUT_CHANGE_KEY}$");
+    let tracked = subprocess::git_output(&["config", "--get-regexp", &pattern], &root)
+        .map(|out| branches_tracking_change(&out, change_num))
+        .unwrap_or_default();
+    let existing = tracked
+        .into_iter()
+        .find(|b| subprocess::git_local_branch_exists(b, &root).unwrap_or(false));
+    let reused = existing.is_some();
+    let branch = existing.unwrap_or_else(|| checkout_branch_name(&change));
+
+    eprintln!("Fetching {change_id} patchset {ps_num}...");
+    subprocess::git_fetch_ref(&remote, git_ref, &root)?;
+    verify_fetched_revision(sha, &root, verify)?;
+
+    if reused {
+        warn_dropped_commits(&branch, sha, &root)?;
+    }
+    subprocess::git_checkout_or_reset_branch(&branch, "FETCH_HEAD", &root)?;
+    if !reused {
+        let upstream = format!("{remote}/{}", change.branch.as_deref().unwrap_or("master"));
+        subprocess::git_set_upstream_tracking(&branch, &upstream, &root)?;
+    }
+
+    for (key, value) in [
+        (CHECKOUT_CHANGE_KEY, change_num.to_string()),
+        (CHECKOUT_PATCHSET_KEY, ps_num.to_string()),
+        (CHECKOUT_REVISION_KEY, sha.to_string()),
+    ] {
+        subprocess::git_exec(
+            &["config", &format!("branch.{branch}.{key}"), &value],
+            &root,
+        )?;
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let result = CheckoutResult {
+                branch: branch.clone(),
+                change_number: Some(change_num),
+                patchset: ps_num,
+                reused,
+            };
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        OutputFormat::Text => {
+            let verb = if reused { "Updated" } else { "Created" };
+            eprintln!("{verb} branch '{branch}' at patchset {ps_num}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Warn about commits on `branch` beyond its recorded patchset, which moving
+/// the branch to `target` would drop.
+fn warn_dropped_commits(branch: &str, target: &str, root: &std::path::Path) -> Result<()> {
+    let key = format!("branch.{branch}.{CHECKOUT_REVISION_KEY}");
+    let Some(recorded) = subprocess::git_config_get(&key, root)? else {
+        return Ok(());
+    };
+    let tip = subprocess::git_output(&["rev-parse", branch], root)?;
+    let (recorded, tip) = (recorded.trim(), tip.trim());
+    if revision_matches(recorded, tip) || revision_matches(target, tip) {
+        return Ok(());
+    }
+    let range = format!("{recorded}..{tip}");
+    let log = subprocess::git_output(&["log", "--oneline", &range], root).unwrap_or_default();
+    eprintln!(
+        "warning: branch '{branch}' has local commits that will be dropped \
+         (old tip {tip}, recoverable with `git reflog`):"
+    );
+    for line in log.lines() {
+        eprintln!("  {line}");
+    }
+    Ok(())
+}
+
+/// Warning to show when a change targets a different branch than the one checked out.
+///
+/// Returns `None` when the branches match or either side is unknown
+/// (e.g. detached HEAD), since there is nothing useful to compare.
+pub fn branch_mismatch_warning(
+    change_branch: Option<&str>,
+    current: Option<&str>,
+) -> Option<String> {
+    let (target, current) = (change_branch?, current?);
+    if target == current {
+        return None;
+    }
+    Some(format!(
+        "warning: change targets branch '{target}' but you are on '{current}'; \
+         the pick may conflict (use --onto to pick onto '{target}')"
+    ))
+}
+
+/// Prepare the working tree for a cherry-pick of `change`.
+///
+/// With `onto`, check out a local branch named after the change's target
+/// (creating it from the remote branch if needed). Otherwise warn when the
+/// current branch differs from the change's target.
+fn prepare_cherrypick_branch(
+    app: &App,
+    change: &ChangeInfo,
+    remote: &str,
+    root: &std::path::Path,
+    onto: bool,
+) -> Result<()> {
+    let current = app.require_git()?.current_branch().ok();
+    if !onto {
+        if let Some(warning) = branch_mismatch_warning(change.branch.as_deref(), current.as_deref())
+        {
+            eprintln!("{warning}");
+        }
+        return Ok(());
+    }
+
+    let target = change
+        .branch
+        .as_deref()
+        .context("change has no target branch; cannot use --onto")?;
+    if current.as_deref() == Some(target) {
+        return Ok(());
+    }
+    eprintln!("Checking out {target} to pick onto...");
+    if !subprocess::git_local_branch_exists(target, root)? {
+        subprocess::git_fetch_ref(remote, target, root)?;
+        subprocess::git_checkout_new_branch(target, "FETCH_HEAD", root)?;
+        subprocess::git_set_upstream_tracking(target, &format!("{remote}/{target}"), root)?;
+    } else {
+        subprocess::git_checkout(target, root)?;
+    }
+    Ok(())
+}
+
+/// How `-x`, `-X` and `-N` apply a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickMode {
+    /// `-x`: commit the pick.
+    Commit,
+    /// `-X`: commit with a `(cherry picked from commit ...)` line.
+    Indicate,
+    /// `-N`: apply to the working tree without committing.
+    NoCommit,
+}
+
+/// Cherry-pick `commit` onto HEAD.
+///
+/// When the pick stops on conflicts, the conflicted files and the commands to
+/// finish or back out are printed on stderr and the pick is left in progress;
+/// with `abort_on_conflict` it is rolled back first instead. Either way the
+/// result is an error.
+pub fn cherry_pick_change(
+    commit: &str,
+    mode: PickMode,
+    abort_on_conflict: bool,
+    root: &std::path::Path,
+) -> Result<()> {
+    let picked = match mode {
+        PickMode::Commit => subprocess::git_cherry_pick(commit, root),
+        PickMode::Indicate => subprocess::git_cherry_pick_indicate(commit, root),
+        PickMode::NoCommit => subprocess::git_cherry_pick_no_commit(commit, root),
+    };
+    let Err(err) = picked else {
+        return Ok(());
+    };
+    let conflicted = subprocess::git_conflicted_files(root).unwrap_or_default();
+    if conflicted.is_empty() {
+        return Err(err);
+    }
+    if abort_on_conflict {
+        match mode {
+            PickMode::NoCommit => subprocess::git_reset_merge(root)?,
+            PickMode::Commit | PickMode::Indicate => subprocess::git_cherry_pick_abort(root)?,
+        }
+        anyhow::bail!(
+            "cherry-pick conflicted in {}; rolled back (--abort-on-conflict)",
+            conflicted.join(", ")
+        );
+    }
+    eprint!("{}", conflict_guidance(mode, &conflicted));
+    Err(err.context("cherry-pick stopped on conflicts"))
+}
+
+/// Next steps after a pick stopped on conflicts in `conflicted`.
+pub fn conflict_guidance(mode: PickMode, conflicted: &[String]) -> String {
+    let mut out = String::from("Cherry-pick stopped on conflicts in:\n");
+    for path in conflicted {
+        out.push_str(&format!("  {path}\n"));
+    }
+    match mode {
+        PickMode::Commit | PickMode::Indicate => out.push_str(
+            "Resolve them, then run:\n  \
+             git add <files>\n  \
+             git cherry-pick --continue\n\
+             To give up and restore the previous state:\n  \
+             git cherry-pick --abort\n",
+        ),
+        PickMode::NoCommit => out.push_str(
+            "Resolve them and run `git add <files>`; nothing is committed.\n\
+             To give up and restore the previous state:\n  \
+             git reset --merge\n",
+        ),
+    }
+    out
+}
+
 /// Cherry-pick a change onto the current branch.
-pub async fn cmd_review_cherrypick(app: &mut App, change_arg: &str) -> Result<()> {
+pub async fn cmd_review_cherrypick(
+    app: &mut App,
+    change_arg: &str,
+    verify: bool,
+    onto: bool,
+    abort_on_conflict: bool,
+) -> Result<()> {
     let normalized = normalize_change_arg(change_arg);
     let (change_id, patchset) = parse_change_patchset(&normalized);
 
     let root = app.require_git()?.root()?;
     let remote = app.config.remote.clone();
-    let remote_url =
-        review_query::resolve_remote_url(&remote, &root, Some(&app.config.make_remote_url()))?
-            .context("no remote URL configured")?;
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
 
     if review_query::is_http_remote(&remote_url) {
         app.authenticate_and_verify().await?;
@@ -417,27 +1215,34 @@ pub async fn cmd_review_cherrypick(app: &mut App, change_arg: &str) -> Result<()
 
     let change =
         review_query::get_change_all_revisions(&remote_url, &change_id, &app.gerrit, &root).await?;
-    let (_, revision) = find_target_revision(&change, patchset)?;
+    let (sha, revision) = find_target_revision(&change, patchset)?;
     let git_ref = revision.git_ref.as_deref().context("revision has no ref")?;
+    prepare_cherrypick_branch(app, &change, &remote, &root, onto)?;
 
     eprintln!("Cherry-picking change {}...", change_id);
     subprocess::git_fetch_ref(&remote, git_ref, &root)?;
-    subprocess::git_cherry_pick("FETCH_HEAD", &root)?;
+    verify_fetched_revision(sha, &root, verify)?;
+    cherry_pick_change("FETCH_HEAD", PickMode::Commit, abort_on_conflict, &root)?;
     eprintln!("Cherry-pick applied.");
 
     Ok(())
 }
 
 /// Cherry-pick with "(cherry picked from commit ...)" indication.
-pub async fn cmd_review_cherrypickindicate(app: &mut App, change_arg: &str) -> Result<()> {
+pub async fn cmd_review_cherrypickindicate(
+    app: &mut App,
+    change_arg: &str,
+    verify: bool,
+    onto: bool,
+    abort_on_conflict: bool,
+) -> Result<()> {
     let normalized = normalize_change_arg(change_arg);
     let (change_id, patchset) = parse_change_patchset(&normalized);
 
     let root = app.require_git()?.root()?;
     let remote = app.config.remote.clone();
-    let remote_url =
-        review_query::resolve_remote_url(&remote, &root, Some(&app.config.make_remote_url()))?
-            .context("no remote URL configured")?;
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
 
     if review_query::is_http_remote(&remote_url) {
         app.authenticate_and_verify().await?;
@@ -445,27 +1250,34 @@ pub async fn cmd_review_cherrypickindicate(app: &mut App, change_arg: &str) -> R
 
     let change =
         review_query::get_change_all_revisions(&remote_url, &change_id, &app.gerrit, &root).await?;
-    let (_, revision) = find_target_revision(&change, patchset)?;
+    let (sha, revision) = find_target_revision(&change, patchset)?;
     let git_ref = revision.git_ref.as_deref().context("revision has no ref")?;
+    prepare_cherrypick_branch(app, &change, &remote, &root, onto)?;
 
     eprintln!("Cherry-picking change {} (with indication)...", change_id);
     subprocess::git_fetch_ref(&remote, git_ref, &root)?;
-    subprocess::git_cherry_pick_indicate("FETCH_HEAD", &root)?;
+    verify_fetched_revision(sha, &root, verify)?;
+    cherry_pick_change("FETCH_HEAD", PickMode::Indicate, abort_on_conflict, &root)?;
     eprintln!("Cherry-pick applied with cherry-picked-from indication.");
 
     Ok(())
 }
 
 /// Cherry-pick without committing (apply to working directory only).
-pub async fn cmd_review_cherrypickonly(app: &mut App, change_arg: &str) -> Result<()> {
+pub async fn cmd_review_cherrypickonly(
+    app: &mut App,
+    change_arg: &str,
+    verify: bool,
+    onto: bool,
+    abort_on_conflict: bool,
+) -> Result<()> {
     let normalized = normalize_change_arg(change_arg);
     let (change_id, patchset) = parse_change_patchset(&normalized);
 
     let root = app.require_git()?.root()?;
     let remote = app.config.remote.clone();
-    let remote_url =
-        review_query::resolve_remote_url(&remote, &root, Some(&app.config.make_remote_url()))?
-            .context("no remote URL configured")?;
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
 
     if review_query::is_http_remote(&remote_url) {
         app.authenticate_and_verify().await?;
@@ -473,15 +1285,17 @@ pub async fn cmd_review_cherrypickonly(app: &mut App, change_arg: &str) -> Resul
 
     let change =
         review_query::get_change_all_revisions(&remote_url, &change_id, &app.gerrit, &root).await?;
-    let (_, revision) = find_target_revision(&change, patchset)?;
+    let (sha, revision) = find_target_revision(&change, patchset)?;
     let git_ref = revision.git_ref.as_deref().context("revision has no ref")?;
+    prepare_cherrypick_branch(app, &change, &remote, &root, onto)?;
 
     eprintln!(
         "Applying change {} to working directory (no commit)...",
         change_id
     );
     subprocess::git_fetch_ref(&remote, git_ref, &root)?;
-    subprocess::git_cherry_pick_no_commit("FETCH_HEAD", &root)?;
+    verify_fetched_revision(sha, &root, verify)?;
+    cherry_pick_change("FETCH_HEAD", PickMode::NoCommit, abort_on_conflict, &root)?;
     eprintln!("Change applied to working directory.");
 
     Ok(())
@@ -533,12 +1347,17 @@ pub fn parse_compare_arg(input: &str) -> Result<(String, Option<i32>, Option<i32
 /// - `(Some(ps), None)`: single PS vs latest (git-review compat)
 /// - `(Some(0), Some(to))`: base vs PS (0 = base sentinel)
 /// - `(Some(from), Some(to))`: PS vs PS range
+///
+/// With `interdiff`, two patchsets are compared with `git range-diff`, which
+/// hides changes that only come from rebasing onto a newer parent.
 pub async fn cmd_review_compare(
     app: &mut App,
     compare_arg: &str,
     branch: &str,
     no_rebase: bool,
     force_rebase: bool,
+    interdiff: bool,
+    difftool: bool,
 ) -> Result<()> {
     const OLD_BRANCH: &str = "grt-compare-old";
     const NEW_BRANCH: &str = "grt-compare-new";
@@ -553,9 +1372,8 @@ pub async fn cmd_review_compare(
 
     let root = app.require_git()?.root()?;
     let remote = app.config.remote.clone();
-    let remote_url =
-        review_query::resolve_remote_url(&remote, &root, Some(&app.config.make_remote_url()))?
-            .context("no remote URL configured")?;
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
 
     if review_query::is_http_remote(&remote_url) {
         app.authenticate_and_verify().await?;
@@ -665,17 +1483,23 @@ pub async fn cmd_review_compare(
         }
     };
 
-    let sha_from = if is_vs_base {
-        ref_from.clone()
-    } else {
-        subprocess::git_fetch_ref_sha(&remote, &ref_from, &root)?
-    };
-    let sha_to = if is_vs_base {
-        ref_to.clone()
+    if interdiff && is_vs_base {
+        anyhow::bail!("--interdiff compares two patchsets; use CHANGE,PS or CHANGE,PS-PS");
+    }
+
+    let (sha_from, sha_to) = if is_vs_base {
+        (ref_from.clone(), ref_to.clone())
     } else {
-        subprocess::git_fetch_ref_sha(&remote, &ref_to, &root)?
+        // Fetch both patchsets in one round trip
+        let shas = subprocess::git_fetch_refs(&remote, &[&ref_from, &ref_to], &root)?;
+        (shas[0].clone(), shas[1].clone())
     };
 
+    // range-diff pairs the two commits itself, so no rebase is needed
+    if interdiff {
+        return subprocess::git_range_diff(&sha_from, &sha_to, &root);
+    }
+
     let should_rebase = !is_vs_base && !no_rebase && (force_rebase || app.config.default_rebase);
 
     if should_rebase {
@@ -722,7 +1546,11 @@ pub async fn cmd_review_compare(
             (sha_from, sha_to)
         };
 
-    subprocess::git_diff(&diff_from, &diff_to, &root)?;
+    let result = if difftool {
+        difftool_compare(&diff_from, &diff_to, &root)
+    } else {
+        subprocess::git_diff(&diff_from, &diff_to, &root)
+    };
 
     // Restore original state and clean up temp branches
     subprocess::git_checkout(&orig_ref, &root)?;
@@ -731,36 +1559,79 @@ pub async fn cmd_review_compare(
         subprocess::git_delete_branch(NEW_BRANCH, &root).ok();
     }
 
-    Ok(())
+    result
+}
+
+/// Directory for the temporary `--difftool` worktree of this process.
+fn difftool_worktree_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("grt-compare-{}", std::process::id()))
+}
+
+/// Show `from..to` in `git difftool --dir-diff`.
+///
+/// `to` is checked out in a temporary worktree and the tool is run there
+/// against `from`, so it gets complete files on the new side instead of the
+/// copies of changed files a commit-to-commit dir-diff provides. The
+/// worktree is removed once the tool exits.
+fn difftool_compare(from: &str, to: &str, root: &std::path::Path) -> Result<()> {
+    let dir = difftool_worktree_dir();
+    subprocess::git_worktree_add_detached(&dir, to, root)?;
+    let result = subprocess::git_difftool_dir_diff(from, &dir);
+    if let Err(e) = subprocess::git_worktree_remove(&dir, root) {
+        eprintln!(
+            "warning: could not remove temporary worktree {}: {e}",
+            dir.display()
+        );
+    }
+    result
 }
 
 /// List open changes on Gerrit.
 ///
-/// Queries `status:open project:<project>` (and `branch:<branch>` if specified).
+/// Queries `status:open project:<project>` (and `branch:<branch>` if specified),
+/// narrowed by `filters`.
 /// Brief mode (`-l`) shows number, branch, subject.
-/// Verbose mode (`-ll`) adds a topic column.
+/// Verbose mode (`-ll`) adds a topic column, an owner column with `show_owner`,
+/// and a CI column from the change messages, which it fetches over HTTP.
+///
+/// Each successful query refreshes the offline cache; `cache_mode` decides
+/// whether the cached result is read instead of, or as a fallback for, Gerrit.
+#[allow(clippy::too_many_arguments)]
 pub async fn cmd_review_list(
     app: &mut App,
     branch: Option<&str>,
+    filters: &list::ListFilters,
     verbose: bool,
+    show_owner: bool,
     format: &OutputFormat,
+    style: Style,
+    limit: Option<usize>,
+    cache_mode: CacheMode,
 ) -> Result<()> {
-    let root = app.require_git()?.root()?;
-    let remote = app.config.remote.clone();
-    let remote_url =
-        review_query::resolve_remote_url(&remote, &root, Some(&app.config.make_remote_url()))?
-            .context("no remote URL configured")?;
+    let query = list::build_list_query(&app.config.project, branch, filters);
+    let cache = app.cache()?;
 
-    if review_query::is_http_remote(&remote_url) {
-        app.authenticate_and_verify().await?;
-    }
-
-    let query = list::build_list_query(&app.config.project, branch);
-    debug!("listing changes with query: {}", query);
-
-    let changes =
-        review_query::query_changes(&remote_url, &app.config.project, branch, &app.gerrit, &root)
-            .await?;
+    let changes = if cache_mode == CacheMode::Offline {
+        cached_list(&cache, &query)?
+    } else {
+        match fetch_list(app, &query, limit, verbose).await {
+            Ok(changes) => {
+                let entry = CachedList {
+                    fetched_at: cache::now_secs(),
+                    changes,
+                };
+                if let Err(e) = cache.store_list(&query, &entry) {
+                    debug!("could not update list cache: {e:#}");
+                }
+                entry.changes
+            }
+            Err(e) if cache_mode == CacheMode::Auto && cache::is_offline_error(&e) => {
+                eprintln!("warning: {e:#}; showing cached results");
+                cached_list(&cache, &query)?
+            }
+            Err(e) => return Err(e),
+        }
+    };
 
     if changes.is_empty() {
         if matches!(format, OutputFormat::Json) {
@@ -771,13 +1642,14 @@ pub async fn cmd_review_list(
 
     match format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&changes)?);
+            let entries: Vec<list::ListEntry> = changes.iter().map(list::ListEntry::new).collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
         }
         OutputFormat::Text => {
             let output = if verbose {
-                list::format_reviews_verbose(&changes)
+                list::format_reviews_verbose(&changes, style, show_owner)
             } else {
-                list::format_reviews_text(&changes)
+                list::format_reviews_text(&changes, style)
             };
             print!("{output}");
         }
@@ -786,10 +1658,181 @@ pub async fn cmd_review_list(
     Ok(())
 }
 
+/// Query Gerrit for the open changes matching `query`, with their messages
+/// when `with_messages` is set.
+async fn fetch_list(
+    app: &mut App,
+    query: &str,
+    limit: Option<usize>,
+    with_messages: bool,
+) -> Result<Vec<ChangeInfo>> {
+    let root = app.require_git()?.root()?;
+    let remote = app.config.remote.clone();
+    let remote_url = review_query::resolve_gerrit_url(&remote, &root, &app.config)?
+        .context("no remote URL configured")?;
+
+    if review_query::is_http_remote(&remote_url) {
+        app.authenticate_and_verify().await?;
+    }
+
+    debug!("listing changes with query: {}", query);
+
+    // Without a cap, show a progress footer on stderr while pages arrive
+    let show_progress = limit.is_none();
+    let options: &[&str] = if with_messages { &["MESSAGES"] } else { &[] };
+    // Ask for one extra change so a truncated list can be told apart from
+    // one that has exactly `limit` changes
+    let mut changes = review_query::query_changes(
+        &remote_url,
+        query,
+        limit.map(|limit| limit.saturating_add(1)),
+        options,
+        &app.gerrit,
+        &root,
+        |fetched| {
+            if show_progress {
+                eprint!("\rFetched {fetched} changes...");
+            }
+        },
+    )
+    .await?;
+    if show_progress {
+        eprintln!();
+    } else if list::truncate_to_limit(&mut changes, limit) {
+        eprintln!(
+            "Showing the first {} changes; use --limit or --no-limit to see more.",
+            changes.len()
+        );
+    }
+    Ok(changes)
+}
+
+/// Read the cached result of `query`, noting its age on stderr.
+fn cached_list(cache: &cache::Cache, query: &str) -> Result<Vec<ChangeInfo>> {
+    let entry = cache.load_list(query)?.with_context(|| {
+        format!("no cached list for '{query}'; run `grt review -l` while Gerrit is reachable")
+    })?;
+    eprintln!(
+        "Showing cached list from {}.",
+        cache::describe_age(entry.fetched_at, cache::now_secs())
+    );
+    Ok(entry.changes)
+}
+
+/// Locations searched for a CODEOWNERS file, in GitHub's precedence order.
+pub const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Read the first CODEOWNERS file found under `root`, if any.
+pub fn read_codeowners(root: &std::path::Path) -> Option<String> {
+    CODEOWNERS_PATHS
+        .iter()
+        .find_map(|path| std::fs::read_to_string(root.join(path)).ok())
+}
+
+/// Parse a GitHub-style CODEOWNERS file into `(pattern, owners)` rules.
+///
+/// Comments and blank lines are skipped. Owners are normalized for Gerrit:
+/// a leading `@` is stripped from `@user` and `@org/team`, and email
+/// addresses are kept as-is. Rules keep file order, since the last matching
+/// rule wins.
+pub fn parse_codeowners(content: &str) -> Vec<(String, Vec<String>)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.split_once('#').map_or(line, |(before, _)| before);
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?;
+            let owners = fields
+                .map(|owner| owner.strip_prefix('@').unwrap_or(owner).to_string())
+                .collect();
+            Some((pattern.to_string(), owners))
+        })
+        .collect()
+}
+
+/// Owners for `path` under CODEOWNERS `rules`: the last matching rule wins.
+///
+/// A matching rule with no owners clears ownership, as on GitHub.
+pub fn codeowners_for_path<'a>(rules: &'a [(String, Vec<String>)], path: &str) -> &'a [String] {
+    rules
+        .iter()
+        .rev()
+        .find(|(pattern, _)| codeowners_pattern_matches(pattern, path))
+        .map_or(&[], |(_, owners)| owners.as_slice())
+}
+
+/// Collect the owners of all `files`, de-duplicated in first-seen order.
+pub fn codeowners_for_files(rules: &[(String, Vec<String>)], files: &[String]) -> Vec<String> {
+    let mut owners: Vec<String> = Vec::new();
+    for file in files {
+        for owner in codeowners_for_path(rules, file) {
+            if !owners.contains(owner) {
+                owners.push(owner.clone());
+            }
+        }
+    }
+    owners
+}
+
+/// Match a CODEOWNERS (gitignore-style) pattern against a repo-relative path.
+///
+/// Patterns with a leading or inner `/` are anchored at the repo root;
+/// others match at any depth. A pattern that matches a directory owns
+/// everything beneath it, except that a trailing `/*` only covers direct
+/// children. `*` and `?` stay within a path segment; `**` spans segments.
+pub fn codeowners_pattern_matches(pattern: &str, path: &str) -> bool {
+    let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+    let dir_only = pattern.ends_with('/');
+    let pat: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let covers_subtree = pat.last() != Some(&"*");
+
+    let starts = if anchored { 0..1 } else { 0..segments.len() };
+    for start in starts {
+        for end in start + 1..=segments.len() {
+            let is_file = end == segments.len();
+            if (is_file && dir_only) || (!is_file && !covers_subtree) {
+                continue;
+            }
+            if glob_segments_match(&pat, &segments[start..end]) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn glob_segments_match(pat: &[&str], path: &[&str]) -> bool {
+    match pat.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=path.len()).any(|skip| glob_segments_match(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => {
+                glob_segment_matches(first.as_bytes(), segment.as_bytes())
+                    && glob_segments_match(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn glob_segment_matches(pat: &[u8], text: &[u8]) -> bool {
+    match pat.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => {
+            (0..=text.len()).any(|skip| glob_segment_matches(rest, &text[skip..]))
+        }
+        Some((b'?', rest)) => !text.is_empty() && glob_segment_matches(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && glob_segment_matches(rest, &text[1..]),
+    }
+}
+
 /// Warn about flags that are parsed but not yet implemented.
 pub fn warn_unused_flags(_args: &ReviewArgs) {
     // --use-pushurl: implemented via CliOverrides
-    // --no-custom-script: accepted for compatibility, no-op (we don't run custom scripts)
+    // --no-custom-script: skips the pre-review/post-review scripts in main's cmd_review
 }
 
 #[cfg(test)]
@@ -856,6 +1899,23 @@ mod tests {
         assert_eq!(args.download.as_deref(), Some("12345,2"));
     }
 
+    #[test]
+    fn parse_download_detach_and_branch() {
+        let args = parse_review(&["-d", "12345", "--detach"]);
+        assert!(args.detach);
+
+        let args = parse_review(&["-d", "12345", "--branch", "fix-it"]);
+        assert_eq!(args.download_branch.as_deref(), Some("fix-it"));
+        assert!(args.branch.is_none());
+    }
+
+    #[test]
+    fn download_branch_options_require_download() {
+        assert!(try_parse_review(&["--detach"]).is_err());
+        assert!(try_parse_review(&["--branch", "x"]).is_err());
+        assert!(try_parse_review(&["-d", "1", "--detach", "--branch", "x"]).is_err());
+    }
+
     #[test]
     fn parse_cherrypick_short() {
         let args = parse_review(&["-x", "12345"]);
@@ -904,6 +1964,25 @@ mod tests {
         assert_eq!(args.compare.as_deref(), Some("12345,1"));
     }
 
+    #[test]
+    fn parse_compare_interdiff() {
+        let args = parse_review(&["-m", "12345,1-3", "--interdiff"]);
+        assert!(args.interdiff);
+    }
+
+    #[test]
+    fn parse_compare_difftool() {
+        let args = parse_review(&["-m", "12345,1-3", "--difftool"]);
+        assert!(args.difftool);
+        assert!(try_parse_review(&["--difftool"]).is_err());
+        assert!(try_parse_review(&["-m", "12345,1-3", "--difftool", "--interdiff"]).is_err());
+    }
+
+    #[test]
+    fn interdiff_requires_compare() {
+        assert!(try_parse_review(&["--interdiff"]).is_err());
+    }
+
     #[test]
     fn parse_list_short() {
         let args = parse_review(&["-l"]);
@@ -1116,12 +2195,91 @@ mod tests {
         assert_eq!(args.reviewers, vec!["alice", "bob"]);
     }
 
+    #[test]
+    fn parse_no_default_reviewers() {
+        let args = parse_review(&["--no-default-reviewers"]);
+        assert!(args.no_default_reviewers);
+    }
+
+    #[test]
+    fn parse_list_limit() {
+        let args = parse_review(&["-l", "--limit", "25"]);
+        assert_eq!(args.limit, Some(25));
+        assert!(!args.no_limit);
+    }
+
+    #[test]
+    fn parse_list_cached() {
+        let args = parse_review(&["-l", "--cached"]);
+        assert!(args.cached);
+        assert!(!args.refresh);
+        assert!(try_parse_review(&["-l", "--cached", "--refresh"]).is_err());
+    }
+
+    #[test]
+    fn parse_list_no_limit() {
+        let args = parse_review(&["-l", "--no-limit"]);
+        assert!(args.no_limit);
+        assert_eq!(args.limit, None);
+    }
+
+    #[test]
+    fn parse_list_filters() {
+        let args = parse_review(&["-l", "--mine", "--wip", "--private", "--reviewer", "alice"]);
+        assert!(args.mine);
+        assert!(args.wip);
+        assert!(args.private);
+        assert_eq!(args.reviewer.as_deref(), Some("alice"));
+        assert!(!args.owner);
+        assert!(parse_review(&["-l", "--owner"]).owner);
+    }
+
+    #[test]
+    fn parse_list_filters_require_list() {
+        assert!(try_parse_review(&["--mine"]).is_err());
+        assert!(try_parse_review(&["--reviewer", "alice"]).is_err());
+        assert!(try_parse_review(&["--owner"]).is_err());
+    }
+
+    #[test]
+    fn parse_limit_conflicts_with_no_limit() {
+        assert!(try_parse_review(&["-l", "--limit", "25", "--no-limit"]).is_err());
+    }
+
     #[test]
     fn parse_cc() {
         let args = parse_review(&["--cc", "carol"]);
         assert_eq!(args.cc, vec!["carol"]);
     }
 
+    #[test]
+    fn parse_label_base_merged_publish_comments() {
+        let args = parse_review(&[
+            "--label",
+            "Verified+1",
+            "Code-Review-1",
+            "--base",
+            "abc123",
+            "--merged",
+            "--publish-comments",
+        ]);
+        assert_eq!(args.labels, vec!["Verified+1", "Code-Review-1"]);
+        assert_eq!(args.base.as_deref(), Some("abc123"));
+        assert!(args.merged);
+        assert!(args.publish_comments);
+    }
+
+    #[test]
+    fn parse_push_options() {
+        let args = parse_review(&[
+            "--push-option",
+            "trace=1",
+            "--push-option",
+            "ignore-attention-set",
+        ]);
+        assert_eq!(args.push_options, vec!["trace=1", "ignore-attention-set"]);
+    }
+
     #[test]
     fn parse_hashtags() {
         let args = parse_review(&["--hashtags", "urgent", "bug"]);
@@ -1140,6 +2298,13 @@ mod tests {
         assert!(result.is_err(), "invalid notify value should be rejected");
     }
 
+    #[test]
+    fn parse_notify_to_and_cc() {
+        let args = parse_review(&["--notify-to", "alice", "bob", "--notify-cc", "carol"]);
+        assert_eq!(args.notify_to, vec!["alice", "bob"]);
+        assert_eq!(args.notify_cc, vec!["carol"]);
+    }
+
     #[test]
     fn parse_message() {
         let args = parse_review(&["--message", "fix the bug"]);
@@ -1244,6 +2409,12 @@ mod tests {
         assert!(args.no_custom_script);
     }
 
+    #[test]
+    fn parse_verify() {
+        let args = parse_review(&["-d", "12345", "--verify"]);
+        assert!(args.verify);
+    }
+
     // === Track flags ===
 
     #[test]
@@ -1258,6 +2429,36 @@ mod tests {
         assert!(args.no_track);
     }
 
+    #[test]
+    fn track_setting_applies_unless_overridden() {
+        assert!(!parse_review(&[]).use_track(false));
+        assert!(parse_review(&[]).use_track(true));
+        assert!(parse_review(&["--track"]).use_track(false));
+        assert!(!parse_review(&["--no-track"]).use_track(true));
+    }
+
+    #[test]
+    fn topic_defaults_to_current_branch() {
+        let branch = || Some("feature".to_string());
+        assert_eq!(
+            parse_review(&[]).push_topic(false, branch).as_deref(),
+            Some("feature")
+        );
+        assert_eq!(parse_review(&["-T"]).push_topic(false, branch), None);
+    }
+
+    #[test]
+    fn notopic_setting_skips_default_topic_only() {
+        let branch = || Some("feature".to_string());
+        assert_eq!(parse_review(&[]).push_topic(true, branch), None);
+        assert_eq!(
+            parse_review(&["-t", "mytopic"])
+                .push_topic(true, branch)
+                .as_deref(),
+            Some("mytopic")
+        );
+    }
+
     // === Combined flags ===
 
     #[test]
@@ -1387,6 +2588,72 @@ mod tests {
         assert_eq!(ps, None);
     }
 
+    // === Change-Id and topic arguments ===
+
+    #[test]
+    fn change_id_detection() {
+        assert!(is_change_id("I0123456789abcdef0123456789abcdef01234567"));
+        assert!(!is_change_id("Iabcdef1234"));
+        assert!(!is_change_id("12345"));
+        assert!(!is_change_id("I0123456789abcdef0123456789abcdef0123456z"));
+    }
+
+    #[test]
+    fn topic_arg_parsing() {
+        assert_eq!(parse_topic_arg("topic:my-feature"), Some("my-feature"));
+        assert_eq!(parse_topic_arg("topic:\"my feature\""), Some("my feature"));
+        assert_eq!(parse_topic_arg("topic:"), None);
+        assert_eq!(parse_topic_arg("12345"), None);
+    }
+
+    #[test]
+    fn download_queries() {
+        assert_eq!(change_id_query("Iabc", "proj"), "change:Iabc project:proj");
+        assert_eq!(
+            topic_query("my-feature", "proj"),
+            "status:open topic:\"my-feature\" project:proj"
+        );
+    }
+
+    fn numbered_change(number: i64, branch: &str) -> ChangeInfo {
+        ChangeInfo {
+            number: Some(number),
+            branch: Some(branch.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn select_change_for_id_single_match() {
+        let found = vec![numbered_change(42, "main")];
+        assert_eq!(select_change_for_id("Iabc", &found).unwrap(), 42);
+    }
+
+    #[test]
+    fn select_change_for_id_no_match() {
+        let err = select_change_for_id("Iabc", &[]).unwrap_err();
+        assert!(err.to_string().contains("no change found"));
+    }
+
+    #[test]
+    fn select_change_for_id_ambiguous_lists_branches() {
+        let found = vec![numbered_change(43, "stable/1"), numbered_change(42, "main")];
+        let err = select_change_for_id("Iabc", &found)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("42 (main), 43 (stable/1)"), "{err}");
+    }
+
+    #[test]
+    fn stack_order_puts_parents_first() {
+        // c is built on b, which is built on a; d is independent.
+        let shas: Vec<String> = ["c", "d", "a", "b"].iter().map(|s| s.to_string()).collect();
+        let ancestors = |a: &str, b: &str| matches!((a, b), ("a", "b") | ("a", "c") | ("b", "c"));
+        let order = stack_order(&shas, ancestors);
+        let names: Vec<&str> = order.iter().map(|&i| shas[i].as_str()).collect();
+        assert_eq!(names, vec!["d", "a", "b", "c"]);
+    }
+
     // === find_target_revision ===
 
     fn make_test_change() -> ChangeInfo {
@@ -1425,6 +2692,7 @@ mod tests {
                 email: None,
                 username: Some("alice".to_string()),
                 display_name: None,
+                tags: None,
             }),
             current_revision: Some("def456".to_string()),
             revisions: Some(revisions),
@@ -1432,6 +2700,8 @@ mod tests {
             insertions: None,
             deletions: None,
             labels: None,
+            attention_set: None,
+            more_changes: None,
         }
     }
 
@@ -1506,6 +2776,36 @@ mod tests {
         );
     }
 
+    // === checkout_branch_name ===
+
+    #[test]
+    fn checkout_branch_uses_owner_and_number() {
+        let change = make_test_change();
+        assert_eq!(checkout_branch_name(&change), "review/alice/12345");
+    }
+
+    #[test]
+    fn checkout_branch_no_owner() {
+        let mut change = make_test_change();
+        change.owner = None;
+        assert_eq!(checkout_branch_name(&change), "review/12345");
+    }
+
+    // === branches_tracking_change ===
+
+    #[test]
+    fn branches_tracking_change_matches_number() {
+        let config = "branch.review/alice/12345.grt-change 12345\n\
+                      branch.review/bob/678.grt-change 678\n\
+                      branch.feature.x.grt-change 12345\n";
+        assert_eq!(
+            branches_tracking_change(config, 12345),
+            vec!["review/alice/12345", "feature.x"]
+        );
+        assert!(branches_tracking_change(config, 1).is_empty());
+        assert!(branches_tracking_change("", 12345).is_empty());
+    }
+
     // === parse_compare_arg ===
 
     #[test]
@@ -1648,4 +2948,151 @@ mod tests {
         };
         assert_eq!(normalized, "12345,1-3");
     }
+
+    // === CODEOWNERS ===
+
+    #[test]
+    fn parse_codeowners_extracts_owners() {
+        let rules = parse_codeowners(
+            "# Default owners\n\
+             *       @alice bob@example.com\n\
+             \n\
+             /docs/  @org/docs-team   # docs reviewers\n",
+        );
+        assert_eq!(
+            rules,
+            vec![
+                (
+                    "*".to_string(),
+                    vec!["alice".to_string(), "bob@example.com".to_string()]
+                ),
+                ("/docs/".to_string(), vec!["org/docs-team".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn codeowners_last_match_wins() {
+        let rules = parse_codeowners("* @alice\n*.rs @rustacean\n/src/legacy/ @bob\n");
+        assert_eq!(codeowners_for_path(&rules, "README.md"), ["alice"]);
+        assert_eq!(codeowners_for_path(&rules, "src/main.rs"), ["rustacean"]);
+        assert_eq!(codeowners_for_path(&rules, "src/legacy/old.rs"), ["bob"]);
+    }
+
+    #[test]
+    fn codeowners_rule_without_owners_clears_ownership() {
+        let rules = parse_codeowners("* @alice\n/vendor/\n");
+        assert!(codeowners_for_path(&rules, "vendor/lib.c").is_empty());
+    }
+
+    #[test]
+    fn codeowners_patterns() {
+        assert!(codeowners_pattern_matches("*.js", "web/app/main.js"));
+        assert!(codeowners_pattern_matches("apps/", "src/apps/x/y.rs"));
+        assert!(!codeowners_pattern_matches("/apps/", "src/apps/x.rs"));
+        assert!(codeowners_pattern_matches(
+            "/build/logs/",
+            "build/logs/a/b.log"
+        ));
+        assert!(codeowners_pattern_matches("docs/*", "docs/intro.md"));
+        assert!(!codeowners_pattern_matches("docs/*", "docs/guide/setup.md"));
+        assert!(codeowners_pattern_matches(
+            "**/logs",
+            "deep/nested/logs/x.log"
+        ));
+        assert!(codeowners_pattern_matches("/src/**/*.rs", "src/a/b/c.rs"));
+        assert!(codeowners_pattern_matches("file?.txt", "file1.txt"));
+        assert!(!codeowners_pattern_matches("file?.txt", "file10.txt"));
+    }
+
+    #[test]
+    fn codeowners_for_files_dedups_in_order() {
+        let rules = parse_codeowners("*.rs @alice @bob\n*.md @bob @carol\n");
+        let files = vec!["a.rs".to_string(), "b.md".to_string(), "c.rs".to_string()];
+        assert_eq!(
+            codeowners_for_files(&rules, &files),
+            ["alice", "bob", "carol"]
+        );
+    }
+
+    // === revision_matches ===
+
+    #[test]
+    fn revision_matches_identical_sha() {
+        let sha = "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678";
+        assert!(revision_matches(sha, sha));
+    }
+
+    #[test]
+    fn revision_matches_ignores_case_and_whitespace() {
+        assert!(revision_matches(
+            "A1B2C3D4E5F60718293A4B5C6D7E8F9012345678",
+            "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678\n"
+        ));
+    }
+
+    #[test]
+    fn revision_mismatch_detected() {
+        assert!(!revision_matches(
+            "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678",
+            "ffffffffffffffffffffffffffffffffffffffff"
+        ));
+    }
+
+    // === --onto / branch mismatch ===
+
+    #[test]
+    fn parse_onto_with_cherrypick() {
+        let args = parse_review(&["-x", "12345", "--onto"]);
+        assert!(args.onto);
+    }
+
+    #[test]
+    fn onto_conflicts_with_download() {
+        assert!(try_parse_review(&["-d", "12345", "--onto"]).is_err());
+    }
+
+    // === conflicts ===
+
+    #[test]
+    fn parse_abort_on_conflict() {
+        assert!(parse_review(&["-N", "12345", "--abort-on-conflict"]).abort_on_conflict);
+        assert!(!parse_review(&["-x", "12345"]).abort_on_conflict);
+        assert!(try_parse_review(&["-d", "12345", "--abort-on-conflict"]).is_err());
+    }
+
+    #[test]
+    fn conflict_guidance_lists_files_and_next_steps() {
+        let files = vec!["src/lib.rs".to_string(), "README.md".to_string()];
+        let text = conflict_guidance(PickMode::Indicate, &files);
+        assert!(
+            text.starts_with("Cherry-pick stopped on conflicts in:\n  src/lib.rs\n  README.md\n")
+        );
+        assert!(text.contains("  git cherry-pick --continue\n"));
+        assert!(text.contains("  git cherry-pick --abort\n"));
+
+        let text = conflict_guidance(PickMode::NoCommit, &files);
+        assert!(text.contains("nothing is committed"));
+        assert!(text.contains("  git reset --merge\n"));
+        assert!(!text.contains("--continue"));
+    }
+
+    #[test]
+    fn branch_mismatch_warns_on_different_branch() {
+        let warning = branch_mismatch_warning(Some("stable/2024.1"), Some("main")).unwrap();
+        assert!(warning.contains("'stable/2024.1'"), "{warning}");
+        assert!(warning.contains("'main'"), "{warning}");
+        assert!(warning.contains("--onto"), "{warning}");
+    }
+
+    #[test]
+    fn branch_mismatch_silent_when_branches_match() {
+        assert!(branch_mismatch_warning(Some("main"), Some("main")).is_none());
+    }
+
+    #[test]
+    fn branch_mismatch_silent_when_unknown() {
+        assert!(branch_mismatch_warning(None, Some("main")).is_none());
+        assert!(branch_mismatch_warning(Some("main"), None).is_none());
+    }
 }
@@ -10,9 +10,8 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 
-use crate::config::{alias_url, populate_rewrites};
-use crate::gerrit::{ChangeInfo, GerritClient};
-use crate::list;
+use crate::config::{alias_url, populate_rewrites, GerritConfig};
+use crate::gerrit::{ChangeInfo, GerritClient, ReviewInput};
 use crate::subprocess;
 
 /// Resolve the effective remote URL for push operations.
@@ -50,6 +49,48 @@ pub fn resolve_remote_url(
     Ok(Some(url))
 }
 
+/// Resolve the Gerrit URL of `remote` under the effective configuration.
+///
+/// Like [`resolve_remote_url`] with the configured server as fallback, except
+/// that with `usepushurl` a remote that has no `pushurl` yet resolves to the
+/// configured server: its `url` then points at a mirror, not at Gerrit.
+pub fn resolve_gerrit_url(
+    remote: &str,
+    work_dir: &Path,
+    config: &GerritConfig,
+) -> Result<Option<String>> {
+    if config.usepushurl && !config.host.is_empty() {
+        let pushurl = subprocess::git_config_get(&format!("remote.{remote}.pushurl"), work_dir)?;
+        if pushurl.is_none() {
+            let rewrites = subprocess::git_config_list(work_dir)
+                .map(|out| populate_rewrites(&out))
+                .unwrap_or_default();
+            return Ok(Some(config.make_rewritten_remote_url(&rewrites, true)));
+        }
+    }
+    resolve_remote_url(remote, work_dir, Some(&config.make_remote_url()))
+}
+
+/// Point `remote`'s push URL at the configured Gerrit server (`usepushurl`).
+///
+/// An existing `remote.<name>.pushurl` is left alone, as git-review does.
+/// The stored URL honours `pushInsteadOf`. Returns the URL when one was set.
+pub fn ensure_push_url(
+    remote: &str,
+    work_dir: &Path,
+    config: &GerritConfig,
+) -> Result<Option<String>> {
+    if subprocess::git_config_get(&format!("remote.{remote}.pushurl"), work_dir)?.is_some() {
+        return Ok(None);
+    }
+    let rewrites = subprocess::git_config_list(work_dir)
+        .map(|out| populate_rewrites(&out))
+        .unwrap_or_default();
+    let push_url = config.make_rewritten_remote_url(&rewrites, true);
+    subprocess::git_remote_set_push_url(remote, &push_url, work_dir)?;
+    Ok(Some(push_url))
+}
+
 /// Return true if the URL uses HTTP or HTTPS (REST API path).
 #[inline]
 pub fn is_http_remote(url: &str) -> bool {
@@ -122,21 +163,164 @@ mod tests {
         let url = resolve_remote_url("origin", work_dir, None).unwrap();
         assert_eq!(url.as_deref(), Some("ssh://user@push.example.com:29418/p"));
     }
+
+    fn git(work_dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(work_dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn pushurl_config() -> GerritConfig {
+        GerritConfig {
+            host: "review.example.com".into(),
+            scheme: "ssh".into(),
+            ssh_port: Some(29418),
+            project: "my/project".into(),
+            username: Some("alice".into()),
+            remote: "origin".into(),
+            usepushurl: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_gerrit_url_usepushurl_ignores_mirror_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path();
+        git(work_dir, &["init", "-q"]);
+        git(
+            work_dir,
+            &["remote", "add", "origin", "https://mirror.example.com/p"],
+        );
+
+        let config = pushurl_config();
+        let url = resolve_gerrit_url("origin", work_dir, &config).unwrap();
+        assert_eq!(
+            url.as_deref(),
+            Some("ssh://alice@review.example.com:29418/my/project")
+        );
+
+        let config = GerritConfig {
+            usepushurl: false,
+            ..pushurl_config()
+        };
+        let url = resolve_gerrit_url("origin", work_dir, &config).unwrap();
+        assert_eq!(url.as_deref(), Some("https://mirror.example.com/p"));
+    }
+
+    #[test]
+    fn ensure_push_url_sets_pushurl_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path();
+        git(work_dir, &["init", "-q"]);
+        git(
+            work_dir,
+            &["remote", "add", "origin", "https://mirror.example.com/p"],
+        );
+
+        let config = pushurl_config();
+        let set = ensure_push_url("origin", work_dir, &config).unwrap();
+        assert_eq!(
+            set.as_deref(),
+            Some("ssh://alice@review.example.com:29418/my/project")
+        );
+        // The fetch URL is untouched
+        assert_eq!(
+            subprocess::git_config_get("remote.origin.url", work_dir).unwrap(),
+            Some("https://mirror.example.com/p".into())
+        );
+
+        git(
+            work_dir,
+            &[
+                "remote",
+                "set-url",
+                "--push",
+                "origin",
+                "ssh://other.example.com/p",
+            ],
+        );
+        assert_eq!(ensure_push_url("origin", work_dir, &config).unwrap(), None);
+        assert_eq!(
+            resolve_gerrit_url("origin", work_dir, &config)
+                .unwrap()
+                .as_deref(),
+            Some("ssh://other.example.com/p")
+        );
+    }
+
+    #[test]
+    fn ensure_push_url_rewrite_interaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path();
+        git(work_dir, &["init", "-q"]);
+        git(
+            work_dir,
+            &["remote", "add", "origin", "https://mirror.example.com/p"],
+        );
+        git(
+            work_dir,
+            &[
+                "config",
+                "url.ssh://push.example.com/.pushInsteadOf",
+                "ssh://alice@review.example.com:29418/",
+            ],
+        );
+
+        // The stored push URL honours pushInsteadOf
+        let config = pushurl_config();
+        let set = ensure_push_url("origin", work_dir, &config).unwrap();
+        assert_eq!(set.as_deref(), Some("ssh://push.example.com/my/project"));
+
+        // A pushurl gets insteadOf, never pushInsteadOf, as in git
+        git(
+            work_dir,
+            &["config", "url.gerrit:.insteadOf", "ssh://push.example.com/"],
+        );
+        let url = resolve_gerrit_url("origin", work_dir, &config).unwrap();
+        assert_eq!(url.as_deref(), Some("gerrit:my/project"));
+    }
 }
 
-/// Query open changes, dispatching to HTTP or SSH based on remote URL.
+/// Run a list query (see [`crate::list::build_list_query`]), dispatching to HTTP or
+/// SSH based on remote URL.
+///
+/// Over HTTP, results are paginated up to `limit` (`None` fetches every page,
+/// reporting the running total through `on_page`), and `options` adds `o=`
+/// options to the request. Over SSH, `limit` is passed as a `limit:` operator
+/// and `options` is ignored.
 pub async fn query_changes(
     remote_url: &str,
-    project: &str,
-    branch: Option<&str>,
+    query: &str,
+    limit: Option<usize>,
+    options: &[&str],
+    gerrit: &GerritClient,
+    work_dir: &Path,
+    on_page: impl FnMut(usize),
+) -> Result<Vec<ChangeInfo>> {
+    if is_http_remote(remote_url) {
+        gerrit
+            .query_changes_with_options(query, limit, options, on_page)
+            .await
+    } else {
+        ssh::query_changes_over_ssh(remote_url, query, limit, work_dir).await
+    }
+}
+
+/// Run a Gerrit search (`status:open topic:x ...`), over HTTP or SSH.
+pub async fn search_changes(
+    remote_url: &str,
+    query: &str,
     gerrit: &GerritClient,
     work_dir: &Path,
 ) -> Result<Vec<ChangeInfo>> {
     if is_http_remote(remote_url) {
-        let query = list::build_list_query(project, branch);
-        gerrit.query_changes(&query).await
+        gerrit.query_changes(query).await
     } else {
-        ssh::query_changes_over_ssh(remote_url, project, branch, work_dir).await
+        ssh::search_changes_ssh(remote_url, query, work_dir).await
     }
 }
 
@@ -154,8 +338,83 @@ pub async fn get_change_all_revisions(
     }
 }
 
+/// Get the Gerrit server version (`gerrit version` over SSH).
+pub async fn get_version(
+    remote_url: &str,
+    gerrit: &GerritClient,
+    work_dir: &Path,
+) -> Result<String> {
+    if is_http_remote(remote_url) {
+        gerrit.get_version().await
+    } else {
+        ssh::get_version_ssh(remote_url, work_dir).await
+    }
+}
+
+/// Post a review message and/or votes on `revision` of `change_id`.
+///
+/// `revision` is a commit SHA, which both protocols accept. Over SSH this
+/// runs `gerrit review`, which has no per-recipient notification, so
+/// `notify_details` is rejected there.
+pub async fn post_review(
+    remote_url: &str,
+    change_id: &str,
+    revision: &str,
+    input: &ReviewInput,
+    gerrit: &GerritClient,
+    work_dir: &Path,
+) -> Result<()> {
+    if is_http_remote(remote_url) {
+        gerrit.set_review(change_id, revision, input).await
+    } else {
+        ssh::post_review_ssh(remote_url, revision, input, work_dir).await
+    }
+}
+
+/// Add reviewers (accounts or groups) to a change.
+pub async fn add_reviewers(
+    remote_url: &str,
+    change_id: &str,
+    reviewers: &[String],
+    gerrit: &GerritClient,
+    work_dir: &Path,
+) -> Result<()> {
+    if reviewers.is_empty() {
+        return Ok(());
+    }
+    if is_http_remote(remote_url) {
+        for reviewer in reviewers {
+            gerrit.add_reviewer(change_id, reviewer).await?;
+        }
+        Ok(())
+    } else {
+        ssh::set_reviewers_ssh(remote_url, change_id, reviewers, &[], work_dir).await
+    }
+}
+
+/// Remove reviewers or CCs from a change.
+pub async fn remove_reviewers(
+    remote_url: &str,
+    change_id: &str,
+    reviewers: &[String],
+    gerrit: &GerritClient,
+    work_dir: &Path,
+) -> Result<()> {
+    if reviewers.is_empty() {
+        return Ok(());
+    }
+    if is_http_remote(remote_url) {
+        for reviewer in reviewers {
+            gerrit.remove_reviewer(change_id, reviewer).await?;
+        }
+        Ok(())
+    } else {
+        ssh::set_reviewers_ssh(remote_url, change_id, &[], reviewers, work_dir).await
+    }
+}
+
 /// SSH-based Gerrit query backend.
-mod ssh {
+pub(crate) mod ssh {
     use super::*;
     use crate::gerrit::AccountInfo;
     use serde::Deserialize;
@@ -323,6 +582,8 @@ mod ssh {
             insertions: None,
             deletions: None,
             labels: None,
+            attention_set: None,
+            more_changes: None,
         }
     }
 
@@ -396,16 +657,15 @@ mod ssh {
     /// Run `ssh ... gerrit query --format=JSON ...` and parse JSON-per-line output.
     pub async fn query_changes_over_ssh(
         remote_url: &str,
-        project: &str,
-        branch: Option<&str>,
+        query: &str,
+        limit: Option<usize>,
         work_dir: &Path,
     ) -> Result<Vec<ChangeInfo>> {
         let (hostname, username, port, _project_name) = parse_gerrit_ssh_params(remote_url)?;
 
-        let project_clean = project.strip_suffix(".git").unwrap_or(project);
-        let mut query = format!("project:{} status:open", project_clean);
-        if let Some(b) = branch {
-            query.push_str(&format!(" branch:{b}"));
+        let mut query = query.to_string();
+        if let Some(limit) = limit {
+            query.push_str(&format!(" limit:{limit}"));
         }
 
         let output =
@@ -413,6 +673,18 @@ mod ssh {
         parse_ssh_query_output(&output)
     }
 
+    /// Run an arbitrary `gerrit query` over SSH.
+    pub async fn search_changes_ssh(
+        remote_url: &str,
+        query: &str,
+        work_dir: &Path,
+    ) -> Result<Vec<ChangeInfo>> {
+        let (hostname, username, port, _project) = parse_gerrit_ssh_params(remote_url)?;
+        let output =
+            run_gerrit_query_ssh(&hostname, username.as_deref(), port, query, work_dir).await?;
+        parse_ssh_query_output(&output)
+    }
+
     /// Get change with all revisions via SSH.
     pub async fn get_change_all_revisions_ssh(
         remote_url: &str,
@@ -433,12 +705,130 @@ mod ssh {
             .context("change not found in SSH query output")
     }
 
+    /// Run `gerrit version` over SSH and return the bare version string.
+    pub async fn get_version_ssh(remote_url: &str, work_dir: &Path) -> Result<String> {
+        let (hostname, username, port, _project) = parse_gerrit_ssh_params(remote_url)?;
+        let args = vec!["gerrit".to_string(), "version".to_string()];
+        let output = run_gerrit_ssh(&hostname, username.as_deref(), port, args, work_dir).await?;
+        Ok(parse_version_output(&output))
+    }
+
+    /// Run `gerrit review` over SSH for `revision` (a commit SHA or `CHANGE,PS`).
+    pub async fn post_review_ssh(
+        remote_url: &str,
+        revision: &str,
+        input: &ReviewInput,
+        work_dir: &Path,
+    ) -> Result<()> {
+        let (hostname, username, port, project) = parse_gerrit_ssh_params(remote_url)?;
+        let args = review_command_args(revision, Some(&project), input)?;
+        run_gerrit_ssh(&hostname, username.as_deref(), port, args, work_dir).await?;
+        Ok(())
+    }
+
+    /// Run `gerrit set-reviewers --add ... --remove ...` over SSH.
+    pub async fn set_reviewers_ssh(
+        remote_url: &str,
+        change_id: &str,
+        add: &[String],
+        remove: &[String],
+        work_dir: &Path,
+    ) -> Result<()> {
+        let (hostname, username, port, project) = parse_gerrit_ssh_params(remote_url)?;
+        let args = set_reviewers_args(change_id, Some(&project), add, remove);
+        run_gerrit_ssh(&hostname, username.as_deref(), port, args, work_dir).await?;
+        Ok(())
+    }
+
+    /// Strip the `gerrit version ` prefix from `gerrit version` output.
+    pub(super) fn parse_version_output(output: &str) -> String {
+        let line = output.trim();
+        line.strip_prefix("gerrit version ")
+            .unwrap_or(line)
+            .to_string()
+    }
+
+    /// Quote an argument for Gerrit's SSH command-line parser.
+    ///
+    /// ssh joins the remote command into one string, so free text such as a
+    /// review message must survive a round of shell-style splitting.
+    pub(super) fn quote_ssh_arg(arg: &str) -> String {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+
+    /// Build the `gerrit review` command line for `input`.
+    pub(super) fn review_command_args(
+        revision: &str,
+        project: Option<&str>,
+        input: &ReviewInput,
+    ) -> Result<Vec<String>> {
+        if !input.notify_details.is_empty() {
+            anyhow::bail!(
+                "per-recipient notification (--notify-to/--notify-cc) needs the REST API; \
+                 it is not available over SSH"
+            );
+        }
+        let mut args = vec!["gerrit".to_string(), "review".to_string()];
+        if let Some(project) = project.filter(|p| !p.is_empty()) {
+            args.extend(["--project".to_string(), project.to_string()]);
+        }
+        if let Some(message) = &input.message {
+            args.extend(["--message".to_string(), quote_ssh_arg(message)]);
+        }
+        if let Some(notify) = &input.notify {
+            args.extend(["--notify".to_string(), notify.clone()]);
+        }
+        for (label, value) in &input.labels {
+            args.extend(["--label".to_string(), format!("{label}={value}")]);
+        }
+        args.push(revision.to_string());
+        Ok(args)
+    }
+
+    /// Build the `gerrit set-reviewers` command line adding `add` and
+    /// removing `remove`.
+    pub(super) fn set_reviewers_args(
+        change_id: &str,
+        project: Option<&str>,
+        add: &[String],
+        remove: &[String],
+    ) -> Vec<String> {
+        let mut args = vec!["gerrit".to_string(), "set-reviewers".to_string()];
+        if let Some(project) = project.filter(|p| !p.is_empty()) {
+            args.extend(["--project".to_string(), project.to_string()]);
+        }
+        for reviewer in add {
+            args.extend(["--add".to_string(), quote_ssh_arg(reviewer)]);
+        }
+        for reviewer in remove {
+            args.extend(["--remove".to_string(), quote_ssh_arg(reviewer)]);
+        }
+        args.push(change_id.to_string());
+        args
+    }
+
     async fn run_gerrit_query_ssh(
         hostname: &str,
         username: Option<&str>,
         port: Option<u16>,
         query: &str,
         work_dir: &Path,
+    ) -> Result<String> {
+        let args = vec![
+            "gerrit".to_string(),
+            "query".to_string(),
+            format!("--format=JSON {query}"),
+        ];
+        run_gerrit_ssh(hostname, username, port, args, work_dir).await
+    }
+
+    /// Run a Gerrit SSH command (`ssh -x -p<port> [user@]host <args...>`).
+    async fn run_gerrit_ssh(
+        hostname: &str,
+        username: Option<&str>,
+        port: Option<u16>,
+        args: Vec<String>,
+        work_dir: &Path,
     ) -> Result<String> {
         let userhost = match username {
             Some(u) => format!("{u}@{hostname}"),
@@ -451,12 +841,13 @@ mod ssh {
         };
 
         let ssh_bin = std::env::var("GIT_SSH").unwrap_or_else(|_| "ssh".to_string());
-        let full_query = format!("--format=JSON {query}");
+        let command = args.get(..2).map(|a| a.join(" ")).unwrap_or_default();
         let work_dir = work_dir.to_path_buf();
 
         let output = tokio::task::spawn_blocking(move || {
             Command::new(&ssh_bin)
-                .args(["-x", &port_arg, &userhost, "gerrit", "query", &full_query])
+                .args(["-x", &port_arg, &userhost])
+                .args(&args)
                 .current_dir(&work_dir)
                 .env("LANG", "C")
                 .env("LANGUAGE", "C")
@@ -464,11 +855,11 @@ mod ssh {
         })
         .await
         .map_err(|e| anyhow::anyhow!("spawn_blocking: {e}"))?
-        .context("running ssh gerrit query")?;
+        .with_context(|| format!("running ssh {command}"))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("ssh gerrit query failed: {}", stderr.trim());
+            anyhow::bail!("ssh {command} failed: {}", stderr.trim());
         }
 
         String::from_utf8(output.stdout).context("ssh output is not valid UTF-8")
@@ -525,6 +916,88 @@ mod ssh {
             assert_eq!(proj, "repo");
         }
 
+        #[test]
+        fn parse_version_output_strips_prefix() {
+            assert_eq!(parse_version_output("gerrit version 3.9.1\n"), "3.9.1");
+            assert_eq!(parse_version_output("3.10.0"), "3.10.0");
+        }
+
+        #[test]
+        fn quote_ssh_arg_escapes_single_quotes() {
+            assert_eq!(quote_ssh_arg("looks good"), "'looks good'");
+            assert_eq!(quote_ssh_arg("it's fine"), r"'it'\''s fine'");
+        }
+
+        #[test]
+        fn review_command_args_message_notify_and_votes() {
+            let mut input = ReviewInput {
+                message: Some("Build passed".into()),
+                notify: Some("OWNER".into()),
+                ..Default::default()
+            };
+            input.labels.insert("Code-Review".into(), 1);
+            input.labels.insert("Verified".into(), -1);
+            let args = review_command_args("abc123", Some("proj"), &input).unwrap();
+            assert_eq!(
+                args,
+                vec![
+                    "gerrit",
+                    "review",
+                    "--project",
+                    "proj",
+                    "--message",
+                    "'Build passed'",
+                    "--notify",
+                    "OWNER",
+                    "--label",
+                    "Code-Review=1",
+                    "--label",
+                    "Verified=-1",
+                    "abc123",
+                ]
+            );
+        }
+
+        #[test]
+        fn review_command_args_rejects_notify_details() {
+            let mut input = ReviewInput::default();
+            input.add_notify_recipients("TO", &["alice".into()]);
+            assert!(review_command_args("abc123", None, &input).is_err());
+        }
+
+        #[test]
+        fn set_reviewers_args_adds_each_reviewer() {
+            let args = set_reviewers_args(
+                "I123",
+                Some("proj"),
+                &["alice".into(), "core team".into()],
+                &[],
+            );
+            assert_eq!(
+                args,
+                vec![
+                    "gerrit",
+                    "set-reviewers",
+                    "--project",
+                    "proj",
+                    "--add",
+                    "'alice'",
+                    "--add",
+                    "'core team'",
+                    "I123",
+                ]
+            );
+        }
+
+        #[test]
+        fn set_reviewers_args_removes_each_reviewer() {
+            let args = set_reviewers_args("I123", None, &[], &["bob".into()]);
+            assert_eq!(
+                args,
+                vec!["gerrit", "set-reviewers", "--remove", "'bob'", "I123"]
+            );
+        }
+
         #[test]
         fn parse_ssh_query_output_skips_stats_lines() {
             // Gerrit SSH query emits JSON-per-line; lines with "type" are stats, not changes
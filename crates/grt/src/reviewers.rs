@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Argument handling and output for `grt reviewers`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use crate::gerrit::{AccountInfo, SuggestedReviewerInfo};
+
+/// Whether `arg` names a change rather than an account.
+///
+/// URLs, Change-Ids and `project~...` triplets always do. A bare number is
+/// only taken as a change when more arguments follow, since account IDs are
+/// numeric too.
+pub fn looks_like_change(arg: &str, more_follow: bool) -> bool {
+    let is_change_id =
+        arg.len() == 41 && arg.starts_with('I') && arg[1..].chars().all(|c| c.is_ascii_hexdigit());
+    let is_number = !arg.is_empty() && arg.chars().all(|c| c.is_ascii_digit());
+    arg.contains("://") || arg.contains('~') || is_change_id || (is_number && more_follow)
+}
+
+/// Split `[CHANGE] USER...` into the optional change and the users.
+pub fn split_change_and_users(args: &[String]) -> (Option<String>, Vec<String>) {
+    match args.split_first() {
+        Some((first, rest)) if looks_like_change(first, !rest.is_empty()) => {
+            (Some(first.clone()), rest.to_vec())
+        }
+        _ => (None, args.to_vec()),
+    }
+}
+
+/// Display form of an account: `Name <email>`, falling back to username or id.
+pub fn account_label(account: &AccountInfo) -> String {
+    let name = account
+        .name
+        .as_deref()
+        .or(account.display_name.as_deref())
+        .or(account.username.as_deref());
+    match (name, account.email.as_deref()) {
+        (Some(name), Some(email)) => format!("{name} <{email}>"),
+        (Some(name), None) => name.to_string(),
+        (None, Some(email)) => email.to_string(),
+        (None, None) => account
+            .account_id
+            .map_or_else(|| "unknown".to_string(), |id| id.to_string()),
+    }
+}
+
+/// Render reviewers grouped by state, reviewers first, then CCs.
+pub fn format_reviewers(by_state: &BTreeMap<String, Vec<AccountInfo>>) -> String {
+    let mut out = String::new();
+    for (state, heading) in [("REVIEWER", "Reviewers"), ("CC", "CC")] {
+        let accounts = by_state.get(state).map(Vec::as_slice).unwrap_or(&[]);
+        let _ = writeln!(out, "{heading}:");
+        if accounts.is_empty() {
+            out.push_str("  (none)\n");
+        }
+        for account in accounts {
+            let _ = writeln!(out, "  {}", account_label(account));
+        }
+    }
+    out
+}
+
+/// Render reviewer suggestions, one per line; groups are marked as such.
+pub fn format_suggestions(suggestions: &[SuggestedReviewerInfo]) -> String {
+    let mut out = String::new();
+    for suggestion in suggestions {
+        if let Some(account) = &suggestion.account {
+            let _ = writeln!(out, "  {}", account_label(account));
+        } else if let Some(name) = suggestion.group.as_ref().and_then(|g| g.name.as_deref()) {
+            let _ = writeln!(out, "  {name} (group)");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(json: serde_json::Value) -> AccountInfo {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn strings(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    const CHANGE_ID: &str = "I0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn split_detects_change_forms() {
+        assert_eq!(
+            split_change_and_users(&strings(&["12345", "alice"])),
+            (Some("12345".into()), strings(&["alice"]))
+        );
+        assert_eq!(
+            split_change_and_users(&strings(&[CHANGE_ID, "alice", "bob"])),
+            (Some(CHANGE_ID.into()), strings(&["alice", "bob"]))
+        );
+        assert_eq!(
+            split_change_and_users(&strings(&["https://review.example.com/c/p/+/1", "a"])).0,
+            Some("https://review.example.com/c/p/+/1".into())
+        );
+    }
+
+    #[test]
+    fn split_lone_number_is_a_user() {
+        assert_eq!(
+            split_change_and_users(&strings(&["1000096"])),
+            (None, strings(&["1000096"]))
+        );
+        assert_eq!(
+            split_change_and_users(&strings(&["alice", "bob"])),
+            (None, strings(&["alice", "bob"]))
+        );
+        assert_eq!(split_change_and_users(&[]), (None, vec![]));
+    }
+
+    #[test]
+    fn account_label_fallbacks() {
+        let full = account(serde_json::json!({"name": "Alice", "email": "a@example.com"}));
+        assert_eq!(account_label(&full), "Alice <a@example.com>");
+        let user = account(serde_json::json!({"username": "bob"}));
+        assert_eq!(account_label(&user), "bob");
+        let id_only = account(serde_json::json!({"_account_id": 7}));
+        assert_eq!(account_label(&id_only), "7");
+    }
+
+    #[test]
+    fn format_reviewers_groups_by_state() {
+        let mut by_state = BTreeMap::new();
+        by_state.insert(
+            "REVIEWER".to_string(),
+            vec![account(serde_json::json!({"name": "Alice"}))],
+        );
+        by_state.insert(
+            "REMOVED".to_string(),
+            vec![account(serde_json::json!({"name": "Gone"}))],
+        );
+        assert_eq!(
+            format_reviewers(&by_state),
+            "Reviewers:\n  Alice\nCC:\n  (none)\n"
+        );
+    }
+
+    #[test]
+    fn format_suggestions_marks_groups() {
+        let suggestions: Vec<SuggestedReviewerInfo> = serde_json::from_value(serde_json::json!([
+            {"account": {"name": "Alice", "email": "a@example.com"}},
+            {"group": {"id": "abc", "name": "core-team"}}
+        ]))
+        .unwrap();
+        assert_eq!(
+            format_suggestions(&suggestions),
+            "  Alice <a@example.com>\n  core-team (group)\n"
+        );
+    }
+}
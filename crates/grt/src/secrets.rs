@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Gerrit HTTP credentials in the OS keyring (Secret Service on Linux,
+//! Keychain on macOS, Credential Manager on Windows).
+//!
+//! Each host has one entry under the service `grt` whose secret is a small
+//! JSON object with the username, password and auth type, so a lookup only
+//! needs the host. Without the `keyring` feature every lookup finds nothing.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, LoadedCredentials};
+use crate::gerrit::AuthType;
+
+/// Keyring service name for grt entries.
+pub const SERVICE: &str = "grt";
+
+#[derive(Serialize, Deserialize)]
+struct StoredSecret {
+    username: String,
+    password: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth_type: Option<String>,
+}
+
+/// Encode credentials as the keyring secret.
+pub fn encode_secret(creds: &LoadedCredentials) -> Result<String> {
+    let stored = StoredSecret {
+        username: creds.username.clone(),
+        password: creds.password.clone(),
+        auth_type: match creds.auth_type {
+            AuthType::Basic => None,
+            AuthType::Bearer => Some("bearer".to_string()),
+        },
+    };
+    Ok(serde_json::to_string(&stored)?)
+}
+
+/// Decode a keyring secret written by [`encode_secret`].
+pub fn decode_secret(secret: &str) -> Result<LoadedCredentials> {
+    let stored: StoredSecret = serde_json::from_str(secret)
+        .map_err(|e| anyhow::anyhow!("keyring entry is not a grt credential: {e}"))?;
+    Ok(LoadedCredentials {
+        username: stored.username,
+        password: stored.password,
+        auth_type: config::parse_auth_type(stored.auth_type.as_deref()),
+    })
+}
+
+/// Whether grt was built with keyring support.
+pub fn available() -> bool {
+    cfg!(feature = "keyring")
+}
+
+/// Credentials stored for `host`, if any.
+#[cfg(feature = "keyring")]
+pub fn load(host: &str) -> Result<Option<LoadedCredentials>> {
+    match keyring::Entry::new(SERVICE, host)?.get_password() {
+        Ok(secret) => decode_secret(&secret).map(Some),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Store credentials for `host`, replacing any existing entry.
+#[cfg(feature = "keyring")]
+pub fn store(host: &str, creds: &LoadedCredentials) -> Result<()> {
+    keyring::Entry::new(SERVICE, host)?.set_password(&encode_secret(creds)?)?;
+    Ok(())
+}
+
+/// Remove the entry for `host`; returns whether there was one.
+#[cfg(feature = "keyring")]
+pub fn delete(host: &str) -> Result<bool> {
+    match keyring::Entry::new(SERVICE, host)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn load(_host: &str) -> Result<Option<LoadedCredentials>> {
+    Ok(None)
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn store(_host: &str, _creds: &LoadedCredentials) -> Result<()> {
+    anyhow::bail!("grt was built without keyring support; add the server to credentials.toml")
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn delete(_host: &str) -> Result<bool> {
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_round_trip() {
+        let creds = LoadedCredentials {
+            username: "alice".into(),
+            password: "s3cret".into(),
+            auth_type: AuthType::Bearer,
+        };
+        let secret = encode_secret(&creds).unwrap();
+        assert_eq!(decode_secret(&secret).unwrap(), creds);
+    }
+
+    #[test]
+    fn basic_auth_omits_auth_type() {
+        let creds = LoadedCredentials {
+            username: "alice".into(),
+            password: "pw".into(),
+            auth_type: AuthType::Basic,
+        };
+        let secret = encode_secret(&creds).unwrap();
+        assert_eq!(secret, r#"{"username":"alice","password":"pw"}"#);
+        assert_eq!(decode_secret(&secret).unwrap().auth_type, AuthType::Basic);
+    }
+
+    #[test]
+    fn foreign_secret_is_rejected() {
+        assert!(decode_secret("plain-password").is_err());
+    }
+}
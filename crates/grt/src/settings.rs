@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! `grt config`: the effective configuration with the layer each value came
+//! from, and writing a setting to the layer it belongs to.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::config::{self, ConfigSource, ConfigSources, GerritConfig};
+
+/// One row of `grt config show`.
+#[derive(Debug, Serialize)]
+pub struct ConfigEntry {
+    pub key: &'static str,
+    pub value: Option<String>,
+    pub source: ConfigSource,
+}
+
+/// Every setting of `config` with the layer that set it.
+pub fn config_entries(config: &GerritConfig, sources: &ConfigSources) -> Vec<ConfigEntry> {
+    config
+        .entries()
+        .into_iter()
+        .map(|(key, value)| ConfigEntry {
+            key,
+            value,
+            source: sources.source(key),
+        })
+        .collect()
+}
+
+/// One line per setting: key, value (`-` when unset) and source.
+pub fn format_entries(entries: &[ConfigEntry]) -> String {
+    let key_width = entries.iter().map(|e| e.key.len()).max().unwrap_or(0);
+    let value_width = entries
+        .iter()
+        .map(|e| e.value.as_deref().map_or(1, str::len))
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for entry in entries {
+        let _ = writeln!(
+            out,
+            "{:key_width$}  {:value_width$}  {}",
+            entry.key,
+            entry.value.as_deref().unwrap_or("-"),
+            entry.source.label()
+        );
+    }
+    out
+}
+
+/// Where `grt config set` writes a setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetTarget {
+    /// The repository's git config, under this key.
+    GitConfig(&'static str),
+    /// The `[gerrit]` table of the user's config.toml (or of the selected
+    /// profile), under this key.
+    UserToml(&'static str),
+    /// The `[gerrit."<host>"]` table of the user's config.toml for the
+    /// repository's host, under this key.
+    UserHostToml(&'static str),
+    /// A top-level key of the repository's `.grt.toml`.
+    RepoToml,
+}
+
+impl SetTarget {
+    /// The layer values written here are read back from.
+    pub fn source(self) -> ConfigSource {
+        match self {
+            SetTarget::GitConfig(_) => ConfigSource::GitConfig,
+            SetTarget::UserToml(_) | SetTarget::UserHostToml(_) => ConfigSource::UserToml,
+            SetTarget::RepoToml => ConfigSource::RepoToml,
+        }
+    }
+}
+
+/// How a setting's value is checked and stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Text,
+    Port,
+    Bool,
+    Seconds,
+}
+
+/// Settings `grt config set` can write, and where each goes: what
+/// identifies the Gerrit project goes to the repository's git config, HTTP
+/// transport options to the user's config.toml (per server for the auth
+/// type), and repo-wide preferences to `.grt.toml`.
+const SETTABLE: &[(&str, SetTarget, ValueKind)] = &[
+    (
+        "host",
+        SetTarget::GitConfig("gitreview.host"),
+        ValueKind::Text,
+    ),
+    (
+        "project",
+        SetTarget::GitConfig("gitreview.project"),
+        ValueKind::Text,
+    ),
+    (
+        "branch",
+        SetTarget::GitConfig("gitreview.branch"),
+        ValueKind::Text,
+    ),
+    (
+        "remote",
+        SetTarget::GitConfig("gitreview.remote"),
+        ValueKind::Text,
+    ),
+    (
+        "ssh_port",
+        SetTarget::GitConfig("gitreview.port"),
+        ValueKind::Port,
+    ),
+    (
+        "username",
+        SetTarget::GitConfig("gitreview.username"),
+        ValueKind::Text,
+    ),
+    (
+        "default_reviewers",
+        SetTarget::GitConfig("gitreview.defaultreviewers"),
+        ValueKind::Text,
+    ),
+    (
+        "signed_push",
+        SetTarget::GitConfig("gitreview.signedpush"),
+        ValueKind::Bool,
+    ),
+    ("scheme", SetTarget::UserToml("scheme"), ValueKind::Text),
+    ("http_port", SetTarget::UserToml("port"), ValueKind::Port),
+    (
+        "http1_only",
+        SetTarget::UserToml("http1_only"),
+        ValueKind::Bool,
+    ),
+    (
+        "pool_idle_timeout",
+        SetTarget::UserToml("pool_idle_timeout"),
+        ValueKind::Seconds,
+    ),
+    ("proxy", SetTarget::UserToml("proxy"), ValueKind::Text),
+    (
+        "ssl_ca_path",
+        SetTarget::UserToml("ssl_ca_path"),
+        ValueKind::Text,
+    ),
+    (
+        "auth_type",
+        SetTarget::UserHostToml("auth_type"),
+        ValueKind::Text,
+    ),
+    (
+        "default_output_format",
+        SetTarget::RepoToml,
+        ValueKind::Text,
+    ),
+    ("color", SetTarget::RepoToml, ValueKind::Text),
+    ("topic_from_trailer", SetTarget::RepoToml, ValueKind::Text),
+    (
+        "push.message_template",
+        SetTarget::RepoToml,
+        ValueKind::Text,
+    ),
+];
+
+/// A validated `grt config set`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetRequest {
+    pub key: &'static str,
+    pub target: SetTarget,
+    /// The value as written to git config or `.grt.toml`.
+    pub value: String,
+    /// The value as written to config.toml.
+    pub toml_value: toml::Value,
+}
+
+/// Check `key` and `value` and decide where the setting goes.
+pub fn plan_set(key: &str, value: &str) -> Result<SetRequest> {
+    let Some(&(key, target, kind)) = SETTABLE.iter().find(|(k, ..)| *k == key) else {
+        if let Some(hint) = read_only_hint(key) {
+            anyhow::bail!("{key} cannot be set with grt config: {hint}");
+        }
+        let known: Vec<&str> = SETTABLE.iter().map(|(k, ..)| *k).collect();
+        anyhow::bail!(
+            "unknown setting {key:?}; expected one of: {}",
+            known.join(", ")
+        );
+    };
+    let value = value.trim();
+    let toml_value = match kind {
+        ValueKind::Text => toml::Value::String(value.to_string()),
+        ValueKind::Port => toml::Value::Integer(
+            value
+                .parse::<u16>()
+                .with_context(|| format!("{key} must be a port number"))?
+                .into(),
+        ),
+        ValueKind::Seconds => toml::Value::Integer(
+            value
+                .parse::<u32>()
+                .with_context(|| format!("{key} must be a number of seconds"))?
+                .into(),
+        ),
+        ValueKind::Bool => toml::Value::Boolean(match value.to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => true,
+            "false" | "no" | "off" | "0" => false,
+            _ => anyhow::bail!("{key} must be true or false"),
+        }),
+    };
+    if target == SetTarget::RepoToml {
+        // Reject what loading the file would reject
+        let settings = HashMap::from([(key.to_string(), value.to_string())]);
+        config::apply_repo_settings(&mut GerritConfig::default(), &settings, "grt config set")?;
+    }
+    if key == "scheme" && !matches!(value, "ssh" | "http" | "https") {
+        anyhow::bail!("scheme must be ssh, http or https");
+    }
+    if key == "auth_type" && !matches!(value, "basic" | "bearer") {
+        anyhow::bail!("auth_type must be basic or bearer");
+    }
+    Ok(SetRequest {
+        key,
+        target,
+        value: value.to_string(),
+        toml_value,
+    })
+}
+
+/// Where to set the settings `grt config set` does not write.
+fn read_only_hint(key: &str) -> Option<&'static str> {
+    match key {
+        "default_rebase" | "track" | "notopic" | "usepushurl" | "default_cc" => {
+            Some("it is read from .gitreview or given on the command line")
+        }
+        "ssl_verify" => Some("set http.sslVerify in git config"),
+        _ => None,
+    }
+}
+
+/// Set `key` in the TOML file at `path`, under the table at `table_path`
+/// (created as needed), keeping the file's other settings.
+///
+/// The file is rewritten, so comments in it are not kept.
+pub fn write_toml_setting(
+    path: &Path,
+    table_path: &[&str],
+    key: &str,
+    value: toml::Value,
+) -> Result<()> {
+    let mut root: toml::Table = if path.exists() {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("parsing {}", path.display()))?
+    } else {
+        toml::Table::new()
+    };
+    let mut table = &mut root;
+    for name in table_path {
+        let entry = table
+            .entry(name.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        table = entry
+            .as_table_mut()
+            .with_context(|| format!("{name} in {} is not a table", path.display()))?;
+    }
+    table.insert(key.to_string(), value);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display()))?;
+    }
+    let content = toml::to_string(&root).context("encoding TOML")?;
+    std::fs::write(path, content).with_context(|| format!("writing {}", path.display()))
+}
+
+/// The `[gerrit."<host>"]` table path in config.toml.
+pub fn user_host_toml_table(host: &str) -> Vec<&str> {
+    vec!["gerrit", host]
+}
+
+/// The `[gerrit]` table path in config.toml, inside `profile` when one is selected.
+pub fn user_toml_table(profile: Option<&str>) -> Vec<&str> {
+    match profile {
+        Some(name) => vec!["profile", name, "gerrit"],
+        None => vec!["gerrit"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_set_routes_by_key() {
+        let host = plan_set("host", "review.example.org").unwrap();
+        assert_eq!(host.target, SetTarget::GitConfig("gitreview.host"));
+        let port = plan_set("http_port", "8443").unwrap();
+        assert_eq!(port.target, SetTarget::UserToml("port"));
+        assert_eq!(port.toml_value, toml::Value::Integer(8443));
+        let color = plan_set("color", "never").unwrap();
+        assert_eq!(color.target, SetTarget::RepoToml);
+        assert_eq!(color.target.source(), ConfigSource::RepoToml);
+        let template = plan_set("push.message_template", "[{ticket}] {topic}").unwrap();
+        assert_eq!(template.target, SetTarget::RepoToml);
+        let auth = plan_set("auth_type", "bearer").unwrap();
+        assert_eq!(auth.target, SetTarget::UserHostToml("auth_type"));
+        assert_eq!(auth.target.source(), ConfigSource::UserToml);
+        assert!(plan_set("auth_type", "digest").is_err());
+    }
+
+    #[test]
+    fn plan_set_validates_values() {
+        assert!(plan_set("ssh_port", "99999").is_err());
+        assert!(plan_set("http1_only", "maybe").is_err());
+        assert_eq!(
+            plan_set("http1_only", "yes").unwrap().toml_value,
+            toml::Value::Boolean(true)
+        );
+        let err = plan_set("color", "sometimes").unwrap_err();
+        assert!(err.to_string().contains("expected auto, always or never"));
+        assert!(plan_set("scheme", "ftp").is_err());
+    }
+
+    #[test]
+    fn plan_set_rejects_unknown_and_read_only_keys() {
+        let err = plan_set("hostname", "x").unwrap_err().to_string();
+        assert!(err.starts_with("unknown setting \"hostname\""), "{err}");
+        let err = plan_set("track", "true").unwrap_err().to_string();
+        assert!(err.contains(".gitreview"), "{err}");
+    }
+
+    #[test]
+    fn write_toml_setting_keeps_other_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("grt").join("config.toml");
+        write_toml_setting(&path, &["gerrit"], "proxy", "http://proxy:3128".into()).unwrap();
+        write_toml_setting(
+            &path,
+            &user_toml_table(Some("work")),
+            "port",
+            toml::Value::Integer(8443),
+        )
+        .unwrap();
+        write_toml_setting(&path, &["gerrit"], "http1_only", true.into()).unwrap();
+
+        let table: toml::Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let gerrit = table["gerrit"].as_table().unwrap();
+        assert_eq!(gerrit["proxy"].as_str(), Some("http://proxy:3128"));
+        assert_eq!(gerrit["http1_only"].as_bool(), Some(true));
+        assert_eq!(
+            table["profile"]["work"]["gerrit"]["port"].as_integer(),
+            Some(8443)
+        );
+    }
+
+    #[test]
+    fn format_lists_value_and_source() {
+        let entries = [
+            ConfigEntry {
+                key: "host",
+                value: Some("review.example.org".to_string()),
+                source: ConfigSource::Gitreview,
+            },
+            ConfigEntry {
+                key: "username",
+                value: None,
+                source: ConfigSource::Default,
+            },
+        ];
+        assert_eq!(
+            format_entries(&entries),
+            "host      review.example.org  .gitreview\n\
+             username  -                   default\n"
+        );
+    }
+}
@@ -0,0 +1,469 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Review status of one change for `grt status`: labels, submit requirements,
+//! the attention set and the latest CI results on the current patchset.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::attention::{self, AttentionEntry};
+use crate::ci::{self, CiReport, CiResult};
+use crate::gerrit::{ApprovalInfo, ChangeStatusInfo, LabelInfo};
+
+/// Where a label stands, as decided by its deciding vote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelState {
+    Approved,
+    Rejected,
+    Recommended,
+    Disliked,
+    Needed,
+    Optional,
+}
+
+impl LabelState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LabelState::Approved => "approved",
+            LabelState::Rejected => "rejected",
+            LabelState::Recommended => "recommended",
+            LabelState::Disliked => "disliked",
+            LabelState::Needed => "needed",
+            LabelState::Optional => "optional",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Vote {
+    pub account: String,
+    pub value: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelStatus {
+    pub name: String,
+    pub state: LabelState,
+    /// Non-zero votes, highest first.
+    pub votes: Vec<Vote>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequirementStatus {
+    pub name: String,
+    pub status: String,
+    /// Atoms that keep the requirement from being met, e.g. `label:Verified=MAX`.
+    pub failing: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    pub number: Option<i64>,
+    pub subject: Option<String>,
+    pub status: Option<String>,
+    pub patchset: Option<i32>,
+    pub submittable: Option<bool>,
+    /// Whether local HEAD is the current patchset; `None` when not compared.
+    pub head_is_current: Option<bool>,
+    pub labels: Vec<LabelStatus>,
+    pub submit_requirements: Vec<RequirementStatus>,
+    pub ci: Vec<CiReport>,
+    /// Users expected to act on the change next.
+    pub attention: Vec<AttentionEntry>,
+}
+
+impl StatusReport {
+    /// Names of what still blocks submission: unmet submit requirements, or
+    /// (on servers without them) labels that are not approved.
+    pub fn blockers(&self) -> Vec<String> {
+        let mut blockers: Vec<String> = if self.submit_requirements.is_empty() {
+            self.labels
+                .iter()
+                .filter(|l| !matches!(l.state, LabelState::Approved | LabelState::Optional))
+                .map(|l| l.name.clone())
+                .collect()
+        } else {
+            self.submit_requirements
+                .iter()
+                .filter(|r| matches!(r.status.as_str(), "UNSATISFIED" | "ERROR"))
+                .map(|r| r.name.clone())
+                .collect()
+        };
+        if self.ci.iter().any(|c| c.result == CiResult::Failed) {
+            blockers.push("CI failing".to_string());
+        }
+        blockers
+    }
+}
+
+/// Build the status report for `info`; `head` is local HEAD's SHA when the
+/// change was taken from HEAD.
+pub fn build_report(info: &ChangeStatusInfo, head: Option<&str>) -> StatusReport {
+    let change = &info.change;
+    let patchset = change
+        .current_revision
+        .as_ref()
+        .and_then(|rev| change.revisions.as_ref()?.get(rev)?.number);
+
+    let labels: BTreeMap<&String, &LabelInfo> = change.labels.iter().flatten().collect();
+    let labels = labels
+        .into_iter()
+        .map(|(name, info)| label_status(name, info))
+        .collect();
+
+    let submit_requirements = info
+        .submit_requirements
+        .iter()
+        .filter(|r| r.status != "NOT_APPLICABLE")
+        .map(|r| RequirementStatus {
+            name: r.name.clone(),
+            status: r.status.clone(),
+            failing: r
+                .submittability_expression_result
+                .as_ref()
+                .and_then(|e| e.failing_atoms.clone())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    let messages = change.messages.as_deref().unwrap_or(&[]);
+    let head_is_current = match (head, change.current_revision.as_deref()) {
+        (Some(head), Some(current)) => Some(head == current),
+        _ => None,
+    };
+
+    StatusReport {
+        number: change.number,
+        subject: change.subject.clone(),
+        status: change.status.clone(),
+        patchset,
+        submittable: info.submittable,
+        head_is_current,
+        labels,
+        submit_requirements,
+        ci: ci::latest_reports(messages, patchset),
+        attention: attention::attention_entries(change),
+    }
+}
+
+fn label_status(name: &str, info: &LabelInfo) -> LabelStatus {
+    // A veto blocks the change even when the label also has its maximum vote
+    let state = if info.rejected.is_some() {
+        LabelState::Rejected
+    } else if info.approved.is_some() {
+        LabelState::Approved
+    } else if info.recommended.is_some() {
+        LabelState::Recommended
+    } else if info.disliked.is_some() {
+        LabelState::Disliked
+    } else if info.optional == Some(true) {
+        LabelState::Optional
+    } else {
+        LabelState::Needed
+    };
+    let mut votes: Vec<Vote> = info
+        .all
+        .iter()
+        .flatten()
+        .filter(|a| a.value.is_some_and(|v| v != 0))
+        .map(|a| Vote {
+            account: approval_label(a),
+            value: a.value.unwrap_or(0),
+        })
+        .collect();
+    votes.sort_by_key(|v| std::cmp::Reverse(v.value));
+    LabelStatus {
+        name: name.to_string(),
+        state,
+        votes,
+    }
+}
+
+fn approval_label(approval: &ApprovalInfo) -> String {
+    approval
+        .name
+        .clone()
+        .or_else(|| approval.email.clone())
+        .or_else(|| approval.account_id.map(|id| id.to_string()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Render the report as a short human-readable summary.
+pub fn format_text(report: &StatusReport) -> String {
+    let mut out = String::new();
+    let _ = write!(out, "Change {}", report.number.unwrap_or(0));
+    if let Some(ps) = report.patchset {
+        let _ = write!(out, " (patchset {ps})");
+    }
+    let _ = writeln!(out, ": {}", report.subject.as_deref().unwrap_or("-"));
+
+    let status = report.status.as_deref().unwrap_or("UNKNOWN");
+    let blockers = report.blockers();
+    let verdict = match status {
+        "NEW" if report.submittable == Some(true) || blockers.is_empty() => {
+            "ready to submit".to_string()
+        }
+        "NEW" => format!("not ready: {}", blockers.join(", ")),
+        other => other.to_ascii_lowercase(),
+    };
+    let _ = writeln!(out, "Status: {verdict}");
+
+    if !report.labels.is_empty() {
+        out.push_str("Labels:\n");
+        let width = report
+            .labels
+            .iter()
+            .map(|l| l.name.len())
+            .max()
+            .unwrap_or(0);
+        for label in &report.labels {
+            let votes: Vec<String> = label
+                .votes
+                .iter()
+                .map(|v| format!("{:+} {}", v.value, v.account))
+                .collect();
+            let _ = writeln!(
+                out,
+                "  {:width$}  {:11}  {}",
+                label.name,
+                label.state.as_str(),
+                votes.join(", ")
+            );
+        }
+    }
+
+    if !report.submit_requirements.is_empty() {
+        out.push_str("Submit requirements:\n");
+        for req in &report.submit_requirements {
+            let _ = write!(out, "  {}  {}", req.name, req.status);
+            if req.status != "SATISFIED" && !req.failing.is_empty() {
+                let _ = write!(out, "  (needs {})", req.failing.join(", "));
+            }
+            out.push('\n');
+        }
+    }
+
+    if !report.attention.is_empty() {
+        out.push_str("Attention:\n");
+        out.push_str(&attention::format_entries(&report.attention));
+    }
+
+    match report.patchset {
+        Some(ps) => {
+            let _ = writeln!(out, "CI (patchset {ps}):");
+        }
+        None => out.push_str("CI:\n"),
+    }
+    if report.ci.is_empty() {
+        out.push_str("  no results yet\n");
+    }
+    for ci in &report.ci {
+        let result = match ci.result {
+            CiResult::Passed => "PASS",
+            CiResult::Failed => "FAIL",
+            CiResult::Skipped | CiResult::Unknown => "INFO",
+        };
+        let _ = writeln!(out, "  {result}  {}: {}", ci.author, ci.summary);
+        for job in &ci.jobs {
+            let _ = write!(out, "    {} {}", ci::result_mark(job.result), job.name);
+            if job.result != CiResult::Passed {
+                let _ = write!(out, " ({})", job.status);
+            }
+            if !job.voting {
+                out.push_str(" (non-voting)");
+            }
+            out.push('\n');
+        }
+    }
+
+    if report.head_is_current == Some(false) {
+        out.push_str("Note: HEAD is not the current patchset; push to update the change\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_info(json: serde_json::Value) -> ChangeStatusInfo {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn sample() -> ChangeStatusInfo {
+        status_info(serde_json::json!({
+            "_number": 12345,
+            "subject": "Fix the thing",
+            "status": "NEW",
+            "current_revision": "abc",
+            "revisions": {"abc": {"_number": 2}},
+            "submittable": false,
+            "attention_set": {
+                "1000096": {
+                    "account": {"_account_id": 1000096, "name": "Alice"},
+                    "reason": "Zuul replied on the change"
+                }
+            },
+            "labels": {
+                "Verified": {
+                    "rejected": {"name": "Zuul"},
+                    "all": [{"name": "Zuul", "value": -1}]
+                },
+                "Code-Review": {
+                    "recommended": {"name": "Alice"},
+                    "all": [
+                        {"name": "Bob", "value": 0},
+                        {"name": "Alice", "value": 1}
+                    ]
+                }
+            },
+            "submit_requirements": [
+                {
+                    "name": "Code-Review",
+                    "status": "UNSATISFIED",
+                    "submittability_expression_result": {
+                        "expression": "label:Code-Review=MAX",
+                        "fulfilled": false,
+                        "failing_atoms": ["label:Code-Review=MAX"]
+                    }
+                },
+                {"name": "Verified", "status": "UNSATISFIED"},
+                {"name": "Backport", "status": "NOT_APPLICABLE"}
+            ],
+            "messages": [
+                {
+                    "author": {"name": "Zuul"},
+                    "tag": "autogenerated:zuul:check",
+                    "message": "Patch Set 1: Verified+1\n\nBuild succeeded (check pipeline).",
+                    "_revision_number": 1
+                },
+                {
+                    "author": {"name": "Zuul"},
+                    "tag": "autogenerated:zuul:check",
+                    "message": "Patch Set 2: Verified+1\n\nBuild succeeded (check pipeline).",
+                    "_revision_number": 2
+                },
+                {
+                    "author": {"name": "Alice"},
+                    "message": "Patch Set 2: Code-Review+1\n\nLooks good",
+                    "_revision_number": 2
+                },
+                {
+                    "author": {"name": "Gerrit"},
+                    "tag": "autogenerated:gerrit:newPatchSet",
+                    "message": "Uploaded patch set 2.",
+                    "_revision_number": 2
+                },
+                {
+                    "author": {"name": "Zuul"},
+                    "tag": "autogenerated:zuul:check",
+                    "message": "Patch Set 2: Verified-1\n\nBuild failed (check pipeline).",
+                    "_revision_number": 2
+                }
+            ]
+        }))
+    }
+
+    // === report building ===
+
+    #[test]
+    fn report_keeps_latest_ci_result_on_current_patchset() {
+        let report = build_report(&sample(), None);
+        assert_eq!(report.patchset, Some(2));
+        assert_eq!(report.ci.len(), 1);
+        assert_eq!(report.ci[0].result, CiResult::Failed);
+        assert_eq!(report.ci[0].summary, "Build failed (check pipeline).");
+    }
+
+    #[test]
+    fn report_labels_sorted_with_nonzero_votes() {
+        let report = build_report(&sample(), None);
+        let names: Vec<&str> = report.labels.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["Code-Review", "Verified"]);
+        assert_eq!(report.labels[0].state, LabelState::Recommended);
+        assert_eq!(report.labels[0].votes.len(), 1);
+        assert_eq!(report.labels[1].state, LabelState::Rejected);
+    }
+
+    #[test]
+    fn report_skips_not_applicable_requirements() {
+        let report = build_report(&sample(), None);
+        assert_eq!(report.submit_requirements.len(), 2);
+        assert_eq!(
+            report.submit_requirements[0].failing,
+            ["label:Code-Review=MAX"]
+        );
+        assert_eq!(report.blockers(), ["Code-Review", "Verified", "CI failing"]);
+    }
+
+    #[test]
+    fn head_comparison() {
+        assert_eq!(
+            build_report(&sample(), Some("abc")).head_is_current,
+            Some(true)
+        );
+        assert_eq!(
+            build_report(&sample(), Some("def")).head_is_current,
+            Some(false)
+        );
+        assert_eq!(build_report(&sample(), None).head_is_current, None);
+    }
+
+    #[test]
+    fn blockers_fall_back_to_labels_without_requirements() {
+        let mut info = sample();
+        info.submit_requirements.clear();
+        let report = build_report(&info, None);
+        assert_eq!(report.blockers(), ["Code-Review", "Verified", "CI failing"]);
+    }
+
+    // === text output ===
+
+    #[test]
+    fn format_text_summarizes() {
+        let text = format_text(&build_report(&sample(), Some("def")));
+        assert!(text.starts_with("Change 12345 (patchset 2): Fix the thing\n"));
+        assert!(text.contains("Status: not ready: Code-Review, Verified, CI failing\n"));
+        assert!(text.contains("  Code-Review  recommended  +1 Alice\n"));
+        assert!(text.contains("  Code-Review  UNSATISFIED  (needs label:Code-Review=MAX)\n"));
+        assert!(text.contains("Attention:\n  Alice  Zuul replied on the change\n"));
+        assert!(text.contains("  FAIL  Zuul: Build failed (check pipeline).\n"));
+        assert!(text.ends_with("push to update the change\n"));
+    }
+
+    #[test]
+    fn format_text_lists_ci_jobs() {
+        let mut info = sample();
+        let messages = info.change.messages.as_mut().unwrap();
+        messages.last_mut().unwrap().message = Some(
+            "Patch Set 2: Verified-1\n\nBuild failed (check pipeline).\n\n\
+             - tox-pep8 https://zuul.example.org/build/1 : SUCCESS in 3m 12s\n\
+             - tox-py312 https://zuul.example.org/build/2 : FAILURE in 10m 02s\n\
+             - tempest https://zuul.example.org/build/3 : TIMED_OUT in 2h (non-voting)\n"
+                .to_string(),
+        );
+        let report = build_report(&info, None);
+        assert_eq!(report.ci[0].jobs.len(), 3);
+        let text = format_text(&report);
+        assert!(text.contains("  FAIL  Zuul: Build failed (check pipeline).\n    ✔ tox-pep8\n"));
+        assert!(text.contains("    ✘ tox-py312 (FAILURE)\n"));
+        assert!(text.contains("    ✘ tempest (TIMED_OUT) (non-voting)\n"));
+    }
+
+    #[test]
+    fn format_text_ready_and_merged() {
+        let mut info = sample();
+        info.submittable = Some(true);
+        info.change.messages = None;
+        let text = format_text(&build_report(&info, None));
+        assert!(text.contains("Status: ready to submit\n"));
+        assert!(text.contains("CI (patchset 2):\n  no results yet\n"));
+
+        info.change.status = Some("MERGED".into());
+        assert!(format_text(&build_report(&info, None)).contains("Status: merged\n"));
+    }
+}
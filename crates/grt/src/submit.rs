@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+use std::fmt::Write as _;
+
+use anyhow::Result;
+
+use crate::gerrit::{ChangeInfo, ChangeStatusInfo, GerritClient};
+use crate::status::{LabelState, StatusReport};
+
+/// Label voted by `grt submit --when-ready`. Servers running an auto-submit
+/// service merge changes carrying a positive vote once they are submittable.
+pub const AUTO_SUBMIT_LABEL: &str = "Auto-Submit";
+
+/// Build the query for open changes in a topic, optionally scoped to a project.
+pub fn topic_query(topic: &str, project: Option<&str>) -> String {
+    let mut query = format!("status:open topic:\"{topic}\"");
+    if let Some(project) = project.filter(|p| !p.is_empty()) {
+        query.push_str(&format!(" project:{project}"));
+    }
+    query
+}
+
+/// Submit every open change in a topic.
+///
+/// Submits the first change; with `change.submitWholeTopic` enabled Gerrit
+/// merges the rest of the topic along with it. Each remaining change is then
+/// re-checked and submitted individually if it is still open. Returns the
+/// changes that merged, in query order.
+pub async fn submit_topic(
+    client: &GerritClient,
+    topic: &str,
+    project: Option<&str>,
+) -> Result<Vec<ChangeInfo>> {
+    let changes = client.query_changes(&topic_query(topic, project)).await?;
+    if changes.is_empty() {
+        anyhow::bail!("no open changes found in topic {topic:?}");
+    }
+
+    let mut merged = Vec::with_capacity(changes.len());
+    for change in changes {
+        let Some(id) = change_key(&change) else {
+            anyhow::bail!("change in topic {topic:?} has no number or id");
+        };
+        if merged.is_empty() {
+            merged.push(client.submit_change(&id).await?);
+            continue;
+        }
+        let current = client.get_change_detail(&id).await?;
+        if current.status.as_deref() == Some("MERGED") {
+            merged.push(current);
+        } else {
+            merged.push(client.submit_change(&id).await?);
+        }
+    }
+    Ok(merged)
+}
+
+/// Format the changes merged by a topic submit.
+pub fn format_submitted(topic: &str, merged: &[ChangeInfo]) -> String {
+    let mut out = format!(
+        "Submitted topic {topic}: {} change(s) merged\n",
+        merged.len()
+    );
+    for change in merged {
+        let _ = writeln!(
+            out,
+            "  {}  {}",
+            change.number.unwrap_or(0),
+            change.subject.as_deref().unwrap_or("-")
+        );
+    }
+    out
+}
+
+/// Fail unless the change is open.
+pub fn check_open(report: &StatusReport) -> Result<()> {
+    let number = report.number.unwrap_or(0);
+    match report.status.as_deref() {
+        Some("MERGED") => anyhow::bail!("change {number} is already merged"),
+        Some("ABANDONED") => {
+            anyhow::bail!("change {number} is abandoned; run `grt restore {number}` first")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check a change before submitting it: it must be open and, when Gerrit says
+/// whether it is submittable, submittable. The error names what is missing.
+pub fn preflight(report: &StatusReport) -> Result<()> {
+    check_open(report)?;
+    if report.submittable == Some(false) {
+        anyhow::bail!("{}", describe_blockers(report));
+    }
+    Ok(())
+}
+
+/// Explain why a change cannot be submitted, one line per unmet requirement.
+pub fn describe_blockers(report: &StatusReport) -> String {
+    let mut out = format!(
+        "change {} cannot be submitted yet:\n",
+        report.number.unwrap_or(0)
+    );
+    let mut reasons = Vec::new();
+    for req in &report.submit_requirements {
+        if !matches!(req.status.as_str(), "UNSATISFIED" | "ERROR") {
+            continue;
+        }
+        if req.failing.is_empty() {
+            reasons.push(format!("{}: {}", req.name, req.status.to_ascii_lowercase()));
+        } else {
+            reasons.push(format!("{}: needs {}", req.name, req.failing.join(", ")));
+        }
+    }
+    // Servers without submit requirements only report labels
+    if report.submit_requirements.is_empty() {
+        for label in &report.labels {
+            if matches!(label.state, LabelState::Approved | LabelState::Optional) {
+                continue;
+            }
+            let votes: Vec<String> = label
+                .votes
+                .iter()
+                .map(|v| format!("{:+} {}", v.value, v.account))
+                .collect();
+            if votes.is_empty() {
+                reasons.push(format!("{}: {}", label.name, label.state.as_str()));
+            } else {
+                reasons.push(format!(
+                    "{}: {} ({})",
+                    label.name,
+                    label.state.as_str(),
+                    votes.join(", ")
+                ));
+            }
+        }
+    }
+    if reasons.is_empty() {
+        reasons.push(
+            "Gerrit reports it is not submittable (work in progress, or a parent is not merged?)"
+                .to_string(),
+        );
+    }
+    for reason in reasons {
+        let _ = writeln!(out, "  - {reason}");
+    }
+    out.push_str(
+        "Run `grt status` for details, or `grt submit --when-ready` to submit once approved",
+    );
+    out
+}
+
+/// Whether the change can take an `Auto-Submit` vote.
+pub fn supports_auto_submit(info: &ChangeStatusInfo) -> bool {
+    info.change
+        .labels
+        .as_ref()
+        .is_some_and(|labels| labels.contains_key(AUTO_SUBMIT_LABEL))
+}
+
+fn change_key(change: &ChangeInfo) -> Option<String> {
+    change
+        .number
+        .map(|n| n.to_string())
+        .or_else(|| change.id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topic_query_without_project() {
+        assert_eq!(topic_query("stack", None), "status:open topic:\"stack\"");
+    }
+
+    #[test]
+    fn topic_query_with_project() {
+        assert_eq!(
+            topic_query("stack", Some("my/project")),
+            "status:open topic:\"stack\" project:my/project"
+        );
+    }
+
+    fn report(json: serde_json::Value) -> StatusReport {
+        let info: ChangeStatusInfo = serde_json::from_value(json).unwrap();
+        crate::status::build_report(&info, None)
+    }
+
+    #[test]
+    fn preflight_rejects_closed_changes() {
+        let merged = report(serde_json::json!({"_number": 7, "status": "MERGED"}));
+        assert_eq!(
+            preflight(&merged).unwrap_err().to_string(),
+            "change 7 is already merged"
+        );
+        let abandoned = report(serde_json::json!({"_number": 7, "status": "ABANDONED"}));
+        assert!(preflight(&abandoned)
+            .unwrap_err()
+            .to_string()
+            .contains("grt restore 7"));
+    }
+
+    #[test]
+    fn preflight_lists_unmet_requirements() {
+        let blocked = report(serde_json::json!({
+            "_number": 7,
+            "status": "NEW",
+            "submittable": false,
+            "submit_requirements": [
+                {
+                    "name": "Code-Review",
+                    "status": "UNSATISFIED",
+                    "submittability_expression_result": {
+                        "failing_atoms": ["label:Code-Review=MAX"]
+                    }
+                },
+                {"name": "Verified", "status": "SATISFIED"}
+            ]
+        }));
+        let err = preflight(&blocked).unwrap_err().to_string();
+        assert!(err.starts_with("change 7 cannot be submitted yet:\n"));
+        assert!(err.contains("  - Code-Review: needs label:Code-Review=MAX\n"));
+        assert!(!err.contains("Verified"));
+    }
+
+    #[test]
+    fn preflight_falls_back_to_labels() {
+        let blocked = report(serde_json::json!({
+            "_number": 7,
+            "status": "NEW",
+            "submittable": false,
+            "labels": {
+                "Code-Review": {"approved": {"name": "Alice"}},
+                "Verified": {"rejected": {}, "all": [{"name": "CI", "value": -1}]}
+            }
+        }));
+        let err = preflight(&blocked).unwrap_err().to_string();
+        assert!(err.contains("  - Verified: rejected (-1 CI)\n"));
+        assert!(!err.contains("Code-Review"));
+    }
+
+    #[test]
+    fn preflight_passes_submittable_or_unknown() {
+        let ready = report(serde_json::json!({"status": "NEW", "submittable": true}));
+        assert!(preflight(&ready).is_ok());
+        let unknown = report(serde_json::json!({"status": "NEW"}));
+        assert!(preflight(&unknown).is_ok());
+    }
+
+    #[test]
+    fn auto_submit_support_follows_labels() {
+        let info: ChangeStatusInfo = serde_json::from_value(serde_json::json!({
+            "labels": {"Auto-Submit": {}, "Code-Review": {}}
+        }))
+        .unwrap();
+        assert!(supports_auto_submit(&info));
+        let info: ChangeStatusInfo =
+            serde_json::from_value(serde_json::json!({"labels": {"Code-Review": {}}})).unwrap();
+        assert!(!supports_auto_submit(&info));
+    }
+
+    #[test]
+    fn format_submitted_lists_changes() {
+        let merged = vec![ChangeInfo {
+            number: Some(101),
+            subject: Some("First".into()),
+            ..Default::default()
+        }];
+        assert_eq!(
+            format_submitted("stack", &merged),
+            "Submitted topic stack: 1 change(s) merged\n  101  First\n"
+        );
+    }
+}
@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 // Copyright (c) 2026 grt contributors
 
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 use anyhow::{Context, Result};
 
@@ -53,6 +53,57 @@ pub fn git_exec(args: &[&str], work_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// A git command that exited non-zero, with the stderr it printed.
+#[derive(Debug, thiserror::Error)]
+#[error("git {command} failed (exit {code})")]
+pub struct GitCommandError {
+    pub command: String,
+    pub code: i32,
+    pub stderr: String,
+}
+
+/// Run a git command with stdout inherited and stderr both shown and captured.
+///
+/// `git push` reports the created change URLs on stderr (`remote:` lines);
+/// this keeps them visible to the user while letting the caller parse them.
+pub fn git_exec_capture_stderr(args: &[&str], work_dir: &Path) -> Result<String> {
+    use std::io::{Read, Write};
+
+    let mut child = git_command(args, work_dir)
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("running git {}", args.join(" ")))?;
+
+    let mut captured = Vec::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let mut buf = [0u8; 4096];
+        let mut out = std::io::stderr();
+        loop {
+            let n = stderr.read(&mut buf).context("reading git stderr")?;
+            if n == 0 {
+                break;
+            }
+            let _ = out.write_all(&buf[..n]);
+            captured.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    let status = child
+        .wait()
+        .with_context(|| format!("running git {}", args.join(" ")))?;
+    let stderr = String::from_utf8_lossy(&captured).into_owned();
+    if !status.success() {
+        return Err(GitCommandError {
+            command: args.join(" "),
+            code: status.code().unwrap_or(-1),
+            stderr,
+        }
+        .into());
+    }
+
+    Ok(stderr)
+}
+
 /// Count unpushed commits between HEAD and a remote tracking branch.
 pub fn count_unpushed_commits(remote: &str, branch: &str, work_dir: &Path) -> Result<usize> {
     let remote_ref = format!("remotes/{}/{}", remote, branch);
@@ -108,6 +159,136 @@ pub fn list_unpushed_commits(remote: &str, branch: &str, work_dir: &Path) -> Res
     }
 }
 
+/// Return the remote's default branch from `refs/remotes/<remote>/HEAD`, if known.
+///
+/// That ref is set by `git clone` (or `git remote set-head`); repos whose
+/// remote was added later may not have it.
+pub fn git_remote_default_branch(remote: &str, work_dir: &Path) -> Option<String> {
+    let head = format!("refs/remotes/{remote}/HEAD");
+    let target = git_output(&["symbolic-ref", "--quiet", "--short", &head], work_dir).ok()?;
+    target.strip_prefix(&format!("{remote}/")).map(String::from)
+}
+
+/// Most commits [`unpushed_log`] lists when the remote branch is missing.
+const UNPUSHED_FALLBACK_LIMIT: usize = 100;
+
+/// An unpushed commit, as listed by [`list_unpushed_log`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnpushedCommit {
+    /// Abbreviated commit hash.
+    pub commit: String,
+    /// Ref names pointing at the commit, e.g. `HEAD -> topic`; empty if none.
+    pub refs: String,
+    /// Full commit message.
+    pub message: String,
+}
+
+/// `git log` of the unpushed commits in `format`, newest first.
+///
+/// If the remote branch does not exist yet, commits on any of the remote's
+/// other branches are left out, and at most [`UNPUSHED_FALLBACK_LIMIT`] are
+/// listed, rather than the whole history.
+fn unpushed_log(remote: &str, branch: &str, format: &str, work_dir: &Path) -> Result<String> {
+    let remote_ref = format!("remotes/{}/{}", remote, branch);
+    let format = format!("--format={format}");
+    git_output(&["log", &format, "HEAD", "--not", &remote_ref], work_dir).or_else(|_| {
+        git_output(
+            &[
+                "log",
+                &format,
+                "-n",
+                &UNPUSHED_FALLBACK_LIMIT.to_string(),
+                "HEAD",
+                "--not",
+                &format!("--remotes={remote}"),
+            ],
+            work_dir,
+        )
+    })
+}
+
+/// Return the full messages of unpushed commits, newest first.
+pub fn list_unpushed_messages(remote: &str, branch: &str, work_dir: &Path) -> Result<Vec<String>> {
+    let output = unpushed_log(remote, branch, "%B%x00", work_dir)?;
+    Ok(output
+        .split('\0')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Return the unpushed commits with their hashes and ref names, newest first.
+pub fn list_unpushed_log(
+    remote: &str,
+    branch: &str,
+    work_dir: &Path,
+) -> Result<Vec<UnpushedCommit>> {
+    let output = unpushed_log(remote, branch, "%h%x1f%D%x1f%B%x00", work_dir)?;
+    Ok(output
+        .split('\0')
+        .filter_map(|entry| {
+            let mut fields = entry.trim_start().splitn(3, '\x1f');
+            let commit = fields.next().filter(|c| !c.is_empty())?;
+            Some(UnpushedCommit {
+                commit: commit.to_string(),
+                refs: fields.next()?.to_string(),
+                message: fields.next()?.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Return the messages of the last `count` commits of HEAD that are already on
+/// `remote/branch`, newest first.
+///
+/// Fails if the remote branch does not exist or shares no history with HEAD.
+pub fn list_merged_messages(
+    remote: &str,
+    branch: &str,
+    count: usize,
+    work_dir: &Path,
+) -> Result<Vec<String>> {
+    let remote_ref = format!("remotes/{}/{}", remote, branch);
+    let base = git_merge_base("HEAD", &remote_ref, work_dir)?;
+    let output = git_output(
+        &["log", "--format=%B%x00", "-n", &count.to_string(), &base],
+        work_dir,
+    )?;
+    Ok(output
+        .split('\0')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// List the files touched by unpushed commits, de-duplicated and sorted.
+pub fn list_unpushed_files(remote: &str, branch: &str, work_dir: &Path) -> Result<Vec<String>> {
+    let remote_ref = format!("remotes/{}/{}", remote, branch);
+    let output = git_output(
+        &[
+            "log",
+            "--name-only",
+            "--format=",
+            "HEAD",
+            "--not",
+            &remote_ref,
+        ],
+        work_dir,
+    )
+    .or_else(|_| {
+        // Remote branch may not exist yet; fall back to the files in HEAD
+        git_output(&["show", "--name-only", "--format=", "HEAD"], work_dir)
+    })?;
+    let files: std::collections::BTreeSet<String> = output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    Ok(files.into_iter().collect())
+}
+
 /// Fetch a specific ref from a remote.
 pub fn git_fetch_ref(remote: &str, git_ref: &str, work_dir: &Path) -> Result<()> {
     git_exec(&["fetch", remote, git_ref], work_dir)
@@ -118,6 +299,23 @@ pub fn git_checkout_new_branch(branch: &str, start_point: &str, work_dir: &Path)
     git_exec(&["checkout", "-b", branch, start_point], work_dir)
 }
 
+/// Return true if a local branch named `branch` exists.
+pub fn git_local_branch_exists(branch: &str, work_dir: &Path) -> Result<bool> {
+    let status = git_command(
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{branch}"),
+        ],
+        work_dir,
+    )
+    .output()
+    .context("running git rev-parse")?
+    .status;
+    Ok(status.success())
+}
+
 /// Cherry-pick a commit onto the current branch.
 pub fn git_cherry_pick(commit: &str, work_dir: &Path) -> Result<()> {
     git_exec(&["cherry-pick", commit], work_dir)
@@ -133,6 +331,27 @@ pub fn git_cherry_pick_no_commit(commit: &str, work_dir: &Path) -> Result<()> {
     git_exec(&["cherry-pick", "--no-commit", commit], work_dir)
 }
 
+/// Abort an in-progress cherry-pick, restoring the pre-pick state.
+pub fn git_cherry_pick_abort(work_dir: &Path) -> Result<()> {
+    git_exec(&["cherry-pick", "--abort"], work_dir)
+}
+
+/// Back out a conflicted merge-like operation that left no sequencer state
+/// (e.g. `cherry-pick --no-commit`), keeping unrelated local changes.
+pub fn git_reset_merge(work_dir: &Path) -> Result<()> {
+    git_exec(&["reset", "--merge"], work_dir)
+}
+
+/// Paths with unresolved conflicts in the index.
+pub fn git_conflicted_files(work_dir: &Path) -> Result<Vec<String>> {
+    let output = git_output(&["diff", "--name-only", "--diff-filter=U"], work_dir)?;
+    Ok(output
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
 /// Fill credentials from git credential helper.
 ///
 /// Returns `Ok(Some((username, password)))` if credentials were found,
@@ -277,6 +496,11 @@ pub fn git_checkout(branch: &str, work_dir: &Path) -> Result<()> {
     git_exec(&["checkout", branch], work_dir)
 }
 
+/// Check out a commit on a detached HEAD.
+pub fn git_checkout_detached(commit: &str, work_dir: &Path) -> Result<()> {
+    git_exec(&["checkout", "--detach", commit], work_dir)
+}
+
 /// Delete a local branch.
 pub fn git_delete_branch(branch: &str, work_dir: &Path) -> Result<()> {
     git_exec(&["branch", "-D", branch], work_dir)
@@ -319,6 +543,17 @@ pub fn git_rev_parse_head(work_dir: &Path) -> Result<String> {
     git_output(&["rev-parse", "HEAD"], work_dir)
 }
 
+/// Return the git directory shared by all worktrees of the repository at
+/// `work_dir`.
+///
+/// Unlike `<work_dir>/.git`, this also works for submodules and linked
+/// worktrees, whose `.git` is a file pointing elsewhere.
+pub fn git_common_dir(work_dir: &Path) -> Result<PathBuf> {
+    let dir = git_output(&["rev-parse", "--git-common-dir"], work_dir)?;
+    // Relative to `work_dir` unless git printed an absolute path
+    Ok(work_dir.join(dir))
+}
+
 /// Return the ref to restore to after a temporary checkout.
 /// When on a branch, returns the branch name (e.g. "main"); when detached, returns the SHA.
 pub fn git_head_restore_ref(work_dir: &Path) -> Result<String> {
@@ -372,10 +607,43 @@ pub fn git_regenerate_changeid(work_dir: &Path) -> Result<()> {
     git_exec(&["commit", "--amend", "-m", &new_msg], work_dir)
 }
 
+/// Return the SHA that `FETCH_HEAD` currently resolves to.
+pub fn fetch_head_sha(work_dir: &Path) -> Result<String> {
+    git_output(&["rev-parse", "FETCH_HEAD"], work_dir)
+}
+
 /// Fetch a ref from a remote and return the SHA it resolves to.
 pub fn git_fetch_ref_sha(remote: &str, git_ref: &str, work_dir: &Path) -> Result<String> {
     git_exec(&["fetch", remote, git_ref], work_dir)?;
-    git_output(&["rev-parse", "FETCH_HEAD"], work_dir)
+    fetch_head_sha(work_dir)
+}
+
+/// Fetch several refs from a remote in a single `git fetch` and return their SHAs.
+///
+/// `FETCH_HEAD` only records the last ref usefully, so each ref is fetched into
+/// its own local ref (`refs/grt/cmp/a`, `refs/grt/cmp/b`, ...) and rev-parsed
+/// from there. SHAs are returned in the same order as `git_refs`.
+pub fn git_fetch_refs(remote: &str, git_refs: &[&str], work_dir: &Path) -> Result<Vec<String>> {
+    if git_refs.len() > 26 {
+        anyhow::bail!("cannot fetch more than 26 refs at once");
+    }
+    let local_refs: Vec<String> = (b'a'..)
+        .take(git_refs.len())
+        .map(|c| format!("refs/grt/cmp/{}", c as char))
+        .collect();
+    let mut args = vec!["fetch".to_string(), remote.to_string()];
+    args.extend(
+        git_refs
+            .iter()
+            .zip(&local_refs)
+            .map(|(git_ref, local)| format!("+{git_ref}:{local}")),
+    );
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    git_exec(&args, work_dir)?;
+    local_refs
+        .iter()
+        .map(|local| git_output(&["rev-parse", local], work_dir))
+        .collect()
 }
 
 /// Diff two commits, inheriting stdout/stderr for interactive output.
@@ -383,6 +651,81 @@ pub fn git_diff(commit_a: &str, commit_b: &str, work_dir: &Path) -> Result<()> {
     git_exec(&["diff", commit_a, commit_b], work_dir)
 }
 
+/// Show a `git range-diff` between two single-commit patchsets.
+pub fn git_range_diff(old: &str, new: &str, work_dir: &Path) -> Result<()> {
+    let old_range = format!("{old}^..{old}");
+    let new_range = format!("{new}^..{new}");
+    git_exec(&["range-diff", &old_range, &new_range], work_dir)
+}
+
+/// Absolute paths of all checked-out submodules, recursively, parents before
+/// their nested submodules.
+///
+/// Returns an empty list when there are no submodules or `git submodule`
+/// fails.
+pub fn list_submodules(work_dir: &Path) -> Result<Vec<PathBuf>> {
+    let output = git_command(
+        &[
+            "submodule",
+            "foreach",
+            "--quiet",
+            "--recursive",
+            "echo $toplevel/$sm_path",
+        ],
+        work_dir,
+    )
+    .output()
+    .context("listing submodules")?;
+
+    if !output.status.success() {
+        // No submodules or git submodule not available — not an error
+        return Ok(Vec::new());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Check out `commit` on a detached HEAD in a new worktree at `path`.
+pub fn git_worktree_add_detached(path: &Path, commit: &str, work_dir: &Path) -> Result<()> {
+    let path = path.to_string_lossy();
+    git_output(&["worktree", "add", "--detach", &path, commit], work_dir).map(|_| ())
+}
+
+/// Remove the worktree at `path`, discarding any changes made in it.
+pub fn git_worktree_remove(path: &Path, work_dir: &Path) -> Result<()> {
+    let path = path.to_string_lossy();
+    git_output(&["worktree", "remove", "--force", &path], work_dir).map(|_| ())
+}
+
+/// Run `git difftool --dir-diff` between `commit` and the working tree of `work_dir`.
+pub fn git_difftool_dir_diff(commit: &str, work_dir: &Path) -> Result<()> {
+    git_exec(&["difftool", "--dir-diff", commit], work_dir)
+}
+
+/// Return true if `ancestor` is reachable from `commit` (`merge-base --is-ancestor`).
+pub fn git_is_ancestor(ancestor: &str, commit: &str, work_dir: &Path) -> Result<bool> {
+    let status = git_command(&["merge-base", "--is-ancestor", ancestor, commit], work_dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("running git merge-base --is-ancestor")?;
+    match status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => anyhow::bail!("git merge-base --is-ancestor {ancestor} {commit} failed"),
+    }
+}
+
+/// Return the best common ancestor of two commits.
+pub fn git_merge_base(commit_a: &str, commit_b: &str, work_dir: &Path) -> Result<String> {
+    git_output(&["merge-base", commit_a, commit_b], work_dir)
+}
+
 /// Return the full `git config --list` output for URL rewrite parsing.
 pub fn git_config_list(work_dir: &Path) -> Result<String> {
     git_output(&["config", "--list"], work_dir)
@@ -463,4 +806,185 @@ mod tests {
         let result = git_output(&["log", "--invalid-flag-that-does-not-exist"], dir.path());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn git_fetch_refs_fetches_two_refs_at_once() {
+        let upstream = tempfile::tempdir().unwrap();
+        let up = upstream.path();
+        git_output(&["init", "-q", "-b", "main"], up).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], up).unwrap();
+        git_output(&["config", "user.name", "Test"], up).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "one"], up).unwrap();
+        git_output(&["update-ref", "refs/changes/45/12345/1", "HEAD"], up).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "two"], up).unwrap();
+        git_output(&["update-ref", "refs/changes/45/12345/2", "HEAD"], up).unwrap();
+        let ps1 = git_output(&["rev-parse", "refs/changes/45/12345/1"], up).unwrap();
+        let ps2 = git_output(&["rev-parse", "refs/changes/45/12345/2"], up).unwrap();
+
+        let work = tempfile::tempdir().unwrap();
+        git_output(&["init", "-q"], work.path()).unwrap();
+        let shas = git_fetch_refs(
+            up.to_str().unwrap(),
+            &["refs/changes/45/12345/1", "refs/changes/45/12345/2"],
+            work.path(),
+        )
+        .unwrap();
+        assert_eq!(shas, vec![ps1, ps2]);
+    }
+
+    #[test]
+    fn git_worktree_add_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path().join("repo");
+        std::fs::create_dir(&d).unwrap();
+        git_output(&["init", "-q", "-b", "main"], &d).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], &d).unwrap();
+        git_output(&["config", "user.name", "Test"], &d).unwrap();
+        std::fs::write(d.join("file"), "one\n").unwrap();
+        git_output(&["add", "file"], &d).unwrap();
+        git_output(&["commit", "-q", "-m", "one"], &d).unwrap();
+        let first = git_rev_parse_head(&d).unwrap();
+        std::fs::write(d.join("file"), "two\n").unwrap();
+        git_output(&["commit", "-q", "-am", "two"], &d).unwrap();
+
+        let tree = dir.path().join("tree");
+        git_worktree_add_detached(&tree, &first, &d).unwrap();
+        assert_eq!(std::fs::read_to_string(tree.join("file")).unwrap(), "one\n");
+        assert_eq!(git_rev_parse_head(&tree).unwrap(), first);
+
+        git_worktree_remove(&tree, &d).unwrap();
+        assert!(!tree.exists());
+        let list = git_output(&["worktree", "list"], &d).unwrap();
+        assert_eq!(list.lines().count(), 1, "{list}");
+    }
+
+    #[test]
+    fn list_unpushed_files_without_remote_uses_head() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        git_output(&["init", "-q", "-b", "main"], d).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], d).unwrap();
+        git_output(&["config", "user.name", "Test"], d).unwrap();
+        std::fs::create_dir(d.join("src")).unwrap();
+        std::fs::write(d.join("src/lib.rs"), "").unwrap();
+        std::fs::write(d.join("README.md"), "").unwrap();
+        git_output(&["add", "."], d).unwrap();
+        git_output(&["commit", "-q", "-m", "one"], d).unwrap();
+        let files = list_unpushed_files("origin", "main", d).unwrap();
+        assert_eq!(files, vec!["README.md", "src/lib.rs"]);
+    }
+
+    #[test]
+    fn list_unpushed_messages_splits_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        git_output(&["init", "-q", "-b", "main"], d).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], d).unwrap();
+        git_output(&["config", "user.name", "Test"], d).unwrap();
+        git_output(
+            &[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "one\n\nChange-Id: Iaaa",
+            ],
+            d,
+        )
+        .unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "two"], d).unwrap();
+        let messages = list_unpushed_messages("origin", "main", d).unwrap();
+        assert_eq!(messages, vec!["two", "one\n\nChange-Id: Iaaa"]);
+    }
+
+    #[test]
+    fn list_unpushed_log_has_hashes_and_refs() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        git_output(&["init", "-q", "-b", "topic"], d).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], d).unwrap();
+        git_output(&["config", "user.name", "Test"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "pushed"], d).unwrap();
+        git_output(&["update-ref", "refs/remotes/origin/main", "HEAD"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "one\n\nBody"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "two"], d).unwrap();
+        let log = list_unpushed_log("origin", "main", d).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].refs, "HEAD -> topic");
+        assert_eq!(log[0].message, "two");
+        assert_eq!(log[1].refs, "");
+        assert_eq!(log[1].message, "one\n\nBody");
+        assert_eq!(
+            log[1].commit,
+            git_output(&["rev-parse", "--short", "HEAD~1"], d).unwrap()
+        );
+    }
+
+    #[test]
+    fn list_unpushed_messages_without_remote_branch_skips_other_remote_branches() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        git_output(&["init", "-q", "-b", "main"], d).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], d).unwrap();
+        git_output(&["config", "user.name", "Test"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "old"], d).unwrap();
+        git_output(&["update-ref", "refs/remotes/origin/stable", "HEAD"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "new"], d).unwrap();
+        let messages = list_unpushed_messages("origin", "main", d).unwrap();
+        assert_eq!(messages, vec!["new"]);
+    }
+
+    #[test]
+    fn list_unpushed_commits_stops_at_remote_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        git_output(&["init", "-q", "-b", "main"], d).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], d).unwrap();
+        git_output(&["config", "user.name", "Test"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "pushed"], d).unwrap();
+        git_output(&["update-ref", "refs/remotes/origin/main", "HEAD"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "one"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "two"], d).unwrap();
+        let log = list_unpushed_commits("origin", "main", d).unwrap();
+        let subjects: Vec<&str> = log.lines().map(|l| l.rsplit(' ').next().unwrap()).collect();
+        assert_eq!(subjects, vec!["two", "one"]);
+    }
+
+    #[test]
+    fn git_remote_default_branch_reads_remote_head() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        git_output(&["init", "-q", "-b", "main"], d).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], d).unwrap();
+        git_output(&["config", "user.name", "Test"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "one"], d).unwrap();
+        assert_eq!(git_remote_default_branch("origin", d), None);
+
+        git_output(&["update-ref", "refs/remotes/origin/main", "HEAD"], d).unwrap();
+        git_output(
+            &[
+                "symbolic-ref",
+                "refs/remotes/origin/HEAD",
+                "refs/remotes/origin/main",
+            ],
+            d,
+        )
+        .unwrap();
+        assert_eq!(
+            git_remote_default_branch("origin", d).as_deref(),
+            Some("main")
+        );
+    }
+
+    #[test]
+    fn git_local_branch_exists_detects_branches() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        git_output(&["init", "-q", "-b", "main"], d).unwrap();
+        git_output(&["config", "user.email", "test@example.com"], d).unwrap();
+        git_output(&["config", "user.name", "Test"], d).unwrap();
+        git_output(&["commit", "-q", "--allow-empty", "-m", "one"], d).unwrap();
+        assert!(git_local_branch_exists("main", d).unwrap());
+        assert!(!git_local_branch_exists("stable/2024.1", d).unwrap());
+    }
 }
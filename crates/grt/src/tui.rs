@@ -0,0 +1,513 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Interactive dashboard (`grt tui`): browse open changes, preview them, and
+//! act on the selected one.
+//!
+//! State and key handling are plain data so they can be tested without a
+//! terminal; the event loop only draws, reads keys, and performs the
+//! resulting [`Action`]. Errors from Gerrit are shown in the status bar
+//! rather than ending the session.
+
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use tracing::debug;
+
+use crate::app::App;
+use crate::comments::{self, CommentThread};
+use crate::gerrit::ChangeInfo;
+use crate::list;
+
+/// Which screen the dashboard is showing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    List,
+    Detail,
+}
+
+/// An action that ends the dashboard and runs outside the alternate screen,
+/// where git output and prompts behave normally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Download(i64),
+    CherryPick(i64),
+}
+
+/// What the event loop should do after a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    Exit(Outcome),
+    LoadPreview(i64),
+    Refresh,
+    Abandon(i64),
+}
+
+/// Commit message and inline comments of the change being previewed.
+#[derive(Debug, Default)]
+pub struct Preview {
+    pub commit_message: String,
+    pub threads: Vec<CommentThread>,
+}
+
+/// Dashboard state.
+#[derive(Debug)]
+pub struct Dashboard {
+    pub changes: Vec<ChangeInfo>,
+    pub selected: usize,
+    pub view: View,
+    pub preview: Option<Preview>,
+    pub scroll: u16,
+    pub status: String,
+    pub confirm_abandon: bool,
+}
+
+const LIST_HELP: &str =
+    "↑/↓ move  enter preview  d download  x cherry-pick  a abandon  r refresh  q quit";
+const DETAIL_HELP: &str = "↑/↓ scroll  esc back  d download  x cherry-pick  a abandon  q quit";
+
+impl Dashboard {
+    pub fn new(changes: Vec<ChangeInfo>) -> Self {
+        Self {
+            changes,
+            selected: 0,
+            view: View::List,
+            preview: None,
+            scroll: 0,
+            status: String::new(),
+            confirm_abandon: false,
+        }
+    }
+
+    /// Replace the change list, keeping the selection in range.
+    pub fn set_changes(&mut self, changes: Vec<ChangeInfo>) {
+        self.changes = changes;
+        self.selected = self.selected.min(self.changes.len().saturating_sub(1));
+    }
+
+    /// Number of the selected change, if any.
+    pub fn selected_number(&self) -> Option<i64> {
+        self.changes.get(self.selected).and_then(|c| c.number)
+    }
+
+    /// Update state for a key press and return the follow-up action.
+    pub fn handle_key(&mut self, key: KeyCode) -> Action {
+        if self.confirm_abandon {
+            self.confirm_abandon = false;
+            return match (key, self.selected_number()) {
+                (KeyCode::Char('y') | KeyCode::Char('Y'), Some(number)) => Action::Abandon(number),
+                _ => {
+                    self.status = "Abandon cancelled".to_string();
+                    Action::None
+                }
+            };
+        }
+        self.status.clear();
+
+        match key {
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Char('d') => {
+                return self
+                    .selected_number()
+                    .map_or(Action::None, |n| Action::Exit(Outcome::Download(n)))
+            }
+            KeyCode::Char('x') => {
+                return self
+                    .selected_number()
+                    .map_or(Action::None, |n| Action::Exit(Outcome::CherryPick(n)))
+            }
+            KeyCode::Char('a') => {
+                if let Some(number) = self.selected_number() {
+                    self.confirm_abandon = true;
+                    self.status = format!("Abandon change {number}? (y/n)");
+                }
+                return Action::None;
+            }
+            _ => {}
+        }
+
+        match self.view {
+            View::List => match key {
+                KeyCode::Esc => Action::Quit,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.selected = self.selected.saturating_sub(1);
+                    Action::None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if self.selected + 1 < self.changes.len() {
+                        self.selected += 1;
+                    }
+                    Action::None
+                }
+                KeyCode::Home | KeyCode::Char('g') => {
+                    self.selected = 0;
+                    Action::None
+                }
+                KeyCode::End | KeyCode::Char('G') => {
+                    self.selected = self.changes.len().saturating_sub(1);
+                    Action::None
+                }
+                KeyCode::Enter => match self.selected_number() {
+                    Some(number) => {
+                        self.view = View::Detail;
+                        self.preview = None;
+                        self.scroll = 0;
+                        Action::LoadPreview(number)
+                    }
+                    None => Action::None,
+                },
+                KeyCode::Char('r') => Action::Refresh,
+                _ => Action::None,
+            },
+            View::Detail => match key {
+                KeyCode::Esc | KeyCode::Backspace | KeyCode::Left => {
+                    self.view = View::List;
+                    Action::None
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.scroll = self.scroll.saturating_sub(1);
+                    Action::None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.scroll = self.scroll.saturating_add(1);
+                    Action::None
+                }
+                _ => Action::None,
+            },
+        }
+    }
+}
+
+/// One row of the change list: number, branch, owner, subject.
+pub fn change_row(change: &ChangeInfo) -> String {
+    let owner = change
+        .owner
+        .as_ref()
+        .and_then(|o| o.username.as_deref().or(o.name.as_deref()))
+        .unwrap_or("");
+    format!(
+        "{:>7}  {:<16}  {:<16}  {}",
+        change.number.unwrap_or(0),
+        change.branch.as_deref().unwrap_or(""),
+        owner,
+        change.subject.as_deref().unwrap_or("")
+    )
+}
+
+/// Text lines of the detail view for `change`.
+pub fn preview_lines(change: &ChangeInfo, preview: Option<&Preview>) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "Change {}: {}",
+            change.number.unwrap_or(0),
+            change.subject.as_deref().unwrap_or("")
+        ),
+        format!(
+            "Project: {}  Branch: {}  Status: {}",
+            change.project.as_deref().unwrap_or(""),
+            change.branch.as_deref().unwrap_or(""),
+            change.status.as_deref().unwrap_or("")
+        ),
+        String::new(),
+    ];
+    let Some(preview) = preview else {
+        lines.push("Loading...".to_string());
+        return lines;
+    };
+
+    lines.extend(preview.commit_message.lines().map(str::to_string));
+    lines.push(String::new());
+    if preview.threads.is_empty() {
+        lines.push("No inline comments".to_string());
+        return lines;
+    }
+    lines.push(format!(
+        "Inline comments ({} threads)",
+        preview.threads.len()
+    ));
+    for thread in &preview.threads {
+        let location = match thread.line {
+            Some(line) => format!("{}:{line}", thread.file),
+            None => thread.file.clone(),
+        };
+        let state = if thread.resolved {
+            "resolved"
+        } else {
+            "unresolved"
+        };
+        lines.push(String::new());
+        lines.push(format!("{location} [{state}]"));
+        for comment in &thread.comments {
+            let mut message = comment.message.lines();
+            lines.push(format!(
+                "  {}: {}",
+                comment.author,
+                message.next().unwrap_or("")
+            ));
+            lines.extend(message.map(|l| format!("    {l}")));
+        }
+    }
+    lines
+}
+
+/// Draw the dashboard. Takes the state by reference; drawing never mutates it.
+pub fn render(frame: &mut Frame, dashboard: &Dashboard) {
+    let [main, status] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+    match dashboard.view {
+        View::List => {
+            let items: Vec<ListItem> = dashboard
+                .changes
+                .iter()
+                .map(|c| ListItem::new(change_row(c)))
+                .collect();
+            let title = format!(" Open changes ({}) ", dashboard.changes.len());
+            let list = List::new(items)
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            let mut state = ListState::default().with_selected(Some(dashboard.selected));
+            frame.render_stateful_widget(list, main, &mut state);
+        }
+        View::Detail => {
+            let lines: Vec<Line> = dashboard
+                .changes
+                .get(dashboard.selected)
+                .map(|c| preview_lines(c, dashboard.preview.as_ref()))
+                .unwrap_or_default()
+                .into_iter()
+                .map(Line::from)
+                .collect();
+            let paragraph = Paragraph::new(lines)
+                .block(Block::default().borders(Borders::ALL))
+                .scroll((dashboard.scroll, 0));
+            frame.render_widget(paragraph, main);
+        }
+    }
+
+    let help = match dashboard.view {
+        View::List => LIST_HELP,
+        View::Detail => DETAIL_HELP,
+    };
+    let text = if dashboard.status.is_empty() {
+        help
+    } else {
+        dashboard.status.as_str()
+    };
+    frame.render_widget(Paragraph::new(text), status);
+}
+
+/// Fetch open changes for the configured project (and `branch`, if given).
+async fn fetch_changes(app: &App, branch: Option<&str>, limit: usize) -> Result<Vec<ChangeInfo>> {
+    let query = list::build_list_query(&app.config.project, branch, &list::ListFilters::default());
+    app.gerrit
+        .query_changes_paginated(&query, Some(limit), |_| {})
+        .await
+        .context("listing open changes")
+}
+
+/// Fetch the commit message and inline comments of change `number`.
+async fn load_preview(app: &App, number: i64) -> Result<Preview> {
+    let id = number.to_string();
+    let commit = app.gerrit.get_revision_commit(&id, "current").await?;
+    let comments = app.gerrit.get_change_comments(&id).await?;
+    Ok(Preview {
+        commit_message: commit.message.unwrap_or_default(),
+        threads: comments::build_threads(&comments),
+    })
+}
+
+/// Run the dashboard until the user quits or picks an [`Outcome`].
+///
+/// `app` must already be authenticated. The terminal is restored before
+/// returning, including on error.
+pub async fn run(app: &App, branch: Option<&str>, limit: usize) -> Result<Option<Outcome>> {
+    let changes = fetch_changes(app, branch, limit).await?;
+    let mut dashboard = Dashboard::new(changes);
+    if dashboard.changes.is_empty() {
+        dashboard.status = "No open changes".to_string();
+    }
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, app, &mut dashboard, branch, limit).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &App,
+    dashboard: &mut Dashboard,
+    branch: Option<&str>,
+    limit: usize,
+) -> Result<Option<Outcome>> {
+    loop {
+        terminal.draw(|frame| render(frame, dashboard))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match dashboard.handle_key(key.code) {
+            Action::None => {}
+            Action::Quit => return Ok(None),
+            Action::Exit(outcome) => return Ok(Some(outcome)),
+            Action::LoadPreview(number) => match load_preview(app, number).await {
+                Ok(preview) => dashboard.preview = Some(preview),
+                Err(e) => dashboard.status = format!("error: {e:#}"),
+            },
+            Action::Refresh => match fetch_changes(app, branch, limit).await {
+                Ok(changes) => {
+                    dashboard.status = format!("Loaded {} open changes", changes.len());
+                    dashboard.set_changes(changes);
+                }
+                Err(e) => dashboard.status = format!("error: {e:#}"),
+            },
+            Action::Abandon(number) => {
+                match app.gerrit.abandon_change(&number.to_string(), None).await {
+                    Ok(_) => {
+                        dashboard.view = View::List;
+                        match fetch_changes(app, branch, limit).await {
+                            Ok(changes) => dashboard.set_changes(changes),
+                            Err(e) => debug!("refreshing after abandon failed: {e:#}"),
+                        }
+                        dashboard.status = format!("Abandoned change {number}");
+                    }
+                    Err(e) => dashboard.status = format!("error: {e:#}"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(number: i64, subject: &str) -> ChangeInfo {
+        ChangeInfo {
+            number: Some(number),
+            subject: Some(subject.to_string()),
+            branch: Some("main".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn dashboard() -> Dashboard {
+        Dashboard::new(vec![change(1, "one"), change(2, "two"), change(3, "three")])
+    }
+
+    #[test]
+    fn navigation_stays_in_bounds() {
+        let mut d = dashboard();
+        d.handle_key(KeyCode::Up);
+        assert_eq!(d.selected, 0);
+        for _ in 0..5 {
+            d.handle_key(KeyCode::Down);
+        }
+        assert_eq!(d.selected, 2);
+        d.handle_key(KeyCode::Char('g'));
+        assert_eq!(d.selected, 0);
+        d.handle_key(KeyCode::Char('G'));
+        assert_eq!(d.selected, 2);
+    }
+
+    #[test]
+    fn enter_opens_preview_and_esc_returns() {
+        let mut d = dashboard();
+        d.handle_key(KeyCode::Down);
+        assert_eq!(d.handle_key(KeyCode::Enter), Action::LoadPreview(2));
+        assert_eq!(d.view, View::Detail);
+        d.handle_key(KeyCode::Down);
+        assert_eq!(d.scroll, 1);
+        assert_eq!(d.handle_key(KeyCode::Esc), Action::None);
+        assert_eq!(d.view, View::List);
+        assert_eq!(d.handle_key(KeyCode::Esc), Action::Quit);
+    }
+
+    #[test]
+    fn download_and_cherry_pick_exit_with_selected_change() {
+        let mut d = dashboard();
+        d.handle_key(KeyCode::Down);
+        assert_eq!(
+            d.handle_key(KeyCode::Char('d')),
+            Action::Exit(Outcome::Download(2))
+        );
+        assert_eq!(
+            d.handle_key(KeyCode::Char('x')),
+            Action::Exit(Outcome::CherryPick(2))
+        );
+    }
+
+    #[test]
+    fn abandon_requires_confirmation() {
+        let mut d = dashboard();
+        assert_eq!(d.handle_key(KeyCode::Char('a')), Action::None);
+        assert!(d.confirm_abandon);
+        assert_eq!(d.handle_key(KeyCode::Char('n')), Action::None);
+        assert!(!d.confirm_abandon);
+        assert_eq!(d.status, "Abandon cancelled");
+
+        d.handle_key(KeyCode::Char('a'));
+        assert_eq!(d.handle_key(KeyCode::Char('y')), Action::Abandon(1));
+    }
+
+    #[test]
+    fn empty_list_has_no_actions() {
+        let mut d = Dashboard::new(Vec::new());
+        assert_eq!(d.handle_key(KeyCode::Enter), Action::None);
+        assert_eq!(d.handle_key(KeyCode::Char('d')), Action::None);
+        assert_eq!(d.handle_key(KeyCode::Char('a')), Action::None);
+        assert!(!d.confirm_abandon);
+    }
+
+    #[test]
+    fn set_changes_clamps_selection() {
+        let mut d = dashboard();
+        d.selected = 2;
+        d.set_changes(vec![change(9, "nine")]);
+        assert_eq!(d.selected, 0);
+        assert_eq!(d.selected_number(), Some(9));
+    }
+
+    #[test]
+    fn preview_lines_include_message_and_threads() {
+        let preview = Preview {
+            commit_message: "Fix bug\n\nLonger body\n".to_string(),
+            threads: vec![CommentThread {
+                file: "src/lib.rs".to_string(),
+                line: Some(10),
+                resolved: false,
+                comments: vec![comments::ThreadComment {
+                    author: "alice".to_string(),
+                    author_email: None,
+                    account_id: None,
+                    patch_set: Some(1),
+                    date: String::new(),
+                    message: "Typo here\nand here".to_string(),
+                    robot_id: None,
+                }],
+            }],
+        };
+        let lines = preview_lines(&change(5, "Fix bug"), Some(&preview));
+        assert_eq!(lines[0], "Change 5: Fix bug");
+        assert!(lines.contains(&"Longer body".to_string()));
+        assert!(lines.contains(&"src/lib.rs:10 [unresolved]".to_string()));
+        assert!(lines.contains(&"  alice: Typo here".to_string()));
+        assert!(lines.contains(&"    and here".to_string()));
+    }
+
+    #[test]
+    fn preview_lines_while_loading() {
+        let lines = preview_lines(&change(5, "Fix bug"), None);
+        assert_eq!(lines.last().map(String::as_str), Some("Loading..."));
+    }
+}
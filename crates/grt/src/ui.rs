@@ -0,0 +1,255 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Terminal output helpers: when to color, ANSI styling and OSC 8 links.
+
+use std::io::IsTerminal;
+
+/// When to emit colors and other terminal escapes (`--color WHEN`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorWhen {
+    /// Only when the output stream is a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorWhen {
+    /// Parse a `color` setting or git-review `--color` value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ColorWhen::Auto),
+            "always" => Some(ColorWhen::Always),
+            "never" => Some(ColorWhen::Never),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColorWhen::Auto => "auto",
+            ColorWhen::Always => "always",
+            ColorWhen::Never => "never",
+        }
+    }
+}
+
+/// The color choice the user expressed, if any: `--no-color`, then
+/// `--color`, then a non-empty `NO_COLOR`, then the repo's `color` setting.
+pub fn color_preference(
+    no_color: bool,
+    flag: Option<ColorWhen>,
+    setting: Option<&str>,
+) -> Option<ColorWhen> {
+    color_preference_with_env(no_color, flag, setting, no_color_env())
+}
+
+fn color_preference_with_env(
+    no_color: bool,
+    flag: Option<ColorWhen>,
+    setting: Option<&str>,
+    no_color_env: bool,
+) -> Option<ColorWhen> {
+    if no_color {
+        return Some(ColorWhen::Never);
+    }
+    if flag.is_some() {
+        return flag;
+    }
+    if no_color_env {
+        return Some(ColorWhen::Never);
+    }
+    setting.and_then(ColorWhen::parse)
+}
+
+/// [`color_preference`], defaulting to [`ColorWhen::Auto`].
+pub fn resolve_color(no_color: bool, flag: Option<ColorWhen>, setting: Option<&str>) -> ColorWhen {
+    color_preference(no_color, flag, setting).unwrap_or_default()
+}
+
+/// Applies ANSI colors and OSC 8 links when enabled; otherwise text passes
+/// through unchanged, so formatters can style unconditionally.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    enabled: bool,
+}
+
+impl Style {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// No escapes at all (pipes, files, tests).
+    pub fn plain() -> Self {
+        Self::default()
+    }
+
+    /// Style for output written to stdout.
+    pub fn stdout(when: ColorWhen) -> Self {
+        Self::for_stream(when, std::io::stdout().is_terminal())
+    }
+
+    /// Style for status lines written to stderr.
+    pub fn stderr(when: ColorWhen) -> Self {
+        Self::for_stream(when, std::io::stderr().is_terminal())
+    }
+
+    fn for_stream(when: ColorWhen, is_terminal: bool) -> Self {
+        Self::new(match when {
+            ColorWhen::Always => true,
+            ColorWhen::Never => false,
+            ColorWhen::Auto => is_terminal,
+        })
+    }
+
+    pub fn enabled(self) -> bool {
+        self.enabled
+    }
+
+    pub fn bold(self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    pub fn dim(self, text: &str) -> String {
+        self.paint("2", text)
+    }
+
+    pub fn red(self, text: &str) -> String {
+        self.paint("31", text)
+    }
+
+    pub fn green(self, text: &str) -> String {
+        self.paint("32", text)
+    }
+
+    pub fn yellow(self, text: &str) -> String {
+        self.paint("33", text)
+    }
+
+    pub fn cyan(self, text: &str) -> String {
+        self.paint("36", text)
+    }
+
+    /// `text` linking to `url`, or the bare `url` without escapes.
+    pub fn link(self, url: &str, text: &str) -> String {
+        if self.enabled {
+            hyperlink(url, text)
+        } else {
+            url.to_string()
+        }
+    }
+
+    /// A `warning:` prefix for stderr messages.
+    pub fn warning(self) -> String {
+        self.paint("1;33", "warning:")
+    }
+
+    fn paint(self, code: &str, text: &str) -> String {
+        if self.enabled && !text.is_empty() {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Wrap `text` in an OSC 8 escape so terminals render it as a link to `url`.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Return true if stdout should receive terminal escapes such as hyperlinks.
+///
+/// Disabled by `--no-color`, by a non-empty `NO_COLOR`, or when stdout is not
+/// a terminal (pipes, files, LLM tooling).
+pub fn stdout_supports_escapes(no_color: bool) -> bool {
+    escapes_enabled(no_color, None)
+}
+
+/// [`stdout_supports_escapes`] with the repo's `color` setting applied:
+/// `never` disables escapes and `always` keeps them when stdout is piped.
+/// `--no-color` and `NO_COLOR` still win over `always`.
+pub fn escapes_enabled(no_color: bool, color: Option<&str>) -> bool {
+    Style::stdout(resolve_color(no_color, None, color)).enabled()
+}
+
+fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hyperlink_emits_osc8_sequence() {
+        assert_eq!(
+            hyperlink("https://review.example.com/c/p/+/1", "open"),
+            "\x1b]8;;https://review.example.com/c/p/+/1\x1b\\open\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn escapes_disabled_by_no_color_flag() {
+        assert!(!stdout_supports_escapes(true));
+    }
+
+    #[test]
+    fn color_setting_never_and_flag_win() {
+        assert!(!escapes_enabled(false, Some("never")));
+        assert!(!escapes_enabled(true, Some("always")));
+        assert!(!escapes_enabled(true, None));
+    }
+
+    #[test]
+    fn color_preference_order() {
+        let always = Some(ColorWhen::Always);
+        // --no-color beats everything
+        assert_eq!(
+            color_preference_with_env(true, always, Some("always"), false),
+            Some(ColorWhen::Never)
+        );
+        // --color beats NO_COLOR and the repo setting
+        assert_eq!(
+            color_preference_with_env(false, always, Some("never"), true),
+            always
+        );
+        // NO_COLOR beats the repo setting
+        assert_eq!(
+            color_preference_with_env(false, None, Some("always"), true),
+            Some(ColorWhen::Never)
+        );
+        assert_eq!(
+            color_preference_with_env(false, None, Some("always"), false),
+            always
+        );
+        assert_eq!(color_preference_with_env(false, None, None, false), None);
+    }
+
+    #[test]
+    fn style_for_stream() {
+        assert!(Style::for_stream(ColorWhen::Always, false).enabled());
+        assert!(!Style::for_stream(ColorWhen::Never, true).enabled());
+        assert!(Style::for_stream(ColorWhen::Auto, true).enabled());
+        assert!(!Style::for_stream(ColorWhen::Auto, false).enabled());
+    }
+
+    #[test]
+    fn plain_style_passes_text_through() {
+        let style = Style::plain();
+        assert_eq!(style.red("FAILED"), "FAILED");
+        assert_eq!(style.warning(), "warning:");
+        assert_eq!(
+            style.link("https://r.example.com/1", "open"),
+            "https://r.example.com/1"
+        );
+    }
+
+    #[test]
+    fn enabled_style_wraps_in_sgr() {
+        let style = Style::new(true);
+        assert_eq!(style.green("OK"), "\x1b[32mOK\x1b[0m");
+        assert_eq!(style.bold(""), "");
+    }
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Projects for `grt push --recurse`: the superproject and its submodules
+//! that carry their own `.gitreview`.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Directories to push, in push order.
+///
+/// Nested submodules come before the submodules that contain them and the
+/// superproject comes last, so a superproject commit that bumps a submodule
+/// is pushed after the submodule change it points to. Only directories with
+/// a `.gitreview` are included.
+pub fn gerrit_projects(root: &Path, submodules: &[PathBuf]) -> Vec<PathBuf> {
+    submodules
+        .iter()
+        .rev()
+        .cloned()
+        .chain(std::iter::once(root.to_path_buf()))
+        .filter(|dir| dir.join(".gitreview").is_file())
+        .collect()
+}
+
+/// `dir` relative to `root` for display, `.` for the superproject.
+pub fn display_path(root: &Path, dir: &Path) -> String {
+    match dir.strip_prefix(root) {
+        Ok(rel) if rel.as_os_str().is_empty() => ".".to_string(),
+        Ok(rel) => rel.display().to_string(),
+        Err(_) => dir.display().to_string(),
+    }
+}
+
+/// One project in the combined push plan.
+#[derive(Debug)]
+pub struct PlanEntry {
+    pub path: String,
+    pub remote: String,
+    pub branch: String,
+    /// Unpushed commits, `None` when the remote branch is not known locally.
+    pub commits: Option<usize>,
+}
+
+impl PlanEntry {
+    /// Whether the project may have something to push.
+    pub fn pending(&self) -> bool {
+        self.commits != Some(0)
+    }
+}
+
+/// Combined plan: one line per project with its target and commit count.
+pub fn format_plan(entries: &[PlanEntry]) -> String {
+    let path_width = entries.iter().map(|e| e.path.len()).max().unwrap_or(0);
+    let target_width = entries
+        .iter()
+        .map(|e| e.remote.len() + e.branch.len() + 1)
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for entry in entries {
+        let target = format!("{}/{}", entry.remote, entry.branch);
+        let commits = match entry.commits {
+            Some(0) => "nothing to push".to_string(),
+            Some(n) => format!("{n} commit(s)"),
+            None => "unknown (remote branch not fetched)".to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "  {:path_width$}  {target:target_width$}  {commits}",
+            entry.path
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projects_need_gitreview_and_put_superproject_last() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let lib = root.join("lib");
+        let nested = lib.join("vendor");
+        let docs = root.join("docs");
+        for d in [&lib, &nested, &docs] {
+            std::fs::create_dir_all(d).unwrap();
+        }
+        for d in [root, lib.as_path(), nested.as_path()] {
+            std::fs::write(d.join(".gitreview"), "[gerrit]\n").unwrap();
+        }
+
+        let projects = gerrit_projects(root, &[lib.clone(), nested.clone(), docs]);
+        assert_eq!(projects, vec![nested, lib, root.to_path_buf()]);
+    }
+
+    #[test]
+    fn display_path_relative_to_root() {
+        let root = Path::new("/work/super");
+        assert_eq!(display_path(root, root), ".");
+        assert_eq!(display_path(root, &root.join("lib/a")), "lib/a");
+        assert_eq!(display_path(root, Path::new("/elsewhere")), "/elsewhere");
+    }
+
+    #[test]
+    fn plan_lists_targets_and_counts() {
+        let plan = format_plan(&[
+            PlanEntry {
+                path: "lib".to_string(),
+                remote: "gerrit".to_string(),
+                branch: "main".to_string(),
+                commits: Some(2),
+            },
+            PlanEntry {
+                path: ".".to_string(),
+                remote: "origin".to_string(),
+                branch: "master".to_string(),
+                commits: Some(0),
+            },
+        ]);
+        assert_eq!(
+            plan,
+            "  lib  gerrit/main    2 commit(s)\n  .    origin/master  nothing to push\n"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Conflict handling when `-x`/`-X`/`-N` cherry-pick a change that does not
+//! apply cleanly.
+
+use std::path::Path;
+use std::process::Command;
+
+use grt::review::{cherry_pick_change, PickMode};
+use grt::subprocess;
+
+/// Run git in `dir`, isolated from user/global config, and return stdout.
+fn git(args: &[&str], dir: &Path) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .env("GIT_CONFIG_SYSTEM", "/dev/null")
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .output()
+        .expect("running git");
+    assert!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .trim_end()
+        .to_string()
+}
+
+/// A repository on `master` whose `file.txt` conflicts with the commit
+/// returned, which changes the same line on a side branch.
+fn conflicting_repo(dir: &Path) -> String {
+    git(&["init", "-q", "-b", "master"], dir);
+    std::fs::write(dir.join("file.txt"), "base\n").unwrap();
+    git(&["add", "file.txt"], dir);
+    git(&["commit", "-q", "-m", "base"], dir);
+    git(&["checkout", "-q", "-b", "side"], dir);
+    std::fs::write(dir.join("file.txt"), "side\n").unwrap();
+    git(&["commit", "-q", "-am", "side"], dir);
+    let side = git(&["rev-parse", "HEAD"], dir);
+    git(&["checkout", "-q", "master"], dir);
+    std::fs::write(dir.join("file.txt"), "master\n").unwrap();
+    git(&["commit", "-q", "-am", "master"], dir);
+    side
+}
+
+#[test]
+fn conflict_is_left_in_progress_by_default() {
+    let tmp = tempfile::tempdir().unwrap();
+    let side = conflicting_repo(tmp.path());
+
+    let err = cherry_pick_change(&side, PickMode::Commit, false, tmp.path()).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("stopped on conflicts"),
+        "{err:#}"
+    );
+    assert_eq!(
+        subprocess::git_conflicted_files(tmp.path()).unwrap(),
+        ["file.txt"]
+    );
+    assert!(tmp.path().join(".git/CHERRY_PICK_HEAD").exists());
+}
+
+#[test]
+fn abort_on_conflict_restores_head() {
+    let tmp = tempfile::tempdir().unwrap();
+    let side = conflicting_repo(tmp.path());
+    let head = git(&["rev-parse", "HEAD"], tmp.path());
+
+    for mode in [PickMode::Indicate, PickMode::NoCommit] {
+        let err = cherry_pick_change(&side, mode, true, tmp.path()).unwrap_err();
+        assert!(format!("{err:#}").contains("rolled back"), "{err:#}");
+        assert!(subprocess::git_conflicted_files(tmp.path())
+            .unwrap()
+            .is_empty());
+        assert_eq!(git(&["rev-parse", "HEAD"], tmp.path()), head);
+        assert_eq!(git(&["status", "--porcelain"], tmp.path()), "");
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// Copyright (c) 2026 grt contributors
+
+//! Hook installation from linked worktrees (`git worktree add`) and from
+//! submodules, whose `.git` is a file rather than a directory.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use grt::git::GitRepo;
+use grt::hook;
+use grt::push;
+use grt::subprocess;
+
+/// Run git in `dir`, isolated from user/global config, and return stdout.
+fn git(args: &[&str], dir: &Path) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .env("GIT_CONFIG_SYSTEM", "/dev/null")
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .output()
+        .expect("running git");
+    assert!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .trim_end()
+        .to_string()
+}
+
+/// A repository at `<tmp>/main` with a linked worktree at `<tmp>/linked`.
+fn main_and_linked(tmp: &Path) -> (PathBuf, PathBuf) {
+    let main = tmp.join("main");
+    let linked = tmp.join("linked");
+    std::fs::create_dir(&main).unwrap();
+    git(&["init", "-q", "-b", "master"], &main);
+    git(&["commit", "-q", "--allow-empty", "-m", "initial"], &main);
+    git(
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature",
+            linked.to_str().unwrap(),
+        ],
+        &main,
+    );
+    (main, linked)
+}
+
+#[test]
+fn hook_installed_from_linked_worktree_runs_in_every_worktree() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (main, linked) = main_and_linked(tmp.path());
+
+    let repo = GitRepo::open(&linked).unwrap();
+    assert!(repo.is_linked_worktree());
+    hook::install_hook(&repo.hooks_dir().unwrap()).unwrap();
+    assert!(main.join(".git/hooks/commit-msg").is_file());
+
+    git(
+        &["commit", "-q", "--allow-empty", "-m", "In linked"],
+        &linked,
+    );
+    let message = repo.head_commit_message().unwrap();
+    assert!(push::extract_change_id(&message).is_some(), "{message}");
+
+    git(&["commit", "-q", "--allow-empty", "-m", "In main"], &main);
+    let message = git(&["log", "-1", "--format=%B"], &main);
+    assert!(push::extract_change_id(&message).is_some(), "{message}");
+}
+
+#[test]
+fn linked_worktree_tracks_its_own_branch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (main, linked) = main_and_linked(tmp.path());
+    git(&["config", "branch.feature.remote", "origin"], &linked);
+    git(
+        &["config", "branch.feature.merge", "refs/heads/stable"],
+        &linked,
+    );
+
+    let repo = GitRepo::open(&linked).unwrap();
+    assert_eq!(repo.current_branch().unwrap(), "feature");
+    assert_eq!(
+        repo.upstream_branch().unwrap(),
+        Some(("origin".to_string(), "stable".to_string()))
+    );
+    assert_eq!(
+        repo.root().unwrap().canonicalize().unwrap(),
+        linked.canonicalize().unwrap()
+    );
+    assert_eq!(
+        GitRepo::open(&main).unwrap().current_branch().unwrap(),
+        "master"
+    );
+}
+
+#[test]
+fn hook_propagates_to_submodule_with_gitfile() {
+    let tmp = tempfile::tempdir().unwrap();
+    let sub = tmp.path().join("sub");
+    let main = tmp.path().join("main");
+    std::fs::create_dir(&sub).unwrap();
+    std::fs::create_dir(&main).unwrap();
+    git(&["init", "-q", "-b", "master"], &sub);
+    git(&["commit", "-q", "--allow-empty", "-m", "sub"], &sub);
+    git(&["init", "-q", "-b", "master"], &main);
+    git(
+        &[
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            "-q",
+            sub.to_str().unwrap(),
+            "sub",
+        ],
+        &main,
+    );
+    assert!(main.join("sub/.git").is_file());
+
+    hook::propagate_hook_to_submodules(&main).unwrap();
+    let git_dir = subprocess::git_common_dir(&main.join("sub")).unwrap();
+    assert!(git_dir.join("hooks/commit-msg").is_file());
+}